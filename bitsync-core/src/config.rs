@@ -2,8 +2,13 @@ use std::{
     net::{AddrParseError, IpAddr, SocketAddr},
     path::PathBuf,
     str::FromStr,
+    sync::Arc,
 };
 
+use bitsync_storage::{
+    backend::s3::{CreateS3StorageBackendError, S3StorageBackend, S3StorageBackendOptions},
+    storage_root::StorageRoot,
+};
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -12,7 +17,48 @@ pub struct Config {
     pub address: ServiceAddress,
     pub database_url: String,
     pub fs_storage_root_dir: PathBuf,
+    /// Keeps the files, trash and versions of all users in an S3-compatible bucket instead of
+    /// `fs_storage_root_dir`, which then only holds temporary files, staged uploads and cached
+    /// thumbnails.
+    #[serde(default)]
+    pub s3_storage: Option<S3Storage>,
     pub auth: Auth,
+    /// Storage quota in bytes for users without an individual quota. Unlimited if unset.
+    #[serde(default)]
+    pub default_user_storage_quota: Option<u64>,
+    /// Number of days deleted items are kept in the trash before being purged.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u64,
+    /// Number of previous versions kept per file when it is overwritten. `0` disables versioning.
+    #[serde(default = "default_max_file_versions")]
+    pub max_file_versions: usize,
+}
+
+fn default_trash_retention_days() -> u64 {
+    30
+}
+
+fn default_max_file_versions() -> usize {
+    10
+}
+
+#[derive(Deserialize, Debug)]
+pub struct S3Storage {
+    pub bucket: String,
+    /// Required for S3-compatible services other than AWS, like MinIO.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Whether the endpoint may be reached without TLS.
+    #[serde(default)]
+    pub allow_http: bool,
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_owned()
 }
 
 #[derive(Deserialize, Debug)]
@@ -46,6 +92,26 @@ impl TryFrom<RawServiceAddress> for ServiceAddress {
 }
 
 impl Config {
+    pub fn storage_root(&self) -> Result<StorageRoot, CreateS3StorageBackendError> {
+        let Some(s3_storage) = &self.s3_storage else {
+            return Ok(StorageRoot::local(self.fs_storage_root_dir.clone()));
+        };
+
+        let backend = S3StorageBackend::new(S3StorageBackendOptions {
+            bucket: s3_storage.bucket.clone(),
+            endpoint: s3_storage.endpoint.clone(),
+            region: s3_storage.region.clone(),
+            access_key_id: s3_storage.access_key_id.clone(),
+            secret_access_key: s3_storage.secret_access_key.clone(),
+            allow_http: s3_storage.allow_http,
+        })?;
+
+        Ok(StorageRoot {
+            local_directory: self.fs_storage_root_dir.clone(),
+            backend: Arc::new(backend),
+        })
+    }
+
     pub fn tracing_level() -> tracing::level_filters::LevelFilter {
         #[cfg(debug_assertions)]
         return tracing::level_filters::LevelFilter::DEBUG;
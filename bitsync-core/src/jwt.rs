@@ -56,3 +56,33 @@ impl JwtClaims {
         Ok(jsonwebtoken::encode::<Self>(&header, self, &encoding_key)?)
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ShareAccessClaims {
+    pub sub: Uuid,
+    pub exp: i64,
+    /// Ties the token to the share password it was issued for, so changing or removing the
+    /// password revokes it.
+    pub password_fingerprint: String,
+}
+
+impl ShareAccessClaims {
+    pub fn decode_and_validate(token: &str, secret: &str) -> Result<Self, Error> {
+        let decoding_key = DecodingKey::from_secret(secret.as_bytes());
+        let header = jsonwebtoken::decode_header(token)?;
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = 0;
+
+        Ok(
+            jsonwebtoken::decode::<Self>(token, &decoding_key, &validation)
+                .map(|token_data| token_data.claims)?,
+        )
+    }
+
+    pub fn encode(&self, secret: &str) -> Result<String, Error> {
+        let header = jsonwebtoken::Header::default();
+        let encoding_key = EncodingKey::from_secret(secret.as_bytes());
+
+        Ok(jsonwebtoken::encode::<Self>(&header, self, &encoding_key)?)
+    }
+}
@@ -1,5 +1,11 @@
+pub mod app_password;
 pub mod auth;
+pub mod file_checksum;
+pub mod file_version;
 pub mod invite_token;
+pub mod shared_with_me;
+pub mod trash;
+pub mod upload_session;
 pub mod user;
 pub mod user_files;
 pub mod user_settings;
@@ -0,0 +1,5 @@
+pub mod authenticate_app_password;
+pub mod create_app_password;
+pub mod list_app_passwords;
+pub mod revoke_app_password;
+pub mod shared;
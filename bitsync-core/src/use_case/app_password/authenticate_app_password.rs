@@ -0,0 +1,51 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{AppPassword, User},
+    repository,
+};
+
+use super::shared::hash_app_password_token;
+
+pub struct AppPasswordAuthentication {
+    pub app_password: AppPassword,
+    pub user: User,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to authenticate with an app password")]
+pub enum AuthenticateAppPasswordError {
+    InvalidCredentials(#[from] InvalidAppPasswordCredentialsError),
+    DatabaseQuery(#[from] repository::QueryError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the username or app password is invalid")]
+pub struct InvalidAppPasswordCredentialsError;
+
+/// Resolves the user owning `token` and records the use of the app password. If a username is
+/// given, as with HTTP basic auth, it has to match the owner of the app password.
+pub async fn authenticate_app_password(
+    database: &Database,
+    username: Option<&str>,
+    token: &str,
+) -> Result<AppPasswordAuthentication, AuthenticateAppPasswordError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let app_password = repository::app_password::find_by_token_hash(
+        &mut *connection,
+        &hash_app_password_token(token),
+    )
+    .await?
+    .ok_or(InvalidAppPasswordCredentialsError)?;
+
+    let user = repository::user::find_by_id(&mut *connection, &app_password.user_id).await?;
+
+    if username.is_some_and(|username| username != user.username) {
+        Err(InvalidAppPasswordCredentialsError)?;
+    }
+
+    let app_password = repository::app_password::touch(&mut *connection, &app_password.id).await?;
+
+    Ok(AppPasswordAuthentication { app_password, user })
+}
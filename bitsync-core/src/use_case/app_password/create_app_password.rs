@@ -0,0 +1,70 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{AppPassword, AppPasswordScope, User},
+    repository,
+};
+
+use crate::{random::GenerateRandomBytesError, validation::is_blank};
+
+use super::shared::{generate_app_password_token, hash_app_password_token};
+
+pub struct AppPasswordCreationResult {
+    pub app_password: AppPassword,
+    /// The plain token. It is not stored and can only be shown to the user once.
+    pub token: String,
+    pub app_passwords: Vec<AppPassword>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create app password")]
+pub enum CreateAppPasswordError {
+    EmptyName(#[from] EmptyAppPasswordNameError),
+    AdminScopeNotAllowed(#[from] AdminScopeNotAllowedError),
+    GenerateRandomBytes(#[from] GenerateRandomBytesError),
+    DatabaseQuery(#[from] repository::QueryError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("app password name cannot be empty")]
+pub struct EmptyAppPasswordNameError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("only admins can create app passwords with the admin scope")]
+pub struct AdminScopeNotAllowedError;
+
+pub async fn create_app_password(
+    database: &Database,
+    user: &User,
+    name: &str,
+    scope: AppPasswordScope,
+) -> Result<AppPasswordCreationResult, CreateAppPasswordError> {
+    if is_blank(name) {
+        Err(EmptyAppPasswordNameError)?;
+    }
+
+    if scope == AppPasswordScope::Admin && !user.is_admin {
+        Err(AdminScopeNotAllowedError)?;
+    }
+
+    let token = generate_app_password_token()?;
+
+    let mut connection = database.acquire_connection().await?;
+
+    let app_password = repository::app_password::create(
+        &mut *connection,
+        &user.id,
+        name.trim(),
+        &hash_app_password_token(&token),
+        &scope,
+    )
+    .await?;
+    let app_passwords =
+        repository::app_password::find_all_by_user_id(&mut *connection, &user.id).await?;
+
+    Ok(AppPasswordCreationResult {
+        app_password,
+        token,
+        app_passwords,
+    })
+}
@@ -0,0 +1,25 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::AppPassword,
+    repository,
+};
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to list app passwords")]
+pub enum ListAppPasswordsError {
+    DatabaseQuery(#[from] repository::QueryError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+}
+
+pub async fn list_app_passwords(
+    database: &Database,
+    user_id: &Uuid,
+) -> Result<Vec<AppPassword>, ListAppPasswordsError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let app_passwords =
+        repository::app_password::find_all_by_user_id(&mut *connection, user_id).await?;
+
+    Ok(app_passwords)
+}
@@ -0,0 +1,28 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::AppPassword,
+    repository,
+};
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to revoke app password")]
+pub enum RevokeAppPasswordError {
+    DatabaseQuery(#[from] repository::QueryError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+}
+
+pub async fn revoke_app_password(
+    database: &Database,
+    user_id: &Uuid,
+    app_password_id: &Uuid,
+) -> Result<Vec<AppPassword>, RevokeAppPasswordError> {
+    let mut connection = database.acquire_connection().await?;
+
+    repository::app_password::delete_by_user_id_and_id(&mut *connection, user_id, app_password_id)
+        .await?;
+    let app_passwords =
+        repository::app_password::find_all_by_user_id(&mut *connection, user_id).await?;
+
+    Ok(app_passwords)
+}
@@ -0,0 +1,19 @@
+use sha2::{Digest, Sha256};
+
+use crate::random::{GenerateRandomBytesError, fill_random};
+
+const APP_PASSWORD_TOKEN_BYTES: usize = 20;
+
+pub(crate) fn generate_app_password_token() -> Result<String, GenerateRandomBytesError> {
+    let mut bytes = [0u8; APP_PASSWORD_TOKEN_BYTES];
+    fill_random(&mut bytes)?;
+
+    Ok(hex::encode(bytes))
+}
+
+/// App passwords are long random tokens rather than user chosen secrets, so a plain SHA-256 digest
+/// is sufficient. Unlike an Argon2 hash it can be looked up directly, which keeps authenticating
+/// every request of a WebDAV client cheap.
+pub(crate) fn hash_app_password_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -1,5 +1,3 @@
-use std::path::Path;
-
 use bitsync_database::{
     database::{Database, TransactionBeginError, transaction::TransactionCommitError},
     entity::User,
@@ -7,6 +5,7 @@ use bitsync_database::{
 };
 use bitsync_storage::{
     operation::write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 use uuid::Uuid;
@@ -50,7 +49,7 @@ pub struct RegistrationResult {
 
 pub async fn perform_registration(
     database: &Database,
-    storage_root_dir: &Path,
+    storage_root: &StorageRoot,
     username: &str,
     password: &str,
     invite_token_id: &Uuid,
@@ -90,7 +89,7 @@ pub async fn perform_registration(
 
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
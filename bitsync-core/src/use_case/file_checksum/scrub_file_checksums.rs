@@ -0,0 +1,189 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        checksum::{CalculateFileChecksumError, calculate_file_checksum},
+        error::{MetadataError, OpenFileError},
+        read::{ReadDirContentsError, item_exists},
+    },
+    storage_item::StorageItemKind,
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+use crate::use_case::user_files::shared::collect_all_storage_items;
+
+pub struct FileChecksumMismatch {
+    pub username: String,
+    pub item_path: String,
+    pub expected_checksum: String,
+    pub actual_checksum: String,
+}
+
+/// How far a scrub has come, the users are scrubbed one after another.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileChecksumScrubProgress {
+    pub scrubbed_user_count: usize,
+    pub total_user_count: usize,
+    pub checked_file_count: usize,
+}
+
+#[derive(Default)]
+pub struct FileChecksumScrubReport {
+    pub checked_file_count: usize,
+    /// Files that had no checksum yet, e.g. because they were stored before checksums existed.
+    pub recorded_checksum_count: usize,
+    /// Checksums of files that no longer exist.
+    pub removed_checksum_count: usize,
+    pub mismatches: Vec<FileChecksumMismatch>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to scrub file checksums")]
+pub enum ScrubFileChecksumsError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    StoragePath(#[from] StoragePathError),
+    Metadata(#[from] MetadataError),
+    ReadDirContents(#[from] ReadDirContentsError),
+    CalculateFileChecksum(#[from] CalculateFileChecksumError),
+}
+
+/// Re-hashes every stored file and compares the result with the recorded checksum. Mismatching
+/// checksums are reported and left untouched so the affected files can be inspected.
+/// `on_progress` is called after every checked file and every scrubbed user.
+pub async fn scrub_file_checksums(
+    database: &Database,
+    storage_root: &StorageRoot,
+    mut on_progress: impl FnMut(FileChecksumScrubProgress),
+) -> Result<FileChecksumScrubReport, ScrubFileChecksumsError> {
+    let mut report = FileChecksumScrubReport::default();
+
+    let users = repository::user::find_all(&mut *database.acquire_connection().await?).await?;
+
+    let mut progress = FileChecksumScrubProgress {
+        scrubbed_user_count: 0,
+        total_user_count: users.len(),
+        checked_file_count: 0,
+    };
+
+    on_progress(progress);
+
+    for user in users {
+        let user_storage = UserStorage {
+            user_id: user.id,
+            storage_root: storage_root.clone(),
+        };
+
+        let root_path = StoragePath::new(user_storage.clone(), PathBuf::from("/"))?;
+
+        if !item_exists(&root_path).await? {
+            progress.scrubbed_user_count += 1;
+            on_progress(progress);
+
+            continue;
+        }
+
+        let mut recorded_checksums: HashMap<String, String> =
+            repository::file_checksum::find_all_by_user_id(
+                &mut *database.acquire_connection().await?,
+                &user.id,
+            )
+            .await?
+            .into_iter()
+            .map(|file_checksum| (file_checksum.item_path, file_checksum.sha256))
+            .collect();
+
+        let mut storage_items = Vec::new();
+        collect_all_storage_items(&root_path, &mut storage_items).await?;
+
+        for storage_item in storage_items {
+            if storage_item.kind != StorageItemKind::File {
+                continue;
+            }
+
+            // directory listings carry paths relative to the storage root
+            let item_path =
+                StoragePath::new(user_storage.clone(), storage_item.path.scoped_path.clone())?
+                    .path();
+
+            let actual_checksum = match calculate_file_checksum(&storage_item.path).await {
+                Ok(actual_checksum) => actual_checksum,
+                // the file was removed while the scrub was running
+                Err(CalculateFileChecksumError::OpenFile(OpenFileError { source, .. }))
+                    if source.kind() == std::io::ErrorKind::NotFound =>
+                {
+                    continue;
+                }
+                Err(error) => Err(error)?,
+            };
+
+            report.checked_file_count += 1;
+            progress.checked_file_count += 1;
+            on_progress(progress);
+
+            match recorded_checksums.remove(&item_path) {
+                None => {
+                    repository::file_checksum::create_if_absent(
+                        &mut *database.acquire_connection().await?,
+                        &user.id,
+                        &item_path,
+                        &actual_checksum,
+                    )
+                    .await?;
+
+                    report.recorded_checksum_count += 1;
+                }
+                Some(expected_checksum) if expected_checksum == actual_checksum => {}
+                Some(..) => {
+                    // the file may have been replaced or moved since the checksums were loaded
+                    let current_checksum =
+                        repository::file_checksum::find_by_user_id_and_item_path(
+                            &mut *database.acquire_connection().await?,
+                            &user.id,
+                            &item_path,
+                        )
+                        .await?
+                        .map(|file_checksum| file_checksum.sha256);
+
+                    match current_checksum {
+                        Some(expected_checksum) if expected_checksum != actual_checksum => {
+                            report.mismatches.push(FileChecksumMismatch {
+                                username: user.username.clone(),
+                                item_path,
+                                expected_checksum,
+                                actual_checksum,
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        for item_path in recorded_checksums.into_keys() {
+            let path = StoragePath::new(user_storage.clone(), PathBuf::from(&item_path))?;
+
+            if !item_exists(&path).await? {
+                repository::file_checksum::delete_by_user_id_and_item_path(
+                    &mut *database.acquire_connection().await?,
+                    &user.id,
+                    &item_path,
+                )
+                .await?;
+
+                report.removed_checksum_count += 1;
+            }
+        }
+
+        progress.scrubbed_user_count += 1;
+        on_progress(progress);
+    }
+
+    Ok(report)
+}
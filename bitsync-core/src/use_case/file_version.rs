@@ -0,0 +1,3 @@
+pub mod download_user_file_version;
+pub mod restore_user_file_version;
+pub mod shared;
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{error::OpenFileError, version::read_file_version_stream},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use uuid::Uuid;
+
+use crate::use_case::user_files::{
+    download_user_file::UserFileDownloadResult, shared::AsyncStorageItemRead,
+};
+
+use super::restore_user_file_version::FileVersionNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to download a file version")]
+pub enum DownloadUserFileVersionError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    FileVersionNotFound(#[from] FileVersionNotFoundError),
+    StoragePath(#[from] StoragePathError),
+    OpenFile(#[from] OpenFileError),
+}
+
+pub async fn download_user_file_version(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user: &User,
+    file_version_id: &Uuid,
+) -> Result<UserFileDownloadResult, DownloadUserFileVersionError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let mut connection = database.acquire_connection().await?;
+
+    let file_version = repository::file_version::find_by_id_and_user_id(
+        &mut *connection,
+        file_version_id,
+        &user.id,
+    )
+    .await?
+    .ok_or(FileVersionNotFoundError)?;
+
+    let path = StoragePath::new(user_storage.clone(), PathBuf::from(&file_version.item_path))?;
+    let file = read_file_version_stream(&user_storage, &file_version.id.to_string()).await?;
+    let mime = mime_guess::from_path(&path.scoped_path).first_or_octet_stream();
+
+    Ok(UserFileDownloadResult {
+        file: AsyncStorageItemRead::File(file),
+        mime,
+        path,
+        checksum: None,
+        size: u64::try_from(file_version.size).ok(),
+        modified_at: Some(file_version.created_at.into()),
+    })
+}
@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        checksum::{CalculateFileChecksumError, calculate_file_checksum},
+        version::{RestoreFileVersionError, restore_file_version},
+    },
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use uuid::Uuid;
+
+use super::shared::{
+    DeleteFileVersionsError, KeepPreviousFileVersionError, keep_previous_file_version,
+    prune_file_versions,
+};
+
+pub struct RestoreUserFileVersionResult {
+    pub path: StoragePath,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the file version does not exist")]
+pub struct FileVersionNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to restore a file version")]
+pub enum RestoreUserFileVersionError {
+    TransactionBegin(#[from] TransactionBeginError),
+    Query(#[from] QueryError),
+    FileVersionNotFound(#[from] FileVersionNotFoundError),
+    StoragePath(#[from] StoragePathError),
+    KeepPreviousFileVersion(#[from] KeepPreviousFileVersionError),
+    RestoreFileVersion(#[from] RestoreFileVersionError),
+    CalculateFileChecksum(#[from] CalculateFileChecksumError),
+    DeleteFileVersions(#[from] DeleteFileVersionsError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+/// Makes a stored version the current content of its file. The replaced content is kept as a
+/// new version.
+pub async fn restore_user_file_version(
+    database: &Database,
+    storage_root: &StorageRoot,
+    max_file_versions: usize,
+    user: &User,
+    file_version_id: &Uuid,
+) -> Result<RestoreUserFileVersionResult, RestoreUserFileVersionError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let mut transaction = database.begin_transaction().await?;
+
+    let file_version = repository::file_version::find_by_id_and_user_id(
+        &mut *transaction,
+        file_version_id,
+        &user.id,
+    )
+    .await?
+    .ok_or(FileVersionNotFoundError)?;
+
+    let path = StoragePath::new(user_storage.clone(), PathBuf::from(&file_version.item_path))?;
+
+    keep_previous_file_version(&mut transaction, &path, max_file_versions).await?;
+
+    repository::file_version::delete_by_id(&mut *transaction, &file_version.id).await?;
+    restore_file_version(&file_version.id.to_string(), &path).await?;
+
+    let checksum = calculate_file_checksum(&path).await?;
+    repository::file_checksum::upsert(&mut *transaction, &user.id, &path.path(), &checksum).await?;
+
+    prune_file_versions(
+        &mut transaction,
+        &user_storage,
+        &path.path(),
+        max_file_versions,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(RestoreUserFileVersionResult { path })
+}
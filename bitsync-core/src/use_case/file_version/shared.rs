@@ -0,0 +1,182 @@
+use bitsync_database::{
+    database::{
+        Database, TransactionBeginError,
+        transaction::{Transaction, TransactionCommitError},
+    },
+    entity::FileVersion,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        error::MetadataError,
+        read::{ReadStorageItemError, read_storage_item},
+        version::{
+            DeleteFileVersionError, RestoreFileVersionError, StoreFileVersionError,
+            delete_file_version, restore_file_version, store_file_version,
+        },
+        write::{
+            PersistTemporaryFileError, WriteFileStreamError, persist_temporary_file,
+            write_temporary_file_stream,
+        },
+    },
+    storage_item::StorageItemKind,
+    storage_path::StoragePath,
+    user_storage::UserStorage,
+};
+use tokio_util::io::StreamReader;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to keep the previous version of a file")]
+pub enum KeepPreviousFileVersionError {
+    ReadStorageItem(#[from] ReadStorageItemError),
+    Query(#[from] QueryError),
+    StoreFileVersion(#[from] StoreFileVersionError),
+}
+
+/// Moves the file at `path` into the version storage so it can be replaced. Returns `None` if
+/// versioning is disabled or there is no file to keep.
+pub(crate) async fn keep_previous_file_version(
+    transaction: &mut Transaction,
+    path: &StoragePath,
+    max_file_versions: usize,
+) -> Result<Option<FileVersion>, KeepPreviousFileVersionError> {
+    if max_file_versions == 0 {
+        return Ok(None);
+    }
+
+    let storage_item = match read_storage_item(path).await {
+        Ok(storage_item) => storage_item,
+        Err(ReadStorageItemError::Metadata(MetadataError { source, .. }))
+            if source.kind() == std::io::ErrorKind::NotFound =>
+        {
+            return Ok(None);
+        }
+        Err(error) => Err(error)?,
+    };
+
+    if storage_item.kind != StorageItemKind::File {
+        return Ok(None);
+    }
+
+    let file_version = repository::file_version::create(
+        &mut **transaction,
+        &path.storage.user_id,
+        &path.path(),
+        storage_item.size as i64,
+    )
+    .await?;
+
+    store_file_version(path, &file_version.id.to_string()).await?;
+
+    Ok(Some(file_version))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete file versions")]
+pub enum DeleteFileVersionsError {
+    Query(#[from] QueryError),
+    DeleteFileVersion(#[from] DeleteFileVersionError),
+}
+
+/// Drops the oldest versions of the file at `item_path` until at most `max_file_versions` remain.
+pub(crate) async fn prune_file_versions(
+    transaction: &mut Transaction,
+    storage: &UserStorage,
+    item_path: &str,
+    max_file_versions: usize,
+) -> Result<(), DeleteFileVersionsError> {
+    let file_versions = repository::file_version::find_all_by_user_id_and_item_path(
+        &mut **transaction,
+        &storage.user_id,
+        item_path,
+    )
+    .await?;
+
+    for file_version in file_versions.into_iter().skip(max_file_versions) {
+        repository::file_version::delete_by_id(&mut **transaction, &file_version.id).await?;
+        delete_file_version(storage, &file_version.id.to_string()).await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes all versions of files at or below `item_path`.
+pub(crate) async fn delete_file_versions_at_or_below(
+    transaction: &mut Transaction,
+    storage: &UserStorage,
+    item_path: &str,
+) -> Result<(), DeleteFileVersionsError> {
+    let file_versions = repository::file_version::find_all_by_user_id_at_or_below_item_path(
+        &mut **transaction,
+        &storage.user_id,
+        item_path,
+    )
+    .await?;
+
+    for file_version in file_versions {
+        repository::file_version::delete_by_id(&mut **transaction, &file_version.id).await?;
+        delete_file_version(storage, &file_version.id.to_string()).await?;
+    }
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to write a versioned file")]
+pub enum WriteVersionedFileStreamError {
+    WriteFileStream(#[from] WriteFileStreamError),
+    TransactionBegin(#[from] TransactionBeginError),
+    KeepPreviousFileVersion(#[from] KeepPreviousFileVersionError),
+    Query(#[from] QueryError),
+    PersistTemporaryFile(#[from] PersistTemporaryFileError),
+    RestoreFileVersion(#[from] RestoreFileVersionError),
+    DeleteFileVersions(#[from] DeleteFileVersionsError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+/// Atomically writes `stream` to `path`, keeping the replaced file as a version.
+/// The previous content is only replaced once the new content has been written completely.
+pub(crate) async fn write_versioned_file_stream<S, B, E>(
+    database: &Database,
+    path: &StoragePath,
+    stream: StreamReader<S, B>,
+    size_limit: Option<u64>,
+    max_file_versions: usize,
+) -> Result<(), WriteVersionedFileStreamError>
+where
+    S: futures::Stream<Item = Result<B, E>>,
+    B: bytes::Buf,
+    E: Into<std::io::Error>,
+{
+    let temporary_file = write_temporary_file_stream(&path.storage, stream, size_limit).await?;
+
+    let mut transaction = database.begin_transaction().await?;
+    let previous_file_version =
+        keep_previous_file_version(&mut transaction, path, max_file_versions).await?;
+    repository::file_checksum::upsert(
+        &mut *transaction,
+        &path.storage.user_id,
+        &path.path(),
+        temporary_file.checksum(),
+    )
+    .await?;
+
+    if let Err(error) = persist_temporary_file(temporary_file, path).await {
+        if let Some(previous_file_version) = previous_file_version {
+            restore_file_version(&previous_file_version.id.to_string(), path).await?;
+        }
+
+        return Err(error)?;
+    }
+
+    prune_file_versions(
+        &mut transaction,
+        &path.storage,
+        &path.path(),
+        max_file_versions,
+    )
+    .await?;
+    transaction.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod create_shared_directory;
+pub mod delete_shared_item;
+pub mod download_shared_item;
+pub mod list_shared_with_me;
+pub mod read_shared_item;
+pub mod shared;
+pub mod upload_shared_file;
@@ -0,0 +1,72 @@
+use bitsync_database::database::Database;
+use bitsync_storage::{
+    operation::{
+        read::ReadDirContentsError,
+        write::{CreateDirectoryError, create_directory},
+    },
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    validation::{PathIsJustFileNameValidationError, validate_path_is_just_file_name},
+};
+use uuid::Uuid;
+
+use crate::use_case::{
+    user_files::create_directory::EmptyPathError,
+    user_share::read_user_share_item::read_share_directory_contents,
+};
+
+use super::shared::{
+    ResolveSharedItemPathError, SharedDirectoryChangeResult, SharedItemReadOnlyError,
+    ensure_write_permission, resolve_shared_item_path,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create a shared directory")]
+pub enum SharedDirectoryCreationError {
+    ResolveSharedItemPath(#[from] ResolveSharedItemPathError),
+    SharedItemReadOnly(#[from] SharedItemReadOnlyError),
+    EmptyPath(#[from] EmptyPathError),
+    DirectoryNameContainsSeparator(#[from] PathIsJustFileNameValidationError),
+    StoragePath(#[from] StoragePathError),
+    CreateDirectory(#[from] CreateDirectoryError),
+    ReadDirContents(#[from] ReadDirContentsError),
+}
+
+pub async fn create_shared_directory(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    parent_directory: &str,
+    directory_name: &str,
+) -> Result<SharedDirectoryChangeResult, SharedDirectoryCreationError> {
+    let resolved_path = resolve_shared_item_path(
+        database,
+        storage_root,
+        user_id,
+        user_share_id,
+        parent_directory,
+    )
+    .await?;
+
+    ensure_write_permission(&resolved_path.user_share)?;
+
+    if directory_name.is_empty() {
+        return Err(EmptyPathError)?;
+    }
+
+    validate_path_is_just_file_name(directory_name)?;
+
+    let storage_path_to_create = StoragePath::new(
+        resolved_path.path.storage.clone(),
+        resolved_path.path.scoped_path.join(directory_name),
+    )?;
+
+    create_directory(&storage_path_to_create).await?;
+
+    Ok(SharedDirectoryChangeResult {
+        user_share_id: resolved_path.user_share.id,
+        permission: resolved_path.user_share.permission.clone(),
+        dir_contents: read_share_directory_contents(&resolved_path).await?,
+    })
+}
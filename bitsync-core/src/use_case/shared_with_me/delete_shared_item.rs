@@ -0,0 +1,70 @@
+use bitsync_database::database::{
+    Database, TransactionBeginError, transaction::TransactionCommitError,
+};
+use bitsync_storage::{
+    operation::read::{ReadDirContentsError, ReadStorageItemError, read_storage_item},
+    storage_path::StoragePathError,
+    storage_root::StorageRoot,
+};
+use uuid::Uuid;
+
+use crate::use_case::{
+    user_files::delete_user_file::{TrashStorageItemError, trash_storage_item},
+    user_share::read_user_share_item::read_share_directory_contents,
+};
+
+use super::shared::{
+    ResolveSharedItemPathError, SharedDirectoryChangeResult, SharedItemReadOnlyError,
+    ensure_write_permission, resolve_parent_path, resolve_shared_item_path,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("the root of a shared item cannot be deleted")]
+pub struct SharedRootDeletionError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete a shared item")]
+pub enum SharedItemDeletionError {
+    ResolveSharedItemPath(#[from] ResolveSharedItemPathError),
+    SharedItemReadOnly(#[from] SharedItemReadOnlyError),
+    SharedRootDeletion(#[from] SharedRootDeletionError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    TrashStorageItem(#[from] TrashStorageItemError),
+    ReadDirContents(#[from] ReadDirContentsError),
+    TransactionBegin(#[from] TransactionBeginError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+pub async fn delete_shared_item(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    path: &str,
+) -> Result<SharedDirectoryChangeResult, SharedItemDeletionError> {
+    let resolved_path =
+        resolve_shared_item_path(database, storage_root, user_id, user_share_id, path).await?;
+
+    ensure_write_permission(&resolved_path.user_share)?;
+
+    if resolved_path.share_relative_path.file_name().is_none() {
+        return Err(SharedRootDeletionError)?;
+    }
+
+    read_storage_item(&resolved_path.path).await?;
+
+    let mut transaction = database.begin_transaction().await?;
+
+    trash_storage_item(&mut transaction, &resolved_path.path).await?;
+
+    transaction.commit().await?;
+
+    let parent_path = resolve_parent_path(&resolved_path)?;
+
+    Ok(SharedDirectoryChangeResult {
+        user_share_id: parent_path.user_share.id,
+        permission: parent_path.user_share.permission.clone(),
+        dir_contents: read_share_directory_contents(&parent_path).await?,
+    })
+}
@@ -0,0 +1,35 @@
+use bitsync_database::database::Database;
+use bitsync_storage::storage_root::StorageRoot;
+use uuid::Uuid;
+
+use crate::use_case::user_files::download_user_file::{
+    DirectoryArchiveFormat, UserFileDownloadError, UserFileDownloadResult, download_storage_path,
+};
+
+use super::shared::{ResolveSharedItemPathError, resolve_shared_item_path};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to download shared item")]
+pub enum DownloadSharedItemError {
+    ResolveSharedItemPath(#[from] ResolveSharedItemPathError),
+    UserFileDownload(#[from] UserFileDownloadError),
+}
+
+pub async fn download_shared_item(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    path: &str,
+) -> Result<UserFileDownloadResult, DownloadSharedItemError> {
+    let resolved_path =
+        resolve_shared_item_path(database, storage_root, user_id, user_share_id, path).await?;
+
+    Ok(download_storage_path(
+        database,
+        resolved_path.path,
+        &resolved_path.share_root_name,
+        DirectoryArchiveFormat::default(),
+    )
+    .await?)
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{User, UserSharePermission},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::read::read_storage_item, storage_item::StorageItemKind, storage_path::StoragePath,
+    storage_root::StorageRoot, user_storage::UserStorage,
+};
+use uuid::Uuid;
+
+use crate::use_case::user_files::shared::user_root_directory_name;
+
+pub struct SharedWithMeEntry {
+    pub user_share_id: Uuid,
+    pub name: String,
+    pub owner_username: String,
+    pub permission: UserSharePermission,
+    pub kind: StorageItemKind,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to list items shared with user")]
+pub enum ListSharedWithMeError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+}
+
+pub async fn list_shared_with_me(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user: &User,
+) -> Result<Vec<SharedWithMeEntry>, ListSharedWithMeError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let user_shares =
+        repository::user_share::find_all_by_shared_with_user_id(&mut *connection, &user.id).await?;
+
+    let owner_ids = user_shares
+        .iter()
+        .map(|user_share| user_share.user_id)
+        .collect::<Vec<Uuid>>();
+    let owners = repository::user::find_by_ids(&mut *connection, &owner_ids).await?;
+
+    let mut entries = Vec::with_capacity(user_shares.len());
+
+    for user_share in user_shares {
+        let Some(owner) = owners
+            .iter()
+            .find(|owner| owner.id == user_share.user_id && !owner.is_suspended)
+        else {
+            continue;
+        };
+
+        let user_storage = UserStorage {
+            user_id: owner.id,
+            storage_root: storage_root.clone(),
+        };
+
+        // shared items that were removed by their owner are not listed
+        let Ok(storage_path) = StoragePath::new(user_storage, PathBuf::from(&user_share.item_path))
+        else {
+            continue;
+        };
+        let Ok(storage_item) = read_storage_item(&storage_path).await else {
+            continue;
+        };
+
+        let name = storage_path
+            .scoped_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| user_root_directory_name(&owner.username));
+
+        entries.push(SharedWithMeEntry {
+            user_share_id: user_share.id,
+            name,
+            owner_username: owner.username.clone(),
+            permission: user_share.permission,
+            kind: storage_item.kind,
+        });
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,41 @@
+use bitsync_database::{database::Database, entity::UserSharePermission};
+use bitsync_storage::storage_root::StorageRoot;
+use uuid::Uuid;
+
+use crate::use_case::user_share::read_user_share_item::{
+    ReadResolvedUserShareItemError, UserShareItemResult, read_resolved_user_share_item,
+};
+
+use super::shared::{ResolveSharedItemPathError, resolve_shared_item_path};
+
+pub struct SharedItemResult {
+    pub permission: UserSharePermission,
+    pub owner_username: String,
+    pub item: UserShareItemResult,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to read shared item")]
+pub enum ReadSharedItemError {
+    ResolveSharedItemPath(#[from] ResolveSharedItemPathError),
+    ReadResolvedUserShareItem(#[from] ReadResolvedUserShareItemError),
+}
+
+pub async fn read_shared_item(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    path: &str,
+) -> Result<SharedItemResult, ReadSharedItemError> {
+    let resolved_path =
+        resolve_shared_item_path(database, storage_root, user_id, user_share_id, path).await?;
+
+    let item = read_resolved_user_share_item(&resolved_path).await?;
+
+    Ok(SharedItemResult {
+        permission: resolved_path.user_share.permission,
+        owner_username: resolved_path.owner_username,
+        item,
+    })
+}
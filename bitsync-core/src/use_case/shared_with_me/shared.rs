@@ -0,0 +1,89 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{UserShare, UserSharePermission},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+};
+use uuid::Uuid;
+
+use crate::use_case::user_share::{
+    read_user_share_item::UserShareDirectoryItem,
+    shared::{ResolvedUserSharePath, UserShareNotFoundError, build_resolved_user_share_path},
+};
+
+pub struct SharedDirectoryChangeResult {
+    pub user_share_id: Uuid,
+    pub permission: UserSharePermission,
+    pub dir_contents: Vec<UserShareDirectoryItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the shared item is read-only")]
+pub struct SharedItemReadOnlyError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to resolve shared item path")]
+pub enum ResolveSharedItemPathError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UserShareNotFound(#[from] UserShareNotFoundError),
+    StoragePath(#[from] StoragePathError),
+}
+
+pub(crate) async fn resolve_shared_item_path(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    path: &str,
+) -> Result<ResolvedUserSharePath, ResolveSharedItemPathError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let user_share = repository::user_share::find_by_id(&mut *connection, user_share_id)
+        .await?
+        .filter(|user_share| user_share.shared_with_user_id.as_ref() == Some(user_id))
+        .ok_or(UserShareNotFoundError)?;
+    let owner = repository::user::find_by_id(&mut *connection, &user_share.user_id).await?;
+
+    if owner.is_suspended {
+        return Err(UserShareNotFoundError)?;
+    }
+
+    Ok(build_resolved_user_share_path(
+        storage_root,
+        user_share,
+        &owner,
+        path,
+    )?)
+}
+
+pub(crate) fn ensure_write_permission(
+    user_share: &UserShare,
+) -> Result<(), SharedItemReadOnlyError> {
+    match user_share.permission {
+        UserSharePermission::ReadWrite => Ok(()),
+        UserSharePermission::Read => Err(SharedItemReadOnlyError),
+    }
+}
+
+pub(crate) fn resolve_parent_path(
+    resolved_path: &ResolvedUserSharePath,
+) -> Result<ResolvedUserSharePath, StoragePathError> {
+    let mut scoped_path = resolved_path.path.scoped_path.clone();
+    scoped_path.pop();
+
+    let mut share_relative_path = resolved_path.share_relative_path.clone();
+    share_relative_path.pop();
+
+    Ok(ResolvedUserSharePath {
+        user_share: resolved_path.user_share.clone(),
+        path: StoragePath::new(resolved_path.path.storage.clone(), scoped_path)?,
+        share_relative_path,
+        share_root_name: resolved_path.share_root_name.clone(),
+        owner_username: resolved_path.owner_username.clone(),
+        owner_storage_quota: resolved_path.owner_storage_quota,
+    })
+}
@@ -0,0 +1,97 @@
+use bitsync_database::database::Database;
+use bitsync_storage::{
+    operation::read::{CalculateStorageUsageError, ReadDirContentsError},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    validation::{PathIsJustFileNameValidationError, validate_path_is_just_file_name},
+};
+use futures::TryStreamExt;
+use tokio_util::io::StreamReader;
+use uuid::Uuid;
+
+use crate::use_case::{
+    file_version::shared::{WriteVersionedFileStreamError, write_versioned_file_stream},
+    user_files::shared::{UploadPolicy, effective_storage_quota, remaining_storage_quota},
+    user_share::read_user_share_item::read_share_directory_contents,
+};
+
+use super::shared::{
+    ResolveSharedItemPathError, SharedDirectoryChangeResult, SharedItemReadOnlyError,
+    ensure_write_permission, resolve_shared_item_path,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("an error occurred during shared file upload")]
+pub enum SharedFileUploadError {
+    ResolveSharedItemPath(#[from] ResolveSharedItemPathError),
+    SharedItemReadOnly(#[from] SharedItemReadOnlyError),
+    InvalidFileName(#[from] PathIsJustFileNameValidationError),
+    StoragePath(#[from] StoragePathError),
+    WriteVersionedFileStream(#[from] WriteVersionedFileStreamError),
+    ReadDirContents(#[from] ReadDirContentsError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+}
+
+pub struct SharedFileUpload<'a, S> {
+    pub file_name: &'a str,
+    pub stream: S,
+}
+
+pub async fn upload_shared_file<S, B, E>(
+    database: &Database,
+    storage_root: &StorageRoot,
+    upload_policy: &UploadPolicy,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    path: &str,
+    SharedFileUpload {
+        file_name,
+        stream: file_upload_stream,
+    }: SharedFileUpload<'_, S>,
+) -> Result<SharedDirectoryChangeResult, SharedFileUploadError>
+where
+    S: futures::Stream<Item = Result<B, E>>,
+    B: bytes::Buf,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let resolved_path =
+        resolve_shared_item_path(database, storage_root, user_id, user_share_id, path).await?;
+
+    ensure_write_permission(&resolved_path.user_share)?;
+    validate_path_is_just_file_name(file_name)?;
+
+    let destination_storage_path = StoragePath::new(
+        resolved_path.path.storage.clone(),
+        resolved_path.path.scoped_path.join(file_name),
+    )?;
+
+    // uploads into a shared directory count towards the owner's quota
+    let remaining_storage_quota = remaining_storage_quota(
+        &destination_storage_path,
+        effective_storage_quota(
+            resolved_path.owner_storage_quota,
+            upload_policy.default_storage_quota,
+        ),
+        upload_policy.max_file_versions,
+    )
+    .await?;
+
+    let file_upload_stream_with_io_error =
+        file_upload_stream.map_err(|error| std::io::Error::other(error));
+    let file_upload_stream_reader = StreamReader::new(file_upload_stream_with_io_error);
+
+    write_versioned_file_stream(
+        database,
+        &destination_storage_path,
+        file_upload_stream_reader,
+        remaining_storage_quota,
+        upload_policy.max_file_versions,
+    )
+    .await?;
+
+    Ok(SharedDirectoryChangeResult {
+        user_share_id: resolved_path.user_share.id,
+        permission: resolved_path.user_share.permission.clone(),
+        dir_contents: read_share_directory_contents(&resolved_path).await?,
+    })
+}
@@ -0,0 +1,5 @@
+pub mod empty_trash;
+pub mod list_trash_entries;
+pub mod purge_expired_trash_entries;
+pub mod restore_trash_entry;
+pub mod shared;
@@ -0,0 +1,51 @@
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        trash::{DeleteFromTrashError, empty_trash as empty_user_trash},
+        version::{DeleteFileVersionError, delete_file_version},
+    },
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to empty the trash")]
+pub enum EmptyTrashError {
+    TransactionBegin(#[from] TransactionBeginError),
+    Query(#[from] QueryError),
+    DeleteFromTrash(#[from] DeleteFromTrashError),
+    DeleteFileVersion(#[from] DeleteFileVersionError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+pub async fn empty_trash(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user: &User,
+) -> Result<(), EmptyTrashError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let mut transaction = database.begin_transaction().await?;
+
+    let trashed_file_versions =
+        repository::file_version::find_all_trashed_by_user_id(&mut *transaction, &user.id).await?;
+
+    for file_version in trashed_file_versions {
+        delete_file_version(&user_storage, &file_version.id.to_string()).await?;
+    }
+
+    // Deleting the trash entries also deletes the versions and checksums of the trashed files.
+    repository::trash_entry::delete_all_by_user_id(&mut *transaction, &user.id).await?;
+    empty_user_trash(&user_storage).await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    storage_root::StorageRoot,
+    {operation::error::MetadataError, user_storage::UserStorage},
+};
+
+use super::shared::{TrashEntryListing, build_trash_entry_listings};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to list trash entries")]
+pub enum ListTrashEntriesError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    Metadata(#[from] MetadataError),
+}
+
+pub async fn list_trash_entries(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user: &User,
+) -> Result<Vec<TrashEntryListing>, ListTrashEntriesError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let mut connection = database.acquire_connection().await?;
+
+    let trash_entries =
+        repository::trash_entry::find_all_by_user_id(&mut *connection, &user.id).await?;
+
+    Ok(build_trash_entry_listings(&user_storage, trash_entries).await?)
+}
@@ -0,0 +1,55 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        trash::{DeleteFromTrashError, delete_from_trash},
+        version::{DeleteFileVersionError, delete_file_version},
+    },
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to purge expired trash entries")]
+pub enum PurgeExpiredTrashEntriesError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    DeleteFromTrash(#[from] DeleteFromTrashError),
+    DeleteFileVersion(#[from] DeleteFileVersionError),
+}
+
+/// Permanently deletes all trashed items and their versions that were deleted longer than
+/// `retention` ago and returns the number of purged entries.
+pub async fn purge_expired_trash_entries(
+    database: &Database,
+    storage_root: &StorageRoot,
+    retention: time::Duration,
+) -> Result<usize, PurgeExpiredTrashEntriesError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let deleted_before = time::OffsetDateTime::now_utc() - retention;
+    let expired_trash_entries =
+        repository::trash_entry::find_all_deleted_before(&mut *connection, &deleted_before).await?;
+
+    for trash_entry in &expired_trash_entries {
+        let user_storage = UserStorage {
+            user_id: trash_entry.user_id,
+            storage_root: storage_root.clone(),
+        };
+
+        let trashed_file_versions =
+            repository::file_version::find_all_by_trash_entry_id(&mut *connection, &trash_entry.id)
+                .await?;
+
+        for file_version in trashed_file_versions {
+            delete_file_version(&user_storage, &file_version.id.to_string()).await?;
+        }
+
+        delete_from_trash(&user_storage, &trash_entry.id.to_string()).await?;
+        repository::trash_entry::delete_by_id(&mut *connection, &trash_entry.id).await?;
+    }
+
+    Ok(expired_trash_entries.len())
+}
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        error::MetadataError,
+        trash::{RestoreFromTrashError, restore_from_trash},
+    },
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use uuid::Uuid;
+
+use crate::use_case::user_files::shared::{
+    FindAvailableStoragePathError, find_available_storage_path,
+};
+
+use super::shared::{TrashEntryListing, build_trash_entry_listings};
+
+pub struct RestoreTrashEntryResult {
+    pub restored_path: String,
+    pub trash_entries: Vec<TrashEntryListing>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the trash entry does not exist")]
+pub struct TrashEntryNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to restore trash entry")]
+pub enum RestoreTrashEntryError {
+    TransactionBegin(#[from] TransactionBeginError),
+    Query(#[from] QueryError),
+    TrashEntryNotFound(#[from] TrashEntryNotFoundError),
+    StoragePath(#[from] StoragePathError),
+    FindAvailableStoragePath(#[from] FindAvailableStoragePathError),
+    RestoreFromTrash(#[from] RestoreFromTrashError),
+    Metadata(#[from] MetadataError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+pub async fn restore_trash_entry(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user: &User,
+    trash_entry_id: &Uuid,
+) -> Result<RestoreTrashEntryResult, RestoreTrashEntryError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let mut transaction = database.begin_transaction().await?;
+
+    let trash_entry = repository::trash_entry::find_by_id_and_user_id(
+        &mut *transaction,
+        trash_entry_id,
+        &user.id,
+    )
+    .await?
+    .ok_or(TrashEntryNotFoundError)?;
+
+    let original_path = StoragePath::new(
+        user_storage.clone(),
+        PathBuf::from(&trash_entry.original_path),
+    )?;
+    let destination_path = find_available_storage_path(original_path).await?;
+
+    // Checksums left behind at the free destination belong to files that no longer exist.
+    repository::file_checksum::delete_all_by_user_id_at_or_below_item_path(
+        &mut *transaction,
+        &user.id,
+        &destination_path.path(),
+    )
+    .await?;
+
+    repository::file_version::restore_all_by_trash_entry_id(
+        &mut *transaction,
+        &trash_entry.id,
+        &trash_entry.original_path,
+        &destination_path.path(),
+    )
+    .await?;
+
+    repository::file_checksum::restore_all_by_trash_entry_id(
+        &mut *transaction,
+        &trash_entry.id,
+        &trash_entry.original_path,
+        &destination_path.path(),
+    )
+    .await?;
+
+    repository::trash_entry::delete_by_id(&mut *transaction, &trash_entry.id).await?;
+    restore_from_trash(&trash_entry.id.to_string(), &destination_path).await?;
+
+    let trash_entries =
+        repository::trash_entry::find_all_by_user_id(&mut *transaction, &user.id).await?;
+
+    transaction.commit().await?;
+
+    Ok(RestoreTrashEntryResult {
+        restored_path: destination_path.path(),
+        trash_entries: build_trash_entry_listings(&user_storage, trash_entries).await?,
+    })
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use bitsync_database::entity::TrashEntry;
+use bitsync_storage::{
+    operation::{error::MetadataError, trash::read_trash_item},
+    storage_item::StorageItemKind,
+    user_storage::UserStorage,
+};
+use uuid::Uuid;
+
+pub struct TrashEntryListing {
+    pub id: Uuid,
+    pub name: String,
+    pub original_path: String,
+    pub deleted_at: time::OffsetDateTime,
+    pub kind: StorageItemKind,
+    pub size: u64,
+}
+
+/// Pairs trash entries with their trashed items. Entries whose item is gone are skipped.
+pub(crate) async fn build_trash_entry_listings(
+    user_storage: &UserStorage,
+    trash_entries: Vec<TrashEntry>,
+) -> Result<Vec<TrashEntryListing>, MetadataError> {
+    let mut listings = Vec::with_capacity(trash_entries.len());
+
+    for trash_entry in trash_entries {
+        let trash_item = match read_trash_item(user_storage, &trash_entry.id.to_string()).await {
+            Ok(trash_item) => trash_item,
+            Err(error) if error.source.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => Err(error)?,
+        };
+
+        let name = PathBuf::from(&trash_entry.original_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        listings.push(TrashEntryListing {
+            id: trash_entry.id,
+            name,
+            original_path: trash_entry.original_path,
+            deleted_at: trash_entry.deleted_at,
+            kind: trash_item.kind,
+            size: trash_item.size,
+        });
+    }
+
+    Ok(listings)
+}
@@ -0,0 +1,6 @@
+pub mod append_upload_session_chunk;
+pub mod create_upload_session;
+pub mod delete_upload_session;
+pub mod purge_stale_upload_sessions;
+pub mod read_upload_session;
+pub mod shared;
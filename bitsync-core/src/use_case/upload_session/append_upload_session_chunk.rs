@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::upload::{WriteStagedUploadChunkError, write_staged_upload_chunk},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use futures::TryStreamExt;
+use tokio_util::io::StreamReader;
+use uuid::Uuid;
+
+use crate::use_case::user_files::shared::{UploadPolicy, effective_storage_quota};
+
+use super::shared::{
+    FinalizeUploadSessionError, UploadSessionBusyError, UploadSessionNotFoundError,
+    finalize_upload_session,
+};
+
+/// How long a session stays locked for a chunk without the lock being extended. A server that
+/// stops while appending leaves the session locked for at most this long.
+const UPLOAD_SESSION_LOCK_DURATION: Duration = Duration::from_secs(60);
+
+/// How often the lock is extended while a chunk is being received.
+const UPLOAD_SESSION_LOCK_EXTENSION_INTERVAL: Duration = Duration::from_secs(20);
+
+pub struct UploadSessionChunkResult {
+    pub upload_offset: u64,
+    pub is_complete: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the upload offset {expected_offset} does not match the offset of the request")]
+pub struct UploadOffsetMismatchError {
+    pub expected_offset: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to append a chunk to an upload session")]
+pub enum AppendUploadSessionChunkError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UploadSessionNotFound(#[from] UploadSessionNotFoundError),
+    UploadSessionBusy(#[from] UploadSessionBusyError),
+    UploadOffsetMismatch(#[from] UploadOffsetMismatchError),
+    WriteStagedUploadChunk(#[from] WriteStagedUploadChunkError),
+    FinalizeUploadSession(#[from] FinalizeUploadSessionError),
+}
+
+pub async fn append_upload_session_chunk<S, B, E>(
+    database: &Database,
+    storage_root: &StorageRoot,
+    upload_policy: &UploadPolicy,
+    user: &User,
+    upload_session_id: &Uuid,
+    upload_offset: u64,
+    chunk_stream: S,
+) -> Result<UploadSessionChunkResult, AppendUploadSessionChunkError>
+where
+    S: futures::Stream<Item = Result<B, E>>,
+    B: bytes::Buf,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    // the lock keeps concurrent requests for the same session from writing to the staged file at
+    // the same time, without holding a database connection while the chunk is received
+    let Some(upload_session) = repository::upload_session::lock_by_id_and_user_id(
+        &mut *database.acquire_connection().await?,
+        upload_session_id,
+        &user.id,
+        &(time::OffsetDateTime::now_utc() + UPLOAD_SESSION_LOCK_DURATION),
+    )
+    .await?
+    else {
+        repository::upload_session::find_by_id_and_user_id(
+            &mut *database.acquire_connection().await?,
+            upload_session_id,
+            &user.id,
+        )
+        .await?
+        .ok_or(UploadSessionNotFoundError)?;
+
+        return Err(UploadSessionBusyError)?;
+    };
+
+    let current_offset = upload_session.upload_offset as u64;
+
+    if upload_offset != current_offset {
+        repository::upload_session::unlock(
+            &mut *database.acquire_connection().await?,
+            &upload_session.id,
+        )
+        .await?;
+
+        return Err(UploadOffsetMismatchError {
+            expected_offset: current_offset,
+        })?;
+    }
+
+    let chunk_stream_with_io_error = chunk_stream.map_err(|error| std::io::Error::other(error));
+    let chunk_stream_reader = StreamReader::new(chunk_stream_with_io_error);
+
+    let staged_upload_name = upload_session.id.to_string();
+    let write = write_staged_upload_chunk(
+        &user_storage,
+        &staged_upload_name,
+        current_offset,
+        chunk_stream_reader,
+        upload_session.upload_length as u64 - current_offset,
+    );
+    tokio::pin!(write);
+
+    let mut lock_extension = tokio::time::interval_at(
+        tokio::time::Instant::now() + UPLOAD_SESSION_LOCK_EXTENSION_INTERVAL,
+        UPLOAD_SESSION_LOCK_EXTENSION_INTERVAL,
+    );
+
+    let write_result = loop {
+        tokio::select! {
+            write_result = &mut write => break write_result,
+            _ = lock_extension.tick() => {
+                repository::upload_session::extend_lock(
+                    &mut *database.acquire_connection().await?,
+                    &upload_session.id,
+                    &(time::OffsetDateTime::now_utc() + UPLOAD_SESSION_LOCK_DURATION),
+                )
+                .await?;
+            }
+        }
+    };
+
+    // data received before the connection dropped is kept so the client can resume from there
+    let written_bytes = match &write_result {
+        Ok(written_bytes) | Err(WriteStagedUploadChunkError::StreamRead { written_bytes, .. }) => {
+            *written_bytes
+        }
+        Err(..) => 0,
+    };
+
+    let upload_session = repository::upload_session::advance_offset(
+        &mut *database.acquire_connection().await?,
+        &upload_session.id,
+        current_offset as i64,
+        (current_offset + written_bytes) as i64,
+    )
+    .await?
+    .ok_or(UploadOffsetMismatchError {
+        expected_offset: current_offset,
+    })?;
+
+    write_result?;
+
+    let is_complete = upload_session.upload_offset == upload_session.upload_length;
+
+    if is_complete {
+        finalize_upload_session(
+            database,
+            &user_storage,
+            &upload_session,
+            effective_storage_quota(user.storage_quota, upload_policy.default_storage_quota),
+            upload_policy.max_file_versions,
+        )
+        .await?;
+    }
+
+    Ok(UploadSessionChunkResult {
+        upload_offset: upload_session.upload_offset as u64,
+        is_complete,
+    })
+}
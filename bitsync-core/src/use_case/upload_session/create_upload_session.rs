@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::{UploadSession, User},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        read::{CalculateStorageUsageError, ReadStorageItemError, read_storage_item},
+        upload::{CreateStagedUploadError, create_staged_upload},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::StorageItemKind,
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+    validation::{PathIsJustFileNameValidationError, validate_path_is_just_file_name},
+};
+
+use crate::use_case::user_files::shared::{
+    UploadPolicy, effective_storage_quota, remaining_storage_quota,
+};
+
+use super::shared::{
+    FinalizeUploadSessionError, StorageQuotaExceededError, finalize_upload_session,
+};
+
+/// How many unfinished upload sessions a single user can have at the same time.
+const MAX_UPLOAD_SESSIONS_PER_USER: usize = 16;
+
+pub struct UploadSessionCreation<'a> {
+    pub directory_path: &'a str,
+    pub file_name: &'a str,
+    pub upload_length: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the upload destination is not a directory")]
+pub struct DestinationNotADirectoryError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the user already has the maximum number of unfinished upload sessions")]
+pub struct UploadSessionLimitError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create upload session")]
+pub enum CreateUploadSessionError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    InvalidFileName(#[from] PathIsJustFileNameValidationError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    DestinationNotADirectory(#[from] DestinationNotADirectoryError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+    StorageQuotaExceeded(#[from] StorageQuotaExceededError),
+    UploadSessionLimit(#[from] UploadSessionLimitError),
+    TransactionBegin(#[from] TransactionBeginError),
+    Query(#[from] QueryError),
+    TransactionCommit(#[from] TransactionCommitError),
+    CreateStagedUpload(#[from] CreateStagedUploadError),
+    FinalizeUploadSession(#[from] FinalizeUploadSessionError),
+}
+
+pub async fn create_upload_session(
+    database: &Database,
+    storage_root: &StorageRoot,
+    upload_policy: &UploadPolicy,
+    user: &User,
+    UploadSessionCreation {
+        directory_path,
+        file_name,
+        upload_length,
+    }: UploadSessionCreation<'_>,
+) -> Result<UploadSession, CreateUploadSessionError> {
+    validate_path_is_just_file_name(file_name)?;
+
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let directory_storage_path =
+        StoragePath::new(user_storage.clone(), PathBuf::from(directory_path))?;
+
+    if read_storage_item(&directory_storage_path).await?.kind != StorageItemKind::Directory {
+        return Err(DestinationNotADirectoryError)?;
+    }
+
+    let destination_storage_path = StoragePath::new(
+        user_storage.clone(),
+        directory_storage_path.scoped_path.join(file_name),
+    )?;
+
+    let storage_quota =
+        effective_storage_quota(user.storage_quota, upload_policy.default_storage_quota);
+
+    // locking the user keeps sessions created at the same time from reserving the same storage
+    let mut transaction = database.begin_transaction().await?;
+
+    repository::user::lock_by_id(&mut *transaction, &user.id).await?;
+
+    let open_upload_sessions =
+        repository::upload_session::find_all_by_user_id(&mut *transaction, &user.id).await?;
+
+    if open_upload_sessions.len() >= MAX_UPLOAD_SESSIONS_PER_USER {
+        return Err(UploadSessionLimitError)?;
+    }
+
+    // staged data is not part of the storage usage, so every open session reserves its length
+    let reserved_storage = open_upload_sessions
+        .iter()
+        .map(|upload_session| upload_session.upload_length as u64)
+        .sum::<u64>();
+    let remaining_storage_quota = remaining_storage_quota(
+        &destination_storage_path,
+        storage_quota,
+        upload_policy.max_file_versions,
+    )
+    .await?;
+
+    if remaining_storage_quota.is_some_and(|remaining_storage_quota| {
+        reserved_storage + upload_length > remaining_storage_quota
+    }) {
+        return Err(StorageQuotaExceededError)?;
+    }
+
+    let upload_session = repository::upload_session::create(
+        &mut *transaction,
+        &user.id,
+        &destination_storage_path.path(),
+        upload_length as i64,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    create_staged_upload(&user_storage, &upload_session.id.to_string()).await?;
+
+    // there is no data to wait for, so empty files are stored right away
+    if upload_length == 0 {
+        finalize_upload_session(
+            database,
+            &user_storage,
+            &upload_session,
+            storage_quota,
+            upload_policy.max_file_versions,
+        )
+        .await?;
+    }
+
+    Ok(upload_session)
+}
@@ -0,0 +1,59 @@
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::upload::{DeleteStagedUploadError, delete_staged_upload},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use uuid::Uuid;
+
+use super::shared::{UploadSessionBusyError, UploadSessionNotFoundError};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete upload session")]
+pub enum DeleteUploadSessionError {
+    TransactionBegin(#[from] TransactionBeginError),
+    Query(#[from] QueryError),
+    UploadSessionNotFound(#[from] UploadSessionNotFoundError),
+    UploadSessionBusy(#[from] UploadSessionBusyError),
+    DeleteStagedUpload(#[from] DeleteStagedUploadError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+pub async fn delete_upload_session(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user: &User,
+    upload_session_id: &Uuid,
+) -> Result<(), DeleteUploadSessionError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let mut transaction = database.begin_transaction().await?;
+
+    let upload_session = repository::upload_session::find_by_id_and_user_id(
+        &mut *transaction,
+        upload_session_id,
+        &user.id,
+    )
+    .await?
+    .ok_or(UploadSessionNotFoundError)?;
+
+    // the staged file is still being written while a chunk is appended
+    if !repository::upload_session::delete_unlocked_by_id(&mut *transaction, &upload_session.id)
+        .await?
+    {
+        return Err(UploadSessionBusyError)?;
+    }
+
+    delete_staged_upload(&user_storage, &upload_session.id.to_string()).await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::upload::{DeleteStagedUploadError, delete_staged_upload},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to purge stale upload sessions")]
+pub enum PurgeStaleUploadSessionsError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    DeleteStagedUpload(#[from] DeleteStagedUploadError),
+}
+
+/// Deletes upload sessions that have not received data for longer than `max_idle_time` and
+/// returns the number of purged sessions.
+pub async fn purge_stale_upload_sessions(
+    database: &Database,
+    storage_root: &StorageRoot,
+    max_idle_time: time::Duration,
+) -> Result<usize, PurgeStaleUploadSessionsError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let updated_before = time::OffsetDateTime::now_utc() - max_idle_time;
+    let stale_upload_sessions =
+        repository::upload_session::find_all_updated_before(&mut *connection, &updated_before)
+            .await?;
+
+    for upload_session in &stale_upload_sessions {
+        let user_storage = UserStorage {
+            user_id: upload_session.user_id,
+            storage_root: storage_root.clone(),
+        };
+
+        delete_staged_upload(&user_storage, &upload_session.id.to_string()).await?;
+        repository::upload_session::delete_by_id(&mut *connection, &upload_session.id).await?;
+    }
+
+    Ok(stale_upload_sessions.len())
+}
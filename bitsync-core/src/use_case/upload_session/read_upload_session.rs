@@ -0,0 +1,32 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{UploadSession, User},
+    repository::{self, QueryError},
+};
+use uuid::Uuid;
+
+use super::shared::UploadSessionNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to read upload session")]
+pub enum ReadUploadSessionError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UploadSessionNotFound(#[from] UploadSessionNotFoundError),
+}
+
+pub async fn read_upload_session(
+    database: &Database,
+    user: &User,
+    upload_session_id: &Uuid,
+) -> Result<UploadSession, ReadUploadSessionError> {
+    let mut connection = database.acquire_connection().await?;
+
+    Ok(repository::upload_session::find_by_id_and_user_id(
+        &mut *connection,
+        upload_session_id,
+        &user.id,
+    )
+    .await?
+    .ok_or(UploadSessionNotFoundError)?)
+}
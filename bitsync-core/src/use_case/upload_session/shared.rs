@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::UploadSession,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        read::CalculateStorageUsageError,
+        upload::{FinalizeStagedUploadError, finalize_staged_upload},
+    },
+    storage_path::{StoragePath, StoragePathError},
+    user_storage::UserStorage,
+};
+
+use crate::use_case::{
+    file_version::shared::{
+        DeleteFileVersionsError, KeepPreviousFileVersionError, keep_previous_file_version,
+        prune_file_versions,
+    },
+    user_files::shared::remaining_storage_quota,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("the upload session does not exist")]
+pub struct UploadSessionNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("another chunk is currently being appended to the upload session")]
+pub struct UploadSessionBusyError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the upload exceeds the storage quota")]
+pub struct StorageQuotaExceededError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to finalize upload session")]
+pub enum FinalizeUploadSessionError {
+    StoragePath(#[from] StoragePathError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+    StorageQuotaExceeded(#[from] StorageQuotaExceededError),
+    TransactionBegin(#[from] TransactionBeginError),
+    KeepPreviousFileVersion(#[from] KeepPreviousFileVersionError),
+    FinalizeStagedUpload(#[from] FinalizeStagedUploadError),
+    Query(#[from] QueryError),
+    DeleteFileVersions(#[from] DeleteFileVersionsError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+/// Moves the data of a completed upload session into its target path in a single rename and
+/// removes the session.
+pub(crate) async fn finalize_upload_session(
+    database: &Database,
+    user_storage: &UserStorage,
+    upload_session: &UploadSession,
+    storage_quota: Option<u64>,
+    max_file_versions: usize,
+) -> Result<StoragePath, FinalizeUploadSessionError> {
+    let path = StoragePath::new(
+        user_storage.clone(),
+        PathBuf::from(&upload_session.item_path),
+    )?;
+
+    // other uploads may have used up the quota since the session was created
+    let remaining_storage_quota =
+        remaining_storage_quota(&path, storage_quota, max_file_versions).await?;
+
+    if remaining_storage_quota.is_some_and(|remaining_storage_quota| {
+        upload_session.upload_length as u64 > remaining_storage_quota
+    }) {
+        return Err(StorageQuotaExceededError)?;
+    }
+
+    let mut transaction = database.begin_transaction().await?;
+
+    keep_previous_file_version(&mut transaction, &path, max_file_versions).await?;
+    let checksum = finalize_staged_upload(&upload_session.id.to_string(), &path).await?;
+    repository::file_checksum::upsert(
+        &mut *transaction,
+        &user_storage.user_id,
+        &path.path(),
+        &checksum,
+    )
+    .await?;
+    repository::upload_session::delete_by_id(&mut *transaction, &upload_session.id).await?;
+    prune_file_versions(
+        &mut transaction,
+        user_storage,
+        &path.path(),
+        max_file_versions,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(path)
+}
@@ -4,5 +4,6 @@ pub mod list_users;
 pub mod make_admin;
 pub mod reset_user_totp;
 pub mod revoke_admin;
+pub mod set_user_storage_quota;
 pub mod suspend_user;
 pub mod unsuspend_user;
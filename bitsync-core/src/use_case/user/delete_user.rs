@@ -1,5 +1,3 @@
-use std::path::Path;
-
 use bitsync_database::{
     database::{ConnectionAcquisitionError, Database},
     entity::User,
@@ -7,6 +5,7 @@ use bitsync_database::{
 };
 use bitsync_storage::{
     operation::write::{DeleteUserStorageError, delete_user_storage},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 use uuid::Uuid;
@@ -21,13 +20,13 @@ pub enum DeleteUserError {
 
 pub async fn delete_user(
     database: &Database,
-    storage_root_dir: &Path,
+    storage_root: &StorageRoot,
     user_id: &Uuid,
     current_user_id: &Uuid,
 ) -> Result<Vec<User>, DeleteUserError> {
     let user_storage = UserStorage {
         user_id: *user_id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     delete_user_storage(&user_storage).await?;
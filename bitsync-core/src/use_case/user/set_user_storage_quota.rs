@@ -0,0 +1,38 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::User,
+    repository::{self, QueryError},
+};
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the storage quota is too large")]
+pub struct StorageQuotaTooLargeError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to set user storage quota")]
+pub enum SetUserStorageQuotaError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    StorageQuotaTooLarge(#[from] StorageQuotaTooLargeError),
+}
+
+pub async fn set_user_storage_quota(
+    database: &Database,
+    user_id: &Uuid,
+    storage_quota: Option<u64>,
+    current_user_id: &Uuid,
+) -> Result<Vec<User>, SetUserStorageQuotaError> {
+    let storage_quota = storage_quota
+        .map(i64::try_from)
+        .transpose()
+        .map_err(|_| StorageQuotaTooLargeError)?;
+
+    let mut connection = database.acquire_connection().await?;
+
+    repository::user::set_storage_quota(&mut *connection, user_id, storage_quota).await?;
+
+    let users = repository::user::find_all_except(&mut *connection, current_user_id).await?;
+
+    Ok(users)
+}
@@ -1,8 +1,19 @@
+pub mod batch_copy_user_files;
+pub mod batch_delete_user_files;
+pub mod batch_download_user_files;
+pub mod batch_move_user_files;
+pub mod copy_user_file;
 pub mod create_directory;
 pub mod delete_user_file;
 pub mod download_user_file;
+pub mod extract_user_file;
+pub mod list_user_directory;
 pub mod move_user_file;
 pub mod read_user_file_item;
+pub mod rename_user_file;
 pub mod search_user_files;
 pub mod shared;
+pub mod stat_user_file;
+pub mod thumbnail_user_file;
 pub mod upload_user_file;
+pub mod view_user_file;
@@ -0,0 +1,106 @@
+use std::path::{Path, PathBuf};
+
+use bitsync_database::{database::Database, entity::User};
+use bitsync_storage::{
+    operation::{
+        read::{ReadStorageItemError, read_storage_item},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::{StorageItem, StorageItemKind},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+use super::{
+    batch_move_user_files::DestinationNotADirectoryError,
+    copy_user_file::{CopyConflictPolicy, UserFileCopyError, UserFileCopyOutcome, copy_user_file},
+    shared::{BatchItemResult, ReadSortedDirContentsError, read_sorted_dir_contents},
+};
+
+pub struct BatchUserFileCopy<'a> {
+    pub paths: &'a [String],
+    pub destination_directory_path: &'a str,
+    pub conflict_policy: CopyConflictPolicy,
+}
+
+pub struct BatchUserFileCopyResult {
+    pub item_results: Vec<BatchItemResult<UserFileCopyOutcome, UserFileCopyError>>,
+    pub dir_contents: Vec<StorageItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to copy a batch of user files")]
+pub enum BatchUserFileCopyError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    DestinationNotADirectory(#[from] DestinationNotADirectoryError),
+    ReadSortedDirContents(#[from] ReadSortedDirContentsError),
+}
+
+/// Copies each item of `paths` into the existing directory at `destination_directory_path`,
+/// handling existing items according to `conflict_policy`. A failing item does not stop the
+/// others, the returned directory contents are the ones of `directory_path` afterwards.
+pub async fn batch_copy_user_files(
+    database: &Database,
+    storage_root: &StorageRoot,
+    default_storage_quota: Option<u64>,
+    directory_path: &str,
+    user: &User,
+    BatchUserFileCopy {
+        paths,
+        destination_directory_path,
+        conflict_policy,
+    }: BatchUserFileCopy<'_>,
+) -> Result<BatchUserFileCopyResult, BatchUserFileCopyError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let destination_directory = StoragePath::new(
+        user_storage.clone(),
+        PathBuf::from(destination_directory_path),
+    )?;
+
+    if read_storage_item(&destination_directory).await?.kind != StorageItemKind::Directory {
+        Err(DestinationNotADirectoryError)?;
+    }
+
+    let mut item_results = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        // the root directory has no name and ends up as a copy into itself
+        let destination_path = destination_directory
+            .scoped_path
+            .join(Path::new(path).file_name().unwrap_or_default());
+
+        let result = copy_user_file(
+            database,
+            storage_root,
+            default_storage_quota,
+            path,
+            &destination_path.to_string_lossy(),
+            conflict_policy,
+            user,
+        )
+        .await
+        .map(|copy_result| copy_result.outcome);
+
+        item_results.push(BatchItemResult {
+            path: path.clone(),
+            result,
+        });
+    }
+
+    let dir_contents =
+        read_sorted_dir_contents(&user_storage, PathBuf::from(directory_path)).await?;
+
+    Ok(BatchUserFileCopyResult {
+        item_results,
+        dir_contents,
+    })
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use bitsync_database::{database::Database, entity::User};
+use bitsync_storage::{
+    operation::write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    storage_item::StorageItem,
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+use super::{
+    delete_user_file::{UserFileDeletionError, delete_user_file},
+    shared::{BatchItemResult, ReadSortedDirContentsError, read_sorted_dir_contents},
+};
+
+pub struct BatchUserFileDeletionResult {
+    pub item_results: Vec<BatchItemResult<(), UserFileDeletionError>>,
+    pub dir_contents: Vec<StorageItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete a batch of user files")]
+pub enum BatchUserFileDeletionError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    ReadSortedDirContents(#[from] ReadSortedDirContentsError),
+}
+
+/// Moves each item of `paths` to the trash. A failing item does not stop the others, the returned
+/// directory contents are the ones of `directory_path` afterwards.
+pub async fn batch_delete_user_files(
+    database: &Database,
+    storage_root: &StorageRoot,
+    directory_path: &str,
+    paths: &[String],
+    user: &User,
+) -> Result<BatchUserFileDeletionResult, BatchUserFileDeletionError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let mut item_results = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let result = delete_user_file(database, storage_root, path, user)
+            .await
+            .map(|_| ());
+
+        item_results.push(BatchItemResult {
+            path: path.clone(),
+            result,
+        });
+    }
+
+    let dir_contents =
+        read_sorted_dir_contents(&user_storage, PathBuf::from(directory_path)).await?;
+
+    Ok(BatchUserFileDeletionResult {
+        item_results,
+        dir_contents,
+    })
+}
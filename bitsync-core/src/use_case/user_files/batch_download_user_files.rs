@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use bitsync_database::entity::User;
+use bitsync_storage::{
+    operation::{
+        read::{ReadStorageItemError, read_storage_item},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use tracing::Level;
+
+use super::{
+    download_user_file::{UserFileDownloadResult, directory_zipping},
+    shared::{AsyncStorageItemRead, user_root_directory_name},
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("an error occurred during a batch download of user files")]
+pub enum BatchUserFileDownloadError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+}
+
+/// Streams a zip of the items of `paths`, named relative to `directory_path`. The zip is named
+/// after that directory.
+pub async fn batch_download_user_files(
+    storage_root: &StorageRoot,
+    directory_path: &str,
+    paths: &[String],
+    user: &User,
+) -> Result<UserFileDownloadResult, BatchUserFileDownloadError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let directory = StoragePath::new(user_storage.clone(), PathBuf::from(directory_path))?;
+
+    let mut storage_items = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let storage_path = StoragePath::new(user_storage.clone(), PathBuf::from(path))?;
+        storage_items.push(read_storage_item(&storage_path).await?);
+    }
+
+    let (write_stream, read_stream) = tokio::io::duplex(4096);
+    let base_path = directory.scoped_path.clone();
+
+    tokio::spawn(async move {
+        match directory_zipping::write_zipped_storage_items_to_stream(
+            write_stream,
+            &storage_items,
+            &base_path,
+            async_zip::Compression::Stored,
+        )
+        .await
+        {
+            Ok(()) => {}
+            Err(directory_zip_error) => {
+                tracing::event!(
+                    Level::ERROR,
+                    message = "Batch zipping failed",
+                    error_trace = directory_zip_error.to_string(),
+                )
+            }
+        };
+    });
+
+    let mut zip_path = directory.scoped_path.clone();
+
+    if zip_path.file_stem().is_none() {
+        zip_path.set_file_name(user_root_directory_name(&user.username));
+    }
+
+    zip_path.set_extension("zip");
+
+    let fake_zip_path = StoragePath::new(user_storage, zip_path)?;
+    let mime = mime_guess::from_path(&fake_zip_path.scoped_path).first_or_octet_stream();
+
+    Ok(UserFileDownloadResult {
+        file: AsyncStorageItemRead::Directory(read_stream),
+        mime,
+        path: fake_zip_path,
+        checksum: None,
+        size: None,
+        modified_at: None,
+    })
+}
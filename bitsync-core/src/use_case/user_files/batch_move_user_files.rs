@@ -0,0 +1,101 @@
+use std::path::{Path, PathBuf};
+
+use bitsync_database::{database::Database, entity::User};
+use bitsync_storage::{
+    operation::{
+        read::{ReadStorageItemError, read_storage_item},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::{StorageItem, StorageItemKind},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+use super::{
+    move_user_file::{UserFileMoveError, move_user_file},
+    shared::{
+        BatchItemResult, ConflictPolicy, ReadSortedDirContentsError, read_sorted_dir_contents,
+    },
+};
+
+pub struct BatchUserFileMoveResult {
+    pub item_results: Vec<BatchItemResult<(), UserFileMoveError>>,
+    pub dir_contents: Vec<StorageItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to move a batch of user files")]
+pub enum BatchUserFileMoveError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    DestinationNotADirectory(#[from] DestinationNotADirectoryError),
+    ReadSortedDirContents(#[from] ReadSortedDirContentsError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the destination is not a directory")]
+pub struct DestinationNotADirectoryError;
+
+/// Moves each item of `paths` into the existing directory at `destination_directory_path`,
+/// resolving name collisions according to `conflict_policy`. A failing item does not stop the
+/// others, the returned directory contents are the ones of `directory_path` afterwards.
+pub async fn batch_move_user_files(
+    database: &Database,
+    storage_root: &StorageRoot,
+    directory_path: &str,
+    paths: &[String],
+    destination_directory_path: &str,
+    conflict_policy: ConflictPolicy,
+    user: &User,
+) -> Result<BatchUserFileMoveResult, BatchUserFileMoveError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let destination_directory = StoragePath::new(
+        user_storage.clone(),
+        PathBuf::from(destination_directory_path),
+    )?;
+
+    if read_storage_item(&destination_directory).await?.kind != StorageItemKind::Directory {
+        Err(DestinationNotADirectoryError)?;
+    }
+
+    let mut item_results = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        // the root directory has no name and ends up as a move into itself
+        let destination_path = destination_directory
+            .scoped_path
+            .join(Path::new(path).file_name().unwrap_or_default());
+
+        let result = move_user_file(
+            database,
+            storage_root,
+            path,
+            &destination_path.to_string_lossy(),
+            conflict_policy,
+            user,
+        )
+        .await
+        .map(|_| ());
+
+        item_results.push(BatchItemResult {
+            path: path.clone(),
+            result,
+        });
+    }
+
+    let dir_contents =
+        read_sorted_dir_contents(&user_storage, PathBuf::from(directory_path)).await?;
+
+    Ok(BatchUserFileMoveResult {
+        item_results,
+        dir_contents,
+    })
+}
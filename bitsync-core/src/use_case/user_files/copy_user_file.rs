@@ -0,0 +1,231 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    operation::{
+        error::MetadataError,
+        read::{
+            CalculateStorageUsageError, ReadDirContentsError, ReadStorageItemError,
+            calculate_storage_usage, item_exists, read_storage_item,
+        },
+        write::{
+            CopyItemError, EnsureUserStorageExistsError, copy_item, ensure_user_storage_exists,
+        },
+    },
+    storage_item::{StorageItem, StorageItemKind},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+use super::{
+    delete_user_file::{TrashStorageItemError, trash_storage_item},
+    move_user_file::DestinationSameAsSourceError,
+    shared::{
+        DestinationExistsError, FindAvailableStoragePathError, ReadSortedDirContentsError,
+        collect_all_storage_items, effective_storage_quota, find_available_storage_path,
+        read_sorted_dir_contents,
+    },
+};
+
+/// What to do when an item already exists at the copy destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyConflictPolicy {
+    /// Refuse to copy.
+    Fail,
+    /// Leave the existing item untouched and copy nothing.
+    Skip,
+    /// Move the existing item to the trash and replace it with the copy.
+    Overwrite,
+    /// Copy to the first free sibling named like `name (1).ext`.
+    AutoRename,
+}
+
+pub enum UserFileCopyOutcome {
+    Copied { destination_path: String },
+    Skipped,
+}
+
+pub struct UserFileCopyResult {
+    pub outcome: UserFileCopyOutcome,
+    pub dir_contents: Vec<StorageItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to copy a user's file")]
+pub enum UserFileCopyError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    ReadDirContents(#[from] ReadDirContentsError),
+    ReadSortedDirContents(#[from] ReadSortedDirContentsError),
+    Metadata(#[from] MetadataError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+    FindAvailableStoragePath(#[from] FindAvailableStoragePathError),
+    TrashStorageItem(#[from] TrashStorageItemError),
+    DestinationSameAsSource(#[from] DestinationSameAsSourceError),
+    DestinationInsideSource(#[from] DestinationInsideSourceError),
+    SourceInsideDestination(#[from] SourceInsideDestinationError),
+    DestinationExists(#[from] DestinationExistsError),
+    StorageQuotaExceeded(#[from] CopyStorageQuotaExceededError),
+    TransactionBegin(#[from] TransactionBeginError),
+    Query(#[from] QueryError),
+    CopyItem(#[from] CopyItemError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("a directory cannot be copied into itself")]
+pub struct DestinationInsideSourceError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("a directory containing the source cannot be replaced")]
+pub struct SourceInsideDestinationError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the copy exceeds the storage quota")]
+pub struct CopyStorageQuotaExceededError;
+
+/// Copies a file or directory. An existing item at the destination is handled according to
+/// `conflict_policy`. Recorded checksums are copied along, the version history and shares of the
+/// source are not.
+pub async fn copy_user_file(
+    database: &Database,
+    storage_root: &StorageRoot,
+    default_storage_quota: Option<u64>,
+    path_to_copy: &str,
+    copy_destination_path: &str,
+    conflict_policy: CopyConflictPolicy,
+    user: &User,
+) -> Result<UserFileCopyResult, UserFileCopyError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let mut scoped_path_to_copy = PathBuf::from(path_to_copy);
+
+    let storage_path_to_copy = StoragePath::new(user_storage.clone(), scoped_path_to_copy.clone())?;
+    let mut copy_destination_storage_path =
+        StoragePath::new(user_storage.clone(), PathBuf::from(copy_destination_path))?;
+
+    let is_destination_same_as_source =
+        storage_path_to_copy.scoped_path == copy_destination_storage_path.scoped_path;
+
+    // a renamed copy next to the source is a duplicate
+    if is_destination_same_as_source && conflict_policy != CopyConflictPolicy::AutoRename {
+        Err(DestinationSameAsSourceError)?;
+    }
+
+    if !is_destination_same_as_source
+        && copy_destination_storage_path
+            .scoped_path
+            .starts_with(&storage_path_to_copy.scoped_path)
+    {
+        Err(DestinationInsideSourceError)?;
+    }
+
+    let storage_item_to_copy = read_storage_item(&storage_path_to_copy).await?;
+
+    let mut is_destination_replaced = false;
+
+    if item_exists(&copy_destination_storage_path).await? {
+        match conflict_policy {
+            CopyConflictPolicy::Fail => Err(DestinationExistsError)?,
+            CopyConflictPolicy::Skip => {
+                scoped_path_to_copy.pop();
+
+                return Ok(UserFileCopyResult {
+                    outcome: UserFileCopyOutcome::Skipped,
+                    dir_contents: read_sorted_dir_contents(&user_storage, scoped_path_to_copy)
+                        .await?,
+                });
+            }
+            CopyConflictPolicy::Overwrite => {
+                if storage_path_to_copy
+                    .scoped_path
+                    .starts_with(&copy_destination_storage_path.scoped_path)
+                {
+                    Err(SourceInsideDestinationError)?;
+                }
+
+                is_destination_replaced = true;
+            }
+            CopyConflictPolicy::AutoRename => {
+                copy_destination_storage_path =
+                    find_available_storage_path(copy_destination_storage_path).await?;
+            }
+        }
+    }
+
+    if let Some(quota_bytes) = effective_storage_quota(user.storage_quota, default_storage_quota) {
+        let copied_bytes = total_file_size(&storage_item_to_copy).await?;
+        let replaced_bytes = match is_destination_replaced {
+            true => {
+                total_file_size(&read_storage_item(&copy_destination_storage_path).await?).await?
+            }
+            false => 0,
+        };
+
+        if (calculate_storage_usage(&user_storage).await? + copied_bytes)
+            .saturating_sub(replaced_bytes)
+            > quota_bytes
+        {
+            Err(CopyStorageQuotaExceededError)?;
+        }
+    }
+
+    if is_destination_replaced {
+        let mut transaction = database.begin_transaction().await?;
+
+        trash_storage_item(&mut transaction, &copy_destination_storage_path).await?;
+
+        transaction.commit().await?;
+    }
+
+    let mut transaction = database.begin_transaction().await?;
+
+    repository::file_checksum::copy_item_paths_at_or_below(
+        &mut *transaction,
+        &user.id,
+        &storage_path_to_copy.path(),
+        &copy_destination_storage_path.path(),
+    )
+    .await?;
+
+    copy_item(&storage_path_to_copy, &copy_destination_storage_path).await?;
+
+    transaction.commit().await?;
+
+    scoped_path_to_copy.pop();
+
+    Ok(UserFileCopyResult {
+        outcome: UserFileCopyOutcome::Copied {
+            destination_path: copy_destination_storage_path.path(),
+        },
+        dir_contents: read_sorted_dir_contents(&user_storage, scoped_path_to_copy).await?,
+    })
+}
+
+async fn total_file_size(storage_item: &StorageItem) -> Result<u64, ReadDirContentsError> {
+    match storage_item.kind {
+        StorageItemKind::File => Ok(storage_item.size),
+        StorageItemKind::Directory => {
+            let mut items = Vec::new();
+            collect_all_storage_items(&storage_item.path, &mut items).await?;
+
+            Ok(items
+                .iter()
+                .filter(|item| item.kind == StorageItemKind::File)
+                .map(|item| item.size)
+                .sum())
+        }
+    }
+}
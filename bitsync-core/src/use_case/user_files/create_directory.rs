@@ -1,6 +1,9 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-use bitsync_database::entity::User;
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+};
 use bitsync_storage::{
     operation::{
         read::{ReadDirContentsError, ReadStorageItemError, read_dir_contents},
@@ -11,10 +14,16 @@ use bitsync_storage::{
     },
     storage_item::StorageItem,
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
     validation::{PathIsJustFileNameValidationError, validate_path_is_just_file_name},
 };
 
+use super::{
+    delete_user_file::{TrashStorageItemError, trash_storage_item},
+    shared::{ConflictPolicy, ResolveDestinationConflictError, resolve_destination_conflict},
+};
+
 pub struct DirectoryCreationResult {
     pub dir_contents: Vec<StorageItem>,
 }
@@ -29,16 +38,24 @@ pub enum UserFileDirecoryCreationError {
     CreateDirectory(#[from] CreateDirectoryError),
     ReadDirContents(#[from] ReadDirContentsError),
     EmptyPath(#[from] EmptyPathError),
+    ResolveDestinationConflict(#[from] ResolveDestinationConflictError),
+    TransactionBegin(#[from] TransactionBeginError),
+    TrashStorageItem(#[from] TrashStorageItemError),
+    TransactionCommit(#[from] TransactionCommitError),
 }
 
 #[derive(thiserror::Error, Debug)]
 #[error("path must not be empty")]
 pub struct EmptyPathError;
 
+/// Creates an empty directory. An existing item with the same name is handled according to
+/// `conflict_policy`, a replaced item goes to the trash.
 pub async fn create_direcory(
-    storage_root_dir: &Path,
+    database: &Database,
+    storage_root: &StorageRoot,
     parent_directory: &str,
     direcory_name: &str,
+    conflict_policy: ConflictPolicy,
     user: &User,
 ) -> Result<DirectoryCreationResult, UserFileDirecoryCreationError> {
     if direcory_name.is_empty() {
@@ -49,7 +66,7 @@ pub async fn create_direcory(
 
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
@@ -57,10 +74,21 @@ pub async fn create_direcory(
     let mut directory_to_create = PathBuf::from(parent_directory);
     directory_to_create.push(direcory_name);
 
-    let storage_path_to_create =
-        StoragePath::new(user_storage.clone(), directory_to_create.clone())?;
+    let resolved_destination = resolve_destination_conflict(
+        StoragePath::new(user_storage.clone(), directory_to_create.clone())?,
+        conflict_policy,
+    )
+    .await?;
+
+    if resolved_destination.is_existing_item_replaced {
+        let mut transaction = database.begin_transaction().await?;
+
+        trash_storage_item(&mut transaction, &resolved_destination.path).await?;
+
+        transaction.commit().await?;
+    }
 
-    create_directory(&storage_path_to_create).await?;
+    create_directory(&resolved_destination.path).await?;
 
     directory_to_create.pop();
 
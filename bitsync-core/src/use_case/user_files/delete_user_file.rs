@@ -1,16 +1,23 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-use bitsync_database::entity::User;
+use bitsync_database::{
+    database::{
+        Database, TransactionBeginError,
+        transaction::{Transaction, TransactionCommitError},
+    },
+    entity::User,
+    repository::{self, QueryError},
+};
 use bitsync_storage::{
     operation::{
         read::{ReadDirContentsError, ReadStorageItemError, read_dir_contents, read_storage_item},
-        write::{
-            DeleteDirectoryError, DeleteFileError, EnsureUserStorageExistsError, delete_directory,
-            delete_file, ensure_user_storage_exists,
-        },
+        thumbnail::remove_thumbnails,
+        trash::{MoveToTrashError, move_to_trash},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
     },
-    storage_item::{StorageItem, StorageItemKind},
+    storage_item::StorageItem,
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 
@@ -24,19 +31,68 @@ pub enum UserFileDeletionError {
     EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
     StoragePath(#[from] StoragePathError),
     ReadStorageItem(#[from] ReadStorageItemError),
-    DeleteDirectory(#[from] DeleteDirectoryError),
-    DeleteFile(#[from] DeleteFileError),
+    TrashStorageItem(#[from] TrashStorageItemError),
     ReadDirContents(#[from] ReadDirContentsError),
+    TransactionBegin(#[from] TransactionBeginError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to move an item to the trash")]
+pub enum TrashStorageItemError {
+    MoveToTrash(#[from] MoveToTrashError),
+    Query(#[from] QueryError),
+}
+
+/// Moves the item at `path` to the trash together with its versions and checksums, so they are
+/// restored with it. Its shares and thumbnails are dropped.
+pub(crate) async fn trash_storage_item(
+    transaction: &mut Transaction,
+    path: &StoragePath,
+) -> Result<(), TrashStorageItemError> {
+    let user_id = path.storage.user_id;
+
+    repository::user_share::delete_all_by_user_id_at_or_below_item_path(
+        &mut **transaction,
+        &user_id,
+        &path.path(),
+    )
+    .await?;
+
+    let trash_entry =
+        repository::trash_entry::create(&mut **transaction, &user_id, &path.path()).await?;
+
+    repository::file_version::trash_all_by_user_id_at_or_below_item_path(
+        &mut **transaction,
+        &user_id,
+        &path.path(),
+        &trash_entry.id,
+    )
+    .await?;
+
+    repository::file_checksum::trash_all_by_user_id_at_or_below_item_path(
+        &mut **transaction,
+        &user_id,
+        &path.path(),
+        &trash_entry.id,
+    )
+    .await?;
+
+    move_to_trash(path, &trash_entry.id.to_string()).await?;
+    remove_thumbnails(path).await;
+
+    Ok(())
 }
 
 pub async fn delete_user_file(
-    storage_root_dir: &Path,
+    database: &Database,
+    storage_root: &StorageRoot,
     path: &str,
     user: &User,
 ) -> Result<UserFileDeletionResult, UserFileDeletionError> {
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
@@ -44,16 +100,13 @@ pub async fn delete_user_file(
     let mut scoped_path = PathBuf::from(path);
 
     let storage_path_to_delete = StoragePath::new(user_storage.clone(), scoped_path.clone())?;
-    let storage_item = read_storage_item(&storage_path_to_delete).await?;
-
-    match storage_item.kind {
-        StorageItemKind::Directory => {
-            delete_directory(&storage_path_to_delete).await?;
-        }
-        StorageItemKind::File => {
-            delete_file(&storage_path_to_delete).await?;
-        }
-    }
+    read_storage_item(&storage_path_to_delete).await?;
+
+    let mut transaction = database.begin_transaction().await?;
+
+    trash_storage_item(&mut transaction, &storage_path_to_delete).await?;
+
+    transaction.commit().await?;
 
     scoped_path.pop();
 
@@ -1,25 +1,125 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
 
-use bitsync_database::entity::User;
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::User,
+    repository::{self, QueryError},
+};
 use bitsync_storage::{
     operation::{
         read::{ReadFileStreamError, ReadStorageItemError, read_file_stream, read_storage_item},
         write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
     },
-    storage_item::StorageItemKind,
+    storage_item::{StorageItem, StorageItemKind},
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
+use tokio::io::DuplexStream;
 use tracing::Level;
 
 use super::shared::{AsyncStorageItemRead, user_root_directory_name};
 
-mod directory_zipping;
+pub(crate) mod directory_tarring;
+pub(crate) mod directory_zipping;
+
+/// The archive a downloaded directory is packed into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DirectoryArchiveFormat {
+    /// A zip of uncompressed entries, the fastest to create.
+    #[default]
+    Zip,
+    ZipDeflate,
+    ZipZstd,
+    TarGz,
+    TarZst,
+}
+
+impl DirectoryArchiveFormat {
+    fn file_extension(&self) -> &'static str {
+        match self {
+            DirectoryArchiveFormat::Zip
+            | DirectoryArchiveFormat::ZipDeflate
+            | DirectoryArchiveFormat::ZipZstd => "zip",
+            DirectoryArchiveFormat::TarGz => "tar.gz",
+            DirectoryArchiveFormat::TarZst => "tar.zst",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("an error occurred while writing a directory archive")]
+pub enum DirectoryArchiveError {
+    Zip(#[from] directory_zipping::DirectoryZipError),
+    Tar(#[from] directory_tarring::DirectoryTarError),
+}
+
+/// Writes an archive of the files of `storage_items` and their descendants, named relative to
+/// `base_path`.
+pub(crate) async fn write_archived_storage_items_to_stream(
+    stream: DuplexStream,
+    storage_items: &[StorageItem],
+    base_path: &Path,
+    archive_format: DirectoryArchiveFormat,
+) -> Result<(), DirectoryArchiveError> {
+    let zip_compression = match archive_format {
+        DirectoryArchiveFormat::Zip => async_zip::Compression::Stored,
+        DirectoryArchiveFormat::ZipDeflate => async_zip::Compression::Deflate,
+        DirectoryArchiveFormat::ZipZstd => async_zip::Compression::Zstd,
+        DirectoryArchiveFormat::TarGz => {
+            return Ok(directory_tarring::write_tarred_storage_items_to_stream(
+                GzipEncoder::new(stream),
+                storage_items,
+                base_path,
+            )
+            .await?);
+        }
+        DirectoryArchiveFormat::TarZst => {
+            return Ok(directory_tarring::write_tarred_storage_items_to_stream(
+                ZstdEncoder::new(stream),
+                storage_items,
+                base_path,
+            )
+            .await?);
+        }
+    };
+
+    directory_zipping::write_zipped_storage_items_to_stream(
+        stream,
+        storage_items,
+        base_path,
+        zip_compression,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// The name of an archived item, relative to `base_path`. An item that is the base itself is named
+/// by its path below the storage root.
+pub(crate) fn archived_item_path<'a>(scoped_path: &'a Path, base_path: &Path) -> &'a Path {
+    scoped_path
+        .strip_prefix(base_path)
+        .ok()
+        .filter(|path| !path.as_os_str().is_empty())
+        .or_else(|| scoped_path.strip_prefix("/").ok())
+        .unwrap_or(scoped_path)
+}
 
 pub struct UserFileDownloadResult {
     pub file: AsyncStorageItemRead,
     pub mime: mime_guess::Mime,
     pub path: StoragePath,
+    /// The hex encoded SHA-256 checksum of a downloaded file, if it is known.
+    pub checksum: Option<String>,
+    /// Only known for files, archives are sized while they are written.
+    pub size: Option<u64>,
+    pub modified_at: Option<SystemTime>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -29,50 +129,83 @@ pub enum UserFileDownloadError {
     ReadFileStream(#[from] ReadFileStreamError),
     StoragePath(#[from] StoragePathError),
     ReadStorageItem(#[from] ReadStorageItemError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
 }
 
+/// Downloads a file, or a directory packed into an archive of `archive_format`.
 pub async fn download_user_file(
-    storage_root_dir: &Path,
+    database: &Database,
+    storage_root: &StorageRoot,
     path: &str,
+    archive_format: DirectoryArchiveFormat,
     user: &User,
 ) -> Result<UserFileDownloadResult, UserFileDownloadError> {
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
 
-    let path = StoragePath::new(user_storage.clone(), PathBuf::from(path))?;
+    let path = StoragePath::new(user_storage, PathBuf::from(path))?;
+
+    download_storage_path(
+        database,
+        path,
+        &user_root_directory_name(&user.username),
+        archive_format,
+    )
+    .await
+}
+
+pub(crate) async fn download_storage_path(
+    database: &Database,
+    path: StoragePath,
+    root_directory_name: &str,
+    archive_format: DirectoryArchiveFormat,
+) -> Result<UserFileDownloadResult, UserFileDownloadError> {
     let storage_item = read_storage_item(&path).await?;
 
     match storage_item.kind {
         StorageItemKind::File => {
             let mime = mime_guess::from_path(&path.scoped_path).first_or_octet_stream();
             let file = read_file_stream(&path).await?;
+            let checksum = repository::file_checksum::find_by_user_id_and_item_path(
+                &mut *database.acquire_connection().await?,
+                &path.storage.user_id,
+                &path.path(),
+            )
+            .await?
+            .map(|file_checksum| file_checksum.sha256);
 
             Ok(UserFileDownloadResult {
                 file: AsyncStorageItemRead::File(file),
                 mime,
                 path,
+                checksum,
+                size: Some(storage_item.size),
+                modified_at: storage_item.modified_at,
             })
         }
         StorageItemKind::Directory => {
             let (write_stream, read_stream) = tokio::io::duplex(4096);
 
             tokio::spawn(async move {
-                match directory_zipping::write_zipped_storage_item_to_stream(
+                match write_archived_storage_items_to_stream(
                     write_stream,
-                    &storage_item,
+                    std::slice::from_ref(&storage_item),
+                    &storage_item.path.scoped_path,
+                    archive_format,
                 )
                 .await
                 {
                     Ok(()) => {}
-                    Err(directory_zip_error) => {
+                    Err(directory_archive_error) => {
                         tracing::event!(
                             Level::ERROR,
-                            message = "Directory zipping failed",
-                            error_trace = directory_zip_error.to_string(),
+                            message = "Directory archiving failed",
+                            error_trace = directory_archive_error.to_string(),
                         )
                     }
                 };
@@ -81,19 +214,23 @@ pub async fn download_user_file(
             let mut dir_path = path.scoped_path.clone();
 
             if dir_path.file_stem().is_none() {
-                dir_path.set_file_name(user_root_directory_name(&user.username));
+                dir_path.set_file_name(root_directory_name);
             }
 
-            dir_path.set_extension("zip");
+            dir_path.set_extension(archive_format.file_extension());
 
-            let fake_zip_path = StoragePath::new(user_storage.clone(), dbg!(dir_path))?;
+            let fake_archive_path = StoragePath::new(path.storage.clone(), dir_path)?;
 
-            let mime = mime_guess::from_path(&fake_zip_path.scoped_path).first_or_octet_stream();
+            let mime =
+                mime_guess::from_path(&fake_archive_path.scoped_path).first_or_octet_stream();
 
             Ok(UserFileDownloadResult {
                 file: AsyncStorageItemRead::Directory(read_stream),
                 mime,
-                path: fake_zip_path,
+                path: fake_archive_path,
+                checksum: None,
+                size: None,
+                modified_at: None,
             })
         }
     }
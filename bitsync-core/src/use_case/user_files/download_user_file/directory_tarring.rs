@@ -0,0 +1,86 @@
+use std::{path::Path, time::UNIX_EPOCH};
+
+use bitsync_storage::{
+    operation::read::{
+        ReadDirContentsError, ReadFileStreamError, read_dir_contents, read_file_stream,
+    },
+    storage_item::{StorageItem, StorageItemKind},
+};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_tar::{Builder, Header};
+
+use super::archived_item_path;
+
+#[derive(Debug, thiserror::Error)]
+#[error("an error occurred while writing a directory tar")]
+pub enum DirectoryTarError {
+    ReadDirContents(#[from] ReadDirContentsError),
+    ReadFileStream(#[from] ReadFileStreamError),
+    Write(#[from] std::io::Error),
+}
+
+/// Tars the files of `storage_items` and their descendants, named relative to `base_path`. The
+/// writer is shut down afterwards, which finishes a compressing writer.
+pub async fn write_tarred_storage_items_to_stream<W>(
+    writer: W,
+    storage_items: &[StorageItem],
+    base_path: &Path,
+) -> Result<(), DirectoryTarError>
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let mut tar_builder = Builder::new(writer);
+
+    for storage_item in storage_items {
+        write_storage_item_to_tar(&mut tar_builder, storage_item, base_path).await?;
+    }
+
+    tar_builder.into_inner().await?.shutdown().await?;
+
+    Ok(())
+}
+
+#[async_recursion::async_recursion]
+async fn write_storage_item_to_tar<W>(
+    tar_builder: &mut Builder<W>,
+    storage_item: &StorageItem,
+    base_path: &Path,
+) -> Result<(), DirectoryTarError>
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    match storage_item.kind {
+        StorageItemKind::File => {
+            let tarred_item_path = archived_item_path(&storage_item.path.scoped_path, base_path);
+
+            let mut header = Header::new_gnu();
+            header.set_size(storage_item.size);
+            header.set_mode(0o644);
+
+            if let Some(modified_at) = storage_item
+                .modified_at
+                .and_then(|modified_at| modified_at.duration_since(UNIX_EPOCH).ok())
+            {
+                header.set_mtime(modified_at.as_secs());
+            }
+
+            // the entry has to be exactly as long as the size in its header
+            let file_stream = read_file_stream(&storage_item.path)
+                .await?
+                .take(storage_item.size);
+
+            tar_builder
+                .append_data(&mut header, tarred_item_path, file_stream)
+                .await?;
+        }
+        StorageItemKind::Directory => {
+            let directory_contents = read_dir_contents(&storage_item.path).await?;
+
+            for directory_item in directory_contents {
+                write_storage_item_to_tar(tar_builder, &directory_item, base_path).await?;
+            }
+        }
+    };
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use bitsync_storage::{
     operation::read::{
         ReadDirContentsError, ReadFileStreamError, read_dir_contents, read_file_stream,
@@ -7,6 +9,8 @@ use bitsync_storage::{
 use tokio::io::DuplexStream;
 use tokio_util::compat::TokioAsyncReadCompatExt;
 
+use super::archived_item_path;
+
 #[derive(Debug, thiserror::Error)]
 #[error("failed to copy a stream")]
 pub struct StreamCopyError(#[from] std::io::Error);
@@ -20,13 +24,19 @@ pub enum DirectoryZipError {
     StreamCopy(#[from] StreamCopyError),
 }
 
-pub async fn write_zipped_storage_item_to_stream(
+/// Zips the files of `storage_items` and their descendants, named relative to `base_path`.
+pub async fn write_zipped_storage_items_to_stream(
     stream: DuplexStream,
-    storage_item: &StorageItem,
+    storage_items: &[StorageItem],
+    base_path: &Path,
+    compression: async_zip::Compression,
 ) -> Result<(), DirectoryZipError> {
     let mut zip_file_writer = async_zip::tokio::write::ZipFileWriter::with_tokio(stream);
 
-    write_storage_item_to_zip(&mut zip_file_writer, storage_item, storage_item).await?;
+    for storage_item in storage_items {
+        write_storage_item_to_zip(&mut zip_file_writer, storage_item, base_path, compression)
+            .await?;
+    }
 
     zip_file_writer.close().await?;
 
@@ -37,24 +47,16 @@ pub async fn write_zipped_storage_item_to_stream(
 async fn write_storage_item_to_zip(
     zip_file_writer: &mut async_zip::tokio::write::ZipFileWriter<DuplexStream>,
     storage_item: &StorageItem,
-    root_storage_item: &StorageItem,
+    base_path: &Path,
+    compression: async_zip::Compression,
 ) -> Result<(), DirectoryZipError> {
     match storage_item.kind {
         StorageItemKind::File => {
-            let zipped_item_path = if let StorageItemKind::Directory = root_storage_item.kind {
-                storage_item
-                    .path
-                    .scoped_path
-                    .strip_prefix(&root_storage_item.path.scoped_path)
-                    .map(|path| path.to_path_buf())
-                    .unwrap_or(storage_item.path.scoped_path.clone())
-            } else {
-                storage_item.path.scoped_path.clone()
-            };
+            let zipped_item_path = archived_item_path(&storage_item.path.scoped_path, base_path);
 
             let zip_entry_builder = async_zip::ZipEntryBuilder::new(
                 async_zip::ZipString::from(zipped_item_path.to_string_lossy().to_string()),
-                async_zip::Compression::Stored,
+                compression,
             );
 
             let mut zip_entry_writer = zip_file_writer
@@ -73,7 +75,7 @@ async fn write_storage_item_to_zip(
             let directory_contents = read_dir_contents(&storage_item.path).await?;
 
             for directory_item in directory_contents {
-                write_storage_item_to_zip(zip_file_writer, &directory_item, root_storage_item)
+                write_storage_item_to_zip(zip_file_writer, &directory_item, base_path, compression)
                     .await?;
             }
         }
@@ -0,0 +1,323 @@
+use std::path::{Path, PathBuf};
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+};
+use bitsync_storage::{
+    operation::{
+        read::{
+            CalculateStorageUsageError, ReadFileStreamError, ReadStorageItemError,
+            calculate_storage_usage, read_file_stream, read_storage_item,
+        },
+        write::{EnsureUserStorageExistsError, WriteFileStreamError, ensure_user_storage_exists},
+    },
+    storage_item::{StorageItem, StorageItemKind},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use tokio::io::BufReader;
+use tokio_util::{
+    compat::FuturesAsyncReadCompatExt,
+    io::{ReaderStream, StreamReader},
+};
+
+use crate::use_case::file_version::shared::{
+    WriteVersionedFileStreamError, write_versioned_file_stream,
+};
+
+use super::{
+    delete_user_file::{TrashStorageItemError, trash_storage_item},
+    shared::{
+        ConflictPolicy, CreateDirectoriesError, InvalidRelativePathError,
+        ReadSortedDirContentsError, ResolveDestinationConflictError, UploadPolicy,
+        create_directories, effective_storage_quota, read_sorted_dir_contents,
+        resolve_destination_conflict, validate_relative_path,
+    },
+};
+
+pub struct UserFileExtraction<'a> {
+    pub archive_path: &'a str,
+    /// The directory the entries are written to. It is created if it does not exist yet.
+    pub destination_path: &'a str,
+    /// Applied to every extracted file. With [`ConflictPolicy::Fail`] existing files are kept and
+    /// the entry is skipped.
+    pub conflict_policy: ConflictPolicy,
+}
+
+/// How many of the archive's entries have been written or skipped so far.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExtractionProgress {
+    pub processed_entries: usize,
+    pub total_entries: usize,
+}
+
+pub struct UserFileExtractResult {
+    pub extracted_file_count: usize,
+    pub skipped_file_count: usize,
+    pub dir_contents: Vec<StorageItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to extract a user's archive")]
+pub enum UserFileExtractError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    ArchiveNotAFile(#[from] ArchiveNotAFileError),
+    ReadFileStream(#[from] ReadFileStreamError),
+    Zip(#[from] async_zip::error::ZipError),
+    UnsafeEntryPath(#[from] InvalidRelativePathError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+    StorageQuotaExceeded(#[from] ExtractionStorageQuotaExceededError),
+    CreateDirectories(#[from] CreateDirectoriesError),
+    ResolveDestinationConflict(#[from] ResolveDestinationConflictError),
+    TransactionBegin(#[from] TransactionBeginError),
+    TrashStorageItem(#[from] TrashStorageItemError),
+    TransactionCommit(#[from] TransactionCommitError),
+    WriteVersionedFileStream(#[from] WriteVersionedFileStreamError),
+    ReadSortedDirContents(#[from] ReadSortedDirContentsError),
+    EntrySizeExceeded(#[from] EntrySizeExceededError),
+    Interrupted(#[from] ExtractionInterruptedError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("only files can be extracted")]
+pub struct ArchiveNotAFileError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the extracted files exceed the storage quota")]
+pub struct ExtractionStorageQuotaExceededError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the archive entry {path} is larger than its declared size")]
+pub struct EntrySizeExceededError {
+    pub path: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the extraction failed after {extracted_file_count} files were written")]
+pub struct ExtractionInterruptedError {
+    pub extracted_file_count: usize,
+    #[source]
+    pub source: Box<UserFileExtractError>,
+}
+
+struct ArchiveEntry {
+    path: String,
+    is_directory: bool,
+    size: u64,
+}
+
+/// Extracts the zip file at `archive_path` into `destination_path`, entry by entry. All entry
+/// paths are checked before anything is written, so an archive with entries pointing outside of
+/// the destination is refused as a whole. `on_progress` is called after every entry.
+pub async fn extract_user_file(
+    database: &Database,
+    storage_root: &StorageRoot,
+    upload_policy: &UploadPolicy,
+    user: &User,
+    UserFileExtraction {
+        archive_path,
+        destination_path,
+        conflict_policy,
+    }: UserFileExtraction<'_>,
+    mut on_progress: impl FnMut(ExtractionProgress),
+) -> Result<UserFileExtractResult, UserFileExtractError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let mut scoped_archive_path = PathBuf::from(archive_path);
+    let archive_storage_path = StoragePath::new(user_storage.clone(), scoped_archive_path.clone())?;
+
+    if read_storage_item(&archive_storage_path).await?.kind != StorageItemKind::File {
+        Err(ArchiveNotAFileError)?;
+    }
+
+    let mut zip_file_reader = async_zip::tokio::read::seek::ZipFileReader::with_tokio(
+        BufReader::new(read_file_stream(&archive_storage_path).await?),
+    )
+    .await?;
+
+    let entries = zip_file_reader
+        .file()
+        .entries()
+        .iter()
+        .map(|entry| {
+            Ok(ArchiveEntry {
+                path: entry.filename().as_str()?.to_owned(),
+                is_directory: entry.dir()?,
+                size: entry.uncompressed_size(),
+            })
+        })
+        .collect::<Result<Vec<_>, async_zip::error::ZipError>>()?;
+
+    for entry in &entries {
+        validate_relative_path(&entry.path)?;
+    }
+
+    let quota_bytes =
+        effective_storage_quota(user.storage_quota, upload_policy.default_storage_quota);
+
+    // the usage is calculated once and kept up to date while extracting, walking the whole
+    // storage again for every entry would be far too slow
+    let mut used_bytes = 0;
+
+    if let Some(quota_bytes) = quota_bytes {
+        used_bytes = calculate_storage_usage(&user_storage).await?;
+
+        let extracted_bytes: u64 = entries
+            .iter()
+            .filter(|entry| !entry.is_directory)
+            .map(|entry| entry.size)
+            .sum();
+
+        if used_bytes + extracted_bytes > quota_bytes {
+            Err(ExtractionStorageQuotaExceededError)?;
+        }
+    }
+
+    let destination_storage_path =
+        StoragePath::new(user_storage.clone(), PathBuf::from(destination_path))?;
+
+    create_directories(
+        &user_storage,
+        Path::new("/"),
+        destination_storage_path
+            .scoped_path
+            .strip_prefix("/")
+            .unwrap_or(&destination_storage_path.scoped_path),
+    )
+    .await?;
+
+    let mut progress = ExtractionProgress {
+        processed_entries: 0,
+        total_entries: entries.len(),
+    };
+    let mut extracted_file_count = 0;
+    let mut skipped_file_count = 0;
+
+    on_progress(progress);
+
+    // files written before an error stay in place, so the error reports how many there are
+    let extraction_result: Result<(), UserFileExtractError> = async {
+        for (index, entry) in entries.iter().enumerate() {
+            let relative_path = Path::new(&entry.path);
+
+            if entry.is_directory {
+                create_directories(
+                    &user_storage,
+                    &destination_storage_path.scoped_path,
+                    relative_path,
+                )
+                .await?;
+            } else {
+                create_directories(
+                    &user_storage,
+                    &destination_storage_path.scoped_path,
+                    relative_path.parent().unwrap_or(Path::new("")),
+                )
+                .await?;
+
+                let entry_storage_path = StoragePath::new(
+                    user_storage.clone(),
+                    destination_storage_path.scoped_path.join(relative_path),
+                )?;
+
+                match resolve_destination_conflict(entry_storage_path, conflict_policy).await {
+                    Ok(resolved_destination) => {
+                        let replaced_item = match resolved_destination.is_existing_item_replaced {
+                            true => Some(read_storage_item(&resolved_destination.path).await?),
+                            false => None,
+                        };
+
+                        if replaced_item
+                            .as_ref()
+                            .is_some_and(|item| item.kind == StorageItemKind::Directory)
+                        {
+                            let mut transaction = database.begin_transaction().await?;
+
+                            trash_storage_item(&mut transaction, &resolved_destination.path)
+                                .await?;
+
+                            transaction.commit().await?;
+                        }
+
+                        // a replaced file only frees its space if it is not kept as a version
+                        let replaced_bytes = match replaced_item {
+                            Some(item)
+                                if item.kind == StorageItemKind::File
+                                    && upload_policy.max_file_versions == 0 =>
+                            {
+                                item.size
+                            }
+                            _ => 0,
+                        };
+
+                        if quota_bytes.is_some_and(|quota_bytes| {
+                            used_bytes.saturating_sub(replaced_bytes) + entry.size > quota_bytes
+                        }) {
+                            Err(ExtractionStorageQuotaExceededError)?;
+                        }
+
+                        let entry_reader = zip_file_reader.reader_with_entry(index).await?;
+
+                        // the declared size was checked against the quota, so an entry must not
+                        // inflate to more than that; at most one byte past it is read
+                        match write_versioned_file_stream(
+                            database,
+                            &resolved_destination.path,
+                            StreamReader::new(ReaderStream::new(entry_reader.compat())),
+                            Some(entry.size),
+                            upload_policy.max_file_versions,
+                        )
+                        .await
+                        {
+                            Ok(()) => {}
+                            Err(WriteVersionedFileStreamError::WriteFileStream(
+                                WriteFileStreamError::SizeLimitExceeded(..),
+                            )) => Err(EntrySizeExceededError {
+                                path: entry.path.clone(),
+                            })?,
+                            Err(error) => Err(error)?,
+                        }
+
+                        // the declared size is an upper bound of the written bytes
+                        used_bytes = used_bytes.saturating_sub(replaced_bytes) + entry.size;
+                        extracted_file_count += 1;
+                    }
+                    Err(ResolveDestinationConflictError::DestinationExists(..)) => {
+                        skipped_file_count += 1;
+                    }
+                    Err(error) => Err(error)?,
+                }
+            }
+
+            progress.processed_entries = index + 1;
+            on_progress(progress);
+        }
+
+        Ok(())
+    }
+    .await;
+
+    if let Err(error) = extraction_result {
+        Err(ExtractionInterruptedError {
+            extracted_file_count,
+            source: Box::new(error),
+        })?;
+    }
+
+    scoped_archive_path.pop();
+
+    Ok(UserFileExtractResult {
+        extracted_file_count,
+        skipped_file_count,
+        dir_contents: read_sorted_dir_contents(&user_storage, scoped_archive_path).await?,
+    })
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use bitsync_database::entity::User;
+use bitsync_storage::{
+    operation::{
+        read::{ReadDirContentsError, read_dir_contents},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::StorageItem,
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to list a user's directory")]
+pub enum ListUserDirectoryError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadDirContents(#[from] ReadDirContentsError),
+}
+
+pub async fn list_user_directory(
+    storage_root: &StorageRoot,
+    path: &str,
+    user: &User,
+) -> Result<Vec<StorageItem>, ListUserDirectoryError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let directory_storage_path = StoragePath::new(user_storage, PathBuf::from(path))?;
+    let mut dir_contents = read_dir_contents(&directory_storage_path).await?;
+
+    dir_contents.sort_by_key(|item| item.path.path());
+    dir_contents.sort_by_key(|item| item.kind.clone());
+
+    Ok(dir_contents)
+}
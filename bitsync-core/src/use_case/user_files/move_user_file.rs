@@ -1,18 +1,37 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
-use bitsync_database::entity::User;
+use bitsync_database::{
+    database::{
+        Database, TransactionBeginError,
+        transaction::{Transaction, TransactionCommitError},
+    },
+    entity::User,
+    repository::{self, QueryError},
+};
 use bitsync_storage::{
     operation::{
         read::{ReadDirContentsError, ReadStorageItemError, read_dir_contents},
+        thumbnail::remove_thumbnails,
         write::{
             EnsureUserStorageExistsError, RenameItemError, ensure_user_storage_exists, rename_item,
         },
     },
     storage_item::StorageItem,
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 
+use crate::use_case::file_version::shared::{
+    DeleteFileVersionsError, delete_file_versions_at_or_below,
+};
+
+use super::{
+    copy_user_file::{DestinationInsideSourceError, SourceInsideDestinationError},
+    delete_user_file::{TrashStorageItemError, trash_storage_item},
+    shared::{ConflictPolicy, ResolveDestinationConflictError, resolve_destination_conflict},
+};
+
 pub struct UserFileMoveResult {
     pub dir_contents: Vec<StorageItem>,
 }
@@ -23,24 +42,42 @@ pub enum UserFileMoveError {
     EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
     StoragePath(#[from] StoragePathError),
     ReadStorageItem(#[from] ReadStorageItemError),
-    RenameItem(#[from] RenameItemError),
     ReadDirContents(#[from] ReadDirContentsError),
     DestinationSameAsSource(#[from] DestinationSameAsSourceError),
+    DestinationInsideSource(#[from] DestinationInsideSourceError),
+    SourceInsideDestination(#[from] SourceInsideDestinationError),
+    ResolveDestinationConflict(#[from] ResolveDestinationConflictError),
+    TransactionBegin(#[from] TransactionBeginError),
+    TrashStorageItem(#[from] TrashStorageItemError),
+    MoveStorageItem(#[from] MoveStorageItemError),
+    TransactionCommit(#[from] TransactionCommitError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to move an item")]
+pub enum MoveStorageItemError {
+    RenameItem(#[from] RenameItemError),
+    Query(#[from] QueryError),
+    DeleteFileVersions(#[from] DeleteFileVersionsError),
 }
 
 #[derive(thiserror::Error, Debug)]
 #[error("destination path is the same as the source path")]
 pub struct DestinationSameAsSourceError;
 
+/// Moves a file or directory. An existing item at the destination is handled according to
+/// `conflict_policy`, a replaced item goes to the trash.
 pub async fn move_user_file(
-    storage_root_dir: &Path,
+    database: &Database,
+    storage_root: &StorageRoot,
     path_to_move: &str,
     move_destination_path: &str,
+    conflict_policy: ConflictPolicy,
     user: &User,
 ) -> Result<UserFileMoveResult, UserFileMoveError> {
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
@@ -56,7 +93,44 @@ pub async fn move_user_file(
         Err(DestinationSameAsSourceError)?;
     }
 
-    rename_item(&storage_path_to_move, &move_destination_storage_path).await?;
+    if move_destination_storage_path
+        .scoped_path
+        .starts_with(&storage_path_to_move.scoped_path)
+    {
+        Err(DestinationInsideSourceError)?;
+    }
+
+    let resolved_destination =
+        resolve_destination_conflict(move_destination_storage_path, conflict_policy).await?;
+
+    if resolved_destination.is_existing_item_replaced
+        && storage_path_to_move
+            .scoped_path
+            .starts_with(&resolved_destination.path.scoped_path)
+    {
+        Err(SourceInsideDestinationError)?;
+    }
+
+    // the trashed item is already on disk in the trash, so its records are committed on their own
+    // and a failed move below cannot roll them back
+    if resolved_destination.is_existing_item_replaced {
+        let mut transaction = database.begin_transaction().await?;
+
+        trash_storage_item(&mut transaction, &resolved_destination.path).await?;
+
+        transaction.commit().await?;
+    }
+
+    let mut transaction = database.begin_transaction().await?;
+
+    move_storage_item(
+        &mut transaction,
+        &storage_path_to_move,
+        &resolved_destination.path,
+    )
+    .await?;
+
+    transaction.commit().await?;
 
     scoped_path_to_move.pop();
 
@@ -68,3 +142,54 @@ pub async fn move_user_file(
 
     Ok(UserFileMoveResult { dir_contents })
 }
+
+/// Moves the item at `source` to `destination` along with its shares, versions and checksums.
+/// Anything recorded at `destination` is dropped beforehand.
+pub(crate) async fn move_storage_item(
+    transaction: &mut Transaction,
+    source: &StoragePath,
+    destination: &StoragePath,
+) -> Result<(), MoveStorageItemError> {
+    let user_id = source.storage.user_id;
+
+    repository::user_share::delete_all_by_user_id_at_or_below_item_path(
+        &mut **transaction,
+        &user_id,
+        &destination.path(),
+    )
+    .await?;
+    repository::user_share::move_item_paths_at_or_below(
+        &mut **transaction,
+        &user_id,
+        &source.path(),
+        &destination.path(),
+    )
+    .await?;
+    delete_file_versions_at_or_below(transaction, &source.storage, &destination.path()).await?;
+    repository::file_version::move_item_paths_at_or_below(
+        &mut **transaction,
+        &user_id,
+        &source.path(),
+        &destination.path(),
+    )
+    .await?;
+
+    repository::file_checksum::delete_all_by_user_id_at_or_below_item_path(
+        &mut **transaction,
+        &user_id,
+        &destination.path(),
+    )
+    .await?;
+    repository::file_checksum::move_item_paths_at_or_below(
+        &mut **transaction,
+        &user_id,
+        &source.path(),
+        &destination.path(),
+    )
+    .await?;
+
+    rename_item(source, destination).await?;
+    remove_thumbnails(source).await;
+
+    Ok(())
+}
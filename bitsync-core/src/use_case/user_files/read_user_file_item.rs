@@ -1,17 +1,35 @@
 use std::path::{Path, PathBuf};
 
-use bitsync_database::entity::User;
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{FileVersion, User},
+    repository::{self, QueryError},
+};
 use bitsync_storage::{
     operation::{
-        read::{ReadDirContentsError, ReadStorageItemError, read_dir_contents, read_storage_item},
+        read::{
+            CalculateStorageUsageError, ReadDirContentsError, ReadFileStreamError,
+            ReadStorageItemError, read_dir_contents, read_file_stream, read_storage_item,
+        },
         write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
     },
     storage_item::{StorageItem, StorageItemKind},
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 
-use super::shared::user_root_directory_name;
+use tokio::io::AsyncReadExt;
+
+use super::{
+    shared::{StorageUsage, effective_storage_quota, read_storage_usage, user_root_directory_name},
+    view_user_file::FilePreviewKind,
+};
+
+/// Files larger than this are not read for a text preview.
+pub const TEXT_PREVIEW_SIZE_LIMIT: u64 = 1024 * 1024;
+/// The leading bytes of a file that tell text from binary content.
+const BINARY_DETECTION_SAMPLE_SIZE: u64 = 8 * 1024;
 
 pub struct DirectoryBreadcrumbSegment {
     pub name: String,
@@ -24,6 +42,7 @@ pub struct UserDirectoryContentsResult {
     pub directory_name: String,
     pub is_root_directory: bool,
     pub breadcrumb_segments: Vec<DirectoryBreadcrumbSegment>,
+    pub storage_usage: StorageUsage,
 }
 
 pub struct UserFileItemResult {
@@ -31,6 +50,18 @@ pub struct UserFileItemResult {
     pub size: u64,
     pub file_name: String,
     pub breadcrumb_segments: Vec<DirectoryBreadcrumbSegment>,
+    pub versions: Vec<FileVersion>,
+    pub checksum: Option<String>,
+    /// Not read for images, PDFs, audio and video, which are previewed by the browser.
+    pub text_content: Option<TextFileContent>,
+}
+
+pub enum TextFileContent {
+    Text(String),
+    /// The file is larger than [`TEXT_PREVIEW_SIZE_LIMIT`].
+    TooLarge,
+    /// The file contains bytes that are not UTF-8 encoded text.
+    Binary,
 }
 
 pub enum UserFilesHomeResult {
@@ -45,23 +76,31 @@ pub enum ReadUserFileItemError {
     ReadStorageItem(#[from] ReadStorageItemError),
     ReadDirContents(#[from] ReadDirContentsError),
     StoragePath(#[from] StoragePathError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    ReadFileStream(#[from] ReadFileStreamError),
+    ReadFileContent(#[from] std::io::Error),
 }
 
 pub async fn read_user_file_item(
-    storage_root_dir: &Path,
+    database: &Database,
+    storage_root: &StorageRoot,
+    default_storage_quota: Option<u64>,
     path: &str,
     user: &User,
 ) -> Result<UserFilesHomeResult, ReadUserFileItemError> {
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
 
     let path = StoragePath::new(user_storage.clone(), PathBuf::from(path))?;
     let storage_item = read_storage_item(&path).await?;
-    let breadcrumb_segments = build_breadcrumb_segments(&user.username, &path.scoped_path);
+    let breadcrumb_segments =
+        build_breadcrumb_segments(user_root_directory_name(&user.username), &path.scoped_path);
 
     match storage_item.kind {
         StorageItemKind::Directory => {
@@ -76,6 +115,11 @@ pub async fn read_user_file_item(
                 .file_name()
                 .map(|directory_name| directory_name.to_string_lossy().to_string())
                 .unwrap_or_else(|| user_root_directory_name(&user.username));
+            let storage_usage = read_storage_usage(
+                &user_storage,
+                effective_storage_quota(user.storage_quota, default_storage_quota),
+            )
+            .await?;
 
             Ok(UserFilesHomeResult::Directory(
                 UserDirectoryContentsResult {
@@ -84,6 +128,7 @@ pub async fn read_user_file_item(
                     directory_name,
                     is_root_directory,
                     breadcrumb_segments,
+                    storage_usage,
                 },
             ))
         }
@@ -93,23 +138,82 @@ pub async fn read_user_file_item(
                 .file_name()
                 .map(|name| name.to_string_lossy().to_string())
                 .unwrap_or_default();
+            let mut connection = database.acquire_connection().await?;
+            let versions = repository::file_version::find_all_by_user_id_and_item_path(
+                &mut *connection,
+                &user.id,
+                &path.path(),
+            )
+            .await?;
+            let checksum = repository::file_checksum::find_by_user_id_and_item_path(
+                &mut *connection,
+                &user.id,
+                &path.path(),
+            )
+            .await?
+            .map(|file_checksum| file_checksum.sha256);
+            let text_content = match FilePreviewKind::from_path(&path.scoped_path) {
+                Some(..) => None,
+                None => Some(read_text_content(&path, storage_item.size).await?),
+            };
 
             Ok(UserFilesHomeResult::File(UserFileItemResult {
                 size: storage_item.size,
                 path,
                 file_name,
                 breadcrumb_segments,
+                versions,
+                checksum,
+                text_content,
             }))
         }
     }
 }
 
-fn build_breadcrumb_segments(
-    user_name: &str,
+async fn read_text_content(
+    path: &StoragePath,
+    size: u64,
+) -> Result<TextFileContent, ReadUserFileItemError> {
+    let mut file = read_file_stream(path).await?;
+    let mut content = Vec::new();
+
+    (&mut file)
+        .take(BINARY_DETECTION_SAMPLE_SIZE)
+        .read_to_end(&mut content)
+        .await?;
+
+    if is_binary(&content) {
+        return Ok(TextFileContent::Binary);
+    }
+
+    if size > TEXT_PREVIEW_SIZE_LIMIT {
+        return Ok(TextFileContent::TooLarge);
+    }
+
+    // the file may have grown since its size was read
+    file.take(TEXT_PREVIEW_SIZE_LIMIT.saturating_sub(content.len() as u64))
+        .read_to_end(&mut content)
+        .await?;
+
+    Ok(match String::from_utf8(content) {
+        Ok(text) => TextFileContent::Text(text),
+        Err(..) => TextFileContent::Binary,
+    })
+}
+
+/// Text must not contain NUL bytes and must be valid UTF-8, apart from a character cut off at the
+/// end of the sample.
+fn is_binary(sample: &[u8]) -> bool {
+    sample.contains(&0)
+        || std::str::from_utf8(sample).is_err_and(|error| error.error_len().is_some())
+}
+
+pub(crate) fn build_breadcrumb_segments(
+    root_directory_name: String,
     scoped_path: &Path,
 ) -> Vec<DirectoryBreadcrumbSegment> {
     let mut segments = vec![DirectoryBreadcrumbSegment {
-        name: user_root_directory_name(user_name),
+        name: root_directory_name,
         path: "/".to_owned(),
     }];
 
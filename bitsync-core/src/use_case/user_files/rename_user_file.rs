@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+};
+use bitsync_storage::{
+    operation::{
+        error::MetadataError,
+        read::{ReadStorageItemError, item_exists, read_storage_item},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::StorageItem,
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+    validation::{PathIsJustFileNameValidationError, validate_path_is_just_file_name},
+};
+
+use super::{
+    create_directory::EmptyPathError,
+    move_user_file::{MoveStorageItemError, move_storage_item},
+    shared::{DestinationExistsError, ReadSortedDirContentsError, read_sorted_dir_contents},
+};
+
+pub struct UserFileRenameResult {
+    pub dir_contents: Vec<StorageItem>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to rename a user's file")]
+pub enum UserFileRenameError {
+    EmptyFileName(#[from] EmptyPathError),
+    InvalidFileName(#[from] PathIsJustFileNameValidationError),
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    RootNotRenamable(#[from] RootNotRenamableError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    Metadata(#[from] MetadataError),
+    DestinationExists(#[from] DestinationExistsError),
+    TransactionBegin(#[from] TransactionBeginError),
+    MoveStorageItem(#[from] MoveStorageItemError),
+    TransactionCommit(#[from] TransactionCommitError),
+    ReadSortedDirContents(#[from] ReadSortedDirContentsError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the root directory cannot be renamed")]
+pub struct RootNotRenamableError;
+
+/// Gives the item at `path_to_rename` a new name within its directory. An existing sibling with
+/// the new name is never replaced.
+pub async fn rename_user_file(
+    database: &Database,
+    storage_root: &StorageRoot,
+    path_to_rename: &str,
+    new_file_name: &str,
+    user: &User,
+) -> Result<UserFileRenameResult, UserFileRenameError> {
+    if new_file_name.is_empty() {
+        Err(EmptyPathError)?;
+    }
+
+    validate_path_is_just_file_name(new_file_name)?;
+
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let storage_path_to_rename =
+        StoragePath::new(user_storage.clone(), PathBuf::from(path_to_rename))?;
+
+    let parent_directory_path = match storage_path_to_rename.scoped_path.parent() {
+        Some(parent_directory_path) => parent_directory_path.to_path_buf(),
+        None => Err(RootNotRenamableError)?,
+    };
+
+    read_storage_item(&storage_path_to_rename).await?;
+
+    let renamed_storage_path = StoragePath::new(
+        user_storage.clone(),
+        parent_directory_path.join(new_file_name),
+    )?;
+
+    if renamed_storage_path.scoped_path != storage_path_to_rename.scoped_path {
+        if item_exists(&renamed_storage_path).await? {
+            Err(DestinationExistsError)?;
+        }
+
+        let mut transaction = database.begin_transaction().await?;
+
+        move_storage_item(
+            &mut transaction,
+            &storage_path_to_rename,
+            &renamed_storage_path,
+        )
+        .await?;
+
+        transaction.commit().await?;
+    }
+
+    Ok(UserFileRenameResult {
+        dir_contents: read_sorted_dir_contents(&user_storage, parent_directory_path).await?,
+    })
+}
@@ -1,13 +1,14 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use bitsync_database::entity::User;
 use bitsync_storage::{
     operation::{
-        read::{ReadDirContentsError, read_dir_contents},
+        read::ReadDirContentsError,
         write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
     },
-    storage_item::{StorageItem, StorageItemKind},
+    storage_item::StorageItem,
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 use nucleo_matcher::{
@@ -15,6 +16,8 @@ use nucleo_matcher::{
     pattern::{AtomKind, CaseMatching, Normalization, Pattern},
 };
 
+use super::shared::collect_all_storage_items;
+
 pub struct SearchResult {
     pub storage_item: StorageItem,
     pub parent_path: String,
@@ -37,32 +40,10 @@ pub enum SearchUserFilesError {
     StoragePath(#[from] StoragePathError),
 }
 
-#[async_recursion::async_recursion]
-async fn collect_all_storage_items(
-    path: &StoragePath,
-    items: &mut Vec<StorageItem>,
-) -> Result<(), ReadDirContentsError> {
-    let dir_contents = read_dir_contents(path).await?;
-
-    for item in dir_contents {
-        match item.kind {
-            StorageItemKind::Directory => {
-                collect_all_storage_items(&item.path, items).await?;
-                items.push(item);
-            }
-            StorageItemKind::File => {
-                items.push(item);
-            }
-        }
-    }
-
-    Ok(())
-}
-
 const SEARCH_RESULT_LIMIT: usize = 10;
 
 pub async fn search_user_files(
-    storage_root_dir: &Path,
+    storage_root: &StorageRoot,
     query: &str,
     user: &User,
     current_path: Option<&str>,
@@ -73,7 +54,7 @@ pub async fn search_user_files(
 
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
@@ -120,7 +101,7 @@ pub async fn search_user_files(
         })
         .collect();
 
-    scored_storage_items.sort_by(|a, b| b.score.cmp(&a.score));
+    scored_storage_items.sort_by_key(|search_result| std::cmp::Reverse(search_result.score));
     scored_storage_items.truncate(SEARCH_RESULT_LIMIT);
 
     let (current_dir_results, global_results) = match current_path {
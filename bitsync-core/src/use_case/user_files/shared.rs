@@ -1,8 +1,26 @@
-use std::pin::Pin;
+use std::{
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+};
 
-use bitsync_storage::async_file_read::AsyncFileRead;
+use bitsync_storage::{
+    async_file_read::AsyncFileRead,
+    operation::{
+        error::MetadataError,
+        read::{
+            CalculateStorageUsageError, ReadDirContentsError, ReadStorageItemError,
+            calculate_storage_usage, item_exists, read_dir_contents, read_storage_item,
+        },
+        write::{CreateDirectoryError, create_directory},
+    },
+    storage_item::{StorageItem, StorageItemKind},
+    storage_path::{StoragePath, StoragePathError},
+    user_storage::UserStorage,
+};
 use tokio::io::DuplexStream;
 
+use crate::config::Config;
+
 pub fn user_root_directory_name(user_name: &str) -> String {
     if user_name.ends_with('s') {
         format!("{user_name}' Storage")
@@ -11,6 +29,179 @@ pub fn user_root_directory_name(user_name: &str) -> String {
     }
 }
 
+/// The outcome of a batch operation for one of its items.
+pub struct BatchItemResult<T, E> {
+    pub path: String,
+    pub result: Result<T, E>,
+}
+
+pub struct StorageUsage {
+    pub used_bytes: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+pub fn effective_storage_quota(
+    storage_quota: Option<i64>,
+    default_storage_quota: Option<u64>,
+) -> Option<u64> {
+    match storage_quota {
+        Some(storage_quota) => Some(storage_quota.max(0) as u64),
+        None => default_storage_quota,
+    }
+}
+
+/// Storage limits applied when writing uploaded content.
+pub struct UploadPolicy {
+    pub default_storage_quota: Option<u64>,
+    pub max_file_versions: usize,
+}
+
+impl From<&Config> for UploadPolicy {
+    fn from(value: &Config) -> Self {
+        Self {
+            default_storage_quota: value.default_user_storage_quota,
+            max_file_versions: value.max_file_versions,
+        }
+    }
+}
+
+pub(crate) async fn read_storage_usage(
+    user_storage: &UserStorage,
+    quota_bytes: Option<u64>,
+) -> Result<StorageUsage, CalculateStorageUsageError> {
+    Ok(StorageUsage {
+        used_bytes: calculate_storage_usage(user_storage).await?,
+        quota_bytes,
+    })
+}
+
+/// Number of bytes that may be written to `destination` without exceeding the quota. An existing
+/// file at the destination is replaced, so its size is available again unless it is kept as a
+/// previous version.
+pub(crate) async fn remaining_storage_quota(
+    destination: &StoragePath,
+    quota_bytes: Option<u64>,
+    max_file_versions: usize,
+) -> Result<Option<u64>, CalculateStorageUsageError> {
+    let Some(quota_bytes) = quota_bytes else {
+        return Ok(None);
+    };
+
+    let used_bytes = calculate_storage_usage(&destination.storage).await?;
+    let replaced_bytes = match read_storage_item(destination).await {
+        Ok(storage_item)
+            if storage_item.kind == StorageItemKind::File && max_file_versions == 0 =>
+        {
+            storage_item.size
+        }
+        _ => 0,
+    };
+
+    Ok(Some(
+        quota_bytes.saturating_sub(used_bytes.saturating_sub(replaced_bytes)),
+    ))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to find an available path")]
+pub enum FindAvailableStoragePathError {
+    StoragePath(#[from] StoragePathError),
+    Metadata(#[from] MetadataError),
+}
+
+/// Returns `path` if nothing exists there yet, otherwise the first free sibling named like
+/// `name (1).ext`.
+pub(crate) async fn find_available_storage_path(
+    path: StoragePath,
+) -> Result<StoragePath, FindAvailableStoragePathError> {
+    if !item_exists(&path).await? {
+        return Ok(path);
+    }
+
+    let file_stem = path
+        .scoped_path
+        .file_stem()
+        .map(|file_stem| file_stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .scoped_path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut counter = 1;
+
+    loop {
+        let candidate = StoragePath::new(
+            path.storage.clone(),
+            path.scoped_path
+                .with_file_name(format!("{file_stem} ({counter}){extension}")),
+        )?;
+
+        if !item_exists(&candidate).await? {
+            return Ok(candidate);
+        }
+
+        counter += 1;
+    }
+}
+
+/// What to do when an item already exists at the destination of a write.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Refuse to write.
+    #[default]
+    Fail,
+    /// Replace the existing item.
+    Overwrite,
+    /// Write next to the existing item under a name with a numbered suffix.
+    KeepBoth,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("an item already exists at the destination path")]
+pub struct DestinationExistsError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to resolve a conflict at the destination")]
+pub enum ResolveDestinationConflictError {
+    Metadata(#[from] MetadataError),
+    DestinationExists(#[from] DestinationExistsError),
+    FindAvailableStoragePath(#[from] FindAvailableStoragePathError),
+}
+
+/// The path to write to after applying a [`ConflictPolicy`].
+pub(crate) struct ResolvedDestination {
+    pub path: StoragePath,
+    /// Whether an item exists at `path` that is going to be replaced.
+    pub is_existing_item_replaced: bool,
+}
+
+pub(crate) async fn resolve_destination_conflict(
+    destination: StoragePath,
+    conflict_policy: ConflictPolicy,
+) -> Result<ResolvedDestination, ResolveDestinationConflictError> {
+    if !item_exists(&destination).await? {
+        return Ok(ResolvedDestination {
+            path: destination,
+            is_existing_item_replaced: false,
+        });
+    }
+
+    match conflict_policy {
+        ConflictPolicy::Fail => Err(DestinationExistsError)?,
+        ConflictPolicy::Overwrite => Ok(ResolvedDestination {
+            path: destination,
+            is_existing_item_replaced: true,
+        }),
+        ConflictPolicy::KeepBoth => Ok(ResolvedDestination {
+            path: find_available_storage_path(destination).await?,
+            is_existing_item_replaced: false,
+        }),
+    }
+}
+
 pub enum AsyncStorageItemRead {
     File(AsyncFileRead),
     Directory(DuplexStream),
@@ -38,3 +229,115 @@ impl tokio::io::AsyncRead for AsyncStorageItemRead {
         }
     }
 }
+
+/// Reads the contents of the directory at `scoped_directory_path`, directories first and sorted by
+/// path.
+pub(crate) async fn read_sorted_dir_contents(
+    user_storage: &UserStorage,
+    scoped_directory_path: PathBuf,
+) -> Result<Vec<StorageItem>, ReadSortedDirContentsError> {
+    let directory_storage_path = StoragePath::new(user_storage.clone(), scoped_directory_path)?;
+    let mut dir_contents = read_dir_contents(&directory_storage_path).await?;
+
+    dir_contents.sort_by_key(|item| item.path.path());
+    dir_contents.sort_by_key(|item| item.kind.clone());
+
+    Ok(dir_contents)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to read the sorted contents of a directory")]
+pub enum ReadSortedDirContentsError {
+    StoragePath(#[from] StoragePathError),
+    ReadDirContents(#[from] ReadDirContentsError),
+}
+
+/// Recursively collects all items below `path`. Directories are listed after their contents.
+#[async_recursion::async_recursion]
+pub(crate) async fn collect_all_storage_items(
+    path: &StoragePath,
+    items: &mut Vec<StorageItem>,
+) -> Result<(), ReadDirContentsError> {
+    let dir_contents = read_dir_contents(path).await?;
+
+    for item in dir_contents {
+        match item.kind {
+            StorageItemKind::Directory => {
+                collect_all_storage_items(&item.path, items).await?;
+                items.push(item);
+            }
+            StorageItemKind::File => {
+                items.push(item);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the path '{path}' is not a relative path of names")]
+pub struct InvalidRelativePathError {
+    pub path: String,
+}
+
+/// Accepts paths like `a/b.txt` below some directory. Empty and absolute paths as well as paths
+/// containing `.` or `..` are refused.
+pub(crate) fn validate_relative_path(
+    relative_path: &str,
+) -> Result<&Path, InvalidRelativePathError> {
+    let path = Path::new(relative_path);
+
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(..)))
+    {
+        Err(InvalidRelativePathError {
+            path: relative_path.to_owned(),
+        })?;
+    }
+
+    Ok(path)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("a file exists where a directory of the path is expected")]
+pub struct ParentNotADirectoryError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create directories")]
+pub enum CreateDirectoriesError {
+    StoragePath(#[from] StoragePathError),
+    Metadata(#[from] MetadataError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    ParentNotADirectory(#[from] ParentNotADirectoryError),
+    CreateDirectory(#[from] CreateDirectoryError),
+}
+
+/// Creates the directories of `relative_directory_path` below `scoped_base_path` one after
+/// another. Existing directories are reused.
+pub(crate) async fn create_directories(
+    user_storage: &UserStorage,
+    scoped_base_path: &Path,
+    relative_directory_path: &Path,
+) -> Result<(), CreateDirectoriesError> {
+    let mut scoped_directory_path = scoped_base_path.to_path_buf();
+
+    for directory_name in relative_directory_path.components() {
+        scoped_directory_path.push(directory_name);
+
+        let directory_storage_path =
+            StoragePath::new(user_storage.clone(), scoped_directory_path.clone())?;
+
+        if !item_exists(&directory_storage_path).await? {
+            create_directory(&directory_storage_path).await?;
+        } else if read_storage_item(&directory_storage_path).await?.kind
+            != StorageItemKind::Directory
+        {
+            Err(ParentNotADirectoryError)?;
+        }
+    }
+
+    Ok(())
+}
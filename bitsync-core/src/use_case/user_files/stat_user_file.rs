@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use bitsync_database::entity::User;
+use bitsync_storage::{
+    operation::{
+        read::{ReadStorageItemError, read_storage_item},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::StorageItem,
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to read a user's file information")]
+pub enum StatUserFileError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+}
+
+pub async fn stat_user_file(
+    storage_root: &StorageRoot,
+    path: &str,
+    user: &User,
+) -> Result<StorageItem, StatUserFileError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let storage_path = StoragePath::new(user_storage, PathBuf::from(path))?;
+
+    Ok(read_storage_item(&storage_path).await?)
+}
+
+/// Like [`stat_user_file`], but treats a missing item as `None`.
+pub async fn find_user_file(
+    storage_root: &StorageRoot,
+    path: &str,
+    user: &User,
+) -> Result<Option<StorageItem>, StatUserFileError> {
+    match stat_user_file(storage_root, path, user).await {
+        Ok(storage_item) => Ok(Some(storage_item)),
+        Err(StatUserFileError::ReadStorageItem(ReadStorageItemError::Metadata(error)))
+            if matches!(
+                error.source.kind(),
+                std::io::ErrorKind::NotFound | std::io::ErrorKind::NotADirectory
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bitsync_database::entity::User;
+use bitsync_storage::{
+    operation::{
+        read::{ReadFileStreamError, ReadStorageItemError, read_file_stream, read_storage_item},
+        thumbnail::{
+            CachedThumbnail, ReadCachedThumbnailError, StoreThumbnailError, read_cached_thumbnail,
+            store_thumbnail,
+        },
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::{StorageItem, StorageItemKind},
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use image::{
+    DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader, Limits,
+    codecs::jpeg::JpegEncoder,
+};
+use mime_guess::mime;
+use tokio::{
+    io::AsyncReadExt,
+    sync::{AcquireError, Semaphore},
+};
+
+use super::{download_user_file::UserFileDownloadResult, shared::AsyncStorageItemRead};
+
+/// The maximum width and height of a thumbnail in pixels.
+pub const THUMBNAIL_SIZE: u32 = 256;
+/// Larger images are not decoded to create a thumbnail.
+const THUMBNAIL_SOURCE_SIZE_LIMIT: u64 = 64 * 1024 * 1024;
+/// The memory a decoded image may take up, which keeps small images of huge dimensions out.
+const THUMBNAIL_DECODING_ALLOCATION_LIMIT: u64 = 512 * 1024 * 1024;
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+/// How many thumbnails may be created at the same time. Each one can take up
+/// [`THUMBNAIL_DECODING_ALLOCATION_LIMIT`] and [`THUMBNAIL_SOURCE_SIZE_LIMIT`] of memory.
+pub const MAX_CONCURRENT_THUMBNAIL_CREATIONS: usize = 2;
+
+/// Whether a thumbnail can be created for the file at `path`, judging by its extension.
+pub fn supports_thumbnail(path: &Path) -> bool {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+
+    mime.type_() == mime::IMAGE
+        && matches!(mime.subtype().as_str(), "png" | "jpeg" | "gif" | "webp")
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("no thumbnail can be created for this item")]
+pub struct UnsupportedThumbnailSourceError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("an error occurred while creating a thumbnail")]
+pub enum UserFileThumbnailError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    UnsupportedSource(#[from] UnsupportedThumbnailSourceError),
+    ReadCachedThumbnail(#[from] ReadCachedThumbnailError),
+    ReadFileStream(#[from] ReadFileStreamError),
+    ReadFileContent(#[from] std::io::Error),
+    Image(#[from] ImageError),
+    AcquirePermit(#[from] AcquireError),
+    Join(#[from] tokio::task::JoinError),
+    StoreThumbnail(#[from] StoreThumbnailError),
+}
+
+/// Reads the thumbnail of an image, which is created and cached if the image has none or was
+/// modified since its thumbnail was created. Creating a thumbnail takes one of the
+/// `creation_permits`, which should be shared by all requests.
+pub async fn thumbnail_user_file(
+    storage_root: &StorageRoot,
+    creation_permits: &Arc<Semaphore>,
+    path: &str,
+    user: &User,
+) -> Result<UserFileDownloadResult, UserFileThumbnailError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let path = StoragePath::new(user_storage, PathBuf::from(path))?;
+    let storage_item = read_storage_item(&path).await?;
+
+    if storage_item.kind != StorageItemKind::File
+        || !supports_thumbnail(&path.scoped_path)
+        || storage_item.size > THUMBNAIL_SOURCE_SIZE_LIMIT
+    {
+        return Err(UnsupportedThumbnailSourceError)?;
+    }
+
+    if let Some(cached_thumbnail) =
+        read_cached_thumbnail(&path, storage_item.size, storage_item.modified_at).await?
+    {
+        return Ok(thumbnail_download_result(
+            path,
+            &storage_item,
+            cached_thumbnail,
+        ));
+    }
+
+    let creation_permit = creation_permits.clone().acquire_owned().await?;
+
+    // created by a concurrent request while this one was waiting for a permit
+    let cached_thumbnail =
+        match read_cached_thumbnail(&path, storage_item.size, storage_item.modified_at).await? {
+            Some(cached_thumbnail) => cached_thumbnail,
+            None => {
+                let mut content = Vec::new();
+                read_file_stream(&path)
+                    .await?
+                    .take(THUMBNAIL_SOURCE_SIZE_LIMIT)
+                    .read_to_end(&mut content)
+                    .await?;
+
+                // the permit is only released once the image is decoded, even if the request is
+                // cancelled before
+                let (thumbnail, extension) = tokio::task::spawn_blocking(move || {
+                    let _creation_permit = creation_permit;
+
+                    create_thumbnail(content)
+                })
+                .await??;
+
+                store_thumbnail(
+                    &path,
+                    storage_item.size,
+                    storage_item.modified_at,
+                    extension,
+                    &thumbnail,
+                )
+                .await?;
+
+                read_cached_thumbnail(&path, storage_item.size, storage_item.modified_at)
+                    .await?
+                    // removed again by a concurrent request for a newer state of the file
+                    .ok_or(UnsupportedThumbnailSourceError)?
+            }
+        };
+
+    Ok(thumbnail_download_result(
+        path,
+        &storage_item,
+        cached_thumbnail,
+    ))
+}
+
+fn thumbnail_download_result(
+    path: StoragePath,
+    storage_item: &StorageItem,
+    cached_thumbnail: CachedThumbnail,
+) -> UserFileDownloadResult {
+    let CachedThumbnail {
+        file,
+        size,
+        extension,
+    } = cached_thumbnail;
+
+    UserFileDownloadResult {
+        file: AsyncStorageItemRead::File(file),
+        mime: mime_guess::from_ext(&extension).first_or_octet_stream(),
+        path,
+        checksum: None,
+        size: Some(size),
+        // the thumbnail changes with the image it shows
+        modified_at: storage_item.modified_at,
+    }
+}
+
+/// Scales an image down to fit into [`THUMBNAIL_SIZE`]. Images with transparency are encoded as
+/// PNG, all others as JPEG. Returns the encoded thumbnail and its file extension.
+fn create_thumbnail(content: Vec<u8>) -> Result<(Vec<u8>, &'static str), ImageError> {
+    let mut reader = ImageReader::new(Cursor::new(content)).with_guessed_format()?;
+
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(THUMBNAIL_DECODING_ALLOCATION_LIMIT);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    // photos are often stored sideways, with their rotation noted in their metadata
+    image.apply_orientation(orientation);
+
+    if image.width() > THUMBNAIL_SIZE || image.height() > THUMBNAIL_SIZE {
+        image = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    }
+
+    let mut thumbnail = Cursor::new(Vec::new());
+
+    if image.color().has_alpha() {
+        image.write_to(&mut thumbnail, ImageFormat::Png)?;
+
+        Ok((thumbnail.into_inner(), "png"))
+    } else {
+        image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(
+                &mut thumbnail,
+                THUMBNAIL_JPEG_QUALITY,
+            ))?;
+
+        Ok((thumbnail.into_inner(), "jpg"))
+    }
+}
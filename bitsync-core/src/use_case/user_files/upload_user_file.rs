@@ -1,29 +1,46 @@
 use std::path::{Path, PathBuf};
 
-use bitsync_database::entity::User;
+use bitsync_database::{
+    database::{Database, TransactionBeginError, transaction::TransactionCommitError},
+    entity::User,
+};
 use bitsync_storage::{
     operation::{
-        read::{ReadDirContentsError, read_dir_contents},
-        write::{
-            EnsureUserStorageExistsError, WriteFileStreamError, ensure_user_storage_exists,
-            write_file_stream,
+        read::{
+            CalculateStorageUsageError, ReadDirContentsError, ReadStorageItemError,
+            read_dir_contents, read_storage_item,
         },
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
     },
-    storage_item::StorageItem,
+    storage_item::{StorageItem, StorageItemKind},
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 use futures::TryStreamExt;
 use tokio_util::io::StreamReader;
 
-pub struct UserFileUpload<S, B, E>
-where
-    S: futures::Stream<Item = Result<B, E>>,
-    B: bytes::Buf,
-    E: Into<std::io::Error>,
-{
-    pub file_name: String,
-    pub stream: StreamReader<S, B>,
+use crate::use_case::file_version::shared::{
+    WriteVersionedFileStreamError, write_versioned_file_stream,
+};
+
+use super::{
+    delete_user_file::{TrashStorageItemError, trash_storage_item},
+    shared::{
+        ConflictPolicy, CreateDirectoriesError, InvalidRelativePathError,
+        ResolveDestinationConflictError, UploadPolicy, create_directories, effective_storage_quota,
+        remaining_storage_quota, resolve_destination_conflict, validate_relative_path,
+    },
+};
+
+pub struct UserFileUpload<'a, S> {
+    /// The path of the file below the upload directory. Missing directories on the way are
+    /// created, existing ones are reused.
+    pub relative_path: &'a str,
+    pub stream: S,
+    /// An existing file is replaced by keeping it as a version, an existing directory goes to the
+    /// trash.
+    pub conflict_policy: ConflictPolicy,
 }
 
 pub struct UserFileResult {
@@ -34,17 +51,30 @@ pub struct UserFileResult {
 #[error("an error occurred during user file upload")]
 pub enum UserFileUploadError {
     StorageEnsurance(#[from] EnsureUserStorageExistsError),
-    WriteFileStream(#[from] WriteFileStreamError),
+    WriteVersionedFileStream(#[from] WriteVersionedFileStreamError),
     StoragePath(#[from] StoragePathError),
     ReadDirContents(#[from] ReadDirContentsError),
+    CalculateStorageUsage(#[from] CalculateStorageUsageError),
+    ResolveDestinationConflict(#[from] ResolveDestinationConflictError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    TransactionBegin(#[from] TransactionBeginError),
+    TrashStorageItem(#[from] TrashStorageItemError),
+    TransactionCommit(#[from] TransactionCommitError),
+    InvalidRelativePath(#[from] InvalidRelativePathError),
+    CreateDirectories(#[from] CreateDirectoriesError),
 }
 
 pub async fn upload_user_file<S, B, E>(
-    storage_root_dir: &Path,
+    database: &Database,
+    storage_root: &StorageRoot,
+    upload_policy: &UploadPolicy,
     path: &str,
     user: &User,
-    file_name: &str,
-    file_upload_stream: S,
+    UserFileUpload {
+        relative_path,
+        stream: file_upload_stream,
+        conflict_policy,
+    }: UserFileUpload<'_, S>,
 ) -> Result<UserFileResult, UserFileUploadError>
 where
     S: futures::Stream<Item = Result<B, E>>,
@@ -53,21 +83,58 @@ where
 {
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     ensure_user_storage_exists(&user_storage).await?;
 
-    let mut scoped_destination_path = PathBuf::from(path);
-    scoped_destination_path.push(file_name);
+    let relative_path = validate_relative_path(relative_path)?;
+
+    create_directories(
+        &user_storage,
+        Path::new(path),
+        relative_path.parent().unwrap_or(Path::new("")),
+    )
+    .await?;
+
+    let scoped_destination_path = PathBuf::from(path).join(relative_path);
+
+    let resolved_destination = resolve_destination_conflict(
+        StoragePath::new(user_storage.clone(), scoped_destination_path)?,
+        conflict_policy,
+    )
+    .await?;
+    let destination_storage_path = resolved_destination.path;
+
+    if resolved_destination.is_existing_item_replaced
+        && read_storage_item(&destination_storage_path).await?.kind == StorageItemKind::Directory
+    {
+        let mut transaction = database.begin_transaction().await?;
+
+        trash_storage_item(&mut transaction, &destination_storage_path).await?;
+
+        transaction.commit().await?;
+    }
 
-    let destination_storage_path = StoragePath::new(user_storage.clone(), scoped_destination_path)?;
+    let remaining_storage_quota = remaining_storage_quota(
+        &destination_storage_path,
+        effective_storage_quota(user.storage_quota, upload_policy.default_storage_quota),
+        upload_policy.max_file_versions,
+    )
+    .await?;
 
     let file_upload_stream_with_io_error =
         file_upload_stream.map_err(|error| std::io::Error::other(error));
     let file_upload_stream_reader = StreamReader::new(file_upload_stream_with_io_error);
 
-    write_file_stream(&destination_storage_path, file_upload_stream_reader).await?;
+    write_versioned_file_stream(
+        database,
+        &destination_storage_path,
+        file_upload_stream_reader,
+        remaining_storage_quota,
+        upload_policy.max_file_versions,
+    )
+    .await?;
 
     let directory_storage_path = StoragePath::new(user_storage.clone(), PathBuf::from(path))?;
     let mut dir_contents = read_dir_contents(&directory_storage_path).await?;
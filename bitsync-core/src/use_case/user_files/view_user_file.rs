@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+
+use bitsync_database::{database::Database, entity::User};
+use bitsync_storage::{
+    operation::{
+        read::{ReadStorageItemError, read_storage_item},
+        write::{EnsureUserStorageExistsError, ensure_user_storage_exists},
+    },
+    storage_item::StorageItemKind,
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use mime_guess::{Mime, mime};
+
+use super::{
+    download_user_file::{
+        DirectoryArchiveFormat, UserFileDownloadError, UserFileDownloadResult,
+        download_storage_path,
+    },
+    shared::user_root_directory_name,
+};
+
+/// How a file is shown in the browser. Only types that cannot run scripts when opened directly
+/// are previewed, so SVG images are not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilePreviewKind {
+    Image,
+    Pdf,
+    Audio,
+    Video,
+}
+
+impl FilePreviewKind {
+    pub fn from_mime(mime: &Mime) -> Option<Self> {
+        match (mime.type_(), mime.subtype().as_str()) {
+            (mime::IMAGE, "png" | "jpeg" | "gif" | "webp" | "avif" | "bmp" | "x-icon") => {
+                Some(Self::Image)
+            }
+            (mime::APPLICATION, "pdf") => Some(Self::Pdf),
+            (mime::AUDIO, _) => Some(Self::Audio),
+            (mime::VIDEO, _) => Some(Self::Video),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_mime(&mime_guess::from_path(path).first_or_octet_stream())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("only files can be viewed")]
+pub struct ViewedItemNotAFileError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("an error occurred while viewing a user file")]
+pub enum UserFileViewError {
+    EnsureUserStorageExists(#[from] EnsureUserStorageExistsError),
+    StoragePath(#[from] StoragePathError),
+    ReadStorageItem(#[from] ReadStorageItemError),
+    NotAFile(#[from] ViewedItemNotAFileError),
+    Download(#[from] UserFileDownloadError),
+}
+
+/// Reads a file to be shown in the browser. Files that cannot be previewed are typed as
+/// `application/octet-stream`.
+pub async fn view_user_file(
+    database: &Database,
+    storage_root: &StorageRoot,
+    path: &str,
+    user: &User,
+) -> Result<UserFileDownloadResult, UserFileViewError> {
+    let user_storage = UserStorage {
+        user_id: user.id,
+        storage_root: storage_root.clone(),
+    };
+
+    ensure_user_storage_exists(&user_storage).await?;
+
+    let path = StoragePath::new(user_storage, PathBuf::from(path))?;
+
+    if read_storage_item(&path).await?.kind != StorageItemKind::File {
+        return Err(ViewedItemNotAFileError)?;
+    }
+
+    let mut result = download_storage_path(
+        database,
+        path,
+        &user_root_directory_name(&user.username),
+        DirectoryArchiveFormat::default(),
+    )
+    .await?;
+
+    if FilePreviewKind::from_mime(&result.mime).is_none() {
+        result.mime = mime::APPLICATION_OCTET_STREAM;
+    }
+
+    Ok(result)
+}
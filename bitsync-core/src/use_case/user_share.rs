@@ -1,5 +1,12 @@
+pub mod batch_create_user_shares;
 pub mod create_user_share;
 pub mod delete_all_user_shares;
 pub mod delete_user_share;
+pub mod download_user_share_item;
 pub mod list_shared_paths;
 pub mod list_user_shares;
+pub mod read_user_share_item;
+pub mod share_with_user;
+pub mod shared;
+pub mod unlock_user_share;
+pub mod update_user_share;
@@ -0,0 +1,58 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{User, UserShare},
+    repository::{self, QueryError},
+};
+use bitsync_storage::storage_root::StorageRoot;
+
+use crate::use_case::user_files::{
+    shared::BatchItemResult,
+    stat_user_file::{StatUserFileError, stat_user_file},
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create a batch of user shares")]
+pub enum BatchUserShareCreationError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to share an item of a batch")]
+pub enum BatchUserShareCreationItemError {
+    StatUserFile(#[from] StatUserFileError),
+    Query(#[from] QueryError),
+}
+
+/// Creates a link share for each item of `paths`. A failing item does not stop the others.
+pub async fn batch_create_user_shares(
+    database: &Database,
+    storage_root: &StorageRoot,
+    paths: &[String],
+    user: &User,
+) -> Result<
+    Vec<BatchItemResult<UserShare, BatchUserShareCreationItemError>>,
+    BatchUserShareCreationError,
+> {
+    let mut connection = database.acquire_connection().await?;
+    let mut item_results = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let result = match stat_user_file(storage_root, path, user).await {
+            Ok(storage_item) => repository::user_share::create(
+                &mut *connection,
+                &user.id,
+                &storage_item.path.path(),
+            )
+            .await
+            .map_err(BatchUserShareCreationItemError::from),
+            Err(error) => Err(BatchUserShareCreationItemError::from(error)),
+        };
+
+        item_results.push(BatchItemResult {
+            path: path.clone(),
+            result,
+        });
+    }
+
+    Ok(item_results)
+}
@@ -1,22 +1,29 @@
 use bitsync_database::{
     database::{ConnectionAcquisitionError, Database},
-    entity::UserShare,
     repository::{self, QueryError},
 };
+use bitsync_storage::{storage_path::StoragePathError, storage_root::StorageRoot};
 use uuid::Uuid;
 
+use super::shared::{
+    UserShareListing, build_user_share_listings, normalize_share_item_path, recipient_ids,
+};
+
 #[derive(thiserror::Error, Debug)]
 #[error("failed to create user share")]
 pub enum CreateUserShareError {
     DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
     Query(#[from] QueryError),
+    StoragePath(#[from] StoragePathError),
 }
 
 pub async fn create_user_share(
     database: &Database,
+    storage_root: &StorageRoot,
     user_id: &Uuid,
     item_path: &str,
-) -> Result<Vec<UserShare>, CreateUserShareError> {
+) -> Result<Vec<UserShareListing>, CreateUserShareError> {
+    let item_path = &normalize_share_item_path(storage_root, user_id, item_path)?;
     let mut connection = database.acquire_connection().await?;
 
     repository::user_share::create(&mut *connection, user_id, item_path).await?;
@@ -27,6 +34,8 @@ pub async fn create_user_share(
         item_path,
     )
     .await?;
+    let recipients =
+        repository::user::find_by_ids(&mut *connection, &recipient_ids(&user_shares)).await?;
 
-    Ok(user_shares)
+    Ok(build_user_share_listings(user_shares, &recipients))
 }
@@ -1,10 +1,11 @@
 use bitsync_database::{
     database::{ConnectionAcquisitionError, Database},
-    entity::UserShare,
     repository::{self, QueryError},
 };
 use uuid::Uuid;
 
+use super::shared::{UserShareListing, build_user_share_listings, recipient_ids};
+
 #[derive(thiserror::Error, Debug)]
 #[error("failed to delete user share")]
 pub enum DeleteUserShareError {
@@ -17,7 +18,7 @@ pub async fn delete_user_share(
     user_id: &Uuid,
     user_share_id: &Uuid,
     item_path: &str,
-) -> Result<Vec<UserShare>, DeleteUserShareError> {
+) -> Result<Vec<UserShareListing>, DeleteUserShareError> {
     let mut connection = database.acquire_connection().await?;
 
     repository::user_share::delete_by_id(&mut *connection, user_share_id, user_id).await?;
@@ -28,6 +29,8 @@ pub async fn delete_user_share(
         item_path,
     )
     .await?;
+    let recipients =
+        repository::user::find_by_ids(&mut *connection, &recipient_ids(&user_shares)).await?;
 
-    Ok(user_shares)
+    Ok(build_user_share_listings(user_shares, &recipients))
 }
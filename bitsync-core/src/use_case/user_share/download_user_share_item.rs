@@ -0,0 +1,62 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    repository::{self, QueryError},
+};
+use bitsync_storage::storage_root::StorageRoot;
+use uuid::Uuid;
+
+use crate::use_case::user_files::download_user_file::{
+    DirectoryArchiveFormat, UserFileDownloadError, UserFileDownloadResult, download_storage_path,
+};
+
+use super::shared::{
+    ResolveUserSharePathError, UserShareDownloadLimitReachedError, resolve_user_share_path,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to download user share item")]
+pub enum DownloadUserShareItemError {
+    ResolveUserSharePath(#[from] ResolveUserSharePathError),
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UserShareDownloadLimitReached(#[from] UserShareDownloadLimitReachedError),
+    UserFileDownload(#[from] UserFileDownloadError),
+}
+
+pub async fn download_user_share_item(
+    database: &Database,
+    storage_root: &StorageRoot,
+    jwt_secret: &str,
+    user_share_id: &Uuid,
+    share_access_token: Option<&str>,
+    path: &str,
+) -> Result<UserFileDownloadResult, DownloadUserShareItemError> {
+    let resolved_path = resolve_user_share_path(
+        database,
+        storage_root,
+        jwt_secret,
+        user_share_id,
+        share_access_token,
+        path,
+    )
+    .await?;
+
+    let download = download_storage_path(
+        database,
+        resolved_path.path,
+        &resolved_path.share_root_name,
+        DirectoryArchiveFormat::default(),
+    )
+    .await?;
+
+    let mut connection = database.acquire_connection().await?;
+
+    repository::user_share::increment_download_count(
+        &mut *connection,
+        &resolved_path.user_share.id,
+    )
+    .await?
+    .ok_or(UserShareDownloadLimitReachedError)?;
+
+    Ok(download)
+}
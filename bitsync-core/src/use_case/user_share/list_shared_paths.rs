@@ -1,4 +1,4 @@
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use bitsync_database::{
     database::{ConnectionAcquisitionError, Database},
@@ -6,9 +6,13 @@ use bitsync_database::{
     repository::{self, QueryError},
 };
 use bitsync_storage::{
-    operation::read::{ReadStorageItemError, read_storage_item},
+    operation::{
+        error::MetadataError,
+        read::{ReadStorageItemError, read_storage_item},
+    },
     storage_item::StorageItemKind,
     storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
     user_storage::UserStorage,
 };
 
@@ -29,7 +33,7 @@ pub enum ListSharedPathsError {
 
 pub async fn list_shared_paths(
     database: &Database,
-    storage_root_dir: &Path,
+    storage_root: &StorageRoot,
     user: &User,
 ) -> Result<Vec<SharedPath>, ListSharedPathsError> {
     let mut connection = database.acquire_connection().await?;
@@ -40,14 +44,22 @@ pub async fn list_shared_paths(
 
     let user_storage = UserStorage {
         user_id: user.id,
-        storage_root: storage_root_dir.to_path_buf(),
+        storage_root: storage_root.clone(),
     };
 
     let mut shared_paths = Vec::with_capacity(item_paths.len());
 
     for item_path in item_paths {
         let storage_path = StoragePath::new(user_storage.clone(), PathBuf::from(&item_path))?;
-        let storage_item = read_storage_item(&storage_path).await?;
+        let storage_item = match read_storage_item(&storage_path).await {
+            Ok(storage_item) => storage_item,
+            Err(ReadStorageItemError::Metadata(MetadataError { source, .. }))
+                if source.kind() == std::io::ErrorKind::NotFound =>
+            {
+                continue;
+            }
+            Err(error) => Err(error)?,
+        };
 
         shared_paths.push(SharedPath {
             path: item_path,
@@ -1,10 +1,11 @@
 use bitsync_database::{
     database::{ConnectionAcquisitionError, Database},
-    entity::UserShare,
     repository::{self, QueryError},
 };
 use uuid::Uuid;
 
+use super::shared::{UserShareListing, build_user_share_listings, recipient_ids};
+
 #[derive(thiserror::Error, Debug)]
 #[error("failed to list user shares")]
 pub enum ListUserSharesError {
@@ -16,7 +17,7 @@ pub async fn list_user_shares(
     database: &Database,
     user_id: &Uuid,
     item_path: &str,
-) -> Result<Vec<UserShare>, ListUserSharesError> {
+) -> Result<Vec<UserShareListing>, ListUserSharesError> {
     let mut connection = database.acquire_connection().await?;
 
     let user_shares = repository::user_share::find_all_by_user_id_and_item_path(
@@ -25,6 +26,8 @@ pub async fn list_user_shares(
         item_path,
     )
     .await?;
+    let recipients =
+        repository::user::find_by_ids(&mut *connection, &recipient_ids(&user_shares)).await?;
 
-    Ok(user_shares)
+    Ok(build_user_share_listings(user_shares, &recipients))
 }
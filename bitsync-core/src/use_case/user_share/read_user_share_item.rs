@@ -0,0 +1,149 @@
+use bitsync_database::database::Database;
+use bitsync_storage::{
+    operation::read::{
+        ReadDirContentsError, ReadStorageItemError, read_dir_contents, read_storage_item,
+    },
+    storage_item::StorageItemKind,
+    storage_root::StorageRoot,
+};
+use uuid::Uuid;
+
+use crate::use_case::user_files::read_user_file_item::{
+    DirectoryBreadcrumbSegment, build_breadcrumb_segments,
+};
+
+use super::shared::{ResolveUserSharePathError, ResolvedUserSharePath, resolve_user_share_path};
+
+pub struct UserShareDirectoryItem {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub kind: StorageItemKind,
+}
+
+pub struct UserShareDirectoryResult {
+    pub user_share_id: Uuid,
+    pub dir_contents: Vec<UserShareDirectoryItem>,
+    pub path: String,
+    pub directory_name: String,
+    pub is_root_directory: bool,
+    pub breadcrumb_segments: Vec<DirectoryBreadcrumbSegment>,
+}
+
+pub struct UserShareFileResult {
+    pub user_share_id: Uuid,
+    pub path: String,
+    pub size: u64,
+    pub file_name: String,
+    pub breadcrumb_segments: Vec<DirectoryBreadcrumbSegment>,
+}
+
+pub enum UserShareItemResult {
+    Directory(UserShareDirectoryResult),
+    File(UserShareFileResult),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to read resolved user share item")]
+pub enum ReadResolvedUserShareItemError {
+    ReadStorageItem(#[from] ReadStorageItemError),
+    ReadDirContents(#[from] ReadDirContentsError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to read user share item")]
+pub enum ReadUserShareItemError {
+    ResolveUserSharePath(#[from] ResolveUserSharePathError),
+    ReadResolvedUserShareItem(#[from] ReadResolvedUserShareItemError),
+}
+
+pub async fn read_user_share_item(
+    database: &Database,
+    storage_root: &StorageRoot,
+    jwt_secret: &str,
+    user_share_id: &Uuid,
+    share_access_token: Option<&str>,
+    path: &str,
+) -> Result<UserShareItemResult, ReadUserShareItemError> {
+    let resolved_path = resolve_user_share_path(
+        database,
+        storage_root,
+        jwt_secret,
+        user_share_id,
+        share_access_token,
+        path,
+    )
+    .await?;
+
+    Ok(read_resolved_user_share_item(&resolved_path).await?)
+}
+
+pub(crate) async fn read_resolved_user_share_item(
+    resolved_path: &ResolvedUserSharePath,
+) -> Result<UserShareItemResult, ReadResolvedUserShareItemError> {
+    let user_share_id = resolved_path.user_share.id;
+
+    let storage_item = read_storage_item(&resolved_path.path).await?;
+    let breadcrumb_segments = build_breadcrumb_segments(
+        resolved_path.share_root_name.clone(),
+        &resolved_path.share_relative_path,
+    );
+
+    let item_name = resolved_path
+        .share_relative_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| resolved_path.share_root_name.clone());
+
+    match storage_item.kind {
+        StorageItemKind::Directory => {
+            let dir_contents = read_share_directory_contents(resolved_path).await?;
+
+            Ok(UserShareItemResult::Directory(UserShareDirectoryResult {
+                user_share_id,
+                dir_contents,
+                path: resolved_path
+                    .share_relative_path
+                    .to_string_lossy()
+                    .to_string(),
+                directory_name: item_name,
+                is_root_directory: resolved_path.share_relative_path.file_name().is_none(),
+                breadcrumb_segments,
+            }))
+        }
+        StorageItemKind::File => Ok(UserShareItemResult::File(UserShareFileResult {
+            user_share_id,
+            path: resolved_path
+                .share_relative_path
+                .to_string_lossy()
+                .to_string(),
+            size: storage_item.size,
+            file_name: item_name,
+            breadcrumb_segments,
+        })),
+    }
+}
+
+pub(crate) async fn read_share_directory_contents(
+    resolved_path: &ResolvedUserSharePath,
+) -> Result<Vec<UserShareDirectoryItem>, ReadDirContentsError> {
+    let mut dir_contents = read_dir_contents(&resolved_path.path).await?;
+
+    dir_contents.sort_by_key(|item| item.path.path());
+    dir_contents.sort_by_key(|item| item.kind.clone());
+
+    Ok(dir_contents
+        .into_iter()
+        .map(|item| {
+            let mut share_path = resolved_path.share_relative_path.clone();
+            share_path.push(item.path.file_name());
+
+            UserShareDirectoryItem {
+                name: item.path.file_name(),
+                path: share_path.to_string_lossy().to_string(),
+                size: item.size,
+                kind: item.kind,
+            }
+        })
+        .collect())
+}
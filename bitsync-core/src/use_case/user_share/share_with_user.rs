@@ -0,0 +1,70 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::UserSharePermission,
+    repository::{self, QueryError},
+};
+use bitsync_storage::{storage_path::StoragePathError, storage_root::StorageRoot};
+use uuid::Uuid;
+
+use super::shared::{
+    UserShareListing, build_user_share_listings, normalize_share_item_path, recipient_ids,
+};
+
+#[derive(thiserror::Error, Debug)]
+#[error("the recipient does not exist")]
+pub struct RecipientNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("items cannot be shared with their owner")]
+pub struct RecipientIsOwnerError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to share item with user")]
+pub enum ShareWithUserError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    RecipientNotFound(#[from] RecipientNotFoundError),
+    RecipientIsOwner(#[from] RecipientIsOwnerError),
+    StoragePath(#[from] StoragePathError),
+}
+
+pub async fn share_with_user(
+    database: &Database,
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    item_path: &str,
+    recipient_username: &str,
+    permission: UserSharePermission,
+) -> Result<Vec<UserShareListing>, ShareWithUserError> {
+    let item_path = &normalize_share_item_path(storage_root, user_id, item_path)?;
+    let mut connection = database.acquire_connection().await?;
+
+    let recipient = repository::user::find_by_username(&mut *connection, recipient_username.trim())
+        .await?
+        .filter(|recipient| !recipient.is_suspended)
+        .ok_or(RecipientNotFoundError)?;
+
+    if &recipient.id == user_id {
+        return Err(RecipientIsOwnerError)?;
+    }
+
+    repository::user_share::create_for_recipient(
+        &mut *connection,
+        user_id,
+        item_path,
+        &recipient.id,
+        &permission,
+    )
+    .await?;
+
+    let user_shares = repository::user_share::find_all_by_user_id_and_item_path(
+        &mut *connection,
+        user_id,
+        item_path,
+    )
+    .await?;
+    let recipients =
+        repository::user::find_by_ids(&mut *connection, &recipient_ids(&user_shares)).await?;
+
+    Ok(build_user_share_listings(user_shares, &recipients))
+}
@@ -0,0 +1,210 @@
+use std::path::{Component, PathBuf};
+
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    entity::{User, UserShare},
+    repository::{self, QueryError},
+};
+use bitsync_storage::{
+    storage_path::{StoragePath, StoragePathError},
+    storage_root::StorageRoot,
+    user_storage::UserStorage,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{jwt::ShareAccessClaims, use_case::user_files::shared::user_root_directory_name};
+
+#[derive(Clone)]
+pub struct UserShareListing {
+    pub user_share: UserShare,
+    pub recipient_username: Option<String>,
+}
+
+pub(crate) fn build_user_share_listings(
+    user_shares: Vec<UserShare>,
+    recipients: &[User],
+) -> Vec<UserShareListing> {
+    user_shares
+        .into_iter()
+        .map(|user_share| {
+            let recipient_username = user_share.shared_with_user_id.and_then(|recipient_id| {
+                recipients
+                    .iter()
+                    .find(|recipient| recipient.id == recipient_id)
+                    .map(|recipient| recipient.username.clone())
+            });
+
+            UserShareListing {
+                user_share,
+                recipient_username,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn recipient_ids(user_shares: &[UserShare]) -> Vec<Uuid> {
+    user_shares
+        .iter()
+        .filter_map(|user_share| user_share.shared_with_user_id)
+        .collect()
+}
+
+pub struct ResolvedUserSharePath {
+    pub user_share: UserShare,
+    pub path: StoragePath,
+    pub share_relative_path: PathBuf,
+    pub share_root_name: String,
+    pub owner_username: String,
+    pub owner_storage_quota: Option<i64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the user share does not exist")]
+pub struct UserShareNotFoundError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the user share has expired")]
+pub struct UserShareExpiredError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the user share has reached its download limit")]
+pub struct UserShareDownloadLimitReachedError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("the user share requires a password")]
+pub struct UserSharePasswordRequiredError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to resolve user share path")]
+pub enum ResolveUserSharePathError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UserShareNotFound(#[from] UserShareNotFoundError),
+    UserShareExpired(#[from] UserShareExpiredError),
+    UserShareDownloadLimitReached(#[from] UserShareDownloadLimitReachedError),
+    UserSharePasswordRequired(#[from] UserSharePasswordRequiredError),
+    StoragePath(#[from] StoragePathError),
+}
+
+pub(crate) async fn resolve_user_share_path(
+    database: &Database,
+    storage_root: &StorageRoot,
+    jwt_secret: &str,
+    user_share_id: &Uuid,
+    share_access_token: Option<&str>,
+    path: &str,
+) -> Result<ResolvedUserSharePath, ResolveUserSharePathError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let user_share = repository::user_share::find_by_id(&mut *connection, user_share_id)
+        .await?
+        .ok_or(UserShareNotFoundError)?;
+    let owner = repository::user::find_by_id(&mut *connection, &user_share.user_id).await?;
+
+    if owner.is_suspended || user_share.shared_with_user_id.is_some() {
+        return Err(UserShareNotFoundError)?;
+    }
+
+    if user_share
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= time::OffsetDateTime::now_utc())
+    {
+        return Err(UserShareExpiredError)?;
+    }
+
+    if user_share
+        .max_downloads
+        .is_some_and(|max_downloads| user_share.download_count >= max_downloads)
+    {
+        return Err(UserShareDownloadLimitReachedError)?;
+    }
+
+    if user_share.password.is_some()
+        && !is_share_access_token_valid(jwt_secret, &user_share, share_access_token)
+    {
+        return Err(UserSharePasswordRequiredError)?;
+    }
+
+    Ok(build_resolved_user_share_path(
+        storage_root,
+        user_share,
+        &owner,
+        path,
+    )?)
+}
+
+pub(crate) fn build_resolved_user_share_path(
+    storage_root: &StorageRoot,
+    user_share: UserShare,
+    owner: &User,
+    path: &str,
+) -> Result<ResolvedUserSharePath, StoragePathError> {
+    let user_storage = UserStorage {
+        user_id: owner.id,
+        storage_root: storage_root.clone(),
+    };
+
+    let share_root = StoragePath::new(user_storage.clone(), PathBuf::from(&user_share.item_path))?;
+    let share_relative_path =
+        StoragePath::new(user_storage.clone(), PathBuf::from(path))?.scoped_path;
+
+    let mut scoped_path = share_root.scoped_path.clone();
+    scoped_path.extend(
+        share_relative_path
+            .components()
+            .filter(|component| matches!(component, Component::Normal(..))),
+    );
+
+    let share_root_name = share_root
+        .scoped_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| user_root_directory_name(&owner.username));
+
+    Ok(ResolvedUserSharePath {
+        user_share,
+        path: StoragePath::new(user_storage, scoped_path)?,
+        share_relative_path,
+        share_root_name,
+        owner_username: owner.username.clone(),
+        owner_storage_quota: owner.storage_quota,
+    })
+}
+
+/// The form in which share item paths are stored, which moving and deleting items relies on to
+/// find the shares at or below a path.
+pub(crate) fn normalize_share_item_path(
+    storage_root: &StorageRoot,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<String, StoragePathError> {
+    let user_storage = UserStorage {
+        user_id: *user_id,
+        storage_root: storage_root.clone(),
+    };
+
+    Ok(StoragePath::new(user_storage, PathBuf::from(item_path))?.path())
+}
+
+/// A short digest of the stored password hash. The hash is salted, so every password change
+/// results in a different fingerprint.
+pub(crate) fn share_password_fingerprint(hashed_password: Option<&str>) -> String {
+    let digest = Sha256::digest(hashed_password.unwrap_or_default().as_bytes());
+
+    hex::encode(&digest[..8])
+}
+
+fn is_share_access_token_valid(
+    jwt_secret: &str,
+    user_share: &UserShare,
+    share_access_token: Option<&str>,
+) -> bool {
+    share_access_token
+        .and_then(|token| ShareAccessClaims::decode_and_validate(token, jwt_secret).ok())
+        .is_some_and(|claims| {
+            claims.sub == user_share.id
+                && claims.password_fingerprint
+                    == share_password_fingerprint(user_share.password.as_deref())
+        })
+}
@@ -0,0 +1,49 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    repository::{self, QueryError},
+};
+use uuid::Uuid;
+
+use crate::{
+    hash::{PasswordHashVerificationError, verify_password_hash},
+    jwt::ShareAccessClaims,
+};
+
+use super::shared::{UserShareNotFoundError, share_password_fingerprint};
+
+const SHARE_ACCESS_DURATION: time::Duration = time::Duration::hours(12);
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to unlock user share")]
+pub enum UnlockUserShareError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UserShareNotFound(#[from] UserShareNotFoundError),
+    PasswordHashVerification(#[from] PasswordHashVerificationError),
+    Jwt(#[from] crate::jwt::Error),
+}
+
+pub async fn unlock_user_share(
+    database: &Database,
+    jwt_secret: &str,
+    user_share_id: &Uuid,
+    password: &str,
+) -> Result<String, UnlockUserShareError> {
+    let mut connection = database.acquire_connection().await?;
+
+    let user_share = repository::user_share::find_by_id(&mut *connection, user_share_id)
+        .await?
+        .ok_or(UserShareNotFoundError)?;
+
+    if let Some(hashed_password) = &user_share.password {
+        verify_password_hash(hashed_password, password)?;
+    }
+
+    let claims = ShareAccessClaims {
+        sub: user_share.id,
+        exp: (time::OffsetDateTime::now_utc() + SHARE_ACCESS_DURATION).unix_timestamp(),
+        password_fingerprint: share_password_fingerprint(user_share.password.as_deref()),
+    };
+
+    Ok(claims.encode(jwt_secret)?)
+}
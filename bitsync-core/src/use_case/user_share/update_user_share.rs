@@ -0,0 +1,88 @@
+use bitsync_database::{
+    database::{ConnectionAcquisitionError, Database},
+    repository::{self, QueryError},
+};
+use uuid::Uuid;
+
+use crate::hash::{PasswordHashCreationError, hash_password};
+
+use super::shared::{
+    UserShareListing, UserShareNotFoundError, build_user_share_listings, recipient_ids,
+};
+
+pub enum UserSharePasswordChange {
+    Keep,
+    Set(String),
+    Remove,
+}
+
+pub struct UserShareRestrictions {
+    pub expires_at: Option<time::OffsetDateTime>,
+    pub max_downloads: Option<i32>,
+    pub password: UserSharePasswordChange,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the maximum download count must be at least one")]
+pub struct InvalidMaxDownloadsError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to update user share")]
+pub enum UpdateUserShareError {
+    DatabaseConnectionAcquisition(#[from] ConnectionAcquisitionError),
+    Query(#[from] QueryError),
+    UserShareNotFound(#[from] UserShareNotFoundError),
+    InvalidMaxDownloads(#[from] InvalidMaxDownloadsError),
+    PasswordHashCreation(#[from] PasswordHashCreationError),
+}
+
+pub async fn update_user_share(
+    database: &Database,
+    user_id: &Uuid,
+    user_share_id: &Uuid,
+    item_path: &str,
+    restrictions: UserShareRestrictions,
+) -> Result<Vec<UserShareListing>, UpdateUserShareError> {
+    if restrictions
+        .max_downloads
+        .is_some_and(|max_downloads| max_downloads < 1)
+    {
+        return Err(InvalidMaxDownloadsError)?;
+    }
+
+    let mut connection = database.acquire_connection().await?;
+
+    let user_share = repository::user_share::find_by_id(&mut *connection, user_share_id)
+        .await?
+        .filter(|user_share| {
+            &user_share.user_id == user_id && user_share.shared_with_user_id.is_none()
+        })
+        .ok_or(UserShareNotFoundError)?;
+
+    let password = match restrictions.password {
+        UserSharePasswordChange::Keep => user_share.password,
+        UserSharePasswordChange::Set(password) => Some(hash_password(&password)?),
+        UserSharePasswordChange::Remove => None,
+    };
+
+    repository::user_share::update_restrictions(
+        &mut *connection,
+        user_share_id,
+        user_id,
+        restrictions.expires_at.as_ref(),
+        password.as_deref(),
+        restrictions.max_downloads,
+    )
+    .await?;
+
+    let user_shares = repository::user_share::find_all_by_user_id_and_item_path(
+        &mut *connection,
+        user_id,
+        item_path,
+    )
+    .await?;
+    let recipients =
+        repository::user::find_by_ids(&mut *connection, &recipient_ids(&user_shares)).await?;
+
+    Ok(build_user_share_listings(user_shares, &recipients))
+}
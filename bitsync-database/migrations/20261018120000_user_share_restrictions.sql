@@ -0,0 +1,5 @@
+ALTER TABLE "user_share"
+    ADD COLUMN "expires_at" TIMESTAMPTZ,
+    ADD COLUMN "password" TEXT,
+    ADD COLUMN "max_downloads" INTEGER,
+    ADD COLUMN "download_count" INTEGER DEFAULT 0 NOT NULL;
@@ -0,0 +1,11 @@
+CREATE TYPE "user_share_permission" AS ENUM ('read', 'read_write');
+
+ALTER TABLE "user_share"
+    ADD COLUMN "shared_with_user_id" UUID REFERENCES "user"("id") ON DELETE CASCADE,
+    ADD COLUMN "permission" user_share_permission DEFAULT 'read' NOT NULL;
+
+CREATE UNIQUE INDEX "user_share_recipient_unique"
+    ON "user_share" ("user_id", "item_path", "shared_with_user_id")
+    WHERE "shared_with_user_id" IS NOT NULL;
+
+CREATE INDEX "user_share_shared_with_user_id" ON "user_share" ("shared_with_user_id");
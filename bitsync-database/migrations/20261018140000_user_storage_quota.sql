@@ -0,0 +1 @@
+ALTER TABLE "user" ADD COLUMN "storage_quota" BIGINT;
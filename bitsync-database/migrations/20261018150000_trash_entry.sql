@@ -0,0 +1,9 @@
+CREATE TABLE "trash_entry" (
+    "id" UUID DEFAULT gen_random_uuid() PRIMARY KEY NOT NULL,
+    "user_id" UUID NOT NULL REFERENCES "user"("id") ON DELETE CASCADE,
+    "original_path" TEXT NOT NULL,
+    "deleted_at" TIMESTAMPTZ DEFAULT now() NOT NULL
+);
+
+CREATE INDEX "trash_entry_user_id_idx" ON "trash_entry" ("user_id");
+CREATE INDEX "trash_entry_deleted_at_idx" ON "trash_entry" ("deleted_at");
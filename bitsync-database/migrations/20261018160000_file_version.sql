@@ -0,0 +1,9 @@
+CREATE TABLE "file_version" (
+    "id" UUID DEFAULT gen_random_uuid() PRIMARY KEY NOT NULL,
+    "user_id" UUID NOT NULL REFERENCES "user"("id") ON DELETE CASCADE,
+    "item_path" TEXT NOT NULL,
+    "size" BIGINT NOT NULL,
+    "created_at" TIMESTAMPTZ DEFAULT now() NOT NULL
+);
+
+CREATE INDEX "file_version_user_id_item_path_idx" ON "file_version" ("user_id", "item_path");
@@ -0,0 +1,13 @@
+CREATE TABLE "upload_session" (
+    "id" UUID DEFAULT gen_random_uuid() PRIMARY KEY NOT NULL,
+    "user_id" UUID NOT NULL REFERENCES "user"("id") ON DELETE CASCADE,
+    "item_path" TEXT NOT NULL,
+    "upload_length" BIGINT NOT NULL,
+    "upload_offset" BIGINT DEFAULT 0 NOT NULL,
+    "created_at" TIMESTAMPTZ DEFAULT now() NOT NULL,
+    "updated_at" TIMESTAMPTZ DEFAULT now() NOT NULL,
+    "locked_until" TIMESTAMPTZ
+);
+
+CREATE INDEX "upload_session_user_id_idx" ON "upload_session" ("user_id");
+CREATE INDEX "upload_session_updated_at_idx" ON "upload_session" ("updated_at");
@@ -0,0 +1,7 @@
+CREATE TABLE "file_checksum" (
+    "user_id" UUID NOT NULL REFERENCES "user"("id") ON DELETE CASCADE,
+    "item_path" TEXT NOT NULL,
+    "sha256" TEXT NOT NULL,
+    "updated_at" TIMESTAMPTZ DEFAULT now() NOT NULL,
+    PRIMARY KEY ("user_id", "item_path")
+);
@@ -0,0 +1,13 @@
+CREATE TYPE "app_password_scope" AS ENUM ('read', 'write', 'admin');
+
+CREATE TABLE "app_password" (
+    "id" UUID DEFAULT gen_random_uuid() PRIMARY KEY NOT NULL,
+    "user_id" UUID NOT NULL REFERENCES "user"("id") ON DELETE CASCADE,
+    "name" TEXT NOT NULL,
+    "token_hash" TEXT NOT NULL UNIQUE,
+    "scope" app_password_scope DEFAULT 'write' NOT NULL,
+    "created_at" TIMESTAMPTZ DEFAULT now() NOT NULL,
+    "last_used_at" TIMESTAMPTZ
+);
+
+CREATE INDEX "app_password_user_id_idx" ON "app_password" ("user_id");
@@ -0,0 +1,14 @@
+ALTER TABLE "file_version"
+    ADD COLUMN "trash_entry_id" UUID REFERENCES "trash_entry"("id") ON DELETE CASCADE;
+
+CREATE INDEX "file_version_trash_entry_id_idx" ON "file_version" ("trash_entry_id");
+
+ALTER TABLE "file_checksum"
+    DROP CONSTRAINT "file_checksum_pkey",
+    ADD COLUMN "trash_entry_id" UUID REFERENCES "trash_entry"("id") ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX "file_checksum_user_id_item_path_unique"
+    ON "file_checksum" ("user_id", "item_path")
+    WHERE "trash_entry_id" IS NULL;
+
+CREATE INDEX "file_checksum_trash_entry_id_idx" ON "file_checksum" ("trash_entry_id");
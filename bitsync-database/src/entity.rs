@@ -10,6 +10,7 @@ pub struct User {
     pub active_totp_secret: Option<Vec<u8>>,
     pub dangling_totp_secret: Option<Vec<u8>>,
     pub is_suspended: bool,
+    pub storage_quota: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -29,6 +30,20 @@ pub struct UserShare {
     pub id: Uuid,
     pub user_id: Uuid,
     pub item_path: String,
+    pub expires_at: Option<time::OffsetDateTime>,
+    pub password: Option<String>,
+    pub max_downloads: Option<i32>,
+    pub download_count: i32,
+    pub shared_with_user_id: Option<Uuid>,
+    pub permission: UserSharePermission,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, sqlx::Type)]
+#[sqlx(type_name = "user_share_permission", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum UserSharePermission {
+    Read,
+    ReadWrite,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
@@ -62,3 +77,68 @@ pub struct Session {
     pub created_at: time::OffsetDateTime,
     pub last_seen_at: time::OffsetDateTime,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashEntry {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub original_path: String,
+    pub deleted_at: time::OffsetDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileVersion {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub item_path: String,
+    pub size: i64,
+    pub created_at: time::OffsetDateTime,
+    /// Set while the file is in the trash.
+    pub trash_entry_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub item_path: String,
+    pub upload_length: i64,
+    pub upload_offset: i64,
+    pub created_at: time::OffsetDateTime,
+    pub updated_at: time::OffsetDateTime,
+    /// Set while a chunk is being appended, see `repository::upload_session::lock_by_id_and_user_id`.
+    pub locked_until: Option<time::OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChecksum {
+    pub user_id: Uuid,
+    pub item_path: String,
+    pub sha256: String,
+    pub updated_at: time::OffsetDateTime,
+    /// Set while the file is in the trash.
+    pub trash_entry_id: Option<Uuid>,
+}
+
+/// What an app password may be used for. Each scope includes the ones declared before it.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, sqlx::Type,
+)]
+#[sqlx(type_name = "app_password_scope", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AppPasswordScope {
+    Read,
+    Write,
+    Admin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppPassword {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    pub created_at: time::OffsetDateTime,
+    pub last_used_at: Option<time::OffsetDateTime>,
+    pub scope: AppPasswordScope,
+}
@@ -1,6 +1,11 @@
+pub mod app_password;
+pub mod file_checksum;
+pub mod file_version;
 pub mod invite_token;
 pub mod session;
 pub mod totp_recovery_code;
+pub mod trash_entry;
+pub mod upload_session;
 pub mod user;
 pub mod user_share;
 
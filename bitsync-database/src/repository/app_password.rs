@@ -0,0 +1,138 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::entity::{AppPassword, AppPasswordScope};
+
+use super::QueryError;
+
+pub async fn create<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    name: &str,
+    token_hash: &str,
+    scope: &AppPasswordScope,
+) -> Result<AppPassword, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        AppPassword,
+        r#"
+            INSERT INTO "app_password" (user_id, name, token_hash, scope)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id,
+                user_id,
+                name,
+                token_hash,
+                created_at,
+                last_used_at,
+                scope AS "scope: AppPasswordScope"
+        "#,
+        user_id,
+        name,
+        token_hash,
+        scope as &AppPasswordScope,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn find_all_by_user_id<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+) -> Result<Vec<AppPassword>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        AppPassword,
+        r#"
+            SELECT
+                id,
+                user_id,
+                name,
+                token_hash,
+                created_at,
+                last_used_at,
+                scope AS "scope: AppPasswordScope"
+            FROM "app_password"
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn find_by_token_hash<'e, E>(
+    executor: E,
+    token_hash: &str,
+) -> Result<Option<AppPassword>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        AppPassword,
+        r#"
+            SELECT
+                id,
+                user_id,
+                name,
+                token_hash,
+                created_at,
+                last_used_at,
+                scope AS "scope: AppPasswordScope"
+            FROM "app_password"
+            WHERE token_hash = $1
+        "#,
+        token_hash,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn touch<'e, E>(executor: E, id: &Uuid) -> Result<AppPassword, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        AppPassword,
+        r#"
+            UPDATE "app_password"
+            SET last_used_at = now()
+            WHERE id = $1
+            RETURNING
+                id,
+                user_id,
+                name,
+                token_hash,
+                created_at,
+                last_used_at,
+                scope AS "scope: AppPasswordScope"
+        "#,
+        id,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn delete_by_user_id_and_id<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    id: &Uuid,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"DELETE FROM "app_password" WHERE user_id = $1 AND id = $2"#,
+        user_id,
+        id,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
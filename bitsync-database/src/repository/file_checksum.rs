@@ -0,0 +1,258 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::entity::FileChecksum;
+
+use super::QueryError;
+
+pub async fn upsert<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    sha256: &str,
+) -> Result<FileChecksum, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileChecksum,
+        r#"
+            INSERT INTO "file_checksum" (user_id, item_path, sha256)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, item_path) WHERE trash_entry_id IS NULL
+                DO UPDATE SET sha256 = EXCLUDED.sha256, updated_at = now()
+            RETURNING *
+        "#,
+        user_id,
+        item_path,
+        sha256,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn create_if_absent<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    sha256: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+            INSERT INTO "file_checksum" (user_id, item_path, sha256)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, item_path) WHERE trash_entry_id IS NULL DO NOTHING
+        "#,
+        user_id,
+        item_path,
+        sha256,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_by_user_id_and_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<Option<FileChecksum>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileChecksum,
+        r#"
+            SELECT * FROM "file_checksum"
+            WHERE user_id = $1 AND item_path = $2 AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        item_path,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn find_all_by_user_id<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+) -> Result<Vec<FileChecksum>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileChecksum,
+        r#"
+            SELECT * FROM "file_checksum"
+            WHERE user_id = $1 AND trash_entry_id IS NULL
+            ORDER BY item_path
+        "#,
+        user_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn delete_by_user_id_and_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+            DELETE FROM "file_checksum"
+            WHERE user_id = $1 AND item_path = $2 AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        item_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_by_user_id_at_or_below_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        DELETE FROM "file_checksum"
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+            AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        item_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn move_item_paths_at_or_below<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    source_path: &str,
+    destination_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "file_checksum"
+        SET item_path = rtrim($3, '/') || substr(item_path, length(rtrim($2, '/')) + 1)
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+            AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        source_path,
+        destination_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn copy_item_paths_at_or_below<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    source_path: &str,
+    destination_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO "file_checksum" (user_id, item_path, sha256)
+        SELECT
+            user_id,
+            rtrim($3, '/') || substr(item_path, length(rtrim($2, '/')) + 1),
+            sha256
+        FROM "file_checksum"
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+            AND trash_entry_id IS NULL
+        ON CONFLICT (user_id, item_path) WHERE trash_entry_id IS NULL DO UPDATE
+        SET sha256 = excluded.sha256, updated_at = now()
+        "#,
+        user_id,
+        source_path,
+        destination_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn trash_all_by_user_id_at_or_below_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    trash_entry_id: &Uuid,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "file_checksum"
+        SET trash_entry_id = $3
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+            AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        item_path,
+        trash_entry_id,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn restore_all_by_trash_entry_id<'e, E>(
+    executor: E,
+    trash_entry_id: &Uuid,
+    original_path: &str,
+    destination_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "file_checksum"
+        SET
+            item_path = rtrim($3, '/') || substr(item_path, length(rtrim($2, '/')) + 1),
+            trash_entry_id = NULL
+        WHERE trash_entry_id = $1
+        "#,
+        trash_entry_id,
+        original_path,
+        destination_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
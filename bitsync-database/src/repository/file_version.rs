@@ -0,0 +1,216 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::entity::FileVersion;
+
+use super::QueryError;
+
+pub async fn create<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    size: i64,
+) -> Result<FileVersion, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileVersion,
+        r#"INSERT INTO "file_version" (user_id, item_path, size) VALUES ($1, $2, $3) RETURNING *"#,
+        user_id,
+        item_path,
+        size,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn find_all_by_user_id_and_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<Vec<FileVersion>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileVersion,
+        r#"
+            SELECT * FROM "file_version"
+            WHERE user_id = $1 AND item_path = $2 AND trash_entry_id IS NULL
+            ORDER BY created_at DESC, id
+        "#,
+        user_id,
+        item_path,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn find_by_id_and_user_id<'e, E>(
+    executor: E,
+    id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Option<FileVersion>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileVersion,
+        r#"
+            SELECT * FROM "file_version"
+            WHERE id = $1 AND user_id = $2 AND trash_entry_id IS NULL
+        "#,
+        id,
+        user_id,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn find_all_by_user_id_at_or_below_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<Vec<FileVersion>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileVersion,
+        r#"
+            SELECT * FROM "file_version"
+            WHERE user_id = $1
+                AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+                AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        item_path,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn delete_by_id<'e, E>(executor: E, id: &Uuid) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(r#"DELETE FROM "file_version" WHERE id = $1"#, id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn move_item_paths_at_or_below<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    source_path: &str,
+    destination_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "file_version"
+        SET item_path = rtrim($3, '/') || substr(item_path, length(rtrim($2, '/')) + 1)
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+            AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        source_path,
+        destination_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_all_by_trash_entry_id<'e, E>(
+    executor: E,
+    trash_entry_id: &Uuid,
+) -> Result<Vec<FileVersion>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileVersion,
+        r#"SELECT * FROM "file_version" WHERE trash_entry_id = $1"#,
+        trash_entry_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn find_all_trashed_by_user_id<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+) -> Result<Vec<FileVersion>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        FileVersion,
+        r#"SELECT * FROM "file_version" WHERE user_id = $1 AND trash_entry_id IS NOT NULL"#,
+        user_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn trash_all_by_user_id_at_or_below_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    trash_entry_id: &Uuid,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "file_version"
+        SET trash_entry_id = $3
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+            AND trash_entry_id IS NULL
+        "#,
+        user_id,
+        item_path,
+        trash_entry_id,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn restore_all_by_trash_entry_id<'e, E>(
+    executor: E,
+    trash_entry_id: &Uuid,
+    original_path: &str,
+    destination_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "file_version"
+        SET
+            item_path = rtrim($3, '/') || substr(item_path, length(rtrim($2, '/')) + 1),
+            trash_entry_id = NULL
+        WHERE trash_entry_id = $1
+        "#,
+        trash_entry_id,
+        original_path,
+        destination_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
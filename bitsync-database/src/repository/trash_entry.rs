@@ -0,0 +1,96 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::entity::TrashEntry;
+
+use super::QueryError;
+
+pub async fn create<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    original_path: &str,
+) -> Result<TrashEntry, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        TrashEntry,
+        r#"INSERT INTO "trash_entry" (user_id, original_path) VALUES ($1, $2) RETURNING *"#,
+        user_id,
+        original_path,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn find_all_by_user_id<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+) -> Result<Vec<TrashEntry>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        TrashEntry,
+        r#"SELECT * FROM "trash_entry" WHERE user_id = $1 ORDER BY deleted_at DESC"#,
+        user_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn find_by_id_and_user_id<'e, E>(
+    executor: E,
+    id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Option<TrashEntry>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        TrashEntry,
+        r#"SELECT * FROM "trash_entry" WHERE id = $1 AND user_id = $2"#,
+        id,
+        user_id,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn find_all_deleted_before<'e, E>(
+    executor: E,
+    deleted_before: &time::OffsetDateTime,
+) -> Result<Vec<TrashEntry>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        TrashEntry,
+        r#"SELECT * FROM "trash_entry" WHERE deleted_at < $1"#,
+        deleted_before,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+pub async fn delete_by_id<'e, E>(executor: E, id: &Uuid) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(r#"DELETE FROM "trash_entry" WHERE id = $1"#, id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_by_user_id<'e, E>(executor: E, user_id: &Uuid) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(r#"DELETE FROM "trash_entry" WHERE user_id = $1"#, user_id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::entity::UploadSession;
+
+use super::QueryError;
+
+pub async fn create<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    upload_length: i64,
+) -> Result<UploadSession, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UploadSession,
+        r#"
+            INSERT INTO "upload_session" (user_id, item_path, upload_length)
+            VALUES ($1, $2, $3)
+            RETURNING *
+        "#,
+        user_id,
+        item_path,
+        upload_length,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn find_by_id_and_user_id<'e, E>(
+    executor: E,
+    id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Option<UploadSession>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UploadSession,
+        r#"SELECT * FROM "upload_session" WHERE id = $1 AND user_id = $2"#,
+        id,
+        user_id,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn find_all_by_user_id<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+) -> Result<Vec<UploadSession>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UploadSession,
+        r#"SELECT * FROM "upload_session" WHERE user_id = $1"#,
+        user_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+/// Locks the session until `locked_until`. Returns `None` if the session does not exist or is
+/// already locked.
+pub async fn lock_by_id_and_user_id<'e, E>(
+    executor: E,
+    id: &Uuid,
+    user_id: &Uuid,
+    locked_until: &time::OffsetDateTime,
+) -> Result<Option<UploadSession>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UploadSession,
+        r#"
+            UPDATE "upload_session"
+            SET locked_until = $3
+            WHERE id = $1 AND user_id = $2 AND (locked_until IS NULL OR locked_until < now())
+            RETURNING *
+        "#,
+        id,
+        user_id,
+        locked_until,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn extend_lock<'e, E>(
+    executor: E,
+    id: &Uuid,
+    locked_until: &time::OffsetDateTime,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"UPDATE "upload_session" SET locked_until = $2 WHERE id = $1"#,
+        id,
+        locked_until,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn unlock<'e, E>(executor: E, id: &Uuid) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"UPDATE "upload_session" SET locked_until = NULL WHERE id = $1"#,
+        id,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Advances the offset and unlocks the session, but only if the offset still equals
+/// `current_offset`, so concurrent writers cannot both succeed.
+pub async fn advance_offset<'e, E>(
+    executor: E,
+    id: &Uuid,
+    current_offset: i64,
+    new_offset: i64,
+) -> Result<Option<UploadSession>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UploadSession,
+        r#"
+            UPDATE "upload_session"
+            SET upload_offset = $3, updated_at = now(), locked_until = NULL
+            WHERE id = $1 AND upload_offset = $2
+            RETURNING *
+        "#,
+        id,
+        current_offset,
+        new_offset,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+/// Skips locked sessions, which are still receiving data.
+pub async fn find_all_updated_before<'e, E>(
+    executor: E,
+    updated_before: &time::OffsetDateTime,
+) -> Result<Vec<UploadSession>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UploadSession,
+        r#"
+            SELECT * FROM "upload_session"
+            WHERE updated_at < $1 AND (locked_until IS NULL OR locked_until < now())
+        "#,
+        updated_before,
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+/// Deletes the session unless it is locked. Returns whether it was deleted.
+pub async fn delete_unlocked_by_id<'e, E>(executor: E, id: &Uuid) -> Result<bool, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    let result = sqlx::query!(
+        r#"
+            DELETE FROM "upload_session"
+            WHERE id = $1 AND (locked_until IS NULL OR locked_until < now())
+        "#,
+        id,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_by_id<'e, E>(executor: E, id: &Uuid) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(r#"DELETE FROM "upload_session" WHERE id = $1"#, id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
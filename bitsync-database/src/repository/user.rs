@@ -27,6 +27,19 @@ where
     )
 }
 
+/// Locks the user for the rest of the transaction, which serializes changes that have to see each
+/// other, like reserving storage for uploads.
+pub async fn lock_by_id<'e, E>(executor: E, id: &Uuid) -> Result<User, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(
+        sqlx::query_as!(User, r#"SELECT * FROM "user" WHERE id = $1 FOR UPDATE"#, id)
+            .fetch_one(executor)
+            .await?,
+    )
+}
+
 pub async fn find_all_except<'e, E>(
     executor: E,
     except_user_id: &Uuid,
@@ -184,6 +197,24 @@ where
     .await?)
 }
 
+pub async fn set_storage_quota<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    storage_quota: Option<i64>,
+) -> Result<User, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        User,
+        r#"UPDATE "user" SET storage_quota = $2 WHERE id = $1 RETURNING *"#,
+        user_id,
+        storage_quota,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
 pub async fn delete<'e, E>(executor: E, user_id: &Uuid) -> Result<(), QueryError>
 where
     E: PgExecutor<'e>,
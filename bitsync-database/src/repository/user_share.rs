@@ -1,7 +1,7 @@
 use sqlx::PgExecutor;
 use uuid::Uuid;
 
-use crate::entity::UserShare;
+use crate::entity::{UserShare, UserSharePermission};
 
 use super::QueryError;
 
@@ -15,7 +15,20 @@ where
 {
     Ok(sqlx::query_as!(
         UserShare,
-        r#"INSERT INTO "user_share" (user_id, item_path) VALUES ($1, $2) RETURNING *"#,
+        r#"
+            INSERT INTO "user_share" (user_id, item_path)
+            VALUES ($1, $2)
+            RETURNING
+                id,
+                user_id,
+                item_path,
+                expires_at,
+                password,
+                max_downloads,
+                download_count,
+                shared_with_user_id,
+                permission AS "permission: UserSharePermission"
+        "#,
         user_id,
         item_path,
     )
@@ -33,7 +46,21 @@ where
 {
     Ok(sqlx::query_as!(
         UserShare,
-        r#"SELECT * FROM "user_share" WHERE user_id = $1 AND item_path = $2 ORDER BY id"#,
+        r#"
+            SELECT
+                id,
+                user_id,
+                item_path,
+                expires_at,
+                password,
+                max_downloads,
+                download_count,
+                shared_with_user_id,
+                permission AS "permission: UserSharePermission"
+            FROM "user_share"
+            WHERE user_id = $1 AND item_path = $2
+            ORDER BY id
+        "#,
         user_id,
         item_path,
     )
@@ -89,3 +116,201 @@ where
 
     Ok(())
 }
+
+pub async fn delete_all_by_user_id_at_or_below_item_path<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        DELETE FROM "user_share"
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+        "#,
+        user_id,
+        item_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn move_item_paths_at_or_below<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    source_path: &str,
+    destination_path: &str,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        UPDATE "user_share"
+        SET item_path = rtrim($3, '/') || substr(item_path, length(rtrim($2, '/')) + 1)
+        WHERE user_id = $1
+            AND (item_path = $2 OR starts_with(item_path, rtrim($2, '/') || '/'))
+        "#,
+        user_id,
+        source_path,
+        destination_path,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_by_id<'e, E>(executor: E, id: &Uuid) -> Result<Option<UserShare>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UserShare,
+        r#"
+            SELECT
+                id,
+                user_id,
+                item_path,
+                expires_at,
+                password,
+                max_downloads,
+                download_count,
+                shared_with_user_id,
+                permission AS "permission: UserSharePermission"
+            FROM "user_share"
+            WHERE id = $1
+        "#,
+        id,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn update_restrictions<'e, E>(
+    executor: E,
+    id: &Uuid,
+    user_id: &Uuid,
+    expires_at: Option<&time::OffsetDateTime>,
+    password: Option<&str>,
+    max_downloads: Option<i32>,
+) -> Result<(), QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query!(
+        r#"UPDATE "user_share" SET expires_at = $3, password = $4, max_downloads = $5 WHERE id = $1 AND user_id = $2"#,
+        id,
+        user_id,
+        expires_at,
+        password,
+        max_downloads,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn increment_download_count<'e, E>(
+    executor: E,
+    id: &Uuid,
+) -> Result<Option<UserShare>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UserShare,
+        r#"
+            UPDATE "user_share"
+            SET download_count = download_count + 1
+            WHERE id = $1 AND (max_downloads IS NULL OR download_count < max_downloads)
+            RETURNING
+                id,
+                user_id,
+                item_path,
+                expires_at,
+                password,
+                max_downloads,
+                download_count,
+                shared_with_user_id,
+                permission AS "permission: UserSharePermission"
+        "#,
+        id,
+    )
+    .fetch_optional(executor)
+    .await?)
+}
+
+pub async fn create_for_recipient<'e, E>(
+    executor: E,
+    user_id: &Uuid,
+    item_path: &str,
+    shared_with_user_id: &Uuid,
+    permission: &UserSharePermission,
+) -> Result<UserShare, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UserShare,
+        r#"
+            INSERT INTO "user_share" (user_id, item_path, shared_with_user_id, permission)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, item_path, shared_with_user_id)
+                WHERE shared_with_user_id IS NOT NULL
+                DO UPDATE SET permission = EXCLUDED.permission
+            RETURNING
+                id,
+                user_id,
+                item_path,
+                expires_at,
+                password,
+                max_downloads,
+                download_count,
+                shared_with_user_id,
+                permission AS "permission: UserSharePermission"
+        "#,
+        user_id,
+        item_path,
+        shared_with_user_id,
+        permission as &UserSharePermission,
+    )
+    .fetch_one(executor)
+    .await?)
+}
+
+pub async fn find_all_by_shared_with_user_id<'e, E>(
+    executor: E,
+    shared_with_user_id: &Uuid,
+) -> Result<Vec<UserShare>, QueryError>
+where
+    E: PgExecutor<'e>,
+{
+    Ok(sqlx::query_as!(
+        UserShare,
+        r#"
+            SELECT
+                id,
+                user_id,
+                item_path,
+                expires_at,
+                password,
+                max_downloads,
+                download_count,
+                shared_with_user_id,
+                permission AS "permission: UserSharePermission"
+            FROM "user_share"
+            WHERE shared_with_user_id = $1
+            ORDER BY item_path, id
+        "#,
+        shared_with_user_id,
+    )
+    .fetch_all(executor)
+    .await?)
+}
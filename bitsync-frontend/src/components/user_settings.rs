@@ -1,10 +1,12 @@
 use bitsync_core::use_case::user_share::list_shared_paths::SharedPath;
-use bitsync_database::entity::{InviteToken, Session, User};
+use bitsync_database::entity::{AppPassword, InviteToken, Session, User};
 use hypertext::prelude::*;
 use uuid::Uuid;
 
 use crate::Component;
 
+pub mod app_passwords;
+pub mod integrity;
 pub mod invites;
 pub mod password;
 pub mod sessions;
@@ -13,6 +15,7 @@ pub mod totp;
 pub mod users;
 
 use self::{
+    app_passwords::AppPasswordsTabContent, integrity::IntegrityTabContent,
     invites::InvitesTabContent, password::PasswordTabContent, sessions::SessionsTabContent,
     shares::SharesTabContent, totp::TotpTabContent, users::UsersTabContent,
 };
@@ -26,6 +29,9 @@ pub enum SettingsTab {
         sessions: Vec<Session>,
         current_session_id: Uuid,
     },
+    AppPasswords {
+        app_passwords: Vec<AppPassword>,
+    },
     Totp(TotpTabContent),
     Users {
         users: Vec<User>,
@@ -36,6 +42,7 @@ pub enum SettingsTab {
     Invites {
         invite_tokens: Vec<InviteToken>,
     },
+    Integrity,
 }
 
 pub struct SettingsDialog {
@@ -92,10 +99,12 @@ impl Renderable for SettingsTabArea {
     fn render_to(&self, buffer: &mut hypertext::Buffer) {
         let is_password_active = matches!(self.active_tab, SettingsTab::Password);
         let is_sessions_active = matches!(self.active_tab, SettingsTab::Sessions { .. });
+        let is_app_passwords_active = matches!(self.active_tab, SettingsTab::AppPasswords { .. });
         let is_shares_active = matches!(self.active_tab, SettingsTab::Shares { .. });
         let is_totp_active = matches!(self.active_tab, SettingsTab::Totp(..));
         let is_users_active = matches!(self.active_tab, SettingsTab::Users { .. });
         let is_invites_active = matches!(self.active_tab, SettingsTab::Invites { .. });
+        let is_integrity_active = matches!(self.active_tab, SettingsTab::Integrity);
 
         fn tab_class(active: bool) -> String {
             if active {
@@ -111,10 +120,12 @@ impl Renderable for SettingsTabArea {
 
         let password_tab_class = tab_class(is_password_active);
         let sessions_tab_class = tab_class(is_sessions_active);
+        let app_passwords_tab_class = tab_class(is_app_passwords_active);
         let shares_tab_class = tab_class(is_shares_active);
         let totp_tab_class = tab_class(is_totp_active);
         let users_tab_class = tab_class(is_users_active);
         let invites_tab_class = tab_class(is_invites_active);
+        let integrity_tab_class = tab_class(is_integrity_active);
 
         maud! {
             div id=(self.id()) {
@@ -140,6 +151,18 @@ impl Renderable for SettingsTabArea {
                             "Sessions"
                             div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
                         }
+                        button
+                            class=(app_passwords_tab_class)
+                            data-init=(format!("this.fetch = fetch('{}')", bitsync_routes::GetUserSettingsAppPasswordsTab))
+                            data-on-click__throttle.1s="this.fetch.trigger()"
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.fetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            "App Passwords"
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                        }
                         button
                             class=(shares_tab_class)
                             data-init=(format!("this.fetch = fetch('{}')", bitsync_routes::GetUserSettingsSharesTab))
@@ -201,6 +224,18 @@ impl Renderable for SettingsTabArea {
                                 "Invites"
                                 div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
                             }
+                            button
+                                class=(integrity_tab_class)
+                                data-init=(format!("this.fetch = fetch('{}')", bitsync_routes::GetUserSettingsIntegrityTab))
+                                data-on-click__throttle.1s="this.fetch.trigger()"
+                                data-effect=(format!(
+                                    "handleButtonLoading(this, this.fetch, '{loading}')",
+                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                ))
+                            {
+                                "Integrity"
+                                div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            }
                         }
                     }
                 }
@@ -214,6 +249,14 @@ impl Renderable for SettingsTabArea {
                             current_session_id: *current_session_id,
                         })
                     }
+                    SettingsTab::AppPasswords { app_passwords } => {
+                        (AppPasswordsTabContent {
+                            app_passwords: app_passwords.clone(),
+                            created_app_password: None,
+                            is_admin: self.is_admin,
+                            error: None,
+                        })
+                    }
                     SettingsTab::Totp(content) => {
                         (content.clone())
                     }
@@ -226,6 +269,9 @@ impl Renderable for SettingsTabArea {
                     SettingsTab::Invites { invite_tokens } => {
                         (InvitesTabContent { invite_tokens: invite_tokens.clone() })
                     }
+                    SettingsTab::Integrity => {
+                        (IntegrityTabContent)
+                    }
                 }
             }
         }
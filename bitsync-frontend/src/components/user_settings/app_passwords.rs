@@ -0,0 +1,218 @@
+use bitsync_database::entity::{AppPassword, AppPasswordScope};
+use hypertext::prelude::*;
+
+use crate::{Component, error_banner::OptionalErrorBanner};
+
+pub enum AppPasswordsDisplayError {
+    InternalServerError,
+    EmptyName,
+    AdminScopeNotAllowed,
+}
+
+impl AppPasswordsDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InternalServerError => "An internal server error occurred",
+            Self::EmptyName => "Name cannot be empty",
+            Self::AdminScopeNotAllowed => "Only admins can create app passwords with admin access",
+        }
+    }
+}
+
+static APP_PASSWORDS_TAB_CONTENT_ID: &str = "app-passwords-tab-content";
+
+fn format_datetime(datetime: &time::OffsetDateTime) -> String {
+    let format = time::macros::format_description!(
+        "[month repr:short] [day], [year], [hour repr:12]:[minute] [period]"
+    );
+
+    datetime
+        .format(&format)
+        .unwrap_or_else(|_| "Unknown".to_owned())
+}
+
+/// An app password that was just created. Its token is only known until the response is sent.
+pub struct CreatedAppPassword {
+    pub name: String,
+    pub token: String,
+}
+
+pub struct AppPasswordsTabContent {
+    pub app_passwords: Vec<AppPassword>,
+    pub created_app_password: Option<CreatedAppPassword>,
+    pub is_admin: bool,
+    pub error: Option<AppPasswordsDisplayError>,
+}
+
+impl Component for AppPasswordsTabContent {
+    fn id(&self) -> String {
+        APP_PASSWORDS_TAB_CONTENT_ID.to_owned()
+    }
+}
+
+impl Renderable for AppPasswordsTabContent {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div id=(self.id()) class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                p class=(crate::styles::modal::ClassName::MODAL_DESCRIPTION) {
+                    "App passwords let scripts, WebDAV clients and file managers sign in without using your password or TOTP. Use one with your username on "
+                    code { (bitsync_routes::DavRootDirectory.to_string()) }
+                    " or send it as "
+                    code { "Authorization: Bearer" }
+                    " header."
+                }
+
+                OptionalErrorBanner message=(self.error.as_ref().map(|error| error.message().to_owned()));
+
+                @if let Some(created_app_password) = &self.created_app_password {
+                    div class=(crate::styles::user_settings_page::ClassName::CREATED_APP_PASSWORD) {
+                        p class=(crate::styles::user_settings_page::ClassName::CREATED_APP_PASSWORD_HINT) {
+                            "Copy the app password for "
+                            strong { (created_app_password.name) }
+                            " now. It will not be shown again."
+                        }
+
+                        div class=(crate::styles::user_settings_page::ClassName::INVITE_ITEM) {
+                            div class=(crate::styles::user_settings_page::ClassName::INVITE_TOKEN_WRAPPER) {
+                                pre class=(crate::styles::token_box::ClassName::TOKEN_ID) {
+                                    (created_app_password.token)
+                                }
+                            }
+
+                            button
+                                type="button"
+                                class=(crate::styles::button::ClassName::ICON_BUTTON)
+                                title="Copy app password"
+                                data-on-click=(format!(
+                                    "navigator.clipboard.writeText('{token}'), this.classList.add('{copied}'), setTimeout(() => this.classList.remove('{copied}'), 1200)",
+                                    token = created_app_password.token,
+                                    copied = crate::styles::token_box::ClassName::COPIED,
+                                ))
+                            {
+                                (crate::icons::Copy::with_class(crate::styles::token_box::ClassName::COPY_ICON_DEFAULT))
+
+                                (crate::icons::Check::with_class(crate::styles::token_box::ClassName::COPY_ICON_COPIED))
+                            }
+                        }
+                    }
+                }
+
+                form
+                    class=(crate::styles::user_settings_page::ClassName::APP_PASSWORD_FORM)
+                    data-hijack
+                    action=(bitsync_routes::PostUserSettingsAppPasswordCreate.to_string())
+                    method="POST"
+                {
+                    input
+                        class=(crate::styles::base::ClassName::FORM_CONTROL)
+                        type="text"
+                        name="name"
+                        placeholder="Name, e.g. Laptop file manager";
+
+                    select
+                        class=(crate::styles::base::ClassName::FORM_CONTROL)
+                        name="scope"
+                    {
+                        option value="read" { "Read only" }
+                        option value="write" selected { "Read & write" }
+                        @if self.is_admin {
+                            option value="admin" { "Admin" }
+                        }
+                    }
+
+                    button
+                        type="submit"
+                        class=(
+                            crate::styles::button::ClassName::BUTTON, " ",
+                            crate::styles::button::ClassName::BUTTON_PRIMARY,
+                        )
+                        data-effect=(format!(
+                            "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                        ))
+                    {
+                        div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+
+                        (crate::icons::Plus::default())
+
+                        "Create"
+                    }
+                }
+
+                div class=(crate::styles::user_settings_page::ClassName::SESSION_LIST) {
+                    @if self.app_passwords.is_empty() {
+                        p class=(crate::styles::empty_state::ClassName::EMPTY_STATE) {
+                            "No app passwords yet."
+                        }
+                    } @else {
+                        @for app_password in &self.app_passwords {
+                            div class=(crate::styles::user_settings_page::ClassName::SESSION_ITEM) {
+                                div class=(crate::styles::user_settings_page::ClassName::SESSION_INFO) {
+                                    div class=(crate::styles::user_settings_page::ClassName::SESSION_DEVICE) {
+                                        (app_password.name)
+
+                                        @match app_password.scope {
+                                            AppPasswordScope::Read => {
+                                                span class=(crate::styles::badge::ClassName::BADGE) { "Read only" }
+                                            }
+                                            AppPasswordScope::Write => {
+                                                span class=(
+                                                    crate::styles::badge::ClassName::BADGE, " ",
+                                                    crate::styles::badge::ClassName::BADGE_WARNING,
+                                                ) {
+                                                    "Read & write"
+                                                }
+                                            }
+                                            AppPasswordScope::Admin => {
+                                                span class=(
+                                                    crate::styles::badge::ClassName::BADGE, " ",
+                                                    crate::styles::badge::ClassName::BADGE_DANGER,
+                                                ) {
+                                                    "Admin"
+                                                }
+                                            }
+                                        }
+                                    }
+                                    div class=(crate::styles::user_settings_page::ClassName::SESSION_META) {
+                                        span { "Created " (format_datetime(&app_password.created_at)) }
+                                        @if let Some(last_used_at) = &app_password.last_used_at {
+                                            span { "Last used " (format_datetime(last_used_at)) }
+                                        } @else {
+                                            span { "Never used" }
+                                        }
+                                    }
+                                }
+
+                                form
+                                    data-hijack
+                                    action=(bitsync_routes::PostUserSettingsAppPasswordRevoke {
+                                        app_password_id: app_password.id,
+                                    }.to_string())
+                                    method="POST"
+                                {
+                                    button
+                                        type="submit"
+                                        class=(
+                                            crate::styles::button::ClassName::ICON_BUTTON, " ",
+                                            crate::styles::button::ClassName::ICON_BUTTON_DANGER,
+                                        )
+                                        title="Revoke app password"
+                                        data-effect=(format!(
+                                            "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                        ))
+                                    {
+                                        div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+
+                                        (crate::icons::CircleX::default())
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -0,0 +1,169 @@
+use bitsync_core::use_case::file_checksum::scrub_file_checksums::{
+    FileChecksumScrubProgress, FileChecksumScrubReport,
+};
+use hypertext::prelude::*;
+
+use crate::Component;
+
+static SCRUB_REPORT_ID: &str = "scrub-report";
+static SCRUB_PROGRESS_INDICATOR_ID: &str = "scrub-progress-indicator";
+
+pub struct IntegrityTabContent;
+
+impl Renderable for IntegrityTabContent {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                p class=(crate::styles::modal::ClassName::MODAL_DESCRIPTION) {
+                    "A scrub re-reads every stored file and compares it with the checksum recorded when it was written. Files without a checksum get one recorded. Depending on the amount of stored data this can take a while, the scrub keeps running when this page is closed."
+                }
+
+                (ScrubReport { report: None })
+
+                div class=(crate::styles::user_settings_page::ClassName::INVITES_ACTIONS) {
+                    button
+                        class=(
+                            crate::styles::button::ClassName::BUTTON, " ",
+                            crate::styles::button::ClassName::BUTTON_PRIMARY,
+                        )
+                        data-init=(format!(
+                            "this.fetch = fetch('{}', {{ method: 'POST' }})",
+                            bitsync_routes::PostUserSettingsIntegrityScrub,
+                        ))
+                        data-on-click__throttle.1s="this.fetch.trigger()"
+                        data-effect=(format!(
+                            "handleButtonLoading(this, this.fetch, '{loading}')",
+                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                        ))
+                    {
+                        div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+
+                        (crate::icons::RotateCcw::default())
+
+                        "Run Scrub"
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+/// Takes the place of the report while a scrub runs and polls its progress until the finished
+/// report replaces it.
+pub struct ScrubProgress {
+    pub progress_url: String,
+    pub progress: ScrubProgressIndicator,
+}
+
+impl Component for ScrubProgress {
+    fn id(&self) -> String {
+        SCRUB_REPORT_ID.to_owned()
+    }
+}
+
+impl Renderable for ScrubProgress {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div
+                id=(self.id())
+                class=(crate::styles::user_settings_page::ClassName::SCRUB_REPORT)
+                data-init=(format!(
+                    "this.progressFetch = fetch('{}'), this.progressInterval = setInterval(() => this.isConnected ? this.progressFetch.trigger() : clearInterval(this.progressInterval), 1000)",
+                    self.progress_url,
+                ))
+            {
+                (self.progress)
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct ScrubProgressIndicator {
+    pub progress: FileChecksumScrubProgress,
+}
+
+impl Component for ScrubProgressIndicator {
+    fn id(&self) -> String {
+        SCRUB_PROGRESS_INDICATOR_ID.to_owned()
+    }
+}
+
+impl Renderable for ScrubProgressIndicator {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let FileChecksumScrubProgress {
+            scrubbed_user_count,
+            total_user_count,
+            checked_file_count,
+        } = self.progress;
+        let scrubbed_percentage = match total_user_count {
+            0 => 0,
+            total_user_count => scrubbed_user_count * 100 / total_user_count,
+        };
+
+        maud! {
+            div id=(self.id()) {
+                div class=(crate::styles::modal::ClassName::PROGRESS_BAR) {
+                    div
+                        class=(crate::styles::modal::ClassName::PROGRESS_BAR_FILL)
+                        style=(format!("width: {scrubbed_percentage}%"))
+                    {}
+                }
+                p class=(crate::styles::modal::ClassName::PROGRESS_LABEL) {
+                    (scrubbed_user_count) " of " (total_user_count) " users scrubbed, "
+                    (checked_file_count) " files checked"
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct ScrubReport {
+    pub report: Option<FileChecksumScrubReport>,
+}
+
+impl Component for ScrubReport {
+    fn id(&self) -> String {
+        SCRUB_REPORT_ID.to_owned()
+    }
+}
+
+impl Renderable for ScrubReport {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div
+                id=(self.id())
+                class=(crate::styles::user_settings_page::ClassName::SCRUB_REPORT)
+            {
+                @if let Some(report) = &self.report {
+                    p class=(crate::styles::user_settings_page::ClassName::SCRUB_SUMMARY) {
+                        (report.checked_file_count) " files checked, "
+                        (report.recorded_checksum_count) " new checksums recorded, "
+                        (report.removed_checksum_count) " outdated checksums removed"
+                    }
+
+                    @if report.mismatches.is_empty() {
+                        p class=(crate::styles::empty_state::ClassName::EMPTY_STATE) {
+                            "All files match their recorded checksums."
+                        }
+                    } @else {
+                        @for mismatch in &report.mismatches {
+                            div class=(crate::styles::user_settings_page::ClassName::SCRUB_MISMATCH) {
+                                div class=(crate::styles::user_settings_page::ClassName::SCRUB_MISMATCH_PATH) {
+                                    (mismatch.username) ": " (mismatch.item_path)
+                                }
+                                div class=(crate::styles::user_settings_page::ClassName::SCRUB_MISMATCH_CHECKSUMS) {
+                                    span { "Expected " (mismatch.expected_checksum) }
+                                    span { "Actual " (mismatch.actual_checksum) }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -1,4 +1,5 @@
-use bitsync_database::entity::UserShare;
+use bitsync_core::use_case::user_share::shared::UserShareListing;
+use bitsync_database::entity::{UserShare, UserSharePermission};
 use bitsync_routes::TypedPath;
 use hypertext::prelude::*;
 
@@ -7,24 +8,36 @@ use crate::{Component, error_banner::OptionalErrorBanner};
 use crate::pages::files::FilesHomePageElementId;
 
 pub enum ShareDisplayError {
+    InvalidExpiry,
+    InvalidMaxDownloads,
+    NotFound,
+    RecipientNotFound,
+    RecipientIsOwner,
     InternalServerError,
 }
 
 impl ShareDisplayError {
     pub fn message(&self) -> &'static str {
         match self {
+            Self::InvalidExpiry => "Expiry must be a valid date and time",
+            Self::InvalidMaxDownloads => "Maximum downloads must be a number of at least one",
+            Self::NotFound => "The share does not exist",
+            Self::RecipientNotFound => "No active user with this username exists",
+            Self::RecipientIsOwner => "Items cannot be shared with yourself",
             Self::InternalServerError => "An internal server error occurred",
         }
     }
 }
 
 static SHARE_DIALOG_BODY_ID: &str = "share-dialog-body";
+static SHARE_SETTINGS_FORM_ID_PREFIX: &str = "share-settings";
 
 pub struct FileShareDialog {
-    pub user_shares: Vec<UserShare>,
+    pub user_shares: Vec<UserShareListing>,
     pub item_path: String,
     pub create_user_share_url: String,
     pub delete_all_user_shares_url: String,
+    pub share_with_user_url: String,
 }
 
 impl Component for FileShareDialog {
@@ -57,6 +70,7 @@ impl Renderable for FileShareDialog {
                     item_path: self.item_path.clone(),
                     create_user_share_url: self.create_user_share_url.clone(),
                     delete_all_user_shares_url: self.delete_all_user_shares_url.clone(),
+                    share_with_user_url: self.share_with_user_url.clone(),
                     error: None,
                 })
             }
@@ -66,10 +80,11 @@ impl Renderable for FileShareDialog {
 }
 
 pub struct ShareDialogBody {
-    pub user_shares: Vec<UserShare>,
+    pub user_shares: Vec<UserShareListing>,
     pub item_path: String,
     pub create_user_share_url: String,
     pub delete_all_user_shares_url: String,
+    pub share_with_user_url: String,
     pub error: Option<ShareDisplayError>,
 }
 
@@ -85,13 +100,18 @@ impl Renderable for ShareDialogBody {
             div id=(self.id()) {
                 div class=(crate::styles::modal::ClassName::MODAL_BODY) {
                     p class=(crate::styles::modal::ClassName::MODAL_DESCRIPTION) {
-                        "Share links for this item. Anyone with a share link can access this file."
+                        "Share links for this item. Anyone with a share link can access this item unless it is protected by a password. Items can also be shared with other users of this instance."
                     }
 
                     OptionalErrorBanner message=(self.error.as_ref().map(|error| error.message().to_owned()));
 
                     (ShareList {
-                        user_shares: self.user_shares.clone(),
+                        user_shares: self
+                            .user_shares
+                            .iter()
+                            .filter(|listing| listing.user_share.shared_with_user_id.is_none())
+                            .map(|listing| listing.user_share.clone())
+                            .collect(),
                         item_path: self.item_path.clone(),
                     })
 
@@ -137,6 +157,17 @@ impl Renderable for ShareDialogBody {
                             }
                         }
                     }
+
+                    (RecipientShareSection {
+                        user_shares: self
+                            .user_shares
+                            .iter()
+                            .filter(|listing| listing.user_share.shared_with_user_id.is_some())
+                            .map(|listing| (listing.user_share.clone(), listing.recipient_username.clone().unwrap_or_default()))
+                            .collect(),
+                        item_path: self.item_path.clone(),
+                        share_with_user_url: self.share_with_user_url.clone(),
+                    })
                 }
             }
         }
@@ -144,23 +175,63 @@ impl Renderable for ShareDialogBody {
     }
 }
 
-struct ShareList {
-    user_shares: Vec<UserShare>,
+struct RecipientShareSection {
+    user_shares: Vec<(UserShare, String)>,
     item_path: String,
+    share_with_user_url: String,
 }
 
-impl Renderable for ShareList {
+impl Renderable for RecipientShareSection {
     fn render_to(&self, buffer: &mut hypertext::Buffer) {
         maud! {
-            div class=(crate::styles::files_home_page::ClassName::SHARE_LIST) {
-                @if self.user_shares.is_empty() {
-                    p class=(crate::styles::empty_state::ClassName::EMPTY_STATE) {
-                        "No shares yet. Add one to create a share link."
+            div class=(crate::styles::files_home_page::ClassName::RECIPIENT_SHARES) {
+                h3 class=(crate::styles::files_home_page::ClassName::RECIPIENT_SHARES_TITLE) {
+                    "Shared with users"
+                }
+
+                form
+                    class=(crate::styles::files_home_page::ClassName::RECIPIENT_SHARE_FORM)
+                    data-hijack
+                    action=(self.share_with_user_url)
+                    method="POST"
+                {
+                    input
+                        class=(crate::styles::base::ClassName::FORM_CONTROL)
+                        type="text"
+                        name="username"
+                        placeholder="Username"
+                        autocomplete="off"
+                        required;
+
+                    select
+                        class=(crate::styles::base::ClassName::FORM_CONTROL)
+                        name="permission"
+                    {
+                        option value="read" selected { "Can view" }
+                        option value="read_write" { "Can edit" }
                     }
-                } @else {
-                    @for user_share in &self.user_shares {
-                        (ShareItem {
+
+                    button
+                        type="submit"
+                        class=(
+                            crate::styles::button::ClassName::BUTTON, " ",
+                            crate::styles::button::ClassName::BUTTON_PRIMARY,
+                        )
+                        data-effect=(format!(
+                            "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                        ))
+                    {
+                        div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                        "Share"
+                    }
+                }
+
+                div class=(crate::styles::files_home_page::ClassName::SHARE_LIST) {
+                    @for (user_share, recipient_username) in &self.user_shares {
+                        (RecipientShareItem {
                             user_share: user_share.clone(),
+                            recipient_username: recipient_username.clone(),
                             item_path: self.item_path.clone(),
                         })
                     }
@@ -171,15 +242,14 @@ impl Renderable for ShareList {
     }
 }
 
-struct ShareItem {
+struct RecipientShareItem {
     user_share: UserShare,
+    recipient_username: String,
     item_path: String,
 }
 
-impl Renderable for ShareItem {
+impl Renderable for RecipientShareItem {
     fn render_to(&self, buffer: &mut hypertext::Buffer) {
-        let token = self.user_share.id.to_string();
-
         let delete_url = bitsync_routes::PostUserFileShareDelete {
             user_share_id: self.user_share.id,
         }
@@ -190,45 +260,33 @@ impl Renderable for ShareItem {
 
         maud! {
             div class=(crate::styles::files_home_page::ClassName::SHARE_ITEM) {
-                div class=(crate::styles::files_home_page::ClassName::SHARE_TOKEN_WRAPPER) {
-                    pre
-                        class=(crate::styles::token_box::ClassName::TOKEN_ID)
-                        data-init=(format!(
-                            "this.updateOverflow = createHorizontalOverflowHandler(this, '{overflow_left}', '{overflow_right}'), this.updateOverflow()",
-                            overflow_left = crate::styles::files_home_page::ClassName::OVERFLOW_LEFT,
-                            overflow_right = crate::styles::files_home_page::ClassName::OVERFLOW_RIGHT,
-                        ))
-                        data-on-scroll="this.updateOverflow()"
-                    {
-                        (token)
+                div class=(crate::styles::files_home_page::ClassName::RECIPIENT_NAME) {
+                    (crate::icons::User::default())
+                    span { (self.recipient_username) }
+
+                    @match self.user_share.permission {
+                        UserSharePermission::Read => {
+                            span class=(crate::styles::badge::ClassName::BADGE) { "Can view" }
+                        }
+                        UserSharePermission::ReadWrite => {
+                            span class=(
+                                crate::styles::badge::ClassName::BADGE, " ",
+                                crate::styles::badge::ClassName::BADGE_WARNING,
+                            ) {
+                                "Can edit"
+                            }
+                        }
                     }
                 }
 
                 div class=(crate::styles::files_home_page::ClassName::SHARE_ACTIONS_GROUP) {
-                    button
-                        type="button"
-                        class=(crate::styles::button::ClassName::ICON_BUTTON)
-                        title="Copy share id"
-                        data-init=(format!(
-                            "this.shareId = '{token}'"
-                        ))
-                        data-on-click=(format!(
-                            "navigator.clipboard.writeText(this.shareId), this.classList.add('{copied}'), setTimeout(() => this.classList.remove('{copied}'), 1200)",
-                            copied = crate::styles::token_box::ClassName::COPIED,
-                        ))
-                    {
-                        (crate::icons::Link::with_class(crate::styles::token_box::ClassName::COPY_ICON_DEFAULT))
-
-                        (crate::icons::Check::with_class(crate::styles::token_box::ClassName::COPY_ICON_COPIED))
-                    }
-
                     button
                         type="button"
                         class=(
                             crate::styles::button::ClassName::ICON_BUTTON, " ",
                             crate::styles::button::ClassName::ICON_BUTTON_DANGER,
                         )
-                        title="Remove share"
+                        title="Stop sharing"
                         data-init=(format!(
                             "this.fetch = fetch('{}', {{ method: 'POST' }})",
                             delete_url,
@@ -248,3 +306,265 @@ impl Renderable for ShareItem {
         .render_to(buffer);
     }
 }
+
+struct ShareList {
+    user_shares: Vec<UserShare>,
+    item_path: String,
+}
+
+impl Renderable for ShareList {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::SHARE_LIST) {
+                @if self.user_shares.is_empty() {
+                    p class=(crate::styles::empty_state::ClassName::EMPTY_STATE) {
+                        "No shares yet. Add one to create a share link."
+                    }
+                } @else {
+                    @for user_share in &self.user_shares {
+                        (ShareItem {
+                            user_share: user_share.clone(),
+                            item_path: self.item_path.clone(),
+                        })
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+struct ShareItem {
+    user_share: UserShare,
+    item_path: String,
+}
+
+impl Renderable for ShareItem {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let token = self.user_share.id.to_string();
+
+        let delete_url = bitsync_routes::PostUserFileShareDelete {
+            user_share_id: self.user_share.id,
+        }
+        .with_query_params(bitsync_routes::PostUserFileShareDeleteQueryParameters {
+            path: self.item_path.clone(),
+        })
+        .to_string();
+
+        let share_path = bitsync_routes::GetPublicSharePage {
+            user_share_id: self.user_share.id,
+        }
+        .to_string();
+
+        let update_url = bitsync_routes::PostUserFileShareUpdate {
+            user_share_id: self.user_share.id,
+        }
+        .with_query_params(bitsync_routes::PostUserFileShareUpdateQueryParameters {
+            path: self.item_path.clone(),
+        })
+        .to_string();
+
+        let settings_form_id = format!("{SHARE_SETTINGS_FORM_ID_PREFIX}-{token}");
+
+        let is_expired = self
+            .user_share
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= time::OffsetDateTime::now_utc());
+
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::SHARE_ENTRY) {
+                div class=(crate::styles::files_home_page::ClassName::SHARE_ITEM) {
+                    div class=(crate::styles::files_home_page::ClassName::SHARE_TOKEN_WRAPPER) {
+                        pre
+                            class=(crate::styles::token_box::ClassName::TOKEN_ID)
+                            data-init=(format!(
+                                "this.updateOverflow = createHorizontalOverflowHandler(this, '{overflow_left}', '{overflow_right}'), this.updateOverflow()",
+                                overflow_left = crate::styles::files_home_page::ClassName::OVERFLOW_LEFT,
+                                overflow_right = crate::styles::files_home_page::ClassName::OVERFLOW_RIGHT,
+                            ))
+                            data-on-scroll="this.updateOverflow()"
+                        {
+                            (token)
+                        }
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::SHARE_ACTIONS_GROUP) {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::ICON_BUTTON)
+                            title="Share settings"
+                            onclick=(format!("document.getElementById('{settings_form_id}').toggleAttribute('hidden')"))
+                        {
+                            (crate::icons::Settings2::default())
+                        }
+
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::ICON_BUTTON)
+                            title="Copy share link"
+                            data-init=(format!(
+                                "this.shareLink = window.location.origin + '{share_path}'"
+                            ))
+                            data-on-click=(format!(
+                                "navigator.clipboard.writeText(this.shareLink), this.classList.add('{copied}'), setTimeout(() => this.classList.remove('{copied}'), 1200)",
+                                copied = crate::styles::token_box::ClassName::COPIED,
+                            ))
+                        {
+                            (crate::icons::Link::with_class(crate::styles::token_box::ClassName::COPY_ICON_DEFAULT))
+
+                            (crate::icons::Check::with_class(crate::styles::token_box::ClassName::COPY_ICON_COPIED))
+                        }
+
+                        button
+                            type="button"
+                            class=(
+                                crate::styles::button::ClassName::ICON_BUTTON, " ",
+                                crate::styles::button::ClassName::ICON_BUTTON_DANGER,
+                            )
+                            title="Remove share"
+                            data-init=(format!(
+                                "this.fetch = fetch('{}', {{ method: 'POST' }})",
+                                delete_url,
+                            ))
+                            data-on-click__throttle.1s="this.fetch.trigger()"
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.fetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            (crate::icons::CircleX::default())
+                        }
+                    }
+                }
+
+                @if is_expired || self.user_share.password.is_some() || self.user_share.expires_at.is_some() || self.user_share.max_downloads.is_some() {
+                    div class=(crate::styles::files_home_page::ClassName::SHARE_RESTRICTIONS) {
+                        @if is_expired {
+                            span class=(
+                                crate::styles::badge::ClassName::BADGE, " ",
+                                crate::styles::badge::ClassName::BADGE_DANGER,
+                            ) {
+                                "Expired"
+                            }
+                        } @else if let Some(expires_at) = &self.user_share.expires_at {
+                            span class=(
+                                crate::styles::badge::ClassName::BADGE, " ",
+                                crate::styles::badge::ClassName::BADGE_WARNING,
+                            ) {
+                                "Expires " (format_datetime(expires_at))
+                            }
+                        }
+
+                        @if self.user_share.password.is_some() {
+                            span class=(
+                                crate::styles::badge::ClassName::BADGE, " ",
+                                crate::styles::badge::ClassName::BADGE_SUCCESS,
+                            ) {
+                                "Password"
+                            }
+                        }
+
+                        @if let Some(max_downloads) = self.user_share.max_downloads {
+                            span class=(
+                                crate::styles::badge::ClassName::BADGE, " ",
+                                crate::styles::badge::ClassName::BADGE_WARNING,
+                            ) {
+                                (self.user_share.download_count) " / " (max_downloads) " downloads"
+                            }
+                        }
+                    }
+                }
+
+                form
+                    id=(settings_form_id)
+                    class=(crate::styles::files_home_page::ClassName::SHARE_SETTINGS)
+                    data-hijack
+                    action=(update_url)
+                    method="POST"
+                    hidden
+                {
+                    label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                        "Expires at (UTC)"
+
+                        input
+                            class=(crate::styles::base::ClassName::FORM_CONTROL)
+                            type="datetime-local"
+                            name="expires_at"
+                            value=(self.user_share.expires_at.as_ref().map(format_datetime_input_value).unwrap_or_default());
+                    }
+
+                    label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                        "Maximum downloads"
+
+                        input
+                            class=(crate::styles::base::ClassName::FORM_CONTROL)
+                            type="number"
+                            name="max_downloads"
+                            min="1"
+                            value=(self.user_share.max_downloads.map(|max_downloads| max_downloads.to_string()).unwrap_or_default())
+                            placeholder="Unlimited";
+                    }
+
+                    label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                        "Password"
+
+                        input
+                            class=(crate::styles::base::ClassName::FORM_CONTROL)
+                            type="password"
+                            name="password"
+                            autocomplete="new-password"
+                            placeholder=(match self.user_share.password.is_some() {
+                                true => "Leave empty to keep the current password",
+                                false => "No password",
+                            });
+                    }
+
+                    @if self.user_share.password.is_some() {
+                        label class=(crate::styles::files_home_page::ClassName::SHARE_SETTINGS_CHECKBOX) {
+                            input type="checkbox" name="remove_password" value="true";
+                            "Remove password"
+                        }
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::SHARE_ACTIONS) {
+                        button
+                            type="submit"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY,
+                            )
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            "Save"
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+fn format_datetime(datetime: &time::OffsetDateTime) -> String {
+    let format =
+        time::macros::format_description!("[month repr:short] [day], [year], [hour]:[minute] UTC");
+
+    datetime
+        .to_offset(time::UtcOffset::UTC)
+        .format(&format)
+        .unwrap_or_else(|_| "Unknown".to_owned())
+}
+
+fn format_datetime_input_value(datetime: &time::OffsetDateTime) -> String {
+    let format = time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute]");
+
+    datetime
+        .to_offset(time::UtcOffset::UTC)
+        .format(&format)
+        .unwrap_or_default()
+}
@@ -4,6 +4,7 @@ use hypertext::prelude::*;
 use crate::Component;
 
 static USER_LIST_ID: &str = "user-list";
+pub static USER_STORAGE_QUOTA_DIALOG_ID: &str = "user-storage-quota-dialog";
 
 pub struct UsersTabContent {
     pub users: Vec<User>,
@@ -57,6 +58,14 @@ impl Renderable for UserList {
                         }
 
                         div class=(crate::styles::user_settings_page::ClassName::USER_ACTIONS_GROUP) {
+                            @if let Some(storage_quota) = user.storage_quota {
+                                span
+                                    class=(crate::styles::user_settings_page::ClassName::USER_STORAGE_QUOTA)
+                                    title="Storage quota"
+                                {
+                                    (crate::format_file_size(storage_quota.max(0) as u64))
+                                }
+                            }
                             @if user.is_admin {
                                 span class=(
                                     crate::styles::badge::ClassName::BADGE, " ",
@@ -129,6 +138,17 @@ impl Renderable for UserList {
                             {
                                 span { "Reset TOTP" }
                             }
+                            button
+                                class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", bitsync_routes::GetUserStorageQuotaDialog { user_id: user.id }))
+                                data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                data-effect=(format!(
+                                    "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                ))
+                            {
+                                span { "Set Storage Quota" }
+                            }
 
                             div class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_DIVIDER) {}
 
@@ -182,3 +202,108 @@ impl Renderable for UserList {
         .render_to(buffer);
     }
 }
+
+pub struct UserStorageQuotaDialog {
+    pub action_url: String,
+    pub username: String,
+    pub storage_quota: Option<i64>,
+}
+
+impl Component for UserStorageQuotaDialog {
+    fn id(&self) -> String {
+        USER_STORAGE_QUOTA_DIALOG_ID.to_owned()
+    }
+}
+
+impl Renderable for UserStorageQuotaDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let storage_quota_mebibytes = self
+            .storage_quota
+            .map(|storage_quota| (storage_quota.max(0) / (1024 * 1024)).to_string())
+            .unwrap_or_default();
+
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) {
+                        "Storage Quota for " (self.username)
+                    }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                form
+                    data-hijack
+                    action=(self.action_url)
+                    method="POST"
+                {
+                    div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                        p class=(crate::styles::modal::ClassName::MODAL_DESCRIPTION) {
+                            "Leave empty to apply the instance default."
+                        }
+
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "Quota (MiB)"
+
+                            input
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                type="number"
+                                min="0"
+                                step="1"
+                                name="storage_quota"
+                                value=(storage_quota_mebibytes)
+                                placeholder="Instance default";
+                        }
+                    }
+                    div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::BUTTON)
+                            onclick="closeClosestDialogAndRemoveElement(this)"
+                        {
+                            "Cancel"
+                        }
+                        button
+                            type="submit"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY,
+                            )
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            "Save"
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum UserStorageQuotaDisplayError {
+    InvalidStorageQuota,
+    InternalServerError,
+}
+
+impl UserStorageQuotaDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidStorageQuota => "Quota must be a whole number of MiB",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
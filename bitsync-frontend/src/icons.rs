@@ -59,12 +59,14 @@ macro_rules! icons {
 }
 
 icons! {
+    Archive: "../../static/svg/archive.svg",
     Bolt: "../../static/svg/bolt.svg",
     Check: "../../static/svg/check.svg",
     ChevronLeft: "../../static/svg/chevron-left.svg",
     ChevronRight: "../../static/svg/chevron-right.svg",
     CircleX: "../../static/svg/circle-x.svg",
     Cloudy: "../../static/svg/cloudy.svg",
+    Copy: "../../static/svg/copy.svg",
     Download: "../../static/svg/download.svg",
     EllipsisVertical: "../../static/svg/ellipsis-vertical.svg",
     FileText: "../../static/svg/file-text.svg",
@@ -72,15 +74,25 @@ icons! {
     Folder: "../../static/svg/folder.svg",
     FolderOpen: "../../static/svg/folder-open.svg",
     FolderPlus: "../../static/svg/folder-plus.svg",
+    FolderUp: "../../static/svg/folder-up.svg",
+    LayoutGrid: "../../static/svg/layout-grid.svg",
     Link: "../../static/svg/link.svg",
+    List: "../../static/svg/list.svg",
+    Lock: "../../static/svg/lock.svg",
     LogOut: "../../static/svg/log-out.svg",
     Logo: "../../static/svg/logo.svg",
     Menu: "../../static/svg/menu.svg",
     Move: "../../static/svg/move.svg",
+    PackageOpen: "../../static/svg/package-open.svg",
+    PencilLine: "../../static/svg/pencil-line.svg",
     Plus: "../../static/svg/plus.svg",
+    RotateCcw: "../../static/svg/rotate-ccw.svg",
+    Settings2: "../../static/svg/settings-2.svg",
     Share2: "../../static/svg/share-2.svg",
     Search: "../../static/svg/search.svg",
     Trash2: "../../static/svg/trash-2.svg",
     Upload: "../../static/svg/upload.svg",
+    User: "../../static/svg/user.svg",
+    Users: "../../static/svg/users.svg",
     X: "../../static/svg/x.svg",
 }
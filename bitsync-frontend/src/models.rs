@@ -1,3 +1,4 @@
+use bitsync_core::use_case::user_files::thumbnail_user_file::supports_thumbnail;
 use bitsync_routes::TypedPath;
 use bitsync_storage::storage_item::{StorageItem, StorageItemKind};
 
@@ -11,7 +12,15 @@ pub struct StorageItemPresentation {
     pub kind: StorageItemPresentationKind,
     pub actions_popover_id: String,
     pub download_url: String,
+    /// Only set for directories, which can be downloaded as different archives.
+    pub download_dialog_url: Option<String>,
     pub move_dialog_url: String,
+    pub rename_url: String,
+    pub copy_dialog_url: String,
+    /// Only set for zip files.
+    pub extract_dialog_url: Option<String>,
+    /// Only set for images a thumbnail can be created for.
+    pub thumbnail_url: Option<String>,
     pub share_dialog_url: String,
     pub delete_url: String,
 }
@@ -46,15 +55,60 @@ impl From<StorageItem> for StorageItemPresentation {
         let download_url = bitsync_routes::GetUserFileDownload
             .with_query_params(bitsync_routes::GetUserFileDownloadQueryParameters {
                 path: value.path.path(),
+                archive_format: None,
             })
             .to_string();
 
+        let download_dialog_url = (value.kind == StorageItemKind::Directory).then(|| {
+            bitsync_routes::GetUserFileDownloadDialog
+                .with_query_params(bitsync_routes::GetUserFileDownloadDialogQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string()
+        });
+
         let move_dialog_url = bitsync_routes::GetUserFileMoveDialog
             .with_query_params(bitsync_routes::GetUserFileMoveDialogQueryParameters {
                 path: value.path.path(),
             })
             .to_string();
 
+        let rename_url = bitsync_routes::PostUserFileRename
+            .with_query_params(bitsync_routes::PostUserFileRenameQueryParameters {
+                path: value.path.path(),
+            })
+            .to_string();
+
+        let copy_dialog_url = bitsync_routes::GetUserFileCopyDialog
+            .with_query_params(bitsync_routes::GetUserFileCopyDialogQueryParameters {
+                path: value.path.path(),
+            })
+            .to_string();
+
+        let is_zip_file = value.kind == StorageItemKind::File
+            && value
+                .path
+                .scoped_path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+        let extract_dialog_url = is_zip_file.then(|| {
+            bitsync_routes::GetUserFileExtractDialog
+                .with_query_params(bitsync_routes::GetUserFileExtractDialogQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string()
+        });
+
+        let thumbnail_url = (value.kind == StorageItemKind::File
+            && supports_thumbnail(&value.path.scoped_path))
+        .then(|| {
+            bitsync_routes::GetUserFileThumbnail
+                .with_query_params(bitsync_routes::GetUserFileThumbnailQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string()
+        });
+
         let share_dialog_url = bitsync_routes::GetUserFileShareDialog
             .with_query_params(bitsync_routes::GetUserFileShareDialogQueryParameters {
                 path: value.path.path(),
@@ -76,7 +130,12 @@ impl From<StorageItem> for StorageItemPresentation {
             kind: StorageItemPresentationKind::from(value),
             actions_popover_id,
             download_url,
+            download_dialog_url,
             move_dialog_url,
+            rename_url,
+            copy_dialog_url,
+            extract_dialog_url,
+            thumbnail_url,
             share_dialog_url,
             delete_url,
         }
@@ -2,6 +2,9 @@ pub(crate) mod base;
 pub mod error;
 pub mod files;
 pub mod login;
+pub mod public_share;
 pub mod register;
 pub mod search;
+pub mod shared_with_me;
 pub mod suspended;
+pub mod trash;
@@ -29,6 +29,10 @@ impl<R: Renderable> Renderable for LoggedInDocument<R> {
 
                     script src="/static/js/button-helper.js" {}
                     script src="/static/js/drop-upload-helper.js" {}
+                    script src="/static/js/selection-helper.js" {}
+                    script src="/static/js/rename-helper.js" {}
+                    script src="/static/js/conflict-helper.js" {}
+                    script src="/static/js/view-helper.js" {}
 
                     style { (crate::styles::base::STYLE_SHEET) }
                     style { (crate::styles::button::STYLE_SHEET) }
@@ -121,6 +125,20 @@ impl<R: Renderable> Renderable for LoggedInDocument<R> {
                                 )
                                 popover
                             {
+                                a
+                                    class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                    href=(bitsync_routes::GetSharedWithMePage.to_string())
+                                {
+                                    (crate::icons::Users::default())
+                                    span { "Shared with me" }
+                                }
+                                a
+                                    class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                    href=(bitsync_routes::GetTrashPage.to_string())
+                                {
+                                    (crate::icons::Trash2::default())
+                                    span { "Trash" }
+                                }
                                 button
                                     class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
                                     data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", bitsync_routes::GetUserSettingsDialog))
@@ -208,7 +226,6 @@ impl<R: Renderable> Renderable for AuthDocument<R> {
 }
 
 pub struct PublicShareDocument<R: Renderable> {
-    pub current_path: Option<String>,
     pub is_user_logged_in: bool,
     pub children: R,
 }
@@ -231,7 +248,6 @@ impl<R: Renderable> Renderable for PublicShareDocument<R> {
                     script src="/static/js/overflow-helper.js" defer {}
 
                     script src="/static/js/button-helper.js" {}
-                    // script src="/static/js/drop-upload-helper.js" {}
 
                     style { (crate::styles::base::STYLE_SHEET) }
                     style { (crate::styles::button::STYLE_SHEET) }
@@ -243,7 +259,6 @@ impl<R: Renderable> Renderable for PublicShareDocument<R> {
                     style { (crate::styles::error_modal::STYLE_SHEET) }
                     style { (crate::styles::error_banner::STYLE_SHEET) }
                     style { (crate::styles::error_card::STYLE_SHEET) }
-                    style { (crate::styles::search_launcher::STYLE_SHEET) }
                     style { (crate::styles::toast::STYLE_SHEET) }
                     style { (crate::styles::user_settings_page::STYLE_SHEET) }
                 }
@@ -258,94 +273,53 @@ impl<R: Renderable> Renderable for PublicShareDocument<R> {
                         }
 
                         nav {
-                            button
-                                class=(crate::styles::base::ClassName::SEARCH_BUTTON)
-                                title="Search"
-                                onclick="openDialogModalById('search-launcher')"
-                            {
-                                (crate::icons::Search::default())
-                                span { "Search files and folders..." }
-                            }
-
-                            dialog
-                                class=(crate::styles::search_launcher::ClassName::SEARCH_LAUNCHER)
-                                id="search-launcher"
-                                onclick="if (event.target === this) this.close()"
-                            {
+                            @if self.is_user_logged_in {
                                 button
-                                    class=(crate::styles::search_launcher::ClassName::CLOSE_BUTTON)
-                                    onclick="closeClosestDialog(this)"
-                                {
-                                    (crate::icons::X::default())
-                                }
-
-                                form
-                                    data-hijack
-                                    action=(bitsync_routes::GetSearch.to_string())
-                                    method="GET"
+                                    class=(crate::styles::base::ClassName::NAV_MENU_BUTTON)
+                                    popovertarget=(NAV_MENU_ID)
+                                    title="Menu"
                                 {
-                                    div class=(crate::styles::search_launcher::ClassName::INPUT_WRAPPER) {
-                                        (crate::icons::Search::default())
-                                        @if let Some(path) = &self.current_path {
-                                            input
-                                                type="hidden"
-                                                name="path"
-                                                value=(path);
-                                        }
-                                        input
-                                            class=(crate::styles::search_launcher::ClassName::INPUT)
-                                            type="text"
-                                            name="query"
-                                            placeholder="Search files and folders..."
-                                            autocomplete="off"
-                                            autofocus
-                                            data-on-input__debounce.300ms="this.form.requestSubmit()";
-                                    }
+                                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                    (crate::icons::Menu::default())
                                 }
                                 div
-                                    class=(crate::styles::search_launcher::ClassName::RESULTS)
-                                    id="search-results"
-                                {}
-                            }
-
-                            button
-                                class=(crate::styles::base::ClassName::NAV_MENU_BUTTON)
-                                popovertarget=(NAV_MENU_ID)
-                                title="Menu"
-                            {
-                                div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
-                                (crate::icons::Menu::default())
-                            }
-                            div
-                                id=(NAV_MENU_ID)
-                                class=(
-                                    crate::styles::context_menu::ClassName::CONTEXT_MENU, " ",
-                                    crate::styles::base::ClassName::NAV_CONTEXT_MENU,
-                                )
-                                popover
-                            {
-                                button
-                                    class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
-                                    data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", bitsync_routes::GetUserSettingsDialog))
-                                    data-on-click="closeClosestPopover(this), this.fetch.trigger()"
-                                    data-effect=(format!(
-                                        "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
-                                        loading = crate::styles::button::ClassName::BUTTON_LOADING,
-                                    ))
+                                    id=(NAV_MENU_ID)
+                                    class=(
+                                        crate::styles::context_menu::ClassName::CONTEXT_MENU, " ",
+                                        crate::styles::base::ClassName::NAV_CONTEXT_MENU,
+                                    )
+                                    popover
                                 {
-                                    (crate::icons::Bolt::default())
-                                    span { "Settings" }
+                                    button
+                                        class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                        data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", bitsync_routes::GetUserSettingsDialog))
+                                        data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                        data-effect=(format!(
+                                            "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                        ))
+                                    {
+                                        (crate::icons::Bolt::default())
+                                        span { "Settings" }
+                                    }
+                                    div class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_DIVIDER) {}
+                                    a
+                                        class=(
+                                            crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM, " ",
+                                            crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM_DANGER,
+                                        )
+                                        href=(bitsync_routes::GetLogoutAction.to_string())
+                                    {
+                                        (crate::icons::LogOut::default())
+                                        span { "Sign Out" }
+                                    }
                                 }
-                                div class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_DIVIDER) {}
+                            } @else {
                                 a
-                                    class=(
-                                        crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM, " ",
-                                        crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM_DANGER,
-                                    )
-                                    href=(bitsync_routes::GetLogoutAction.to_string())
+                                    class=(crate::styles::button::ClassName::BUTTON)
+                                    href=(bitsync_routes::GetLoginPage.to_string())
                                 {
-                                    (crate::icons::LogOut::default())
-                                    span { "Sign Out" }
+                                    "Sign In"
                                 }
                             }
                         }
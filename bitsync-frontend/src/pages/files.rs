@@ -1,16 +1,32 @@
+pub mod batch_operations;
+pub mod conflict_resolution;
 pub mod directory_creation;
+pub mod file_copy;
+pub mod file_download;
+pub mod file_extract;
 pub mod file_move;
 pub mod file_operations;
+pub mod file_preview;
+pub mod file_rename;
+pub mod file_versions;
 
 use bitsync_core::use_case::user_files::{
+    batch_copy_user_files::BatchUserFileCopyResult,
+    batch_delete_user_files::BatchUserFileDeletionResult,
+    batch_move_user_files::BatchUserFileMoveResult,
+    copy_user_file::UserFileCopyResult,
     create_directory::DirectoryCreationResult,
     delete_user_file::UserFileDeletionResult,
+    extract_user_file::UserFileExtractResult,
     move_user_file::UserFileMoveResult,
     read_user_file_item::{
         DirectoryBreadcrumbSegment, UserDirectoryContentsResult, UserFileItemResult,
         UserFilesHomeResult,
     },
+    rename_user_file::UserFileRenameResult,
+    shared::StorageUsage,
     upload_user_file::UserFileResult,
+    view_user_file::FilePreviewKind,
 };
 use bitsync_routes::TypedPath;
 use hypertext::prelude::*;
@@ -21,11 +37,25 @@ use crate::{
     pages::base::LoggedInDocument,
 };
 
+use batch_operations::BatchSelectionToolbar;
+use file_preview::{FilePreview, TextFilePreview};
+use file_versions::FileVersionList;
+
 pub enum FilesHomePageElementId {
     FileUploadForm,
     DirectoryCreationDialog,
     FileMoveDialog,
+    FileCopyDialog,
     FileShareDialog,
+    BatchSelectionForm,
+    BatchMoveDialog,
+    BatchCopyDialog,
+    BatchResultDialog,
+    ConflictResolutionDialog,
+    FileExtractDialog,
+    FileDownloadDialog,
+    ExtractionProgressDialog,
+    ExtractionProgressIndicator,
 }
 
 impl FilesHomePageElementId {
@@ -34,7 +64,17 @@ impl FilesHomePageElementId {
             FilesHomePageElementId::FileUploadForm => "file-upload-form",
             FilesHomePageElementId::DirectoryCreationDialog => "directory-creation-dialog",
             FilesHomePageElementId::FileMoveDialog => "file-move-dialog",
+            FilesHomePageElementId::FileCopyDialog => "file-copy-dialog",
             FilesHomePageElementId::FileShareDialog => "file-share-dialog",
+            FilesHomePageElementId::BatchSelectionForm => "batch-selection-form",
+            FilesHomePageElementId::BatchMoveDialog => "batch-move-dialog",
+            FilesHomePageElementId::BatchCopyDialog => "batch-copy-dialog",
+            FilesHomePageElementId::BatchResultDialog => "batch-result-dialog",
+            FilesHomePageElementId::ConflictResolutionDialog => "conflict-resolution-dialog",
+            FilesHomePageElementId::FileExtractDialog => "file-extract-dialog",
+            FilesHomePageElementId::FileDownloadDialog => "file-download-dialog",
+            FilesHomePageElementId::ExtractionProgressDialog => "extraction-progress-dialog",
+            FilesHomePageElementId::ExtractionProgressIndicator => "extraction-progress-indicator",
         }
     }
 }
@@ -42,8 +82,10 @@ impl FilesHomePageElementId {
 pub struct DirectoryHeader {
     pub directory_name: String,
     pub download_zip_url: String,
+    pub download_dialog_url: String,
     pub share_dialog_url: String,
     pub move_dialog_url: String,
+    pub copy_dialog_url: String,
     pub delete_url: String,
     pub actions_popover_id: String,
     pub is_root_directory: bool,
@@ -64,24 +106,34 @@ const BREADCRUMB_COLLAPSED_POPOVER_ID: &str = "breadcrumb-collapsed-popover";
 
 const _: () = assert!(BREADCRUMB_MAX_VISIBLE > 2);
 
-fn to_breadcrumb_link(segment: DirectoryBreadcrumbSegment) -> BreadcrumbLink {
-    let url = bitsync_routes::GetFilesHomePage
-        .with_query_params(bitsync_routes::GetFilesHomePageQueryParameters { path: segment.path })
-        .to_string();
-
+fn to_breadcrumb_link(
+    segment: DirectoryBreadcrumbSegment,
+    url_for_path: &impl Fn(String) -> String,
+) -> BreadcrumbLink {
     BreadcrumbLink {
+        url: url_for_path(segment.path),
         name: segment.name,
-        url,
     }
 }
 
 fn build_breadcrumb(segments: Vec<DirectoryBreadcrumbSegment>) -> Vec<BreadcrumbCrumb> {
+    build_breadcrumb_with_urls(segments, |path| {
+        bitsync_routes::GetFilesHomePage
+            .with_query_params(bitsync_routes::GetFilesHomePageQueryParameters { path })
+            .to_string()
+    })
+}
+
+pub(crate) fn build_breadcrumb_with_urls(
+    segments: Vec<DirectoryBreadcrumbSegment>,
+    url_for_path: impl Fn(String) -> String,
+) -> Vec<BreadcrumbCrumb> {
     let total = segments.len();
 
     if total <= BREADCRUMB_MAX_VISIBLE {
         return segments
             .into_iter()
-            .map(to_breadcrumb_link)
+            .map(|segment| to_breadcrumb_link(segment, &url_for_path))
             .map(BreadcrumbCrumb::Link)
             .collect();
     }
@@ -96,20 +148,80 @@ fn build_breadcrumb(segments: Vec<DirectoryBreadcrumbSegment>) -> Vec<Breadcrumb
         .expect("root segment always present");
 
     let mut crumbs = vec![
-        BreadcrumbCrumb::Link(to_breadcrumb_link(root)),
+        BreadcrumbCrumb::Link(to_breadcrumb_link(root, &url_for_path)),
         BreadcrumbCrumb::CollapsedGroup {
-            hidden_links: middle.into_iter().map(to_breadcrumb_link).collect(),
+            hidden_links: middle
+                .into_iter()
+                .map(|segment| to_breadcrumb_link(segment, &url_for_path))
+                .collect(),
         },
     ];
     crumbs.extend(
         tail.into_iter()
-            .map(to_breadcrumb_link)
+            .map(|segment| to_breadcrumb_link(segment, &url_for_path))
             .map(BreadcrumbCrumb::Link),
     );
 
     crumbs
 }
 
+pub(crate) struct Breadcrumb<'a> {
+    pub crumbs: &'a [BreadcrumbCrumb],
+}
+
+impl Renderable for Breadcrumb<'_> {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            nav class=(crate::styles::files_home_page::ClassName::BREADCRUMB) {
+                @for crumb in self.crumbs.iter() {
+                    @match crumb {
+                        BreadcrumbCrumb::Link(link) => {
+                            a
+                                class=(crate::styles::files_home_page::ClassName::BREADCRUMB_LINK)
+                                href=(link.url)
+                            {
+                                (link.name)
+                            }
+                        }
+                        BreadcrumbCrumb::CollapsedGroup { hidden_links } => {
+                            button
+                                class=(crate::styles::files_home_page::ClassName::BREADCRUMB_ELLIPSIS)
+                                popovertarget=(BREADCRUMB_COLLAPSED_POPOVER_ID)
+                                title="Show hidden folders"
+                            {
+                                "..."
+                            }
+                            div
+                                id=(BREADCRUMB_COLLAPSED_POPOVER_ID)
+                                class=(
+                                    crate::styles::context_menu::ClassName::CONTEXT_MENU, " ",
+                                    crate::styles::context_menu::ClassName::ANCHOR_TOP_LEFT,
+                                )
+                                popover
+                            {
+                                @for link in hidden_links {
+                                    a
+                                        class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                        href=(link.url)
+                                        onclick="closeClosestPopover(this)"
+                                    {
+                                        span { (link.name) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    span class=(crate::styles::files_home_page::ClassName::BREADCRUMB_SEPARATOR) {
+                        "/"
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
 pub enum FilesHomePage {
     Directory(FilesHomeDirectoryPage),
     File(FilesHomeFilePage),
@@ -142,6 +254,8 @@ pub struct FilesHomeDirectoryPage {
     directory_header: DirectoryHeader,
     file_upload_url: String,
     directory_creation_dialog_url: String,
+    batch_selection_toolbar: BatchSelectionToolbar,
+    storage_usage: StorageUsage,
 }
 
 impl From<UserDirectoryContentsResult> for FilesHomeDirectoryPage {
@@ -171,6 +285,12 @@ impl From<UserDirectoryContentsResult> for FilesHomeDirectoryPage {
             download_zip_url: bitsync_routes::GetUserFileDownload
                 .with_query_params(bitsync_routes::GetUserFileDownloadQueryParameters {
                     path: value.path.path(),
+                    archive_format: None,
+                })
+                .to_string(),
+            download_dialog_url: bitsync_routes::GetUserFileDownloadDialog
+                .with_query_params(bitsync_routes::GetUserFileDownloadDialogQueryParameters {
+                    path: value.path.path(),
                 })
                 .to_string(),
             share_dialog_url: bitsync_routes::GetUserFileShareDialog
@@ -183,6 +303,11 @@ impl From<UserDirectoryContentsResult> for FilesHomeDirectoryPage {
                     path: value.path.path(),
                 })
                 .to_string(),
+            copy_dialog_url: bitsync_routes::GetUserFileCopyDialog
+                .with_query_params(bitsync_routes::GetUserFileCopyDialogQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
             delete_url: bitsync_routes::GetUserFileDelete
                 .with_query_params(bitsync_routes::GetUserFileDeleteQueryParameters {
                     path: value.path.path(),
@@ -192,6 +317,34 @@ impl From<UserDirectoryContentsResult> for FilesHomeDirectoryPage {
             is_root_directory: value.is_root_directory,
         };
 
+        let batch_selection_toolbar = BatchSelectionToolbar {
+            download_url: bitsync_routes::PostUserFileBatchDownload
+                .with_query_params(bitsync_routes::PostUserFileBatchDownloadQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
+            share_url: bitsync_routes::PostUserFileBatchShare
+                .with_query_params(bitsync_routes::PostUserFileBatchShareQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
+            move_dialog_url: bitsync_routes::PostUserFileBatchMoveDialog
+                .with_query_params(bitsync_routes::PostUserFileBatchMoveDialogQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
+            copy_dialog_url: bitsync_routes::PostUserFileBatchCopyDialog
+                .with_query_params(bitsync_routes::PostUserFileBatchCopyDialogQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
+            delete_url: bitsync_routes::PostUserFileBatchDelete
+                .with_query_params(bitsync_routes::PostUserFileBatchDeleteQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
+        };
+
         let breadcrumb = build_breadcrumb(value.breadcrumb_segments);
 
         FilesHomeDirectoryPage {
@@ -201,6 +354,8 @@ impl From<UserDirectoryContentsResult> for FilesHomeDirectoryPage {
             directory_header,
             file_upload_url,
             directory_creation_dialog_url,
+            batch_selection_toolbar,
+            storage_usage: value.storage_usage,
         }
     }
 }
@@ -217,6 +372,12 @@ impl Renderable for FilesHomeDirectoryPage {
                         active_class = crate::styles::files_home_page::ClassName::DROP_ZONE_ACTIVE,
                     ))
                 {
+                    // XSS SAFETY: the script only contains a generated class name
+                    script {(hypertext::Raw::dangerously_create(format!(
+                        "initDirectoryView(document.currentScript.parentElement, '{grid_view}')",
+                        grid_view = crate::styles::files_home_page::ClassName::DIRECTORY_GRID_VIEW,
+                    )))}
+
                     div class=(crate::styles::files_home_page::ClassName::DIRECTORY_HEADER) {
                         button
                             title="More"
@@ -235,51 +396,23 @@ impl Renderable for FilesHomeDirectoryPage {
                                 (self.directory_header.directory_name)
                             }
 
-                            nav class=(crate::styles::files_home_page::ClassName::BREADCRUMB) {
-                                @for crumb in self.breadcrumb.iter() {
-                                    @match crumb {
-                                        BreadcrumbCrumb::Link(link) => {
-                                            a
-                                                class=(crate::styles::files_home_page::ClassName::BREADCRUMB_LINK)
-                                                href=(link.url)
-                                            {
-                                                (link.name)
-                                            }
-                                        }
-                                        BreadcrumbCrumb::CollapsedGroup { hidden_links } => {
-                                            button
-                                                class=(crate::styles::files_home_page::ClassName::BREADCRUMB_ELLIPSIS)
-                                                popovertarget=(BREADCRUMB_COLLAPSED_POPOVER_ID)
-                                                title="Show hidden folders"
-                                            {
-                                                "..."
-                                            }
-                                            div
-                                                id=(BREADCRUMB_COLLAPSED_POPOVER_ID)
-                                                class=(
-                                                    crate::styles::context_menu::ClassName::CONTEXT_MENU, " ",
-                                                    crate::styles::context_menu::ClassName::ANCHOR_TOP_LEFT,
-                                                )
-                                                popover
-                                            {
-                                                @for link in hidden_links {
-                                                    a
-                                                        class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
-                                                        href=(link.url)
-                                                        onclick="closeClosestPopover(this)"
-                                                    {
-                                                        span { (link.name) }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
+                            (Breadcrumb { crumbs: &self.breadcrumb })
+                        }
 
-                                    span class=(crate::styles::files_home_page::ClassName::BREADCRUMB_SEPARATOR) {
-                                        "/"
-                                    }
-                                }
-                            }
+                        button
+                            title="Toggle grid view"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::files_home_page::ClassName::ACTION_BUTTON, " ",
+                                crate::styles::files_home_page::ClassName::DIRECTORY_VIEW_TOGGLE,
+                            )
+                            onclick=(format!(
+                                "toggleDirectoryView(this.closest('main'), '{grid_view}')",
+                                grid_view = crate::styles::files_home_page::ClassName::DIRECTORY_GRID_VIEW,
+                            ))
+                        {
+                            (crate::icons::LayoutGrid::with_class(crate::styles::files_home_page::ClassName::GRID_VIEW_ICON))
+                            (crate::icons::List::with_class(crate::styles::files_home_page::ClassName::LIST_VIEW_ICON))
                         }
 
                         dialog
@@ -293,7 +426,7 @@ impl Renderable for FilesHomeDirectoryPage {
                             button
                                 class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
                                 data-init=(format!(
-                                    "this.uploadInput = document.getElementById('{form_id}').querySelector('input[type=file]')",
+                                    "this.uploadInput = document.getElementById('{form_id}').querySelector('input[name=upload]')",
                                     form_id = FilesHomePageElementId::FileUploadForm.to_str(),
                                 ))
                                 data-on-click="closeClosestPopover(this), this.uploadInput.click()"
@@ -302,6 +435,18 @@ impl Renderable for FilesHomeDirectoryPage {
                                 span { "Upload" }
                             }
 
+                            button
+                                class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                data-init=(format!(
+                                    "this.folderInput = document.getElementById('{form_id}').querySelector('input[data-folder-upload]')",
+                                    form_id = FilesHomePageElementId::FileUploadForm.to_str(),
+                                ))
+                                data-on-click="closeClosestPopover(this), this.folderInput.click()"
+                            {
+                                (crate::icons::FolderUp::default())
+                                span { "Upload Folder" }
+                            }
+
                             button
                                 class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
                                 data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", self.directory_creation_dialog_url))
@@ -325,6 +470,19 @@ impl Renderable for FilesHomeDirectoryPage {
                                     (crate::icons::Download::default())
                                     span { "Download" }
                                 }
+
+                                button
+                                    class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                    data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", self.directory_header.download_dialog_url))
+                                    data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                    data-effect=(format!(
+                                        "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                        loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                    ))
+                                {
+                                    (crate::icons::Archive::default())
+                                    span { "Download As" }
+                                }
                             }
 
                             @if !self.directory_header.is_root_directory {
@@ -340,6 +498,19 @@ impl Renderable for FilesHomeDirectoryPage {
                                     (crate::icons::Move::default())
                                     span { "Move" }
                                 }
+
+                                button
+                                    class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                    data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", self.directory_header.copy_dialog_url))
+                                    data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                    data-effect=(format!(
+                                        "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                        loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                    ))
+                                {
+                                    (crate::icons::Copy::default())
+                                    span { "Copy" }
+                                }
                             }
 
                             button
@@ -379,22 +550,69 @@ impl Renderable for FilesHomeDirectoryPage {
 
                     FileUploadForm file_upload_url=(self.file_upload_url.clone());
 
+                    (self.batch_selection_toolbar)
+
                     (FileStorageTable { dir_content: self.dir_content.clone() })
+
+                    (StorageUsageIndicator { storage_usage: &self.storage_usage })
                 }
             }
         }.render_to(buffer);
     }
 }
 
+struct StorageUsageIndicator<'a> {
+    storage_usage: &'a StorageUsage,
+}
+
+impl Renderable for StorageUsageIndicator<'_> {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let used = crate::format_file_size(self.storage_usage.used_bytes);
+
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::STORAGE_USAGE) {
+                @if let Some(quota_bytes) = self.storage_usage.quota_bytes {
+                    @let used_percentage = match quota_bytes {
+                        0 => 100,
+                        quota_bytes => (self.storage_usage.used_bytes.saturating_mul(100) / quota_bytes).min(100),
+                    };
+                    @let available = crate::format_file_size(
+                        quota_bytes.saturating_sub(self.storage_usage.used_bytes),
+                    );
+
+                    div class=(crate::styles::files_home_page::ClassName::STORAGE_USAGE_BAR) {
+                        div
+                            class=(crate::styles::files_home_page::ClassName::STORAGE_USAGE_BAR_FILL)
+                            style=(format!("width: {used_percentage}%"))
+                        {}
+                    }
+                    span title="Includes the trash and previous file versions" {
+                        (used) " of " (crate::format_file_size(quota_bytes)) " used, "
+                        (available) " available"
+                    }
+                } @else {
+                    span title="Includes the trash and previous file versions" { (used) " used" }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
 pub struct FilesHomeFilePage {
     current_path: String,
     file_name: String,
     download_url: String,
     share_dialog_url: String,
     move_dialog_url: String,
+    copy_dialog_url: String,
     delete_url: String,
     actions_popover_id: String,
     breadcrumb: Vec<BreadcrumbCrumb>,
+    checksum: Option<String>,
+    preview: Option<FilePreview>,
+    text_preview: Option<TextFilePreview>,
+    version_list: FileVersionList,
 }
 
 impl From<UserFileItemResult> for FilesHomeFilePage {
@@ -402,6 +620,7 @@ impl From<UserFileItemResult> for FilesHomeFilePage {
         let download_url = bitsync_routes::GetUserFileDownload
             .with_query_params(bitsync_routes::GetUserFileDownloadQueryParameters {
                 path: value.path.path(),
+                archive_format: None,
             })
             .to_string();
 
@@ -417,12 +636,33 @@ impl From<UserFileItemResult> for FilesHomeFilePage {
             })
             .to_string();
 
+        let copy_dialog_url = bitsync_routes::GetUserFileCopyDialog
+            .with_query_params(bitsync_routes::GetUserFileCopyDialogQueryParameters {
+                path: value.path.path(),
+            })
+            .to_string();
+
         let delete_url = bitsync_routes::GetUserFileDelete
             .with_query_params(bitsync_routes::GetUserFileDeleteQueryParameters {
                 path: value.path.path(),
             })
             .to_string();
 
+        let preview = FilePreviewKind::from_path(&value.path.scoped_path).map(|kind| FilePreview {
+            kind,
+            view_url: bitsync_routes::GetUserFileView
+                .with_query_params(bitsync_routes::GetUserFileViewQueryParameters {
+                    path: value.path.path(),
+                })
+                .to_string(),
+            file_name: value.file_name.clone(),
+        });
+
+        let text_preview = value.text_content.map(|content| TextFilePreview {
+            file_name: value.file_name.clone(),
+            content,
+        });
+
         let breadcrumb = build_breadcrumb(value.breadcrumb_segments);
 
         FilesHomeFilePage {
@@ -431,9 +671,14 @@ impl From<UserFileItemResult> for FilesHomeFilePage {
             download_url,
             share_dialog_url,
             move_dialog_url,
+            copy_dialog_url,
             delete_url,
             actions_popover_id: "file-header-actions-popover".to_owned(),
             breadcrumb,
+            checksum: value.checksum,
+            preview,
+            text_preview,
+            version_list: value.versions.into(),
         }
     }
 }
@@ -475,6 +720,19 @@ impl Renderable for FilesHomeFilePage {
                                 span { "Move" }
                             }
 
+                            button
+                                class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", self.copy_dialog_url))
+                                data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                data-effect=(format!(
+                                    "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                ))
+                            {
+                                (crate::icons::Copy::default())
+                                span { "Copy" }
+                            }
+
                             div class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_DIVIDER) {}
 
                             button
@@ -499,51 +757,7 @@ impl Renderable for FilesHomeFilePage {
                                 (self.file_name)
                             }
 
-                            nav class=(crate::styles::files_home_page::ClassName::BREADCRUMB) {
-                                @for crumb in self.breadcrumb.iter() {
-                                    @match crumb {
-                                        BreadcrumbCrumb::Link(link) => {
-                                            a
-                                                class=(crate::styles::files_home_page::ClassName::BREADCRUMB_LINK)
-                                                href=(link.url)
-                                            {
-                                                (link.name)
-                                            }
-                                        }
-                                        BreadcrumbCrumb::CollapsedGroup { hidden_links } => {
-                                            button
-                                                class=(crate::styles::files_home_page::ClassName::BREADCRUMB_ELLIPSIS)
-                                                popovertarget=(BREADCRUMB_COLLAPSED_POPOVER_ID)
-                                                title="Show hidden folders"
-                                            {
-                                                "..."
-                                            }
-                                            div
-                                                id=(BREADCRUMB_COLLAPSED_POPOVER_ID)
-                                                class=(
-                                                    crate::styles::context_menu::ClassName::CONTEXT_MENU, " ",
-                                                    crate::styles::context_menu::ClassName::ANCHOR_TOP_LEFT,
-                                                )
-                                                popover
-                                            {
-                                                @for link in hidden_links {
-                                                    a
-                                                        class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
-                                                        href=(link.url)
-                                                        onclick="closeClosestPopover(this)"
-                                                    {
-                                                        span { (link.name) }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    span class=(crate::styles::files_home_page::ClassName::BREADCRUMB_SEPARATOR) {
-                                        "/"
-                                    }
-                                }
-                            }
+                            (Breadcrumb { crumbs: &self.breadcrumb })
                         }
 
                         div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ACTIONS) {
@@ -579,14 +793,37 @@ impl Renderable for FilesHomeFilePage {
                             }
                         }
                     }
+
+                    @if let Some(preview) = &self.preview {
+                        (preview)
+                    }
+
+                    @if let Some(text_preview) = &self.text_preview {
+                        (text_preview)
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::FILE_CHECKSUM) {
+                        span class=(crate::styles::files_home_page::ClassName::FILE_CHECKSUM_LABEL) {
+                            "SHA-256"
+                        }
+                        @if let Some(checksum) = &self.checksum {
+                            code class=(crate::styles::files_home_page::ClassName::FILE_CHECKSUM_VALUE) {
+                                (checksum)
+                            }
+                        } @else {
+                            span { "Not calculated yet" }
+                        }
+                    }
+
+                    (self.version_list)
                 }
             }
         }.render_to(buffer);
     }
 }
 
-struct FileUploadForm {
-    file_upload_url: String,
+pub(crate) struct FileUploadForm {
+    pub file_upload_url: String,
 }
 
 impl Component for FileUploadForm {
@@ -608,9 +845,17 @@ impl Renderable for FileUploadForm {
                 input
                     type="file"
                     name="upload"
+                    multiple
                     hidden
                     onchange="this.form.requestSubmit()"
                 ;
+                input
+                    type="file"
+                    hidden
+                    data-folder-upload
+                    data-init="this.webkitdirectory = true"
+                    onchange="uploadFolder(this)"
+                ;
             }
         }
         .render_to(buffer);
@@ -631,6 +876,8 @@ impl Component for FileStorageTable {
 
 impl Renderable for FileStorageTable {
     fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let batch_form_id = FilesHomePageElementId::BatchSelectionForm.to_str();
+
         maud! {
             table
                 id=(self.id())
@@ -641,7 +888,7 @@ impl Renderable for FileStorageTable {
                         tr {
                             td
                                 class=(crate::styles::files_home_page::ClassName::EMPTY_STATE)
-                                colspan="4"
+                                colspan="5"
                             {
                                 (crate::icons::Cloudy::default())
                                 p { "This folder is empty" }
@@ -651,6 +898,15 @@ impl Renderable for FileStorageTable {
                 } @else {
                     thead {
                         tr class=(crate::styles::files_home_page::ClassName::FILE_HEADER) {
+                            th {
+                                input
+                                    type="checkbox"
+                                    title="Select all"
+                                    class=(crate::styles::files_home_page::ClassName::SELECTION_CHECKBOX)
+                                    form=(batch_form_id)
+                                    data-select-all
+                                    onchange="toggleSelection(this.form, this.checked)";
+                            }
                             th {}
                             th { "Name" }
                             th { "Size" }
@@ -660,6 +916,17 @@ impl Renderable for FileStorageTable {
                     tbody {
                         @for dir_item in &self.dir_content {
                             tr class=(crate::styles::files_home_page::ClassName::FILE_ITEM) {
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SELECT) {
+                                    input
+                                        type="checkbox"
+                                        title="Select"
+                                        class=(crate::styles::files_home_page::ClassName::SELECTION_CHECKBOX)
+                                        form=(batch_form_id)
+                                        name="paths"
+                                        value=(dir_item.path)
+                                        onchange="updateSelection(this.form)";
+                                }
+
                                 @match &dir_item.kind {
                                     StorageItemPresentationKind::Directory { url } => {
                                         td class=(crate::styles::files_home_page::ClassName::FILE_ICON) {
@@ -667,7 +934,11 @@ impl Renderable for FileStorageTable {
                                         }
 
                                         td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
-                                            a href=(url) { (dir_item.name) }
+                                            (file_rename::InlineRenameName {
+                                                name: &dir_item.name,
+                                                url,
+                                                rename_url: &dir_item.rename_url,
+                                            })
                                         }
                                     }
                                     StorageItemPresentationKind::File { url } => {
@@ -678,10 +949,23 @@ impl Renderable for FileStorageTable {
                                             )
                                         {
                                             (crate::icons::FileText::default())
+
+                                            @if let Some(thumbnail_url) = &dir_item.thumbnail_url {
+                                                img
+                                                    class=(crate::styles::files_home_page::ClassName::FILE_THUMBNAIL)
+                                                    src=(thumbnail_url)
+                                                    alt=""
+                                                    loading="lazy"
+                                                    onerror="this.remove()";
+                                            }
                                         }
 
                                         td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
-                                            a href=(url) { (dir_item.name) }
+                                            (file_rename::InlineRenameName {
+                                                name: &dir_item.name,
+                                                url,
+                                                rename_url: &dir_item.rename_url,
+                                            })
                                         }
                                     }
                                 }
@@ -737,6 +1021,42 @@ impl Renderable for FileStorageTable {
                                             span { "Move" }
                                         }
 
+                                        button
+                                            class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                            data-on-click="closeClosestPopover(this), startInlineRename(this.closest('tr'))"
+                                        {
+                                            (crate::icons::PencilLine::default())
+                                            span { "Rename" }
+                                        }
+
+                                        button
+                                            class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                            data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", dir_item.copy_dialog_url))
+                                            data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                            data-effect=(format!(
+                                                "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                            ))
+                                        {
+                                            (crate::icons::Copy::default())
+                                            span { "Copy" }
+                                        }
+
+                                        @if let Some(extract_dialog_url) = &dir_item.extract_dialog_url {
+                                            button
+                                                class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                                data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", extract_dialog_url))
+                                                data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                                data-effect=(format!(
+                                                    "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                                ))
+                                            {
+                                                (crate::icons::PackageOpen::default())
+                                                span { "Extract" }
+                                            }
+                                        }
+
                                         a
                                             class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
                                             href=(dir_item.download_url)
@@ -746,6 +1066,21 @@ impl Renderable for FileStorageTable {
                                             span { "Download" }
                                         }
 
+                                        @if let Some(download_dialog_url) = &dir_item.download_dialog_url {
+                                            button
+                                                class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_ITEM)
+                                                data-init=(format!("this.triggerButton = getPopoverTrigger(this), this.fetch = fetch('{}')", download_dialog_url))
+                                                data-on-click="closeClosestPopover(this), this.fetch.trigger()"
+                                                data-effect=(format!(
+                                                    "handleButtonLoading(this.triggerButton, this.fetch, '{loading}')",
+                                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                                ))
+                                            {
+                                                (crate::icons::Archive::default())
+                                                span { "Download As" }
+                                            }
+                                        }
+
                                         div class=(crate::styles::context_menu::ClassName::CONTEXT_MENU_DIVIDER) {}
 
                                         button
@@ -826,6 +1161,90 @@ impl From<UserFileMoveResult> for FilesHomePageChangeResult {
     }
 }
 
+impl From<UserFileExtractResult> for FilesHomePageChangeResult {
+    fn from(value: UserFileExtractResult) -> Self {
+        let displayable_dir_content = value
+            .dir_contents
+            .into_iter()
+            .map(StorageItemPresentation::from)
+            .collect();
+
+        FilesHomePageChangeResult {
+            dir_content: displayable_dir_content,
+        }
+    }
+}
+
+impl From<UserFileRenameResult> for FilesHomePageChangeResult {
+    fn from(value: UserFileRenameResult) -> Self {
+        let displayable_dir_content = value
+            .dir_contents
+            .into_iter()
+            .map(StorageItemPresentation::from)
+            .collect();
+
+        FilesHomePageChangeResult {
+            dir_content: displayable_dir_content,
+        }
+    }
+}
+
+impl From<UserFileCopyResult> for FilesHomePageChangeResult {
+    fn from(value: UserFileCopyResult) -> Self {
+        let displayable_dir_content = value
+            .dir_contents
+            .into_iter()
+            .map(StorageItemPresentation::from)
+            .collect();
+
+        FilesHomePageChangeResult {
+            dir_content: displayable_dir_content,
+        }
+    }
+}
+
+impl From<BatchUserFileDeletionResult> for FilesHomePageChangeResult {
+    fn from(value: BatchUserFileDeletionResult) -> Self {
+        let displayable_dir_content = value
+            .dir_contents
+            .into_iter()
+            .map(StorageItemPresentation::from)
+            .collect();
+
+        FilesHomePageChangeResult {
+            dir_content: displayable_dir_content,
+        }
+    }
+}
+
+impl From<BatchUserFileMoveResult> for FilesHomePageChangeResult {
+    fn from(value: BatchUserFileMoveResult) -> Self {
+        let displayable_dir_content = value
+            .dir_contents
+            .into_iter()
+            .map(StorageItemPresentation::from)
+            .collect();
+
+        FilesHomePageChangeResult {
+            dir_content: displayable_dir_content,
+        }
+    }
+}
+
+impl From<BatchUserFileCopyResult> for FilesHomePageChangeResult {
+    fn from(value: BatchUserFileCopyResult) -> Self {
+        let displayable_dir_content = value
+            .dir_contents
+            .into_iter()
+            .map(StorageItemPresentation::from)
+            .collect();
+
+        FilesHomePageChangeResult {
+            dir_content: displayable_dir_content,
+        }
+    }
+}
+
 impl From<DirectoryCreationResult> for FilesHomePageChangeResult {
     fn from(value: DirectoryCreationResult) -> Self {
         let displayable_dir_content = value
@@ -0,0 +1,369 @@
+use hypertext::prelude::*;
+
+use crate::Component;
+
+use super::FilesHomePageElementId;
+
+pub struct BatchSelectionToolbar {
+    pub download_url: String,
+    pub share_url: String,
+    pub move_dialog_url: String,
+    pub copy_dialog_url: String,
+    pub delete_url: String,
+}
+
+impl Component for BatchSelectionToolbar {
+    fn id(&self) -> String {
+        FilesHomePageElementId::BatchSelectionForm
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for BatchSelectionToolbar {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let loading_effect = format!(
+            "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+        );
+
+        maud! {
+            form
+                id=(self.id())
+                class=(crate::styles::files_home_page::ClassName::BATCH_TOOLBAR)
+                data-hijack
+                method="POST"
+            {
+                span
+                    class=(crate::styles::files_home_page::ClassName::BATCH_SELECTION_COUNT)
+                    data-selection-count
+                {}
+
+                button
+                    type="button"
+                    title="Download"
+                    class=(crate::styles::button::ClassName::BUTTON)
+                    onclick=(format!("downloadSelection(this.form, '{}')", self.download_url))
+                {
+                    (crate::icons::Download::default())
+                    span { "Download" }
+                }
+
+                button
+                    type="button"
+                    title="Share"
+                    class=(crate::styles::button::ClassName::BUTTON)
+                    onclick=(format!("submitSelection(this.form, '{}')", self.share_url))
+                    data-effect=(loading_effect)
+                {
+                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                    (crate::icons::Share2::default())
+                    span { "Share" }
+                }
+
+                button
+                    type="button"
+                    title="Move"
+                    class=(crate::styles::button::ClassName::BUTTON)
+                    onclick=(format!("submitSelection(this.form, '{}')", self.move_dialog_url))
+                    data-effect=(loading_effect)
+                {
+                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                    (crate::icons::Move::default())
+                    span { "Move" }
+                }
+
+                button
+                    type="button"
+                    title="Copy"
+                    class=(crate::styles::button::ClassName::BUTTON)
+                    onclick=(format!("submitSelection(this.form, '{}')", self.copy_dialog_url))
+                    data-effect=(loading_effect)
+                {
+                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                    (crate::icons::Copy::default())
+                    span { "Copy" }
+                }
+
+                button
+                    type="button"
+                    title="Delete"
+                    class=(
+                        crate::styles::button::ClassName::BUTTON, " ",
+                        crate::styles::button::ClassName::BUTTON_DANGER,
+                    )
+                    onclick=(format!("submitSelection(this.form, '{}')", self.delete_url))
+                    data-effect=(loading_effect)
+                {
+                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                    (crate::icons::Trash2::default())
+                    span { "Delete" }
+                }
+
+                button
+                    type="button"
+                    title="Clear selection"
+                    class=(crate::styles::button::ClassName::ICON_BUTTON)
+                    onclick="toggleSelection(this.form, false)"
+                {
+                    (crate::icons::X::default())
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum BatchTransferKind {
+    Move,
+    Copy,
+}
+
+/// Asks for the directory that the selected items are moved or copied into.
+pub struct BatchTransferDialog {
+    pub kind: BatchTransferKind,
+    pub action_url: String,
+    pub paths: Vec<String>,
+    pub destination_directory_path: String,
+}
+
+impl Component for BatchTransferDialog {
+    fn id(&self) -> String {
+        match self.kind {
+            BatchTransferKind::Move => FilesHomePageElementId::BatchMoveDialog,
+            BatchTransferKind::Copy => FilesHomePageElementId::BatchCopyDialog,
+        }
+        .to_str()
+        .to_owned()
+    }
+}
+
+impl Renderable for BatchTransferDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let action = match self.kind {
+            BatchTransferKind::Move => "Move",
+            BatchTransferKind::Copy => "Copy",
+        };
+        let item_count = match self.paths.len() {
+            1 => "1 Item".to_owned(),
+            count => format!("{count} Items"),
+        };
+
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { (action) " " (item_count) }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                form
+                    data-hijack
+                    action=(self.action_url)
+                    method="POST"
+                {
+                    @for path in &self.paths {
+                        input type="hidden" name="paths" value=(path);
+                    }
+
+                    div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "Destination Folder"
+
+                            input
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                type="text"
+                                name="destination_directory_path"
+                                value=(self.destination_directory_path)
+                                placeholder="Enter destination folder";
+                        }
+
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "If an item exists"
+
+                            select
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                name="conflict_policy"
+                            {
+                                @match self.kind {
+                                    BatchTransferKind::Move => {
+                                        option value="keep_both" selected { "Keep both" }
+                                        option value="overwrite" { "Replace the existing item" }
+                                        option value="fail" { "Skip" }
+                                    }
+                                    BatchTransferKind::Copy => {
+                                        option value="auto_rename" selected { "Keep both" }
+                                        option value="overwrite" { "Replace the existing item" }
+                                        option value="skip" { "Skip" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::BUTTON)
+                            onclick="closeClosestDialogAndRemoveElement(this)"
+                        {
+                            "Cancel"
+                        }
+                        button
+                            type="submit"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY,
+                            )
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            (action)
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum BatchResultStatus {
+    Succeeded,
+    Skipped,
+    Shared { share_path: String },
+    Failed { message: &'static str },
+}
+
+pub struct BatchResultItem {
+    pub path: String,
+    pub status: BatchResultStatus,
+}
+
+/// Lists the outcome of a batch operation for each of its items.
+pub struct BatchResultDialog {
+    pub title: String,
+    pub items: Vec<BatchResultItem>,
+}
+
+impl Component for BatchResultDialog {
+    fn id(&self) -> String {
+        FilesHomePageElementId::BatchResultDialog
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for BatchResultDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { (self.title) }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                    ul class=(crate::styles::files_home_page::ClassName::BATCH_RESULT_LIST) {
+                        @for item in &self.items {
+                            (item)
+                        }
+                    }
+                }
+                div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                    button
+                        type="button"
+                        class=(crate::styles::button::ClassName::BUTTON)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        "Close"
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+impl Renderable for BatchResultItem {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let status_class = match self.status {
+            BatchResultStatus::Failed { .. } => {
+                crate::styles::files_home_page::ClassName::BATCH_RESULT_FAILED
+            }
+            _ => crate::styles::files_home_page::ClassName::BATCH_RESULT_SUCCEEDED,
+        };
+
+        maud! {
+            li
+                class=(
+                    crate::styles::files_home_page::ClassName::BATCH_RESULT_ITEM, " ",
+                    status_class,
+                )
+            {
+                @match &self.status {
+                    BatchResultStatus::Failed { .. } => { (crate::icons::CircleX::default()) }
+                    _ => { (crate::icons::Check::default()) }
+                }
+
+                span class=(crate::styles::files_home_page::ClassName::BATCH_RESULT_NAME) title=(self.path) {
+                    (self.path)
+                }
+
+                @match &self.status {
+                    BatchResultStatus::Succeeded => {}
+                    BatchResultStatus::Skipped => {
+                        span class=(crate::styles::files_home_page::ClassName::BATCH_RESULT_MESSAGE) {
+                            "Skipped, the destination exists"
+                        }
+                    }
+                    BatchResultStatus::Shared { share_path } => {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::ICON_BUTTON)
+                            title="Copy share link"
+                            data-init=(format!(
+                                "this.shareLink = window.location.origin + '{share_path}'"
+                            ))
+                            data-on-click=(format!(
+                                "navigator.clipboard.writeText(this.shareLink), this.classList.add('{copied}'), setTimeout(() => this.classList.remove('{copied}'), 1200)",
+                                copied = crate::styles::token_box::ClassName::COPIED,
+                            ))
+                        {
+                            (crate::icons::Link::with_class(crate::styles::token_box::ClassName::COPY_ICON_DEFAULT))
+
+                            (crate::icons::Check::with_class(crate::styles::token_box::ClassName::COPY_ICON_COPIED))
+                        }
+                    }
+                    BatchResultStatus::Failed { message } => {
+                        span class=(crate::styles::files_home_page::ClassName::BATCH_RESULT_MESSAGE) {
+                            (message)
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
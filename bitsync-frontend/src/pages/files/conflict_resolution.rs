@@ -0,0 +1,162 @@
+use hypertext::prelude::*;
+
+use crate::Component;
+
+use super::FilesHomePageElementId;
+
+/// How the operation that ran into a conflict is repeated with the chosen conflict policy.
+pub enum ConflictRetry {
+    /// Posts the form fields to the action URL again, the policy is sent as `conflict_policy`.
+    Form {
+        action_url: String,
+        fields: Vec<(&'static str, String)>,
+    },
+    /// Submits the form with the given id again, the policy is appended to its action URL. Used
+    /// for uploads, whose content only exists in the browser. Only the files named like the
+    /// conflicting items are submitted again.
+    ExistingForm { form_id: &'static str },
+}
+
+/// Asks whether existing items with the same names are replaced or kept next to the new ones.
+pub struct ConflictResolutionDialog {
+    pub message: &'static str,
+    pub item_names: Vec<String>,
+    pub retry: ConflictRetry,
+}
+
+impl Component for ConflictResolutionDialog {
+    fn id(&self) -> String {
+        FilesHomePageElementId::ConflictResolutionDialog
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for ConflictResolutionDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let description = maud! {
+            div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                @if let [item_name] = self.item_names.as_slice() {
+                    p {
+                        (self.message) ": "
+                        strong data-conflicting-item=(item_name) { (item_name) }
+                    }
+                } @else {
+                    p { (self.message) ":" }
+                    ul {
+                        @for item_name in &self.item_names {
+                            li {
+                                strong data-conflicting-item=(item_name) { (item_name) }
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        let conflicting_item_names = "Array.from(this.closest('dialog').querySelectorAll('[data-conflicting-item]'), (item) => item.dataset.conflictingItem)";
+
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { "Name Conflict" }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                @match &self.retry {
+                    ConflictRetry::Form { action_url, fields } => {
+                        form
+                            data-hijack
+                            action=(action_url)
+                            method="POST"
+                        {
+                            @for (name, value) in fields {
+                                input type="hidden" name=(name) value=(value);
+                            }
+                            input type="hidden" name="conflict_policy" value="fail";
+
+                            (description)
+
+                            div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                                button
+                                    type="button"
+                                    class=(crate::styles::button::ClassName::BUTTON)
+                                    onclick="closeClosestDialogAndRemoveElement(this)"
+                                {
+                                    "Cancel"
+                                }
+                                button
+                                    type="submit"
+                                    class=(crate::styles::button::ClassName::BUTTON)
+                                    onclick="this.form.elements.conflict_policy.value = 'keep_both'"
+                                {
+                                    "Keep Both"
+                                }
+                                button
+                                    type="submit"
+                                    class=(
+                                        crate::styles::button::ClassName::BUTTON, " ",
+                                        crate::styles::button::ClassName::BUTTON_PRIMARY,
+                                    )
+                                    onclick="this.form.elements.conflict_policy.value = 'overwrite'"
+                                    data-effect=(format!(
+                                        "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                        loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                    ))
+                                {
+                                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                    "Replace"
+                                }
+                            }
+                        }
+                    }
+                    ConflictRetry::ExistingForm { form_id } => {
+                        (description)
+
+                        div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                            button
+                                type="button"
+                                class=(crate::styles::button::ClassName::BUTTON)
+                                onclick="closeClosestDialogAndRemoveElement(this)"
+                            {
+                                "Cancel"
+                            }
+                            button
+                                type="button"
+                                class=(crate::styles::button::ClassName::BUTTON)
+                                onclick=(format!(
+                                    "resubmitWithConflictPolicy(document.getElementById('{form_id}'), 'keep_both', {conflicting_item_names}), closeClosestDialogAndRemoveElement(this)"
+                                ))
+                            {
+                                "Keep Both"
+                            }
+                            button
+                                type="button"
+                                class=(
+                                    crate::styles::button::ClassName::BUTTON, " ",
+                                    crate::styles::button::ClassName::BUTTON_PRIMARY,
+                                )
+                                onclick=(format!(
+                                    "resubmitWithConflictPolicy(document.getElementById('{form_id}'), 'overwrite', {conflicting_item_names}), closeClosestDialogAndRemoveElement(this)"
+                                ))
+                            {
+                                "Replace"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -8,6 +8,7 @@ pub enum DirectoryCreationDisplayError {
     EmptyName,
     InvalidName,
     InvalidPath,
+    DestinationExists,
     InternalServerError,
 }
 
@@ -17,6 +18,7 @@ impl DirectoryCreationDisplayError {
             Self::EmptyName => "Folder name must not be empty",
             Self::InvalidName => "Folder name must not contain path separators",
             Self::InvalidPath => "Path must not contain '..' segments",
+            Self::DestinationExists => "An item with this name already exists",
             Self::InternalServerError => "An internal server error occurred",
         }
     }
@@ -0,0 +1,95 @@
+use hypertext::prelude::*;
+
+use crate::Component;
+
+use super::FilesHomePageElementId;
+
+pub struct FileCopyDialog {
+    pub action_url: String,
+    pub source_path: String,
+}
+
+impl Component for FileCopyDialog {
+    fn id(&self) -> String {
+        FilesHomePageElementId::FileCopyDialog.to_str().to_owned()
+    }
+}
+
+impl Renderable for FileCopyDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { "Copy Item" }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                form
+                    data-hijack
+                    action=(self.action_url)
+                    method="POST"
+                {
+                    div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "Destination Path"
+
+                            input
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                type="text"
+                                name="destination_path"
+                                value=(self.source_path)
+                                placeholder="Enter destination path";
+                        }
+
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "If the destination exists"
+
+                            select
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                name="conflict_policy"
+                            {
+                                option value="auto_rename" selected { "Keep both" }
+                                option value="overwrite" { "Replace the existing item" }
+                                option value="skip" { "Skip" }
+                            }
+                        }
+                    }
+                    div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::BUTTON)
+                            onclick="closeClosestDialogAndRemoveElement(this)"
+                        {
+                            "Cancel"
+                        }
+                        button
+                            type="submit"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY,
+                            )
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            "Copy"
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
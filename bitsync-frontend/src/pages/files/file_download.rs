@@ -0,0 +1,86 @@
+use hypertext::prelude::*;
+
+use crate::Component;
+
+use super::FilesHomePageElementId;
+
+pub struct FileDownloadDialog {
+    pub action_url: String,
+    pub path: String,
+}
+
+impl Component for FileDownloadDialog {
+    fn id(&self) -> String {
+        FilesHomePageElementId::FileDownloadDialog
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for FileDownloadDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { "Download Folder" }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                // a regular form submission, so that the browser handles the download
+                form
+                    action=(self.action_url)
+                    method="GET"
+                    onsubmit="setTimeout(() => closeClosestDialogAndRemoveElement(this))"
+                {
+                    input type="hidden" name="path" value=(self.path);
+
+                    div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "Archive Format"
+
+                            select
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                name="archive_format"
+                            {
+                                option value="zip" selected { "Zip (uncompressed)" }
+                                option value="zip_deflate" { "Zip (deflate)" }
+                                option value="zip_zstd" { "Zip (zstd)" }
+                                option value="tar_gz" { "tar.gz" }
+                                option value="tar_zst" { "tar.zst" }
+                            }
+                        }
+                    }
+                    div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::BUTTON)
+                            onclick="closeClosestDialogAndRemoveElement(this)"
+                        {
+                            "Cancel"
+                        }
+                        button
+                            type="submit"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY,
+                            )
+                        {
+                            "Download"
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -0,0 +1,180 @@
+use hypertext::prelude::*;
+
+use crate::Component;
+
+use super::FilesHomePageElementId;
+
+pub struct FileExtractDialog {
+    pub action_url: String,
+    pub destination_path: String,
+}
+
+impl Component for FileExtractDialog {
+    fn id(&self) -> String {
+        FilesHomePageElementId::FileExtractDialog
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for FileExtractDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init="this.showModal()"
+                onclick="if (event.target === this) closeClosestDialogAndRemoveElement(this)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { "Extract Archive" }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                form
+                    data-hijack
+                    action=(self.action_url)
+                    method="POST"
+                {
+                    div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "Destination Folder"
+
+                            input
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                type="text"
+                                name="destination_path"
+                                value=(self.destination_path)
+                                placeholder="Enter destination folder";
+                        }
+
+                        label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                            "If a file exists"
+
+                            select
+                                class=(crate::styles::base::ClassName::FORM_CONTROL)
+                                name="conflict_policy"
+                            {
+                                option value="keep_both" selected { "Keep both" }
+                                option value="overwrite" { "Replace the existing file" }
+                                option value="fail" { "Skip" }
+                            }
+                        }
+                    }
+                    div class=(crate::styles::modal::ClassName::MODAL_ACTIONS) {
+                        button
+                            type="button"
+                            class=(crate::styles::button::ClassName::BUTTON)
+                            onclick="closeClosestDialogAndRemoveElement(this)"
+                        {
+                            "Cancel"
+                        }
+                        button
+                            type="submit"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY,
+                            )
+                            data-effect=(format!(
+                                "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                            ))
+                        {
+                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                            "Extract"
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+/// Shows how far a running extraction is and polls `progress_url` until it has finished.
+pub struct ExtractionProgressDialog {
+    pub progress_url: String,
+    pub progress: ExtractionProgressIndicator,
+}
+
+impl Component for ExtractionProgressDialog {
+    fn id(&self) -> String {
+        FilesHomePageElementId::ExtractionProgressDialog
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for ExtractionProgressDialog {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            dialog
+                class=(crate::styles::modal::ClassName::MODAL)
+                id=(self.id())
+                data-init=(format!(
+                    "this.showModal(), this.progressFetch = fetch('{}'), this.progressInterval = setInterval(() => this.progressFetch.trigger(), 1000)",
+                    self.progress_url,
+                ))
+                onclose="clearInterval(this.progressInterval)"
+            {
+                div class=(crate::styles::modal::ClassName::MODAL_HEADER) {
+                    h2 class=(crate::styles::modal::ClassName::MODAL_TITLE) { "Extracting Archive" }
+
+                    button
+                        class=(crate::styles::modal::ClassName::MODAL_CLOSE)
+                        title="Continue in the background"
+                        onclick="closeClosestDialogAndRemoveElement(this)"
+                    {
+                        (crate::icons::X::default())
+                    }
+                }
+                div class=(crate::styles::modal::ClassName::MODAL_BODY) {
+                    (self.progress)
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct ExtractionProgressIndicator {
+    pub processed_entries: usize,
+    pub total_entries: usize,
+}
+
+impl Component for ExtractionProgressIndicator {
+    fn id(&self) -> String {
+        FilesHomePageElementId::ExtractionProgressIndicator
+            .to_str()
+            .to_owned()
+    }
+}
+
+impl Renderable for ExtractionProgressIndicator {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let processed_percentage = match self.total_entries {
+            0 => 0,
+            total_entries => self.processed_entries * 100 / total_entries,
+        };
+
+        maud! {
+            div id=(self.id()) {
+                div class=(crate::styles::modal::ClassName::PROGRESS_BAR) {
+                    div
+                        class=(crate::styles::modal::ClassName::PROGRESS_BAR_FILL)
+                        style=(format!("width: {processed_percentage}%"))
+                    {}
+                }
+                p class=(crate::styles::modal::ClassName::PROGRESS_LABEL) {
+                    (self.processed_entries) " of " (self.total_entries) " entries extracted"
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
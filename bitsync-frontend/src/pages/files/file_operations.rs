@@ -2,6 +2,10 @@ pub enum UserFileUploadDisplayError {
     InvalidPath,
     NoFileProvided,
     NoFileNameProvided,
+    QuotaExceeded,
+    ParentNotADirectory,
+    DestinationExists,
+    DestinationsExist,
     InternalServerError,
 }
 
@@ -11,6 +15,10 @@ impl UserFileUploadDisplayError {
             Self::InvalidPath => "Path must not contain '..' segments",
             Self::NoFileProvided => "No file was provided",
             Self::NoFileNameProvided => "No file name was provided",
+            Self::QuotaExceeded => "The upload exceeds the available storage space",
+            Self::ParentNotADirectory => "A file exists where a folder of the upload is expected",
+            Self::DestinationExists => "A file with this name already exists",
+            Self::DestinationsExist => "Files with these names already exist",
             Self::InternalServerError => "An internal server error occurred",
         }
     }
@@ -30,6 +38,42 @@ impl UserFileDownloadDisplayError {
     }
 }
 
+pub enum UserFileViewDisplayError {
+    InvalidPath,
+    NotAFile,
+    NotFound,
+    InternalServerError,
+}
+
+impl UserFileViewDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::NotAFile => "Only files can be viewed",
+            Self::NotFound => "The file does not exist",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub enum UserFileThumbnailDisplayError {
+    InvalidPath,
+    Unsupported,
+    NotFound,
+    InternalServerError,
+}
+
+impl UserFileThumbnailDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::Unsupported => "No thumbnail can be shown for this item",
+            Self::NotFound => "The file does not exist",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
 pub enum UserFileDeletionDisplayError {
     InvalidPath,
     InternalServerError,
@@ -47,6 +91,9 @@ impl UserFileDeletionDisplayError {
 pub enum UserFileMoveDisplayError {
     InvalidPath,
     DestinationSameAsSource,
+    DestinationInsideSource,
+    SourceInsideDestination,
+    DestinationExists,
     InternalServerError,
 }
 
@@ -55,6 +102,115 @@ impl UserFileMoveDisplayError {
         match self {
             Self::InvalidPath => "Path must not contain '..' segments",
             Self::DestinationSameAsSource => "New path is the same as the old path",
+            Self::DestinationInsideSource => "A directory cannot be moved into itself",
+            Self::SourceInsideDestination => {
+                "A folder containing the moved item cannot be replaced"
+            }
+            Self::DestinationExists => "An item with this name already exists",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub enum UserFileRenameDisplayError {
+    EmptyFileName,
+    InvalidFileName,
+    RootNotRenamable,
+    ItemNotFound,
+    DestinationExists,
+    InternalServerError,
+}
+
+impl UserFileRenameDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::EmptyFileName => "The name must not be empty",
+            Self::InvalidFileName => "The name must not contain '/' or be '.' or '..'",
+            Self::RootNotRenamable => "The root folder cannot be renamed",
+            Self::ItemNotFound => "The item does not exist",
+            Self::DestinationExists => "An item with this name already exists",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub enum UserFileCopyDisplayError {
+    InvalidPath,
+    DestinationSameAsSource,
+    DestinationInsideSource,
+    SourceInsideDestination,
+    DestinationExists,
+    QuotaExceeded,
+    InternalServerError,
+}
+
+impl UserFileCopyDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::DestinationSameAsSource => "Copy path is the same as the original path",
+            Self::DestinationInsideSource => "A directory cannot be copied into itself",
+            Self::SourceInsideDestination => {
+                "A folder containing the copied item cannot be replaced"
+            }
+            Self::DestinationExists => "An item already exists at the copy path",
+            Self::QuotaExceeded => "The copy exceeds the available storage space",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub enum UserFileExtractDisplayError {
+    InvalidPath,
+    ArchiveNotAFile,
+    InvalidArchive,
+    UnsafeEntryPath,
+    EntrySizeExceeded,
+    ParentNotADirectory,
+    QuotaExceeded,
+    ExtractionNotFound,
+    ExtractionAlreadyRunning,
+    InternalServerError,
+}
+
+impl UserFileExtractDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::ArchiveNotAFile => "Only files can be extracted",
+            Self::InvalidArchive => "The file is not a readable zip archive",
+            Self::UnsafeEntryPath => {
+                "The archive contains entries outside of the destination folder"
+            }
+            Self::EntrySizeExceeded => {
+                "The archive contains entries larger than their declared size"
+            }
+            Self::ParentNotADirectory => "A file exists where a folder of the archive is expected",
+            Self::QuotaExceeded => "The extracted files exceed the available storage space",
+            Self::ExtractionNotFound => "The extraction is not running anymore",
+            Self::ExtractionAlreadyRunning => {
+                "Wait for the running extraction to finish before starting another one"
+            }
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub enum BatchOperationDisplayError {
+    NothingSelected,
+    InvalidPath,
+    DestinationNotADirectory,
+    ItemNotFound,
+    InternalServerError,
+}
+
+impl BatchOperationDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::NothingSelected => "No items are selected",
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::DestinationNotADirectory => "The destination is not an existing folder",
+            Self::ItemNotFound => "The item does not exist",
             Self::InternalServerError => "An internal server error occurred",
         }
     }
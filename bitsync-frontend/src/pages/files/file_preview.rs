@@ -0,0 +1,236 @@
+use std::{path::Path, sync::LazyLock};
+
+use bitsync_core::use_case::user_files::{
+    read_user_file_item::{TEXT_PREVIEW_SIZE_LIMIT, TextFileContent},
+    view_user_file::FilePreviewKind,
+};
+use hypertext::prelude::*;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag};
+use syntect::{highlighting::ThemeSet, html::highlighted_html_for_string, parsing::SyntaxSet};
+
+/// Larger files are shown without highlighting, which would take too long.
+const SYNTAX_HIGHLIGHTING_SIZE_LIMIT: usize = 256 * 1024;
+const SYNTAX_HIGHLIGHTING_THEME: &str = "base16-ocean.dark";
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// Shows a file in the single file page, using the element the browser needs for its type.
+pub struct FilePreview {
+    pub kind: FilePreviewKind,
+    pub view_url: String,
+    pub file_name: String,
+}
+
+impl Renderable for FilePreview {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::FILE_PREVIEW) {
+                @match self.kind {
+                    FilePreviewKind::Image => {
+                        img
+                            class=(crate::styles::files_home_page::ClassName::FILE_PREVIEW_IMAGE)
+                            src=(self.view_url)
+                            alt=(self.file_name);
+                    }
+                    FilePreviewKind::Video => {
+                        video
+                            class=(crate::styles::files_home_page::ClassName::FILE_PREVIEW_MEDIA)
+                            src=(self.view_url)
+                            controls
+                            preload="metadata"
+                        {}
+                    }
+                    FilePreviewKind::Audio => {
+                        audio
+                            class=(crate::styles::files_home_page::ClassName::FILE_PREVIEW_MEDIA)
+                            src=(self.view_url)
+                            controls
+                            preload="metadata"
+                        {}
+                    }
+                    FilePreviewKind::Pdf => {
+                        iframe
+                            class=(crate::styles::files_home_page::ClassName::FILE_PREVIEW_DOCUMENT)
+                            src=(self.view_url)
+                            title=(self.file_name)
+                        {}
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+/// Shows the content of a text file in the single file page. Markdown is rendered, CSV and TSV
+/// files are shown as table and anything else as syntax highlighted code.
+pub struct TextFilePreview {
+    pub file_name: String,
+    pub content: TextFileContent,
+}
+
+impl Renderable for TextFilePreview {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let text = match &self.content {
+            TextFileContent::Text(text) => text,
+            TextFileContent::TooLarge => {
+                maud! {
+                    p class=(crate::styles::files_home_page::ClassName::TEXT_PREVIEW_NOTICE) {
+                        "Files larger than " (crate::format_file_size(TEXT_PREVIEW_SIZE_LIMIT))
+                        " are not previewed"
+                    }
+                }
+                .render_to(buffer);
+
+                return;
+            }
+            TextFileContent::Binary => return,
+        };
+
+        let extension = Path::new(&self.file_name)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let table_rows = match extension.as_str() {
+            "csv" => parse_delimited_rows(text, b','),
+            "tsv" => parse_delimited_rows(text, b'\t'),
+            _ => None,
+        };
+
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::TEXT_PREVIEW) {
+                @if let Some(table_rows) = &table_rows {
+                    (DelimitedTable { rows: table_rows })
+                } @else if matches!(extension.as_str(), "md" | "markdown") {
+                    div class=(crate::styles::files_home_page::ClassName::MARKDOWN_PREVIEW) {
+                        // XSS SAFETY: raw HTML and links with scripting schemes are removed while
+                        // rendering
+                        (hypertext::Raw::dangerously_create(render_markdown(text)))
+                    }
+                } @else if let Some(highlighted_code) = highlight_code(&self.file_name, &extension, text) {
+                    // XSS SAFETY: the highlighter escapes the code
+                    (hypertext::Raw::dangerously_create(highlighted_code))
+                } @else {
+                    pre { (text) }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+struct DelimitedTable<'a> {
+    rows: &'a [csv::StringRecord],
+}
+
+impl Renderable for DelimitedTable<'_> {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let Some((header, rows)) = self.rows.split_first() else {
+            return;
+        };
+
+        maud! {
+            table class=(crate::styles::files_home_page::ClassName::TABLE_PREVIEW) {
+                thead {
+                    tr {
+                        @for field in header {
+                            th { (field) }
+                        }
+                    }
+                }
+                tbody {
+                    @for row in rows {
+                        tr {
+                            @for field in row {
+                                td { (field) }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+/// `None` if the text is not valid CSV, in which case it is shown as code.
+fn parse_delimited_rows(text: &str, delimiter: u8) -> Option<Vec<csv::StringRecord>> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes())
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .ok()
+}
+
+fn render_markdown(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(text, options).map(|event| match event {
+        // raw HTML could run scripts in the page, so it is shown as text
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    });
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events);
+
+    html
+}
+
+/// Removes URLs with schemes other than `http`, `https` and `mailto`, such as `javascript:`.
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| !scheme.contains(['/', '?', '#']));
+
+    match scheme {
+        Some(scheme)
+            if !["http", "https", "mailto"]
+                .iter()
+                .any(|allowed_scheme| scheme.eq_ignore_ascii_case(allowed_scheme)) =>
+        {
+            CowStr::Borrowed("")
+        }
+        _ => url,
+    }
+}
+
+fn highlight_code(file_name: &str, extension: &str, text: &str) -> Option<String> {
+    // files without extension such as `Makefile` are found by their name
+    let syntax = SYNTAX_SET
+        .find_syntax_by_extension(extension)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(file_name))
+        .or_else(|| SYNTAX_SET.find_syntax_by_first_line(text))
+        .filter(|_| text.len() <= SYNTAX_HIGHLIGHTING_SIZE_LIMIT)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let theme = THEME_SET.themes.get(SYNTAX_HIGHLIGHTING_THEME)?;
+
+    highlighted_html_for_string(text, &SYNTAX_SET, syntax, theme).ok()
+}
@@ -0,0 +1,39 @@
+use hypertext::prelude::*;
+
+/// Name cell content of the directory listing that can be switched into an inline rename form.
+pub struct InlineRenameName<'a> {
+    pub name: &'a str,
+    pub url: &'a str,
+    pub rename_url: &'a str,
+}
+
+impl Renderable for InlineRenameName<'_> {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            a href=(self.url) data-inline-rename-link { (self.name) }
+
+            form
+                class=(crate::styles::files_home_page::ClassName::RENAME_FORM)
+                data-hijack
+                data-inline-rename
+                action=(self.rename_url)
+                method="POST"
+                hidden
+            {
+                input
+                    class=(
+                        crate::styles::base::ClassName::FORM_CONTROL, " ",
+                        crate::styles::files_home_page::ClassName::RENAME_INPUT,
+                    )
+                    type="text"
+                    name="new_file_name"
+                    value=(self.name)
+                    aria-label="New name"
+                    required
+                    onkeydown="if (event.key === 'Escape') cancelInlineRename(this.closest('tr'))"
+                    onblur="cancelInlineRename(this.closest('tr'))";
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -0,0 +1,135 @@
+use bitsync_database::entity::FileVersion;
+use hypertext::prelude::*;
+
+use crate::format_file_size;
+
+fn format_datetime(datetime: &time::OffsetDateTime) -> String {
+    let format =
+        time::macros::format_description!("[month repr:short] [day], [year], [hour]:[minute] UTC");
+
+    datetime
+        .to_offset(time::UtcOffset::UTC)
+        .format(&format)
+        .unwrap_or_else(|_| "Unknown".to_owned())
+}
+
+pub struct FileVersionListItem {
+    created_at: String,
+    size: String,
+    download_url: String,
+    restore_url: String,
+}
+
+impl From<FileVersion> for FileVersionListItem {
+    fn from(value: FileVersion) -> Self {
+        Self {
+            created_at: format_datetime(&value.created_at),
+            size: format_file_size(value.size.max(0) as u64),
+            download_url: bitsync_routes::GetUserFileVersionDownload {
+                file_version_id: value.id,
+            }
+            .to_string(),
+            restore_url: bitsync_routes::PostUserFileVersionRestore {
+                file_version_id: value.id,
+            }
+            .to_string(),
+        }
+    }
+}
+
+pub struct FileVersionList {
+    pub versions: Vec<FileVersionListItem>,
+}
+
+impl From<Vec<FileVersion>> for FileVersionList {
+    fn from(value: Vec<FileVersion>) -> Self {
+        Self {
+            versions: value.into_iter().map(FileVersionListItem::from).collect(),
+        }
+    }
+}
+
+impl Renderable for FileVersionList {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            h2 class=(crate::styles::files_home_page::ClassName::FILE_VERSIONS_TITLE) {
+                "Version history"
+            }
+
+            table class=(crate::styles::files_home_page::ClassName::FILE_BROWSER) {
+                @if self.versions.is_empty() {
+                    tbody {
+                        tr {
+                            td
+                                class=(crate::styles::files_home_page::ClassName::EMPTY_STATE)
+                                colspan="4"
+                            {
+                                (crate::icons::Cloudy::default())
+                                p { "No previous versions" }
+                            }
+                        }
+                    }
+                } @else {
+                    thead {
+                        tr class=(crate::styles::files_home_page::ClassName::FILE_HEADER) {
+                            th {}
+                            th { "Replaced" }
+                            th { "Size" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        @for version in &self.versions {
+                            tr class=(crate::styles::files_home_page::ClassName::FILE_ITEM) {
+                                td
+                                    class=(
+                                        crate::styles::files_home_page::ClassName::FILE_ICON, " ",
+                                        crate::styles::files_home_page::ClassName::FILE_ICON_SECONDARY,
+                                    )
+                                {
+                                    (crate::icons::FileText::default())
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
+                                    (version.created_at)
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                    (version.size)
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_ACTIONS) {
+                                    a
+                                        title="Download"
+                                        class=(crate::styles::files_home_page::ClassName::FILE_ACTION_BUTTON)
+                                        href=(version.download_url)
+                                    {
+                                        (crate::icons::Download::default())
+                                    }
+
+                                    button
+                                        title="Restore"
+                                        class=(crate::styles::files_home_page::ClassName::FILE_ACTION_BUTTON)
+                                        data-init=(format!(
+                                            "this.fetch = fetch('{}', {{ method: 'POST' }})",
+                                            version.restore_url,
+                                        ))
+                                        data-on-click__throttle.1s="this.fetch.trigger()"
+                                        data-effect=(format!(
+                                            "handleButtonLoading(this, this.fetch, '{loading}')",
+                                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                        ))
+                                    {
+                                        div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                        (crate::icons::RotateCcw::default())
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -0,0 +1,421 @@
+use bitsync_core::use_case::user_share::read_user_share_item::{
+    UserShareDirectoryItem, UserShareDirectoryResult, UserShareFileResult, UserShareItemResult,
+};
+use bitsync_routes::TypedPath;
+use bitsync_storage::storage_item::StorageItemKind;
+use hypertext::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    Component,
+    error_banner::OptionalErrorBanner,
+    error_card::ErrorCard,
+    format_file_size,
+    pages::{
+        base::PublicShareDocument,
+        files::{Breadcrumb, BreadcrumbCrumb, build_breadcrumb_with_urls},
+    },
+};
+
+fn public_share_page_url(user_share_id: Uuid, path: String) -> String {
+    bitsync_routes::GetPublicSharePage { user_share_id }
+        .with_query_params(bitsync_routes::GetPublicSharePageQueryParameters { path })
+        .to_string()
+}
+
+fn public_share_download_url(user_share_id: Uuid, path: String) -> String {
+    bitsync_routes::GetPublicShareDownload { user_share_id }
+        .with_query_params(bitsync_routes::GetPublicShareDownloadQueryParameters { path })
+        .to_string()
+}
+
+pub struct PublicSharePage {
+    pub is_user_logged_in: bool,
+    pub item: PublicShareItem,
+}
+
+impl Renderable for PublicSharePage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            PublicShareDocument is_user_logged_in=(self.is_user_logged_in) {
+                style { (crate::styles::files_home_page::STYLE_SHEET) }
+                main {
+                    @match &self.item {
+                        PublicShareItem::Directory(directory) => { (directory) }
+                        PublicShareItem::File(file) => { (file) }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum PublicShareItem {
+    Directory(PublicShareDirectory),
+    File(PublicShareFile),
+}
+
+impl From<UserShareItemResult> for PublicShareItem {
+    fn from(value: UserShareItemResult) -> Self {
+        match value {
+            UserShareItemResult::Directory(directory_result) => {
+                PublicShareItem::Directory(directory_result.into())
+            }
+            UserShareItemResult::File(file_result) => PublicShareItem::File(file_result.into()),
+        }
+    }
+}
+
+struct PublicShareDirectoryEntry {
+    name: String,
+    size: String,
+    is_directory: bool,
+    url: String,
+    download_url: String,
+}
+
+impl PublicShareDirectoryEntry {
+    fn new(user_share_id: Uuid, value: UserShareDirectoryItem) -> Self {
+        Self {
+            url: public_share_page_url(user_share_id, value.path.clone()),
+            download_url: public_share_download_url(user_share_id, value.path),
+            name: value.name,
+            size: format_file_size(value.size),
+            is_directory: value.kind == StorageItemKind::Directory,
+        }
+    }
+}
+
+pub struct PublicShareDirectory {
+    directory_name: String,
+    download_zip_url: String,
+    dir_content: Vec<PublicShareDirectoryEntry>,
+    breadcrumb: Vec<BreadcrumbCrumb>,
+}
+
+impl From<UserShareDirectoryResult> for PublicShareDirectory {
+    fn from(value: UserShareDirectoryResult) -> Self {
+        let user_share_id = value.user_share_id;
+
+        PublicShareDirectory {
+            directory_name: value.directory_name,
+            download_zip_url: public_share_download_url(user_share_id, value.path),
+            dir_content: value
+                .dir_contents
+                .into_iter()
+                .map(|item| PublicShareDirectoryEntry::new(user_share_id, item))
+                .collect(),
+            breadcrumb: build_breadcrumb_with_urls(value.breadcrumb_segments, |path| {
+                public_share_page_url(user_share_id, path)
+            }),
+        }
+    }
+}
+
+impl Renderable for PublicShareDirectory {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_BANNER) {
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ICON) {
+                    (crate::icons::FolderOpen::default())
+                }
+
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TEXT) {
+                    h1 class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TITLE) {
+                        (self.directory_name)
+                    }
+
+                    (Breadcrumb { crumbs: &self.breadcrumb })
+                }
+
+                @if !self.dir_content.is_empty() {
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ACTIONS) {
+                        a
+                            title="Download"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY, " ",
+                                crate::styles::files_home_page::ClassName::FILE_HEADER_DOWNLOAD,
+                            )
+                            href=(self.download_zip_url)
+                        {
+                            (crate::icons::Download::default())
+                            span { "Download" }
+                        }
+                    }
+                }
+            }
+
+            table class=(crate::styles::files_home_page::ClassName::FILE_BROWSER) {
+                @if self.dir_content.is_empty() {
+                    tbody {
+                        tr {
+                            td
+                                class=(crate::styles::files_home_page::ClassName::EMPTY_STATE)
+                                colspan="4"
+                            {
+                                (crate::icons::Cloudy::default())
+                                p { "This folder is empty" }
+                            }
+                        }
+                    }
+                } @else {
+                    thead {
+                        tr class=(crate::styles::files_home_page::ClassName::FILE_HEADER) {
+                            th {}
+                            th { "Name" }
+                            th { "Size" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        @for dir_item in &self.dir_content {
+                            tr class=(crate::styles::files_home_page::ClassName::FILE_ITEM) {
+                                @if dir_item.is_directory {
+                                    td class=(crate::styles::files_home_page::ClassName::FILE_ICON) {
+                                        (crate::icons::Folder::default())
+                                    }
+                                } @else {
+                                    td
+                                        class=(
+                                            crate::styles::files_home_page::ClassName::FILE_ICON, " ",
+                                            crate::styles::files_home_page::ClassName::FILE_ICON_SECONDARY,
+                                        )
+                                    {
+                                        (crate::icons::FileText::default())
+                                    }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
+                                    a href=(dir_item.url) { (dir_item.name) }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                    @if dir_item.is_directory {
+                                        "\u{2014}"
+                                    } @else {
+                                        (dir_item.size)
+                                    }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_ACTIONS) {
+                                    a
+                                        title="Download"
+                                        class=(crate::styles::files_home_page::ClassName::FILE_ACTION_BUTTON)
+                                        href=(dir_item.download_url)
+                                    {
+                                        (crate::icons::Download::default())
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct PublicShareFile {
+    file_name: String,
+    size: String,
+    download_url: String,
+    breadcrumb: Vec<BreadcrumbCrumb>,
+}
+
+impl From<UserShareFileResult> for PublicShareFile {
+    fn from(value: UserShareFileResult) -> Self {
+        let user_share_id = value.user_share_id;
+
+        PublicShareFile {
+            file_name: value.file_name,
+            size: format_file_size(value.size),
+            download_url: public_share_download_url(user_share_id, value.path),
+            breadcrumb: build_breadcrumb_with_urls(value.breadcrumb_segments, |path| {
+                public_share_page_url(user_share_id, path)
+            }),
+        }
+    }
+}
+
+impl Renderable for PublicShareFile {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_BANNER) {
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ICON) {
+                    (crate::icons::FileText::default())
+                }
+
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TEXT) {
+                    h1 class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TITLE) {
+                        (self.file_name)
+                    }
+
+                    (Breadcrumb { crumbs: &self.breadcrumb })
+                }
+
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ACTIONS) {
+                    span class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                        (self.size)
+                    }
+
+                    a
+                        title="Download"
+                        class=(
+                            crate::styles::button::ClassName::BUTTON, " ",
+                            crate::styles::button::ClassName::BUTTON_PRIMARY, " ",
+                            crate::styles::files_home_page::ClassName::FILE_HEADER_DOWNLOAD,
+                        )
+                        href=(self.download_url)
+                    {
+                        (crate::icons::Download::default())
+                        span { "Download" }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum PublicShareDisplayError {
+    NotFound,
+    Expired,
+    DownloadLimitReached,
+    InvalidPath,
+    InternalServerError,
+}
+
+impl PublicShareDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::NotFound => "This share does not exist or is no longer available",
+            Self::Expired => "This share has expired",
+            Self::DownloadLimitReached => "This share has reached its download limit",
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub struct PublicShareErrorPage {
+    pub is_user_logged_in: bool,
+    pub error: PublicShareDisplayError,
+}
+
+impl Renderable for PublicShareErrorPage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            PublicShareDocument is_user_logged_in=(self.is_user_logged_in) {
+                style { (crate::styles::public_share_page::STYLE_SHEET) }
+                main {
+                    div class=(crate::styles::public_share_page::ClassName::ERROR_CONTAINER) {
+                        ErrorCard
+                            title=("Share unavailable".to_owned())
+                            message=(self.error.message().to_owned());
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum PublicShareUnlockDisplayError {
+    InvalidPassword,
+    InternalServerError,
+}
+
+impl PublicShareUnlockDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidPassword => "The password is incorrect",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub struct PublicSharePasswordPage {
+    pub is_user_logged_in: bool,
+    pub form: PublicSharePasswordForm,
+}
+
+impl Renderable for PublicSharePasswordPage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            PublicShareDocument is_user_logged_in=(self.is_user_logged_in) {
+                style { (crate::styles::public_share_page::STYLE_SHEET) }
+                main {
+                    (self.form)
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct PublicSharePasswordForm {
+    pub unlock_url: String,
+    pub error: Option<PublicShareUnlockDisplayError>,
+}
+
+impl Component for PublicSharePasswordForm {
+    fn id(&self) -> String {
+        "public-share-password-form".to_owned()
+    }
+}
+
+impl Renderable for PublicSharePasswordForm {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            form
+                id=(self.id())
+                class=(crate::styles::public_share_page::ClassName::UNLOCK_FORM)
+                data-hijack
+                action=(self.unlock_url)
+                method="POST"
+            {
+                div class=(crate::styles::public_share_page::ClassName::UNLOCK_HEADER) {
+                    (crate::icons::Lock::default())
+                    h1 { "Password required" }
+                }
+
+                p class=(crate::styles::public_share_page::ClassName::UNLOCK_DESCRIPTION) {
+                    "This share is protected. Enter the password to access it."
+                }
+
+                label class=(crate::styles::modal::ClassName::FORM_LABEL) {
+                    "Password"
+
+                    input
+                        class=(crate::styles::base::ClassName::FORM_CONTROL)
+                        type="password"
+                        name="password"
+                        placeholder="Enter the share password"
+                        autofocus
+                        required;
+                }
+
+                OptionalErrorBanner message=(self.error.as_ref().map(|error| error.message().to_owned()));
+
+                button
+                    type="submit"
+                    class=(
+                        crate::styles::button::ClassName::BUTTON, " ",
+                        crate::styles::button::ClassName::BUTTON_PRIMARY,
+                    )
+                    data-effect=(format!(
+                        "handleButtonLoading(this, this.form.hsFetch, '{loading}')",
+                        loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                    ))
+                {
+                    div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                    "Unlock"
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
@@ -0,0 +1,624 @@
+use bitsync_core::use_case::{
+    shared_with_me::{
+        list_shared_with_me::SharedWithMeEntry, read_shared_item::SharedItemResult,
+        shared::SharedDirectoryChangeResult,
+    },
+    user_files::read_user_file_item::DirectoryBreadcrumbSegment,
+    user_share::read_user_share_item::{
+        UserShareDirectoryItem, UserShareDirectoryResult, UserShareFileResult, UserShareItemResult,
+    },
+};
+use bitsync_database::entity::UserSharePermission;
+use bitsync_routes::TypedPath;
+use bitsync_storage::storage_item::StorageItemKind;
+use hypertext::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    Component,
+    error_card::ErrorCard,
+    format_file_size,
+    pages::{
+        base::LoggedInDocument,
+        files::{
+            Breadcrumb, BreadcrumbCrumb, BreadcrumbLink, FileUploadForm, FilesHomePageElementId,
+            build_breadcrumb_with_urls,
+        },
+    },
+};
+
+fn shared_item_page_url(user_share_id: Uuid, path: String) -> String {
+    bitsync_routes::GetSharedItemPage { user_share_id }
+        .with_query_params(bitsync_routes::GetSharedItemPageQueryParameters { path })
+        .to_string()
+}
+
+fn shared_item_download_url(user_share_id: Uuid, path: String) -> String {
+    bitsync_routes::GetSharedItemDownload { user_share_id }
+        .with_query_params(bitsync_routes::GetSharedItemDownloadQueryParameters { path })
+        .to_string()
+}
+
+fn build_shared_item_breadcrumb(
+    user_share_id: Uuid,
+    segments: Vec<DirectoryBreadcrumbSegment>,
+) -> Vec<BreadcrumbCrumb> {
+    let mut breadcrumb = vec![BreadcrumbCrumb::Link(BreadcrumbLink {
+        name: "Shared with me".to_owned(),
+        url: bitsync_routes::GetSharedWithMePage.to_string(),
+    })];
+
+    breadcrumb.extend(build_breadcrumb_with_urls(segments, |path| {
+        shared_item_page_url(user_share_id, path)
+    }));
+
+    breadcrumb
+}
+
+struct PermissionBadge<'a> {
+    permission: &'a UserSharePermission,
+}
+
+impl Renderable for PermissionBadge<'_> {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            @match self.permission {
+                UserSharePermission::Read => {
+                    span class=(crate::styles::badge::ClassName::BADGE) { "Can view" }
+                }
+                UserSharePermission::ReadWrite => {
+                    span class=(
+                        crate::styles::badge::ClassName::BADGE, " ",
+                        crate::styles::badge::ClassName::BADGE_WARNING,
+                    ) {
+                        "Can edit"
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct SharedWithMePage {
+    pub entries: Vec<SharedWithMeEntry>,
+}
+
+impl Renderable for SharedWithMePage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            LoggedInDocument current_path=(None::<String>) {
+                style { (crate::styles::files_home_page::STYLE_SHEET) }
+                main {
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_BANNER) {
+                        div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ICON) {
+                            (crate::icons::Users::default())
+                        }
+
+                        div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TEXT) {
+                            h1 class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TITLE) {
+                                "Shared with me"
+                            }
+                        }
+                    }
+
+                    table class=(crate::styles::files_home_page::ClassName::FILE_BROWSER) {
+                        @if self.entries.is_empty() {
+                            tbody {
+                                tr {
+                                    td
+                                        class=(crate::styles::files_home_page::ClassName::EMPTY_STATE)
+                                        colspan="4"
+                                    {
+                                        (crate::icons::Cloudy::default())
+                                        p { "Nothing has been shared with you yet" }
+                                    }
+                                }
+                            }
+                        } @else {
+                            thead {
+                                tr class=(crate::styles::files_home_page::ClassName::FILE_HEADER) {
+                                    th {}
+                                    th { "Name" }
+                                    th { "Owner" }
+                                    th {}
+                                }
+                            }
+                            tbody {
+                                @for entry in &self.entries {
+                                    tr class=(crate::styles::files_home_page::ClassName::FILE_ITEM) {
+                                        @if entry.kind == StorageItemKind::Directory {
+                                            td class=(crate::styles::files_home_page::ClassName::FILE_ICON) {
+                                                (crate::icons::Folder::default())
+                                            }
+                                        } @else {
+                                            td
+                                                class=(
+                                                    crate::styles::files_home_page::ClassName::FILE_ICON, " ",
+                                                    crate::styles::files_home_page::ClassName::FILE_ICON_SECONDARY,
+                                                )
+                                            {
+                                                (crate::icons::FileText::default())
+                                            }
+                                        }
+
+                                        td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
+                                            a href=(shared_item_page_url(entry.user_share_id, "/".to_owned())) {
+                                                (entry.name)
+                                            }
+                                        }
+
+                                        td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                            (entry.owner_username)
+                                        }
+
+                                        td class=(crate::styles::files_home_page::ClassName::FILE_ACTIONS) {
+                                            (PermissionBadge { permission: &entry.permission })
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum SharedItemPage {
+    Directory(SharedDirectoryPage),
+    File(SharedFilePage),
+}
+
+impl From<SharedItemResult> for SharedItemPage {
+    fn from(value: SharedItemResult) -> Self {
+        match value.item {
+            UserShareItemResult::Directory(directory_result) => SharedItemPage::Directory(
+                SharedDirectoryPage::new(directory_result, value.permission, value.owner_username),
+            ),
+            UserShareItemResult::File(file_result) => SharedItemPage::File(SharedFilePage::new(
+                file_result,
+                value.permission,
+                value.owner_username,
+            )),
+        }
+    }
+}
+
+impl Renderable for SharedItemPage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            LoggedInDocument current_path=(None::<String>) {
+                style { (crate::styles::files_home_page::STYLE_SHEET) }
+                @match self {
+                    SharedItemPage::Directory(page) => { (page) }
+                    SharedItemPage::File(page) => { (page) }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+#[derive(Clone)]
+struct SharedDirectoryEntry {
+    name: String,
+    size: String,
+    is_directory: bool,
+    url: String,
+    download_url: String,
+    delete_url: String,
+}
+
+impl SharedDirectoryEntry {
+    fn new(user_share_id: Uuid, value: UserShareDirectoryItem) -> Self {
+        Self {
+            url: shared_item_page_url(user_share_id, value.path.clone()),
+            download_url: shared_item_download_url(user_share_id, value.path.clone()),
+            delete_url: bitsync_routes::GetSharedItemDelete { user_share_id }
+                .with_query_params(bitsync_routes::GetSharedItemDeleteQueryParameters {
+                    path: value.path,
+                })
+                .to_string(),
+            name: value.name,
+            size: format_file_size(value.size),
+            is_directory: value.kind == StorageItemKind::Directory,
+        }
+    }
+}
+
+pub struct SharedDirectoryPage {
+    directory_name: String,
+    owner_username: String,
+    permission: UserSharePermission,
+    download_zip_url: String,
+    file_upload_url: String,
+    directory_creation_dialog_url: String,
+    breadcrumb: Vec<BreadcrumbCrumb>,
+    table: SharedDirectoryTable,
+}
+
+impl SharedDirectoryPage {
+    fn new(
+        value: UserShareDirectoryResult,
+        permission: UserSharePermission,
+        owner_username: String,
+    ) -> Self {
+        let user_share_id = value.user_share_id;
+
+        SharedDirectoryPage {
+            directory_name: value.directory_name,
+            owner_username,
+            download_zip_url: shared_item_download_url(user_share_id, value.path.clone()),
+            file_upload_url: bitsync_routes::PostSharedItemUpload { user_share_id }
+                .with_query_params(bitsync_routes::PostSharedItemUploadQueryParameters {
+                    path: value.path.clone(),
+                })
+                .to_string(),
+            directory_creation_dialog_url: bitsync_routes::GetSharedItemDirectoryCreationDialog {
+                user_share_id,
+            }
+            .with_query_params(
+                bitsync_routes::GetSharedItemDirectoryCreationDialogQueryParameters {
+                    path: value.path,
+                },
+            )
+            .to_string(),
+            breadcrumb: build_shared_item_breadcrumb(user_share_id, value.breadcrumb_segments),
+            table: SharedDirectoryTable {
+                is_writable: permission == UserSharePermission::ReadWrite,
+                dir_content: value
+                    .dir_contents
+                    .into_iter()
+                    .map(|item| SharedDirectoryEntry::new(user_share_id, item))
+                    .collect(),
+            },
+            permission,
+        }
+    }
+}
+
+impl Renderable for SharedDirectoryPage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        let is_writable = self.permission == UserSharePermission::ReadWrite;
+
+        maud! {
+            main
+                data-init=[is_writable.then(|| format!(
+                    "initDropUpload(this, document.getElementById('{form_id}'), '{active_class}')",
+                    form_id = FilesHomePageElementId::FileUploadForm.to_str(),
+                    active_class = crate::styles::files_home_page::ClassName::DROP_ZONE_ACTIVE,
+                ))]
+            {
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_BANNER) {
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ICON) {
+                        (crate::icons::FolderOpen::default())
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TEXT) {
+                        h1 class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TITLE) {
+                            (self.directory_name)
+                        }
+
+                        (Breadcrumb { crumbs: &self.breadcrumb })
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ACTIONS) {
+                        span class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                            "Shared by " (self.owner_username)
+                        }
+
+                        (PermissionBadge { permission: &self.permission })
+
+                        @if is_writable {
+                            button
+                                title="Upload"
+                                class=(crate::styles::button::ClassName::BUTTON)
+                                data-init=(format!(
+                                    "this.uploadInput = document.getElementById('{form_id}').querySelector('input[type=file]')",
+                                    form_id = FilesHomePageElementId::FileUploadForm.to_str(),
+                                ))
+                                data-on-click="this.uploadInput.click()"
+                            {
+                                (crate::icons::Upload::default())
+                                span { "Upload" }
+                            }
+
+                            button
+                                title="New Folder"
+                                class=(crate::styles::button::ClassName::BUTTON)
+                                data-init=(format!("this.fetch = fetch('{}')", self.directory_creation_dialog_url))
+                                data-on-click__throttle.1s="this.fetch.trigger()"
+                                data-effect=(format!(
+                                    "handleButtonLoading(this, this.fetch, '{loading}')",
+                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                ))
+                            {
+                                div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                (crate::icons::FolderPlus::default())
+                                span { "New Folder" }
+                            }
+                        }
+
+                        a
+                            title="Download"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY, " ",
+                                crate::styles::files_home_page::ClassName::FILE_HEADER_DOWNLOAD,
+                            )
+                            href=(self.download_zip_url)
+                        {
+                            (crate::icons::Download::default())
+                            span { "Download" }
+                        }
+                    }
+                }
+
+                @if is_writable {
+                    FileUploadForm file_upload_url=(self.file_upload_url.clone());
+                }
+
+                (self.table)
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+static SHARED_DIRECTORY_TABLE_ID: &str = "shared-directory-table";
+
+pub struct SharedDirectoryTable {
+    is_writable: bool,
+    dir_content: Vec<SharedDirectoryEntry>,
+}
+
+impl Component for SharedDirectoryTable {
+    fn id(&self) -> String {
+        SHARED_DIRECTORY_TABLE_ID.to_owned()
+    }
+}
+
+impl From<SharedDirectoryChangeResult> for SharedDirectoryTable {
+    fn from(value: SharedDirectoryChangeResult) -> Self {
+        let user_share_id = value.user_share_id;
+
+        SharedDirectoryTable {
+            is_writable: value.permission == UserSharePermission::ReadWrite,
+            dir_content: value
+                .dir_contents
+                .into_iter()
+                .map(|item| SharedDirectoryEntry::new(user_share_id, item))
+                .collect(),
+        }
+    }
+}
+
+impl Renderable for SharedDirectoryTable {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            table
+                id=(self.id())
+                class=(crate::styles::files_home_page::ClassName::FILE_BROWSER)
+            {
+                @if self.dir_content.is_empty() {
+                    tbody {
+                        tr {
+                            td
+                                class=(crate::styles::files_home_page::ClassName::EMPTY_STATE)
+                                colspan="4"
+                            {
+                                (crate::icons::Cloudy::default())
+                                p { "This folder is empty" }
+                            }
+                        }
+                    }
+                } @else {
+                    thead {
+                        tr class=(crate::styles::files_home_page::ClassName::FILE_HEADER) {
+                            th {}
+                            th { "Name" }
+                            th { "Size" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        @for dir_item in &self.dir_content {
+                            tr class=(crate::styles::files_home_page::ClassName::FILE_ITEM) {
+                                @if dir_item.is_directory {
+                                    td class=(crate::styles::files_home_page::ClassName::FILE_ICON) {
+                                        (crate::icons::Folder::default())
+                                    }
+                                } @else {
+                                    td
+                                        class=(
+                                            crate::styles::files_home_page::ClassName::FILE_ICON, " ",
+                                            crate::styles::files_home_page::ClassName::FILE_ICON_SECONDARY,
+                                        )
+                                    {
+                                        (crate::icons::FileText::default())
+                                    }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
+                                    a href=(dir_item.url) { (dir_item.name) }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                    @if dir_item.is_directory {
+                                        "\u{2014}"
+                                    } @else {
+                                        (dir_item.size)
+                                    }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_ACTIONS) {
+                                    a
+                                        title="Download"
+                                        class=(crate::styles::files_home_page::ClassName::FILE_ACTION_BUTTON)
+                                        href=(dir_item.download_url)
+                                    {
+                                        (crate::icons::Download::default())
+                                    }
+
+                                    @if self.is_writable {
+                                        button
+                                            title="Delete"
+                                            class=(crate::styles::files_home_page::ClassName::FILE_ACTION_BUTTON)
+                                            data-init=(format!("this.fetch = fetch('{}')", dir_item.delete_url))
+                                            data-on-click__throttle.1s="this.fetch.trigger()"
+                                            data-effect=(format!(
+                                                "handleButtonLoading(this, this.fetch, '{loading}')",
+                                                loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                            ))
+                                        {
+                                            div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                            (crate::icons::Trash2::default())
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct SharedFilePage {
+    file_name: String,
+    size: String,
+    owner_username: String,
+    permission: UserSharePermission,
+    download_url: String,
+    breadcrumb: Vec<BreadcrumbCrumb>,
+}
+
+impl SharedFilePage {
+    fn new(
+        value: UserShareFileResult,
+        permission: UserSharePermission,
+        owner_username: String,
+    ) -> Self {
+        let user_share_id = value.user_share_id;
+
+        SharedFilePage {
+            file_name: value.file_name,
+            size: format_file_size(value.size),
+            owner_username,
+            permission,
+            download_url: shared_item_download_url(user_share_id, value.path),
+            breadcrumb: build_shared_item_breadcrumb(user_share_id, value.breadcrumb_segments),
+        }
+    }
+}
+
+impl Renderable for SharedFilePage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            main {
+                div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_BANNER) {
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ICON) {
+                        (crate::icons::FileText::default())
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TEXT) {
+                        h1 class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TITLE) {
+                            (self.file_name)
+                        }
+
+                        (Breadcrumb { crumbs: &self.breadcrumb })
+                    }
+
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ACTIONS) {
+                        span class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                            (self.size) " \u{00b7} Shared by " (self.owner_username)
+                        }
+
+                        (PermissionBadge { permission: &self.permission })
+
+                        a
+                            title="Download"
+                            class=(
+                                crate::styles::button::ClassName::BUTTON, " ",
+                                crate::styles::button::ClassName::BUTTON_PRIMARY, " ",
+                                crate::styles::files_home_page::ClassName::FILE_HEADER_DOWNLOAD,
+                            )
+                            href=(self.download_url)
+                        {
+                            (crate::icons::Download::default())
+                            span { "Download" }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum SharedItemDisplayError {
+    NotFound,
+    InvalidPath,
+    InternalServerError,
+}
+
+impl SharedItemDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::NotFound => "This item does not exist or is no longer shared with you",
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
+
+pub struct SharedItemErrorPage {
+    pub error: SharedItemDisplayError,
+}
+
+impl Renderable for SharedItemErrorPage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            LoggedInDocument current_path=(None::<String>) {
+                style { (crate::styles::public_share_page::STYLE_SHEET) }
+                main {
+                    div class=(crate::styles::public_share_page::ClassName::ERROR_CONTAINER) {
+                        ErrorCard
+                            title=("Item unavailable".to_owned())
+                            message=(self.error.message().to_owned());
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub enum SharedItemOperationDisplayError {
+    NotFound,
+    ReadOnly,
+    InvalidName,
+    InvalidPath,
+    CannotDeleteSharedRoot,
+    QuotaExceeded,
+    InternalServerError,
+}
+
+impl SharedItemOperationDisplayError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::NotFound => "This item does not exist or is no longer shared with you",
+            Self::ReadOnly => "You are only allowed to view this item",
+            Self::InvalidName => "Name must not contain path separators",
+            Self::InvalidPath => "Path must not contain '..' segments",
+            Self::CannotDeleteSharedRoot => "The shared item itself cannot be deleted",
+            Self::QuotaExceeded => "The upload exceeds the owner's available storage space",
+            Self::InternalServerError => "An internal server error occurred",
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use bitsync_core::use_case::trash::shared::TrashEntryListing;
+use bitsync_storage::storage_item::StorageItemKind;
+use hypertext::prelude::*;
+
+use crate::{Component, format_file_size, pages::base::LoggedInDocument};
+
+fn format_datetime(datetime: &time::OffsetDateTime) -> String {
+    let format =
+        time::macros::format_description!("[month repr:short] [day], [year], [hour]:[minute] UTC");
+
+    datetime
+        .to_offset(time::UtcOffset::UTC)
+        .format(&format)
+        .unwrap_or_else(|_| "Unknown".to_owned())
+}
+
+pub struct TrashPage {
+    pub entry_list: TrashEntryList,
+}
+
+impl Renderable for TrashPage {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            LoggedInDocument current_path=(None::<String>) {
+                style { (crate::styles::files_home_page::STYLE_SHEET) }
+                main {
+                    div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_BANNER) {
+                        div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ICON) {
+                            (crate::icons::Trash2::default())
+                        }
+
+                        div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TEXT) {
+                            h1 class=(crate::styles::files_home_page::ClassName::FILE_HEADER_TITLE) {
+                                "Trash"
+                            }
+                        }
+
+                        div class=(crate::styles::files_home_page::ClassName::FILE_HEADER_ACTIONS) {
+                            button
+                                title="Empty trash"
+                                class=(
+                                    crate::styles::button::ClassName::BUTTON, " ",
+                                    crate::styles::button::ClassName::BUTTON_DANGER,
+                                )
+                                data-init=(format!("this.fetch = fetch('{}')", bitsync_routes::GetTrashEmptyDialog))
+                                data-on-click__throttle.1s="this.fetch.trigger()"
+                                data-effect=(format!(
+                                    "handleButtonLoading(this, this.fetch, '{loading}')",
+                                    loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                ))
+                            {
+                                div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                (crate::icons::Trash2::default())
+                                span { "Empty trash" }
+                            }
+                        }
+                    }
+
+                    (self.entry_list)
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
+
+pub struct TrashEntryListItem {
+    name: String,
+    original_path: String,
+    deleted_at: String,
+    size: String,
+    is_directory: bool,
+    restore_url: String,
+}
+
+impl From<TrashEntryListing> for TrashEntryListItem {
+    fn from(value: TrashEntryListing) -> Self {
+        Self {
+            name: value.name,
+            original_path: value.original_path,
+            deleted_at: format_datetime(&value.deleted_at),
+            size: format_file_size(value.size),
+            is_directory: value.kind == StorageItemKind::Directory,
+            restore_url: bitsync_routes::PostTrashEntryRestore {
+                trash_entry_id: value.id,
+            }
+            .to_string(),
+        }
+    }
+}
+
+static TRASH_ENTRY_LIST_ID: &str = "trash-entry-list";
+
+pub struct TrashEntryList {
+    pub entries: Vec<TrashEntryListItem>,
+}
+
+impl From<Vec<TrashEntryListing>> for TrashEntryList {
+    fn from(value: Vec<TrashEntryListing>) -> Self {
+        Self {
+            entries: value.into_iter().map(TrashEntryListItem::from).collect(),
+        }
+    }
+}
+
+impl Component for TrashEntryList {
+    fn id(&self) -> String {
+        TRASH_ENTRY_LIST_ID.to_owned()
+    }
+}
+
+impl Renderable for TrashEntryList {
+    fn render_to(&self, buffer: &mut hypertext::Buffer) {
+        maud! {
+            table
+                id=(self.id())
+                class=(crate::styles::files_home_page::ClassName::FILE_BROWSER)
+            {
+                @if self.entries.is_empty() {
+                    tbody {
+                        tr {
+                            td
+                                class=(crate::styles::files_home_page::ClassName::EMPTY_STATE)
+                                colspan="6"
+                            {
+                                (crate::icons::Cloudy::default())
+                                p { "The trash is empty" }
+                            }
+                        }
+                    }
+                } @else {
+                    thead {
+                        tr class=(crate::styles::files_home_page::ClassName::FILE_HEADER) {
+                            th {}
+                            th { "Name" }
+                            th { "Original location" }
+                            th { "Deleted" }
+                            th { "Size" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        @for entry in &self.entries {
+                            tr class=(crate::styles::files_home_page::ClassName::FILE_ITEM) {
+                                @if entry.is_directory {
+                                    td class=(crate::styles::files_home_page::ClassName::FILE_ICON) {
+                                        (crate::icons::Folder::default())
+                                    }
+                                } @else {
+                                    td
+                                        class=(
+                                            crate::styles::files_home_page::ClassName::FILE_ICON, " ",
+                                            crate::styles::files_home_page::ClassName::FILE_ICON_SECONDARY,
+                                        )
+                                    {
+                                        (crate::icons::FileText::default())
+                                    }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_NAME) {
+                                    (entry.name)
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                    (entry.original_path)
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                    (entry.deleted_at)
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_SIZE) {
+                                    @if entry.is_directory {
+                                        "\u{2014}"
+                                    } @else {
+                                        (entry.size)
+                                    }
+                                }
+
+                                td class=(crate::styles::files_home_page::ClassName::FILE_ACTIONS) {
+                                    button
+                                        title="Restore with previous versions, shares are not restored"
+                                        class=(crate::styles::files_home_page::ClassName::FILE_ACTION_BUTTON)
+                                        data-init=(format!(
+                                            "this.fetch = fetch('{}', {{ method: 'POST' }})",
+                                            entry.restore_url,
+                                        ))
+                                        data-on-click__throttle.1s="this.fetch.trigger()"
+                                        data-effect=(format!(
+                                            "handleButtonLoading(this, this.fetch, '{loading}')",
+                                            loading = crate::styles::button::ClassName::BUTTON_LOADING,
+                                        ))
+                                    {
+                                        div class=(crate::styles::button::ClassName::BUTTON_SPINNER) {}
+                                        (crate::icons::RotateCcw::default())
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        .render_to(buffer);
+    }
+}
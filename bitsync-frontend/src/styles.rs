@@ -50,6 +50,10 @@ pub mod files_home_page {
     turf::style_sheet!("../scss/files_home_page.scss");
 }
 
+pub mod public_share_page {
+    turf::style_sheet!("../scss/public_share_page.scss");
+}
+
 pub mod user_settings_page {
     turf::style_sheet!("../scss/user_settings_page.scss");
 }
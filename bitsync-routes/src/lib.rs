@@ -92,14 +92,65 @@ pub struct PostUserFileUploadQueryParameters {
     pub path: String,
 }
 
+/// The archive a directory download is packed into.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    #[default]
+    Zip,
+    ZipDeflate,
+    ZipZstd,
+    TarGz,
+    TarZst,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct GetUserFileDownloadQueryParameters {
     pub path: String,
+    /// Only applies to directories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_format: Option<ArchiveFormat>,
 }
 #[derive(TypedPath, Deserialize)]
 #[typed_path("/user-file/download")]
 pub struct GetUserFileDownload;
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserFileViewQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/view")]
+pub struct GetUserFileView;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserFileThumbnailQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/thumbnail")]
+pub struct GetUserFileThumbnail;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/download/dialog")]
+pub struct GetUserFileDownloadDialog;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserFileDownloadDialogQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/version/{file_version_id}/download")]
+pub struct GetUserFileVersionDownload {
+    pub file_version_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/version/{file_version_id}/restore")]
+pub struct PostUserFileVersionRestore {
+    pub file_version_id: Uuid,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct GetUserFileDeleteQueryParameters {
     pub path: String,
@@ -116,6 +167,22 @@ pub struct PostUserFileMoveQueryParameters {
 #[typed_path("/user-file/move")]
 pub struct PostUserFileMove;
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileRenameQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/rename")]
+pub struct PostUserFileRename;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileCopyQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/copy")]
+pub struct PostUserFileCopy;
+
 #[derive(TypedPath, Deserialize)]
 #[typed_path("/user-file/create-directory")]
 pub struct PostUserFileDirectoryCreation;
@@ -140,6 +207,96 @@ pub struct GetUserFileMoveDialogQueryParameters {
     pub path: String,
 }
 
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/copy/dialog")]
+pub struct GetUserFileCopyDialog;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserFileCopyDialogQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/extract/dialog")]
+pub struct GetUserFileExtractDialog;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserFileExtractDialogQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/extract")]
+pub struct PostUserFileExtract;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileExtractQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/extract/progress")]
+pub struct GetUserFileExtractProgress;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserFileExtractProgressQueryParameters {
+    pub job_id: Uuid,
+}
+
+// batch home actions
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/delete")]
+pub struct PostUserFileBatchDelete;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchDeleteQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/move/dialog")]
+pub struct PostUserFileBatchMoveDialog;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchMoveDialogQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/move")]
+pub struct PostUserFileBatchMove;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchMoveQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/copy/dialog")]
+pub struct PostUserFileBatchCopyDialog;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchCopyDialogQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/copy")]
+pub struct PostUserFileBatchCopy;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchCopyQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/share")]
+pub struct PostUserFileBatchShare;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchShareQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/batch/download")]
+pub struct PostUserFileBatchDownload;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileBatchDownloadQueryParameters {
+    pub path: String,
+}
+
 // search
 
 #[derive(TypedPath, Deserialize)]
@@ -179,6 +336,20 @@ pub struct PostTerminateSession {
 #[typed_path("/user-settings/sessions/terminate-all-others")]
 pub struct PostTerminateAllOtherSessions;
 
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/app-passwords")]
+pub struct GetUserSettingsAppPasswordsTab;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/app-passwords/create")]
+pub struct PostUserSettingsAppPasswordCreate;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/app-passwords/revoke/{app_password_id}")]
+pub struct PostUserSettingsAppPasswordRevoke {
+    pub app_password_id: Uuid,
+}
+
 #[derive(TypedPath, Deserialize)]
 #[typed_path("/user-settings/totp")]
 pub struct GetUserSettingsTotpTab;
@@ -255,6 +426,18 @@ pub struct PostUserSettingsUnsuspendUser {
     pub user_id: Uuid,
 }
 
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/users/{user_id}/storage-quota/dialog")]
+pub struct GetUserStorageQuotaDialog {
+    pub user_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/users/{user_id}/storage-quota")]
+pub struct PostUserSettingsUserStorageQuota {
+    pub user_id: Uuid,
+}
+
 #[derive(TypedPath, Deserialize)]
 #[typed_path("/user-settings/users/{user_id}/delete/dialog")]
 pub struct GetDeleteUserDialog {
@@ -295,6 +478,16 @@ pub struct PostUserFileShareDelete {
     pub user_share_id: Uuid,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileShareUpdateQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/share/update/{user_share_id}")]
+pub struct PostUserFileShareUpdate {
+    pub user_share_id: Uuid,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct PostUserFileShareDeleteAllQueryParameters {
     pub path: String,
@@ -303,6 +496,166 @@ pub struct PostUserFileShareDeleteAllQueryParameters {
 #[typed_path("/user-file/share/delete-all")]
 pub struct PostUserFileShareDeleteAll;
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileShareWithUserQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/share/with-user")]
+pub struct PostUserFileShareWithUser;
+
+// shared with me
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me")]
+pub struct GetSharedWithMePage;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me/{user_share_id}")]
+pub struct GetSharedItemPage {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetSharedItemPageQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me/{user_share_id}/download")]
+pub struct GetSharedItemDownload {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetSharedItemDownloadQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me/{user_share_id}/upload")]
+pub struct PostSharedItemUpload {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostSharedItemUploadQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me/{user_share_id}/create-directory")]
+pub struct PostSharedItemDirectoryCreation {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostSharedItemDirectoryCreationQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me/{user_share_id}/create-directory/dialog")]
+pub struct GetSharedItemDirectoryCreationDialog {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetSharedItemDirectoryCreationDialogQueryParameters {
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/shared-with-me/{user_share_id}/delete")]
+pub struct GetSharedItemDelete {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetSharedItemDeleteQueryParameters {
+    pub path: String,
+}
+
+// trash
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/trash")]
+pub struct GetTrashPage;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/trash/{trash_entry_id}/restore")]
+pub struct PostTrashEntryRestore {
+    pub trash_entry_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/trash/empty/dialog")]
+pub struct GetTrashEmptyDialog;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/trash/empty")]
+pub struct PostTrashEmpty;
+
+// upload sessions
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/upload-session")]
+pub struct PostUserFileUploadSession;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostUserFileUploadSessionQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/upload-session/{upload_session_id}")]
+pub struct HeadUserFileUploadSession {
+    pub upload_session_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/upload-session/{upload_session_id}")]
+pub struct PatchUserFileUploadSession {
+    pub upload_session_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-file/upload-session/{upload_session_id}")]
+pub struct DeleteUserFileUploadSession {
+    pub upload_session_id: Uuid,
+}
+
+// public shares
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/share/{user_share_id}")]
+pub struct GetPublicSharePage {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetPublicSharePageQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/share/{user_share_id}/download")]
+pub struct GetPublicShareDownload {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetPublicShareDownloadQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/share/{user_share_id}/unlock")]
+pub struct PostPublicShareUnlock {
+    pub user_share_id: Uuid,
+}
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PostPublicShareUnlockQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+
 // account
 
 #[derive(TypedPath, Deserialize)]
@@ -322,3 +675,151 @@ pub struct PostUserSettingsInviteTokenCreate;
 pub struct PostUserSettingsInviteTokenDelete {
     pub invite_token_id: Uuid,
 }
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/integrity")]
+pub struct GetUserSettingsIntegrityTab;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/integrity/scrub")]
+pub struct PostUserSettingsIntegrityScrub;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/user-settings/integrity/scrub/progress")]
+pub struct GetUserSettingsIntegrityScrubProgress;
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetUserSettingsIntegrityScrubProgressQueryParameters {
+    pub job_id: Uuid,
+}
+
+// webdav
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/dav")]
+pub struct DavRoot;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/dav/")]
+pub struct DavRootDirectory;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/dav/{*path}")]
+pub struct DavResource {
+    pub path: String,
+}
+
+// api
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/openapi.json")]
+pub struct GetApiV1OpenApiDocument;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetApiV1FilesQueryParameters {
+    #[serde(default = "build_default_files_query_parameter_path")]
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files")]
+pub struct GetApiV1Files;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DeleteApiV1FilesQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files")]
+pub struct DeleteApiV1Files;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetApiV1FileStatQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files/stat")]
+pub struct GetApiV1FileStat;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetApiV1FileContentQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files/content")]
+pub struct GetApiV1FileContent;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PutApiV1FileContentQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files/content")]
+pub struct PutApiV1FileContent;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files/move")]
+pub struct PostApiV1FileMove;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/files/copy")]
+pub struct PostApiV1FileCopy;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/directories")]
+pub struct PostApiV1Directories;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetApiV1SearchQueryParameters {
+    pub query: String,
+    pub path: Option<String>,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/search")]
+pub struct GetApiV1Search;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct GetApiV1SharesQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/shares")]
+pub struct GetApiV1Shares;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/shares")]
+pub struct PostApiV1Shares;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DeleteApiV1ShareQueryParameters {
+    pub path: String,
+}
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/shares/{user_share_id}")]
+pub struct DeleteApiV1Share {
+    pub user_share_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/shared-paths")]
+pub struct GetApiV1SharedPaths;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/users")]
+pub struct GetApiV1Users;
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/users/{user_id}/suspend")]
+pub struct PostApiV1UserSuspend {
+    pub user_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/users/{user_id}/unsuspend")]
+pub struct PostApiV1UserUnsuspend {
+    pub user_id: Uuid,
+}
+
+#[derive(TypedPath, Deserialize)]
+#[typed_path("/api/v1/users/{user_id}/storage-quota")]
+pub struct PutApiV1UserStorageQuota {
+    pub user_id: Uuid,
+}
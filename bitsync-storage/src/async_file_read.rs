@@ -1,6 +1,31 @@
-use std::pin::Pin;
+use std::{io::SeekFrom, ops::Range, pin::Pin, task::Poll};
 
-pub struct AsyncFileRead(pub(crate) tokio::fs::File);
+use tokio::io::{AsyncSeekExt, ReadBuf};
+
+use crate::backend::FileRead;
+
+pub struct AsyncFileRead {
+    file: Box<dyn FileRead>,
+    /// The number of bytes left to read, if the read is bounded to a range of the file.
+    remaining: Option<u64>,
+}
+
+impl AsyncFileRead {
+    pub(crate) fn new(file: Box<dyn FileRead>) -> Self {
+        Self {
+            file,
+            remaining: None,
+        }
+    }
+
+    /// Seeks to the start of `range` and ends the read at its end.
+    pub async fn seek_to_range(&mut self, range: Range<u64>) -> std::io::Result<()> {
+        self.file.seek(SeekFrom::Start(range.start)).await?;
+        self.remaining = Some(range.end.saturating_sub(range.start));
+
+        Ok(())
+    }
+}
 
 impl tokio::io::AsyncRead for AsyncFileRead {
     fn poll_read(
@@ -9,8 +34,46 @@ impl tokio::io::AsyncRead for AsyncFileRead {
         buf: &mut tokio::io::ReadBuf<'_>,
     ) -> std::task::Poll<std::io::Result<()>> {
         let self_mut = self.get_mut();
-        let inner = Pin::new(&mut self_mut.0);
+        let inner = Pin::new(&mut self_mut.file);
+
+        let Some(remaining) = self_mut.remaining else {
+            return inner.poll_read(cx, buf);
+        };
+
+        if remaining == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let bounded_length = usize::try_from(remaining)
+            .unwrap_or(usize::MAX)
+            .min(buf.remaining());
+        let mut bounded_buf = ReadBuf::new(buf.initialize_unfilled_to(bounded_length));
+
+        let result = inner.poll_read(cx, &mut bounded_buf);
+        let read_length = bounded_buf.filled().len();
+
+        buf.advance(read_length);
+        self_mut.remaining = Some(remaining - read_length as u64);
+
+        result
+    }
+}
+
+impl tokio::io::AsyncSeek for AsyncFileRead {
+    fn start_seek(self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        let self_mut = self.get_mut();
+        let inner = Pin::new(&mut self_mut.file);
+
+        inner.start_seek(position)
+    }
+
+    fn poll_complete(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<u64>> {
+        let self_mut = self.get_mut();
+        let inner = Pin::new(&mut self_mut.file);
 
-        inner.poll_read(cx, buf)
+        inner.poll_complete(cx)
     }
 }
@@ -0,0 +1,96 @@
+use std::{
+    fmt::Debug,
+    io::{Error as IoError, ErrorKind},
+    path::Path,
+    time::SystemTime,
+};
+
+use tokio::io::{AsyncRead, AsyncSeek};
+
+use crate::storage_item::StorageItemKind;
+
+pub mod local;
+pub mod s3;
+#[cfg(test)]
+mod tests;
+
+/// Object stores only know objects, so every directory holds an empty object of this name. No
+/// backend accepts it as the name of an item, so storage can be moved between backends.
+pub(crate) static DIRECTORY_MARKER_NAME: &str = ".bitsync-directory";
+
+/// Refuses paths that contain the name reserved for directory markers.
+pub(crate) fn ensure_no_reserved_name(path: &Path) -> Result<(), IoError> {
+    if path
+        .components()
+        .any(|component| component.as_os_str() == DIRECTORY_MARKER_NAME)
+    {
+        return Err(IoError::new(
+            ErrorKind::InvalidInput,
+            "the name is reserved for directory markers",
+        ));
+    }
+
+    Ok(())
+}
+
+pub struct ItemMetadata {
+    /// `None` for items that are neither files nor directories, like symlinks.
+    pub kind: Option<StorageItemKind>,
+    pub size: u64,
+    /// Not available on every platform.
+    pub modified_at: Option<SystemTime>,
+}
+
+pub struct DirEntry {
+    pub name: String,
+    pub metadata: ItemMetadata,
+}
+
+/// A readable and seekable file of a storage backend.
+pub trait FileRead: AsyncRead + AsyncSeek + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncSeek + Send + Unpin> FileRead for T {}
+
+/// Where the files, trash and versions of all users are kept. Paths are relative to the root of
+/// the backend and errors are reported like `tokio::fs` reports them, so items that do not exist
+/// result in [`std::io::ErrorKind::NotFound`].
+#[async_trait::async_trait]
+pub trait StorageBackend: Debug + Send + Sync {
+    async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>, IoError>;
+
+    /// Reads the metadata of an item, following symlinks.
+    async fn stat(&self, path: &Path) -> Result<ItemMetadata, IoError>;
+
+    async fn read_stream(&self, path: &Path) -> Result<Box<dyn FileRead>, IoError>;
+
+    /// Writes `reader` to the file at `path`, replacing any file located there, and returns the
+    /// number of written bytes. The parent directory must exist.
+    async fn write_stream(
+        &self,
+        path: &Path,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, IoError>;
+
+    /// Moves a file of the local filesystem to `path`, replacing any file located there.
+    async fn import_local_file(&self, local_path: &Path, path: &Path) -> Result<(), IoError>;
+
+    /// Moves a file or a directory including all of its contents, replacing any file located at
+    /// `to_path`. A directory also replaces an empty directory; a non-empty one results in
+    /// [`std::io::ErrorKind::DirectoryNotEmpty`]. A file cannot replace a directory.
+    async fn rename(&self, from_path: &Path, to_path: &Path) -> Result<(), IoError>;
+
+    /// Copies a file, or a directory including all of its contents, to a path that does not
+    /// exist yet.
+    async fn copy(&self, from_path: &Path, to_path: &Path) -> Result<(), IoError>;
+
+    async fn delete_file(&self, path: &Path) -> Result<(), IoError>;
+
+    /// Deletes a directory including all of its contents.
+    async fn delete_directory(&self, path: &Path) -> Result<(), IoError>;
+
+    /// Creates a directory whose parent exists.
+    async fn create_directory(&self, path: &Path) -> Result<(), IoError>;
+
+    /// Creates a directory and all of its missing parents.
+    async fn create_directory_all(&self, path: &Path) -> Result<(), IoError>;
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs::Metadata,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+};
+
+use tokio::io::{AsyncRead, AsyncWriteExt, BufWriter};
+use uuid::Uuid;
+
+use crate::{storage_item::StorageItemKind, user_storage::temporary_files_directory};
+
+use super::{DirEntry, FileRead, ItemMetadata, StorageBackend, ensure_no_reserved_name};
+
+/// Keeps all items in a directory of the local filesystem.
+#[derive(Debug)]
+pub struct LocalStorageBackend {
+    root_directory: PathBuf,
+}
+
+impl LocalStorageBackend {
+    pub fn new(root_directory: PathBuf) -> Self {
+        Self { root_directory }
+    }
+
+    fn local_path(&self, path: &Path) -> Result<PathBuf, IoError> {
+        ensure_no_reserved_name(path)?;
+
+        Ok(self
+            .root_directory
+            .join(path.strip_prefix("/").unwrap_or(path)))
+    }
+}
+
+impl From<Metadata> for ItemMetadata {
+    fn from(metadata: Metadata) -> Self {
+        let kind = if metadata.file_type().is_dir() {
+            Some(StorageItemKind::Directory)
+        } else if metadata.file_type().is_file() {
+            Some(StorageItemKind::File)
+        } else {
+            None
+        };
+
+        Self {
+            kind,
+            size: metadata.len(),
+            modified_at: metadata.modified().ok(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for LocalStorageBackend {
+    async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>, IoError> {
+        let mut dir_entries = tokio::fs::read_dir(self.local_path(path)?).await?;
+        let mut entries = Vec::new();
+
+        while let Some(dir_entry) = dir_entries.next_entry().await? {
+            entries.push(DirEntry {
+                name: dir_entry.file_name().to_string_lossy().to_string(),
+                // symlinks are not followed, so they are not mistaken for the item they point to
+                metadata: dir_entry.metadata().await?.into(),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    async fn stat(&self, path: &Path) -> Result<ItemMetadata, IoError> {
+        Ok(tokio::fs::metadata(self.local_path(path)?).await?.into())
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<Box<dyn FileRead>, IoError> {
+        Ok(Box::new(
+            tokio::fs::File::open(self.local_path(path)?).await?,
+        ))
+    }
+
+    async fn write_stream(
+        &self,
+        path: &Path,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, IoError> {
+        let local_path = self.local_path(path)?;
+
+        // the file is renamed into place once it is complete, so a failed write leaves any
+        // replaced file untouched and readers never see a partial one
+        let temporary_directory = temporary_files_directory(&self.root_directory);
+        tokio::fs::create_dir_all(&temporary_directory).await?;
+        let temporary_path = temporary_directory.join(Uuid::new_v4().to_string());
+
+        let write_result = async {
+            let mut file_writer = BufWriter::new(tokio::fs::File::create(&temporary_path).await?);
+
+            let written_bytes = tokio::io::copy(reader, &mut file_writer).await?;
+
+            file_writer.flush().await?;
+            file_writer.get_ref().sync_all().await?;
+
+            tokio::fs::rename(&temporary_path, local_path).await?;
+
+            Ok(written_bytes)
+        }
+        .await;
+
+        if write_result.is_err() {
+            let _ = tokio::fs::remove_file(&temporary_path).await;
+        }
+
+        write_result
+    }
+
+    async fn import_local_file(&self, local_path: &Path, path: &Path) -> Result<(), IoError> {
+        tokio::fs::rename(local_path, self.local_path(path)?).await
+    }
+
+    async fn rename(&self, from_path: &Path, to_path: &Path) -> Result<(), IoError> {
+        let from_path = self.local_path(from_path)?;
+        let to_path = self.local_path(to_path)?;
+
+        // the filesystem only lets a directory replace an empty directory
+        if tokio::fs::metadata(&from_path).await?.is_dir()
+            && tokio::fs::symlink_metadata(&to_path)
+                .await
+                .is_ok_and(|metadata| metadata.is_file())
+        {
+            tokio::fs::remove_file(&to_path).await?;
+        }
+
+        tokio::fs::rename(from_path, to_path).await
+    }
+
+    async fn copy(&self, from_path: &Path, to_path: &Path) -> Result<(), IoError> {
+        let mut pending_items = vec![(self.local_path(from_path)?, self.local_path(to_path)?)];
+
+        while let Some((from, to)) = pending_items.pop() {
+            let metadata = tokio::fs::symlink_metadata(&from).await?;
+
+            if metadata.is_dir() {
+                tokio::fs::create_dir(&to).await?;
+
+                let mut dir_entries = tokio::fs::read_dir(&from).await?;
+
+                while let Some(dir_entry) = dir_entries.next_entry().await? {
+                    pending_items.push((dir_entry.path(), to.join(dir_entry.file_name())));
+                }
+            } else if metadata.is_file() {
+                tokio::fs::copy(&from, &to).await?;
+            }
+            // symlinks are not supported as storage items and are left out
+        }
+
+        Ok(())
+    }
+
+    async fn delete_file(&self, path: &Path) -> Result<(), IoError> {
+        tokio::fs::remove_file(self.local_path(path)?).await
+    }
+
+    async fn delete_directory(&self, path: &Path) -> Result<(), IoError> {
+        tokio::fs::remove_dir_all(self.local_path(path)?).await
+    }
+
+    async fn create_directory(&self, path: &Path) -> Result<(), IoError> {
+        tokio::fs::create_dir(self.local_path(path)?).await
+    }
+
+    async fn create_directory_all(&self, path: &Path) -> Result<(), IoError> {
+        tokio::fs::create_dir_all(self.local_path(path)?).await
+    }
+}
@@ -0,0 +1,425 @@
+use std::{
+    io::{Error as IoError, ErrorKind},
+    path::{Component, Path},
+    sync::Arc,
+};
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::{
+    ObjectMeta, ObjectStore, PutPayload,
+    aws::AmazonS3Builder,
+    buffered::{BufReader, BufWriter},
+    path::{Path as ObjectPath, PathPart},
+};
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+use crate::storage_item::StorageItemKind;
+
+use super::{
+    DIRECTORY_MARKER_NAME, DirEntry, FileRead, ItemMetadata, StorageBackend,
+    ensure_no_reserved_name,
+};
+
+pub struct S3StorageBackendOptions {
+    pub bucket: String,
+    /// Required for S3-compatible services other than AWS, like MinIO.
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Whether the endpoint may be reached without TLS.
+    pub allow_http: bool,
+}
+
+/// Keeps all items in a bucket of an S3-compatible object store. Renaming and deleting
+/// directories works object by object and is not atomic.
+#[derive(Debug)]
+pub struct S3StorageBackend {
+    store: Arc<dyn ObjectStore>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create the S3 storage backend")]
+pub struct CreateS3StorageBackendError(#[from] object_store::Error);
+
+impl S3StorageBackend {
+    pub fn new(options: S3StorageBackendOptions) -> Result<Self, CreateS3StorageBackendError> {
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(options.bucket)
+            .with_region(options.region)
+            .with_access_key_id(options.access_key_id)
+            .with_secret_access_key(options.secret_access_key)
+            .with_allow_http(options.allow_http);
+
+        if let Some(endpoint) = options.endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+
+        Ok(Self::from_object_store(Arc::new(builder.build()?)))
+    }
+
+    /// Keeps the items in any object store, like an in-memory one for tests.
+    pub fn from_object_store(store: Arc<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+
+    async fn head(&self, location: &ObjectPath) -> Result<Option<ObjectMeta>, IoError> {
+        match self.store.head(location).await {
+            Ok(meta) => Ok(Some(meta)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// All objects below a directory, including the markers of nested directories.
+    async fn list_directory_objects(
+        &self,
+        location: &ObjectPath,
+    ) -> Result<Vec<ObjectMeta>, IoError> {
+        let objects = self
+            .store
+            .list(Some(location))
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        if objects.is_empty() {
+            return Err(not_found(location));
+        }
+
+        Ok(objects)
+    }
+
+    /// Whether any object is located below `location`.
+    async fn is_directory(&self, location: &ObjectPath) -> Result<bool, IoError> {
+        match self.store.list(Some(location)).next().await {
+            Some(Ok(..)) => Ok(true),
+            Some(Err(error)) => Err(error.into()),
+            None => Ok(false),
+        }
+    }
+
+    /// Whether a directory holds nothing but its marker. Missing directories are empty.
+    async fn is_empty_directory(&self, location: &ObjectPath) -> Result<bool, IoError> {
+        let list_result = self.store.list_with_delimiter(Some(location)).await?;
+
+        Ok(list_result.common_prefixes.is_empty()
+            && list_result
+                .objects
+                .iter()
+                .all(|object| object.location.filename() == Some(DIRECTORY_MARKER_NAME)))
+    }
+
+    /// Copies all objects below `from_location` and returns the copied ones.
+    async fn copy_directory(
+        &self,
+        from_location: &ObjectPath,
+        to_location: &ObjectPath,
+    ) -> Result<Vec<ObjectPath>, IoError> {
+        let objects = self.list_directory_objects(from_location).await?;
+
+        self.copy_objects(&objects, from_location, to_location)
+            .await?;
+
+        Ok(objects.into_iter().map(|object| object.location).collect())
+    }
+
+    async fn copy_objects(
+        &self,
+        objects: &[ObjectMeta],
+        from_location: &ObjectPath,
+        to_location: &ObjectPath,
+    ) -> Result<(), IoError> {
+        for object in objects {
+            let Some(relative_parts) = object.location.prefix_match(from_location) else {
+                continue;
+            };
+            let to_object = ObjectPath::from_iter(to_location.parts().chain(relative_parts));
+
+            self.store.copy(&object.location, &to_object).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps a path relative to the root of the backend to the location of its object.
+fn object_path(path: &Path) -> Result<ObjectPath, IoError> {
+    ensure_no_reserved_name(path)?;
+
+    let mut parts = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::RootDir | Component::CurDir => {}
+            Component::Normal(name) => {
+                let name = name.to_str().ok_or_else(|| {
+                    IoError::new(ErrorKind::InvalidInput, "path is not valid UTF-8")
+                })?;
+
+                parts.push(PathPart::from(name));
+            }
+            Component::ParentDir | Component::Prefix(..) => {
+                return Err(IoError::new(
+                    ErrorKind::InvalidInput,
+                    "path must not leave the storage",
+                ));
+            }
+        }
+    }
+
+    Ok(ObjectPath::from_iter(parts))
+}
+
+fn is_root(location: &ObjectPath) -> bool {
+    location.parts().next().is_none()
+}
+
+fn item_name(location: &ObjectPath) -> Option<String> {
+    let name = location.filename()?;
+
+    Some(
+        percent_encoding::percent_decode_str(name)
+            .decode_utf8_lossy()
+            .to_string(),
+    )
+}
+
+fn not_found(location: &ObjectPath) -> IoError {
+    IoError::new(ErrorKind::NotFound, format!("{location} does not exist"))
+}
+
+fn file_metadata(object: &ObjectMeta) -> ItemMetadata {
+    ItemMetadata {
+        kind: Some(StorageItemKind::File),
+        size: object.size,
+        modified_at: Some(object.last_modified.into()),
+    }
+}
+
+fn directory_metadata(marker: Option<&ObjectMeta>) -> ItemMetadata {
+    ItemMetadata {
+        kind: Some(StorageItemKind::Directory),
+        size: 0,
+        modified_at: marker.map(|marker| marker.last_modified.into()),
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for S3StorageBackend {
+    async fn read_dir(&self, path: &Path) -> Result<Vec<DirEntry>, IoError> {
+        let location = object_path(path)?;
+        let list_result = self.store.list_with_delimiter(Some(&location)).await?;
+
+        if list_result.objects.is_empty()
+            && list_result.common_prefixes.is_empty()
+            && !is_root(&location)
+        {
+            return Err(not_found(&location));
+        }
+
+        let files = list_result.objects.iter().filter_map(|object| {
+            let name = item_name(&object.location)?;
+
+            (name != DIRECTORY_MARKER_NAME).then(|| DirEntry {
+                name,
+                metadata: file_metadata(object),
+            })
+        });
+        let directories = list_result.common_prefixes.iter().filter_map(|prefix| {
+            Some(DirEntry {
+                name: item_name(prefix)?,
+                // reading every marker would take a request per directory
+                metadata: directory_metadata(None),
+            })
+        });
+
+        Ok(files.chain(directories).collect())
+    }
+
+    async fn stat(&self, path: &Path) -> Result<ItemMetadata, IoError> {
+        let location = object_path(path)?;
+
+        if is_root(&location) {
+            return Ok(directory_metadata(None));
+        }
+
+        if let Some(object) = self.head(&location).await? {
+            return Ok(file_metadata(&object));
+        }
+
+        if let Some(marker) = self.head(&location.child(DIRECTORY_MARKER_NAME)).await? {
+            return Ok(directory_metadata(Some(&marker)));
+        }
+
+        // directories created by other clients of the bucket have no marker
+        match self.store.list(Some(&location)).next().await {
+            Some(Ok(..)) => Ok(directory_metadata(None)),
+            Some(Err(error)) => Err(error.into()),
+            None => Err(not_found(&location)),
+        }
+    }
+
+    async fn read_stream(&self, path: &Path) -> Result<Box<dyn FileRead>, IoError> {
+        let object = self.store.head(&object_path(path)?).await?;
+
+        Ok(Box::new(BufReader::new(self.store.clone(), &object)))
+    }
+
+    async fn write_stream(
+        &self,
+        path: &Path,
+        reader: &mut (dyn AsyncRead + Send + Unpin),
+    ) -> Result<u64, IoError> {
+        let mut writer = BufWriter::new(self.store.clone(), object_path(path)?);
+
+        let written_bytes = match tokio::io::copy(reader, &mut writer).await {
+            Ok(written_bytes) => written_bytes,
+            Err(error) => {
+                // uploaded parts are only discarded when the upload is aborted
+                let _ = writer.abort().await;
+
+                return Err(error);
+            }
+        };
+
+        writer.shutdown().await?;
+
+        Ok(written_bytes)
+    }
+
+    async fn import_local_file(&self, local_path: &Path, path: &Path) -> Result<(), IoError> {
+        let mut file = tokio::fs::File::open(local_path).await?;
+
+        self.write_stream(path, &mut file).await?;
+
+        tokio::fs::remove_file(local_path).await
+    }
+
+    async fn rename(&self, from_path: &Path, to_path: &Path) -> Result<(), IoError> {
+        let from_location = object_path(from_path)?;
+        let to_location = object_path(to_path)?;
+
+        if self.head(&from_location).await?.is_some() {
+            if self.is_directory(&to_location).await? {
+                return Err(IoError::new(
+                    ErrorKind::IsADirectory,
+                    format!("{to_location} is a directory"),
+                ));
+            }
+
+            return Ok(self.store.rename(&from_location, &to_location).await?);
+        }
+
+        let objects = self.list_directory_objects(&from_location).await?;
+
+        // like on a local filesystem, a file or an empty directory at the destination is replaced
+        if self.head(&to_location).await?.is_some() {
+            self.store.delete(&to_location).await?;
+        } else if !self.is_empty_directory(&to_location).await? {
+            return Err(IoError::new(
+                ErrorKind::DirectoryNotEmpty,
+                format!("{to_location} is not empty"),
+            ));
+        }
+
+        self.copy_objects(&objects, &from_location, &to_location)
+            .await?;
+
+        self.store
+            .delete_stream(
+                futures::stream::iter(objects.into_iter().map(|object| Ok(object.location)))
+                    .boxed(),
+            )
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn copy(&self, from_path: &Path, to_path: &Path) -> Result<(), IoError> {
+        let from_location = object_path(from_path)?;
+        let to_location = object_path(to_path)?;
+
+        if self.head(&from_location).await?.is_some() {
+            return Ok(self.store.copy(&from_location, &to_location).await?);
+        }
+
+        self.copy_directory(&from_location, &to_location).await?;
+
+        Ok(())
+    }
+
+    async fn delete_file(&self, path: &Path) -> Result<(), IoError> {
+        let location = object_path(path)?;
+
+        // deleting objects that do not exist succeeds
+        if self.head(&location).await?.is_none() {
+            return Err(not_found(&location));
+        }
+
+        Ok(self.store.delete(&location).await?)
+    }
+
+    async fn delete_directory(&self, path: &Path) -> Result<(), IoError> {
+        let location = object_path(path)?;
+        let objects = self.list_directory_objects(&location).await?;
+
+        self.store
+            .delete_stream(
+                futures::stream::iter(objects.into_iter().map(|object| Ok(object.location)))
+                    .boxed(),
+            )
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn create_directory(&self, path: &Path) -> Result<(), IoError> {
+        match self.stat(path).await {
+            Ok(..) => {
+                return Err(IoError::new(
+                    ErrorKind::AlreadyExists,
+                    format!("{} already exists", path.display()),
+                ));
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        if let Some(parent) = path.parent()
+            && self.stat(parent).await?.kind != Some(StorageItemKind::Directory)
+        {
+            return Err(IoError::new(
+                ErrorKind::NotADirectory,
+                format!("{} is not a directory", parent.display()),
+            ));
+        }
+
+        let marker = object_path(path)?.child(DIRECTORY_MARKER_NAME);
+        self.store.put(&marker, PutPayload::new()).await?;
+
+        Ok(())
+    }
+
+    async fn create_directory_all(&self, path: &Path) -> Result<(), IoError> {
+        for directory in path.ancestors() {
+            match self.stat(directory).await {
+                Ok(metadata) if metadata.kind == Some(StorageItemKind::Directory) => break,
+                Ok(..) => {
+                    return Err(IoError::new(
+                        ErrorKind::NotADirectory,
+                        format!("{} is not a directory", directory.display()),
+                    ));
+                }
+                Err(error) if error.kind() == ErrorKind::NotFound => {
+                    let marker = object_path(directory)?.child(DIRECTORY_MARKER_NAME);
+                    self.store.put(&marker, PutPayload::new()).await?;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,618 @@
+//! Checks that every backend behaves the same. The S3 backend always runs against an in-memory
+//! object store and additionally against a real one when `S3_STORAGE_ENDPOINT` is set, configured
+//! by the same variables as the application.
+
+use std::{
+    io::{ErrorKind, SeekFrom},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use object_store::memory::InMemory;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, ReadBuf};
+use uuid::Uuid;
+
+use crate::storage_item::StorageItemKind;
+
+use super::{
+    DIRECTORY_MARKER_NAME, StorageBackend,
+    local::LocalStorageBackend,
+    s3::{S3StorageBackend, S3StorageBackendOptions},
+};
+
+/// A backend with a directory of its own, so tests can share a bucket.
+struct TestBackend {
+    name: &'static str,
+    backend: Box<dyn StorageBackend>,
+    root: PathBuf,
+    local_root_directory: Option<PathBuf>,
+}
+
+impl TestBackend {
+    fn path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+
+    async fn write(&self, path: &str, content: &str) {
+        self.backend
+            .write_stream(&self.path(path), &mut content.as_bytes())
+            .await
+            .unwrap_or_else(|error| panic!("{}: writing {path} failed: {error}", self.name));
+    }
+
+    async fn read(&self, path: &str) -> String {
+        let mut content = String::new();
+
+        self.backend
+            .read_stream(&self.path(path))
+            .await
+            .unwrap_or_else(|error| panic!("{}: opening {path} failed: {error}", self.name))
+            .read_to_string(&mut content)
+            .await
+            .unwrap_or_else(|error| panic!("{}: reading {path} failed: {error}", self.name));
+
+        content
+    }
+
+    async fn create_directory(&self, path: &str) {
+        self.backend
+            .create_directory_all(&self.path(path))
+            .await
+            .unwrap_or_else(|error| panic!("{}: creating {path} failed: {error}", self.name));
+    }
+
+    async fn kind(&self, path: &str) -> Option<StorageItemKind> {
+        match self.backend.stat(&self.path(path)).await {
+            Ok(metadata) => metadata.kind,
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => panic!("{}: reading {path} failed: {error}", self.name),
+        }
+    }
+
+    async fn dir_entry_names(&self, path: &str) -> Vec<String> {
+        let mut names = self
+            .backend
+            .read_dir(&self.path(path))
+            .await
+            .unwrap_or_else(|error| panic!("{}: listing {path} failed: {error}", self.name))
+            .into_iter()
+            .map(|dir_entry| dir_entry.name)
+            .collect::<Vec<_>>();
+
+        names.sort();
+
+        names
+    }
+
+    fn assert_error_kind<T>(&self, result: Result<T, std::io::Error>, kind: ErrorKind) {
+        match result {
+            Ok(..) => panic!("{}: expected {kind:?}, but the call succeeded", self.name),
+            Err(error) => assert_eq!(error.kind(), kind, "{}: {error}", self.name),
+        }
+    }
+
+    async fn clean_up(self) {
+        let _ = self.backend.delete_directory(&self.root).await;
+
+        if let Some(local_root_directory) = self.local_root_directory {
+            let _ = tokio::fs::remove_dir_all(local_root_directory).await;
+        }
+    }
+}
+
+/// Fails once its content is read, like a client disconnecting in the middle of an upload.
+struct FailingReader(&'static [u8]);
+
+impl AsyncRead for FailingReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buffer: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if self.0.is_empty() {
+            return Poll::Ready(Err(std::io::Error::new(
+                ErrorKind::ConnectionReset,
+                "the client disconnected",
+            )));
+        }
+
+        buffer.put_slice(self.0);
+        self.0 = &[];
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+async fn test_backends() -> Vec<TestBackend> {
+    let root = PathBuf::from(format!("/storage-backend-test-{}", Uuid::new_v4()));
+    let local_root_directory = std::env::temp_dir().join(format!("bitsync-{}", Uuid::new_v4()));
+
+    tokio::fs::create_dir_all(&local_root_directory)
+        .await
+        .unwrap();
+
+    let mut test_backends = vec![TestBackend {
+        name: "local",
+        backend: Box::new(LocalStorageBackend::new(local_root_directory.clone())),
+        root: root.clone(),
+        local_root_directory: Some(local_root_directory),
+    }];
+
+    test_backends.push(TestBackend {
+        name: "s3 in memory",
+        backend: Box::new(S3StorageBackend::from_object_store(Arc::new(
+            InMemory::new(),
+        ))),
+        root: root.clone(),
+        local_root_directory: None,
+    });
+
+    if let Ok(endpoint) = std::env::var("S3_STORAGE_ENDPOINT") {
+        let env_var =
+            |name: &str, default: &str| std::env::var(name).unwrap_or_else(|_| default.to_owned());
+
+        let backend = S3StorageBackend::new(S3StorageBackendOptions {
+            bucket: env_var("S3_STORAGE_BUCKET", "bitsync"),
+            allow_http: endpoint.starts_with("http://"),
+            endpoint: Some(endpoint),
+            region: env_var("S3_STORAGE_REGION", "us-east-1"),
+            access_key_id: env_var("S3_STORAGE_ACCESS_KEY_ID", ""),
+            secret_access_key: env_var("S3_STORAGE_SECRET_ACCESS_KEY", ""),
+        })
+        .unwrap();
+
+        test_backends.push(TestBackend {
+            name: "s3",
+            backend: Box::new(backend),
+            root: root.clone(),
+            local_root_directory: None,
+        });
+    }
+
+    for test_backend in &test_backends {
+        test_backend.create_directory("").await;
+    }
+
+    test_backends
+}
+
+#[tokio::test]
+async fn missing_items_are_not_found() {
+    for test_backend in test_backends().await {
+        let backend = &test_backend.backend;
+        let missing = test_backend.path("missing");
+        let other = test_backend.path("other");
+
+        test_backend.assert_error_kind(backend.stat(&missing).await, ErrorKind::NotFound);
+        test_backend.assert_error_kind(backend.read_dir(&missing).await, ErrorKind::NotFound);
+        test_backend.assert_error_kind(backend.read_stream(&missing).await, ErrorKind::NotFound);
+        test_backend.assert_error_kind(backend.delete_file(&missing).await, ErrorKind::NotFound);
+        test_backend.assert_error_kind(
+            backend.delete_directory(&missing).await,
+            ErrorKind::NotFound,
+        );
+        test_backend.assert_error_kind(backend.rename(&missing, &other).await, ErrorKind::NotFound);
+        test_backend.assert_error_kind(backend.copy(&missing, &other).await, ErrorKind::NotFound);
+        test_backend.assert_error_kind(
+            backend
+                .create_directory(&test_backend.path("missing/directory"))
+                .await,
+            ErrorKind::NotFound,
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn written_items_can_be_read_and_listed() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("directory").await;
+        test_backend.write("file.txt", "content").await;
+
+        let metadata = test_backend
+            .backend
+            .stat(&test_backend.path("file.txt"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            metadata.kind,
+            Some(StorageItemKind::File),
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(metadata.size, 7, "{}", test_backend.name);
+        assert_eq!(
+            test_backend.kind("directory").await,
+            Some(StorageItemKind::Directory),
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(test_backend.read("file.txt").await, "content");
+        assert_eq!(
+            test_backend.dir_entry_names("").await,
+            ["directory", "file.txt"],
+            "{}",
+            test_backend.name
+        );
+        assert!(
+            test_backend.dir_entry_names("directory").await.is_empty(),
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn writing_replaces_a_file() {
+    for test_backend in test_backends().await {
+        test_backend.write("file.txt", "old content").await;
+        test_backend.write("file.txt", "new").await;
+
+        assert_eq!(
+            test_backend.read("file.txt").await,
+            "new",
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn failed_write_keeps_the_replaced_file() {
+    for test_backend in test_backends().await {
+        test_backend.write("file.txt", "old content").await;
+
+        test_backend.assert_error_kind(
+            test_backend
+                .backend
+                .write_stream(
+                    &test_backend.path("file.txt"),
+                    &mut FailingReader(b"partial"),
+                )
+                .await,
+            ErrorKind::ConnectionReset,
+        );
+
+        assert_eq!(
+            test_backend.read("file.txt").await,
+            "old content",
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(
+            test_backend.dir_entry_names("").await,
+            ["file.txt"],
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn read_stream_seeks() {
+    for test_backend in test_backends().await {
+        test_backend.write("file.txt", "0123456789").await;
+
+        let mut file = test_backend
+            .backend
+            .read_stream(&test_backend.path("file.txt"))
+            .await
+            .unwrap();
+        let mut buffer = [0; 3];
+
+        file.seek(SeekFrom::Start(5)).await.unwrap();
+        file.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"567", "{}", test_backend.name);
+
+        file.seek(SeekFrom::Current(-5)).await.unwrap();
+        file.read_exact(&mut buffer).await.unwrap();
+        assert_eq!(&buffer, b"345", "{}", test_backend.name);
+
+        file.seek(SeekFrom::End(-2)).await.unwrap();
+
+        let mut rest = Vec::new();
+        file.read_to_end(&mut rest).await.unwrap();
+        assert_eq!(rest, b"89", "{}", test_backend.name);
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn creating_an_existing_directory_fails() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("directory").await;
+
+        test_backend.assert_error_kind(
+            test_backend
+                .backend
+                .create_directory(&test_backend.path("directory"))
+                .await,
+            ErrorKind::AlreadyExists,
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn renaming_a_directory_moves_its_contents() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("source/nested").await;
+        test_backend.write("source/file.txt", "file").await;
+        test_backend.write("source/nested/file.txt", "nested").await;
+
+        test_backend
+            .backend
+            .rename(&test_backend.path("source"), &test_backend.path("moved"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            test_backend.kind("source").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(test_backend.read("moved/file.txt").await, "file");
+        assert_eq!(test_backend.read("moved/nested/file.txt").await, "nested");
+        assert_eq!(
+            test_backend.dir_entry_names("moved").await,
+            ["file.txt", "nested"],
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+/// The local filesystem refuses this on its own, the local backend deletes the file first to
+/// behave like object stores, where the directory and the file never shared a key.
+#[tokio::test]
+async fn renaming_a_directory_replaces_a_file() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("source").await;
+        test_backend.write("source/file.txt", "file").await;
+        test_backend.write("target", "replaced").await;
+
+        test_backend
+            .backend
+            .rename(&test_backend.path("source"), &test_backend.path("target"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            test_backend.kind("source").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(
+            test_backend.kind("target").await,
+            Some(StorageItemKind::Directory),
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(test_backend.read("target/file.txt").await, "file");
+        assert_eq!(
+            test_backend.dir_entry_names("target").await,
+            ["file.txt"],
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn renaming_a_directory_replaces_an_empty_directory() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("source").await;
+        test_backend.write("source/file.txt", "file").await;
+        test_backend.create_directory("target").await;
+
+        test_backend
+            .backend
+            .rename(&test_backend.path("source"), &test_backend.path("target"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            test_backend.kind("source").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(test_backend.read("target/file.txt").await, "file");
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn renaming_a_directory_onto_a_non_empty_directory_fails() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("source").await;
+        test_backend.write("source/file.txt", "source").await;
+        test_backend.create_directory("target").await;
+        test_backend.write("target/other.txt", "target").await;
+
+        test_backend.assert_error_kind(
+            test_backend
+                .backend
+                .rename(&test_backend.path("source"), &test_backend.path("target"))
+                .await,
+            ErrorKind::DirectoryNotEmpty,
+        );
+
+        assert_eq!(test_backend.read("source/file.txt").await, "source");
+        assert_eq!(
+            test_backend.dir_entry_names("target").await,
+            ["other.txt"],
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn renaming_a_file_replaces_a_file_but_not_a_directory() {
+    for test_backend in test_backends().await {
+        test_backend.write("source.txt", "source").await;
+        test_backend.write("target.txt", "target").await;
+        test_backend.create_directory("directory").await;
+
+        test_backend
+            .backend
+            .rename(
+                &test_backend.path("source.txt"),
+                &test_backend.path("target.txt"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            test_backend.kind("source.txt").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(test_backend.read("target.txt").await, "source");
+
+        test_backend.assert_error_kind(
+            test_backend
+                .backend
+                .rename(
+                    &test_backend.path("target.txt"),
+                    &test_backend.path("directory"),
+                )
+                .await,
+            ErrorKind::IsADirectory,
+        );
+
+        assert_eq!(test_backend.read("target.txt").await, "source");
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn copying_a_directory_copies_its_contents() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("source/nested").await;
+        test_backend.write("source/file.txt", "file").await;
+        test_backend.write("source/nested/file.txt", "nested").await;
+
+        test_backend
+            .backend
+            .copy(&test_backend.path("source"), &test_backend.path("copy"))
+            .await
+            .unwrap();
+        test_backend
+            .backend
+            .copy(
+                &test_backend.path("source/file.txt"),
+                &test_backend.path("file-copy.txt"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(test_backend.read("source/nested/file.txt").await, "nested");
+        assert_eq!(test_backend.read("copy/file.txt").await, "file");
+        assert_eq!(test_backend.read("copy/nested/file.txt").await, "nested");
+        assert_eq!(test_backend.read("file-copy.txt").await, "file");
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn deleting_a_directory_deletes_its_contents() {
+    for test_backend in test_backends().await {
+        test_backend.create_directory("directory/nested").await;
+        test_backend
+            .write("directory/nested/file.txt", "nested")
+            .await;
+        test_backend.write("file.txt", "file").await;
+
+        test_backend
+            .backend
+            .delete_directory(&test_backend.path("directory"))
+            .await
+            .unwrap();
+        test_backend
+            .backend
+            .delete_file(&test_backend.path("file.txt"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            test_backend.kind("directory").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(
+            test_backend.kind("directory/nested/file.txt").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert_eq!(
+            test_backend.kind("file.txt").await,
+            None,
+            "{}",
+            test_backend.name
+        );
+        assert!(
+            test_backend.dir_entry_names("").await.is_empty(),
+            "{}",
+            test_backend.name
+        );
+
+        test_backend.clean_up().await;
+    }
+}
+
+#[tokio::test]
+async fn directory_marker_name_is_rejected() {
+    for test_backend in test_backends().await {
+        let marker = test_backend.path(DIRECTORY_MARKER_NAME);
+        let below_marker = Path::new(&marker).join("file.txt");
+        let backend = &test_backend.backend;
+
+        test_backend.assert_error_kind(backend.stat(&marker).await, ErrorKind::InvalidInput);
+        test_backend.assert_error_kind(
+            backend
+                .write_stream(&marker, &mut "content".as_bytes())
+                .await,
+            ErrorKind::InvalidInput,
+        );
+        test_backend.assert_error_kind(
+            backend.create_directory(&marker).await,
+            ErrorKind::InvalidInput,
+        );
+        test_backend.assert_error_kind(
+            backend
+                .write_stream(&below_marker, &mut "content".as_bytes())
+                .await,
+            ErrorKind::InvalidInput,
+        );
+
+        test_backend.clean_up().await;
+    }
+}
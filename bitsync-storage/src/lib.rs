@@ -1,6 +1,8 @@
 pub mod async_file_read;
+pub mod backend;
 pub mod operation;
 pub mod storage_item;
 pub mod storage_path;
+pub mod storage_root;
 pub mod user_storage;
 pub mod validation;
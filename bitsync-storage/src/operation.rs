@@ -1,3 +1,8 @@
+pub mod checksum;
 pub mod error;
 pub mod read;
+pub mod thumbnail;
+pub mod trash;
+pub mod upload;
+pub mod version;
 pub mod write;
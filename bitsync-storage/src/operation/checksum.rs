@@ -0,0 +1,71 @@
+use std::{io::Error as IoError, path::PathBuf};
+
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::storage_path::StoragePath;
+
+use super::error::OpenFileError;
+
+const CHECKSUM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Copies `reader` into `writer` and returns the number of copied bytes together with the
+/// hex encoded SHA-256 checksum of the data.
+pub(crate) async fn copy_with_checksum<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<(u64, String), IoError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; CHECKSUM_BUFFER_SIZE];
+    let mut copied_bytes = 0;
+
+    loop {
+        let read_bytes = reader.read(&mut buffer).await?;
+
+        if read_bytes == 0 {
+            break;
+        }
+
+        hasher.update(&buffer[..read_bytes]);
+        writer.write_all(&buffer[..read_bytes]).await?;
+        copied_bytes += read_bytes as u64;
+    }
+
+    Ok((copied_bytes, hex::encode(hasher.finalize())))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to calculate a file's checksum")]
+pub enum CalculateFileChecksumError {
+    OpenFile(#[from] OpenFileError),
+    FileRead { source: IoError, path: PathBuf },
+}
+
+/// Re-reads the file at `path` and returns its hex encoded SHA-256 checksum.
+pub async fn calculate_file_checksum(
+    path: &StoragePath,
+) -> Result<String, CalculateFileChecksumError> {
+    let mut file = path
+        .storage
+        .storage_root
+        .backend
+        .read_stream(&path.backend_path())
+        .await
+        .map_err(|error| OpenFileError {
+            source: error,
+            path: path.backend_path(),
+        })?;
+
+    let (_, checksum) = copy_with_checksum(&mut file, &mut tokio::io::sink())
+        .await
+        .map_err(|error| CalculateFileChecksumError::FileRead {
+            source: error,
+            path: path.backend_path(),
+        })?;
+
+    Ok(checksum)
+}
@@ -1,7 +1,8 @@
 use crate::{
     async_file_read::AsyncFileRead,
-    storage_item::{StorageItem, StorageItemCreationError},
+    storage_item::{StorageItem, StorageItemCreationError, StorageItemKind},
     storage_path::StoragePath,
+    user_storage::UserStorage,
 };
 
 use super::error::{MetadataError, OpenFileError, ReadDirectoryError};
@@ -16,31 +17,26 @@ pub enum ReadDirContentsError {
 pub async fn read_dir_contents(
     path: &StoragePath,
 ) -> Result<Vec<StorageItem>, ReadDirContentsError> {
-    let mut dir_entries = tokio::fs::read_dir(path.local_directory())
+    let dir_entries = path
+        .storage
+        .storage_root
+        .backend
+        .read_dir(&path.backend_path())
         .await
         .map_err(|error| ReadDirectoryError {
             source: error,
-            path: path.local_directory(),
+            path: path.backend_path(),
         })?;
 
     let mut storage_items = Vec::new();
 
-    while let Some(dir_entry) =
-        dir_entries
-            .next_entry()
-            .await
-            .map_err(|error| ReadDirectoryError {
-                source: error,
-                path: path.local_directory(),
-            })?
-    {
-        let scoped_path = path.storage.strip_data_dir(dir_entry.path());
+    for dir_entry in dir_entries {
         let storage_path = StoragePath {
             storage: path.storage.clone(),
-            scoped_path,
+            scoped_path: path.scoped_path.join(dir_entry.name),
         };
 
-        storage_items.push(StorageItem::from_dir_entry(storage_path, dir_entry).await?);
+        storage_items.push(StorageItem::try_from((storage_path, dir_entry.metadata))?);
     }
 
     Ok(storage_items)
@@ -54,14 +50,18 @@ pub enum ReadFileStreamError {
 }
 
 pub async fn read_file_stream(path: &StoragePath) -> Result<AsyncFileRead, ReadFileStreamError> {
-    let file = tokio::fs::File::open(path.local_directory())
+    let file = path
+        .storage
+        .storage_root
+        .backend
+        .read_stream(&path.backend_path())
         .await
         .map_err(|error| OpenFileError {
             source: error,
-            path: path.local_directory(),
+            path: path.backend_path(),
         })?;
 
-    Ok(AsyncFileRead(file))
+    Ok(AsyncFileRead::new(file))
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -72,12 +72,75 @@ pub enum ReadStorageItemError {
 }
 
 pub async fn read_storage_item(path: &StoragePath) -> Result<StorageItem, ReadStorageItemError> {
-    let metadata = tokio::fs::metadata(&path.local_directory())
+    let metadata = path
+        .storage
+        .storage_root
+        .backend
+        .stat(&path.backend_path())
         .await
         .map_err(|error| MetadataError {
             source: error,
-            path: path.local_directory(),
+            path: path.backend_path(),
         })?;
 
     Ok(StorageItem::try_from((path.clone(), metadata))?)
 }
+
+#[derive(thiserror::Error, Debug)]
+#[error("could not calculate the storage usage")]
+pub enum CalculateStorageUsageError {
+    ReadDirectory(#[from] ReadDirectoryError),
+    Metadata(#[from] MetadataError),
+}
+
+/// Sums up the size of the user's files, including those in the trash and the kept previous
+/// versions, which still take up space until they are deleted.
+pub async fn calculate_storage_usage(
+    storage: &UserStorage,
+) -> Result<u64, CalculateStorageUsageError> {
+    let mut used_bytes = 0;
+    let mut pending_directories = vec![
+        storage.data_directory(),
+        storage.trash_directory(),
+        storage.versions_directory(),
+    ];
+
+    while let Some(directory) = pending_directories.pop() {
+        let dir_entries = match storage.storage_root.backend.read_dir(&directory).await {
+            Ok(dir_entries) => dir_entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => Err(ReadDirectoryError {
+                source: error,
+                path: directory.clone(),
+            })?,
+        };
+
+        for dir_entry in dir_entries {
+            match dir_entry.metadata.kind {
+                Some(StorageItemKind::Directory) => {
+                    pending_directories.push(directory.join(dir_entry.name))
+                }
+                _ => used_bytes += dir_entry.metadata.size,
+            }
+        }
+    }
+
+    Ok(used_bytes)
+}
+
+pub async fn item_exists(path: &StoragePath) -> Result<bool, MetadataError> {
+    match path
+        .storage
+        .storage_root
+        .backend
+        .stat(&path.backend_path())
+        .await
+    {
+        Ok(..) => Ok(true),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(MetadataError {
+            source: error,
+            path: path.backend_path(),
+        }),
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    io::{Error as IoError, ErrorKind},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tokio::io::AsyncWriteExt;
+
+use crate::{async_file_read::AsyncFileRead, storage_path::StoragePath};
+
+use super::error::{DirectoryCreationError, OpenFileError, ReadDirectoryError};
+
+static TEMPORARY_THUMBNAIL_EXTENSION: &str = "tmp";
+
+/// Thumbnails of a file are cached in a directory of their own, so a replaced thumbnail can be
+/// found and removed without knowing the file's previous state. The directories mirror the
+/// user's storage, so the thumbnails of a whole directory can be removed at once. Thumbnails are
+/// the only files in them, the items below a directory show up as subdirectories.
+fn thumbnail_directory(path: &StoragePath) -> PathBuf {
+    let mut thumbnail_directory = path.storage.thumbnails_directory();
+    thumbnail_directory.push(
+        path.scoped_path
+            .strip_prefix("/")
+            .unwrap_or(&path.scoped_path),
+    );
+
+    thumbnail_directory
+}
+
+/// Names a thumbnail after the size and modification time of the file it was created from, so a
+/// modified file no longer matches it.
+fn thumbnail_name(source_size: u64, source_modified_at: Option<SystemTime>) -> String {
+    let modified_at = source_modified_at
+        .and_then(|modified_at| modified_at.duration_since(UNIX_EPOCH).ok())
+        .map(|modified_at| modified_at.as_nanos())
+        .unwrap_or_default();
+
+    format!("{source_size:x}-{modified_at:x}")
+}
+
+pub struct CachedThumbnail {
+    pub file: AsyncFileRead,
+    pub size: u64,
+    /// The extension the thumbnail was stored with, which names its image format.
+    pub extension: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("could not read a cached thumbnail")]
+pub enum ReadCachedThumbnailError {
+    ReadDirectory(#[from] ReadDirectoryError),
+    OpenFile(#[from] OpenFileError),
+}
+
+/// Opens the cached thumbnail of the file at `path`, unless the file was modified since it was
+/// created.
+pub async fn read_cached_thumbnail(
+    path: &StoragePath,
+    source_size: u64,
+    source_modified_at: Option<SystemTime>,
+) -> Result<Option<CachedThumbnail>, ReadCachedThumbnailError> {
+    let thumbnail_directory = thumbnail_directory(path);
+    let thumbnail_name = thumbnail_name(source_size, source_modified_at);
+
+    let mut dir_entries = match tokio::fs::read_dir(&thumbnail_directory).await {
+        Ok(dir_entries) => dir_entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(ReadDirectoryError {
+                source: error,
+                path: thumbnail_directory,
+            })?;
+        }
+    };
+
+    while let Some(dir_entry) =
+        dir_entries
+            .next_entry()
+            .await
+            .map_err(|error| ReadDirectoryError {
+                source: error,
+                path: thumbnail_directory.clone(),
+            })?
+    {
+        if !dir_entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+
+        let thumbnail_path = dir_entry.path();
+
+        let (Some(file_stem), Some(extension)) = (
+            thumbnail_path.file_stem().and_then(|stem| stem.to_str()),
+            thumbnail_path
+                .extension()
+                .and_then(|extension| extension.to_str()),
+        ) else {
+            continue;
+        };
+
+        if file_stem != thumbnail_name || extension == TEMPORARY_THUMBNAIL_EXTENSION {
+            continue;
+        }
+
+        let file = match tokio::fs::File::open(&thumbnail_path).await {
+            Ok(file) => file,
+            // replaced in the meantime
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(OpenFileError {
+                    source: error,
+                    path: thumbnail_path,
+                })?;
+            }
+        };
+        let metadata = file.metadata().await.map_err(|error| OpenFileError {
+            source: error,
+            path: thumbnail_path.clone(),
+        })?;
+
+        return Ok(Some(CachedThumbnail {
+            file: AsyncFileRead::new(Box::new(file)),
+            size: metadata.len(),
+            extension: extension.to_owned(),
+        }));
+    }
+
+    Ok(None)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("could not store a thumbnail")]
+pub enum StoreThumbnailError {
+    DirectoryCreation(#[from] DirectoryCreationError),
+    ReadDirectory(#[from] ReadDirectoryError),
+    Write { source: IoError, path: PathBuf },
+}
+
+/// Caches the thumbnail of the file at `path` and removes the thumbnails of its previous states.
+pub async fn store_thumbnail(
+    path: &StoragePath,
+    source_size: u64,
+    source_modified_at: Option<SystemTime>,
+    extension: &str,
+    content: &[u8],
+) -> Result<(), StoreThumbnailError> {
+    let thumbnail_directory = thumbnail_directory(path);
+    let thumbnail_name = thumbnail_name(source_size, source_modified_at);
+
+    tokio::fs::create_dir_all(&thumbnail_directory)
+        .await
+        .map_err(|error| DirectoryCreationError {
+            source: error,
+            path: thumbnail_directory.clone(),
+        })?;
+
+    remove_outdated_thumbnails(&thumbnail_directory, &thumbnail_name).await?;
+
+    // thumbnails are written next to their destination first, so a concurrent read never sees a
+    // partially written one
+    let temporary_path = thumbnail_directory.join(format!(
+        "{thumbnail_name}.{id}.{TEMPORARY_THUMBNAIL_EXTENSION}",
+        id = uuid::Uuid::new_v4().simple(),
+    ));
+    let thumbnail_path = thumbnail_directory.join(format!("{thumbnail_name}.{extension}"));
+
+    let write_result = async {
+        let mut file = tokio::fs::File::create(&temporary_path).await?;
+        file.write_all(content).await?;
+        file.sync_all().await?;
+
+        tokio::fs::rename(&temporary_path, &thumbnail_path).await
+    }
+    .await;
+
+    if let Err(error) = write_result {
+        let _ = tokio::fs::remove_file(&temporary_path).await;
+
+        return Err(StoreThumbnailError::Write {
+            source: error,
+            path: thumbnail_path,
+        });
+    }
+
+    Ok(())
+}
+
+async fn remove_outdated_thumbnails(
+    thumbnail_directory: &Path,
+    thumbnail_name: &str,
+) -> Result<(), ReadDirectoryError> {
+    let mut dir_entries = tokio::fs::read_dir(thumbnail_directory)
+        .await
+        .map_err(|error| ReadDirectoryError {
+            source: error,
+            path: thumbnail_directory.to_path_buf(),
+        })?;
+
+    while let Some(dir_entry) =
+        dir_entries
+            .next_entry()
+            .await
+            .map_err(|error| ReadDirectoryError {
+                source: error,
+                path: thumbnail_directory.to_path_buf(),
+            })?
+    {
+        if !dir_entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+
+        let thumbnail_path = dir_entry.path();
+
+        // temporary files belong to thumbnails that are being stored concurrently
+        let is_outdated = thumbnail_path
+            .extension()
+            .is_some_and(|extension| extension != TEMPORARY_THUMBNAIL_EXTENSION)
+            && thumbnail_path
+                .file_stem()
+                .is_some_and(|file_stem| file_stem != thumbnail_name);
+
+        if is_outdated {
+            // a thumbnail that cannot be removed is never read again, as it does not match
+            let _ = tokio::fs::remove_file(&thumbnail_path).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the cached thumbnails of the item at `path` and of everything below it, once the item
+/// is gone from there. Failures are ignored, the item is already gone and thumbnails left behind
+/// only take up space.
+pub async fn remove_thumbnails(path: &StoragePath) {
+    let _ = tokio::fs::remove_dir_all(thumbnail_directory(path)).await;
+}
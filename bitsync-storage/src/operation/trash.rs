@@ -0,0 +1,167 @@
+use std::{io::Error as IoError, path::PathBuf};
+
+use crate::{storage_item::StorageItemKind, storage_path::StoragePath, user_storage::UserStorage};
+
+use super::error::{DirectoryCreationError, MetadataError};
+
+pub struct TrashItem {
+    pub kind: StorageItemKind,
+    pub size: u64,
+}
+
+fn trash_item_path(storage: &UserStorage, trash_item_name: &str) -> PathBuf {
+    let mut trash_item_path = storage.trash_directory();
+    trash_item_path.push(trash_item_name);
+
+    trash_item_path
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to move item to trash")]
+pub enum MoveToTrashError {
+    DirectoryCreation(#[from] DirectoryCreationError),
+    Rename { source: IoError, from_path: PathBuf },
+}
+
+pub async fn move_to_trash(
+    path: &StoragePath,
+    trash_item_name: &str,
+) -> Result<(), MoveToTrashError> {
+    let trash_directory = path.storage.trash_directory();
+
+    path.storage
+        .storage_root
+        .backend
+        .create_directory_all(&trash_directory)
+        .await
+        .map_err(|error| DirectoryCreationError {
+            source: error,
+            path: trash_directory,
+        })?;
+
+    path.storage
+        .storage_root
+        .backend
+        .rename(
+            &path.backend_path(),
+            &trash_item_path(&path.storage, trash_item_name),
+        )
+        .await
+        .map_err(|error| MoveToTrashError::Rename {
+            source: error,
+            from_path: path.backend_path(),
+        })?;
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to restore item from trash")]
+pub enum RestoreFromTrashError {
+    DirectoryCreation(#[from] DirectoryCreationError),
+    Rename { source: IoError, to_path: PathBuf },
+}
+
+/// Moves a trashed item back into the user's storage, recreating missing parent directories.
+pub async fn restore_from_trash(
+    trash_item_name: &str,
+    destination: &StoragePath,
+) -> Result<(), RestoreFromTrashError> {
+    let backend = &destination.storage.storage_root.backend;
+    let destination_path = destination.backend_path();
+
+    if let Some(parent_directory) = destination_path.parent() {
+        backend
+            .create_directory_all(parent_directory)
+            .await
+            .map_err(|error| DirectoryCreationError {
+                source: error,
+                path: parent_directory.to_path_buf(),
+            })?;
+    }
+
+    backend
+        .rename(
+            &trash_item_path(&destination.storage, trash_item_name),
+            &destination_path,
+        )
+        .await
+        .map_err(|error| RestoreFromTrashError::Rename {
+            source: error,
+            to_path: destination_path.clone(),
+        })?;
+
+    Ok(())
+}
+
+pub async fn read_trash_item(
+    storage: &UserStorage,
+    trash_item_name: &str,
+) -> Result<TrashItem, MetadataError> {
+    let path = trash_item_path(storage, trash_item_name);
+    let metadata = storage
+        .storage_root
+        .backend
+        .stat(&path)
+        .await
+        .map_err(|error| MetadataError {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    Ok(TrashItem {
+        kind: metadata.kind.unwrap_or(StorageItemKind::File),
+        size: metadata.size,
+    })
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete item from trash")]
+pub struct DeleteFromTrashError {
+    pub source: IoError,
+    pub path: PathBuf,
+}
+
+/// Permanently deletes a trashed item. Items that no longer exist are ignored.
+pub async fn delete_from_trash(
+    storage: &UserStorage,
+    trash_item_name: &str,
+) -> Result<(), DeleteFromTrashError> {
+    let path = trash_item_path(storage, trash_item_name);
+
+    let backend = &storage.storage_root.backend;
+
+    let result = match backend.stat(&path).await {
+        Ok(metadata) if metadata.kind == Some(StorageItemKind::Directory) => {
+            backend.delete_directory(&path).await
+        }
+        Ok(_) => backend.delete_file(&path).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(DeleteFromTrashError {
+            source: error,
+            path,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Permanently deletes all trashed items of a user.
+pub async fn empty_trash(storage: &UserStorage) -> Result<(), DeleteFromTrashError> {
+    let trash_directory = storage.trash_directory();
+
+    match storage
+        .storage_root
+        .backend
+        .delete_directory(&trash_directory)
+        .await
+    {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(DeleteFromTrashError {
+            source: error,
+            path: trash_directory,
+        }),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,222 @@
+use std::{io::Error as IoError, path::PathBuf};
+
+use futures::pin_mut;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio_util::io::StreamReader;
+
+use crate::{storage_path::StoragePath, user_storage::UserStorage};
+
+use super::{
+    checksum::copy_with_checksum,
+    error::{DirectoryCreationError, OpenFileError},
+};
+
+fn staged_upload_path(storage: &UserStorage, staged_upload_name: &str) -> PathBuf {
+    let mut staged_upload_path = storage.uploads_directory();
+    staged_upload_path.push(staged_upload_name);
+
+    staged_upload_path
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create staged upload")]
+pub enum CreateStagedUploadError {
+    DirectoryCreation(#[from] DirectoryCreationError),
+    OpenFile(#[from] OpenFileError),
+}
+
+/// Creates an empty file outside the user's visible tree to collect the data of an upload.
+pub async fn create_staged_upload(
+    storage: &UserStorage,
+    staged_upload_name: &str,
+) -> Result<(), CreateStagedUploadError> {
+    let uploads_directory = storage.uploads_directory();
+
+    tokio::fs::create_dir_all(&uploads_directory)
+        .await
+        .map_err(|error| DirectoryCreationError {
+            source: error,
+            path: uploads_directory,
+        })?;
+
+    let path = staged_upload_path(storage, staged_upload_name);
+
+    tokio::fs::File::create(&path)
+        .await
+        .map_err(|error| OpenFileError {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to write to staged upload")]
+pub enum WriteStagedUploadChunkError {
+    OpenFile(#[from] OpenFileError),
+    FileWrite {
+        source: IoError,
+        path: PathBuf,
+    },
+    /// The request body ended early. `written_bytes` were stored before the interruption.
+    StreamRead {
+        source: IoError,
+        written_bytes: u64,
+    },
+    SizeLimitExceeded {
+        size_limit: u64,
+    },
+}
+
+const STAGED_UPLOAD_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Writes `stream` into the staged upload starting at `offset` and returns the number of written
+/// bytes. Data beyond `size_limit` is rejected and the staged upload is truncated back to `offset`.
+pub async fn write_staged_upload_chunk<S, B, E>(
+    storage: &UserStorage,
+    staged_upload_name: &str,
+    offset: u64,
+    stream: StreamReader<S, B>,
+    size_limit: u64,
+) -> Result<u64, WriteStagedUploadChunkError>
+where
+    S: futures::Stream<Item = Result<B, E>>,
+    B: bytes::Buf,
+    E: Into<std::io::Error>,
+{
+    let path = staged_upload_path(storage, staged_upload_name);
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .await
+        .map_err(|error| OpenFileError {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    let file_write_error = |error| WriteStagedUploadChunkError::FileWrite {
+        source: error,
+        path: path.clone(),
+    };
+
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(file_write_error)?;
+
+    pin_mut!(stream);
+
+    // reading one byte past the limit is enough to detect an oversized chunk
+    let mut limited_stream = stream.take(size_limit + 1);
+    let mut buffer = vec![0; STAGED_UPLOAD_BUFFER_SIZE];
+    let mut written_bytes = 0;
+
+    loop {
+        let read_bytes = match limited_stream.read(&mut buffer).await {
+            Ok(0) => break,
+            Ok(read_bytes) => read_bytes,
+            Err(error) => {
+                file.flush().await.map_err(file_write_error)?;
+
+                return Err(WriteStagedUploadChunkError::StreamRead {
+                    source: error,
+                    written_bytes,
+                });
+            }
+        };
+
+        if written_bytes + read_bytes as u64 > size_limit {
+            file.set_len(offset).await.map_err(file_write_error)?;
+
+            return Err(WriteStagedUploadChunkError::SizeLimitExceeded { size_limit });
+        }
+
+        file.write_all(&buffer[..read_bytes])
+            .await
+            .map_err(file_write_error)?;
+
+        written_bytes += read_bytes as u64;
+    }
+
+    file.flush().await.map_err(file_write_error)?;
+
+    Ok(written_bytes)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to finalize staged upload")]
+pub enum FinalizeStagedUploadError {
+    OpenFile(#[from] OpenFileError),
+    FileRead { source: IoError, path: PathBuf },
+    FileSync { source: IoError, path: PathBuf },
+    Rename { source: IoError, to_path: PathBuf },
+}
+
+/// Calculates the checksum of a completed staged upload, syncs it to disk and moves it to
+/// `destination`, replacing any file located there. Returns the checksum.
+pub async fn finalize_staged_upload(
+    staged_upload_name: &str,
+    destination: &StoragePath,
+) -> Result<String, FinalizeStagedUploadError> {
+    let path = staged_upload_path(&destination.storage, staged_upload_name);
+
+    let mut file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|error| OpenFileError {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    let (_, checksum) = copy_with_checksum(&mut file, &mut tokio::io::sink())
+        .await
+        .map_err(|error| FinalizeStagedUploadError::FileRead {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    file.sync_all()
+        .await
+        .map_err(|error| FinalizeStagedUploadError::FileSync {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    destination
+        .storage
+        .storage_root
+        .backend
+        .import_local_file(&path, &destination.backend_path())
+        .await
+        .map_err(|error| FinalizeStagedUploadError::Rename {
+            source: error,
+            to_path: destination.backend_path(),
+        })?;
+
+    Ok(checksum)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete staged upload")]
+pub struct DeleteStagedUploadError {
+    pub source: IoError,
+    pub path: PathBuf,
+}
+
+/// Deletes a staged upload. Staged uploads that no longer exist are ignored.
+pub async fn delete_staged_upload(
+    storage: &UserStorage,
+    staged_upload_name: &str,
+) -> Result<(), DeleteStagedUploadError> {
+    let path = staged_upload_path(storage, staged_upload_name);
+
+    match tokio::fs::remove_file(&path).await {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            Err(DeleteStagedUploadError {
+                source: error,
+                path,
+            })
+        }
+        _ => Ok(()),
+    }
+}
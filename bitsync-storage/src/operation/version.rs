@@ -0,0 +1,123 @@
+use std::{io::Error as IoError, path::PathBuf};
+
+use crate::{async_file_read::AsyncFileRead, storage_path::StoragePath, user_storage::UserStorage};
+
+use super::error::{DirectoryCreationError, OpenFileError};
+
+fn file_version_path(storage: &UserStorage, file_version_name: &str) -> PathBuf {
+    let mut file_version_path = storage.versions_directory();
+    file_version_path.push(file_version_name);
+
+    file_version_path
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to store file version")]
+pub enum StoreFileVersionError {
+    DirectoryCreation(#[from] DirectoryCreationError),
+    Rename { source: IoError, from_path: PathBuf },
+}
+
+/// Moves the current content of a file into the user's version storage.
+pub async fn store_file_version(
+    path: &StoragePath,
+    file_version_name: &str,
+) -> Result<(), StoreFileVersionError> {
+    let versions_directory = path.storage.versions_directory();
+
+    path.storage
+        .storage_root
+        .backend
+        .create_directory_all(&versions_directory)
+        .await
+        .map_err(|error| DirectoryCreationError {
+            source: error,
+            path: versions_directory,
+        })?;
+
+    path.storage
+        .storage_root
+        .backend
+        .rename(
+            &path.backend_path(),
+            &file_version_path(&path.storage, file_version_name),
+        )
+        .await
+        .map_err(|error| StoreFileVersionError::Rename {
+            source: error,
+            from_path: path.backend_path(),
+        })?;
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to restore file version")]
+pub struct RestoreFileVersionError {
+    pub source: IoError,
+    pub to_path: PathBuf,
+}
+
+/// Moves a stored file version back to `destination`, replacing any file located there.
+pub async fn restore_file_version(
+    file_version_name: &str,
+    destination: &StoragePath,
+) -> Result<(), RestoreFileVersionError> {
+    destination
+        .storage
+        .storage_root
+        .backend
+        .rename(
+            &file_version_path(&destination.storage, file_version_name),
+            &destination.backend_path(),
+        )
+        .await
+        .map_err(|error| RestoreFileVersionError {
+            source: error,
+            to_path: destination.backend_path(),
+        })?;
+
+    Ok(())
+}
+
+pub async fn read_file_version_stream(
+    storage: &UserStorage,
+    file_version_name: &str,
+) -> Result<AsyncFileRead, OpenFileError> {
+    let path = file_version_path(storage, file_version_name);
+
+    let file = storage
+        .storage_root
+        .backend
+        .read_stream(&path)
+        .await
+        .map_err(|error| OpenFileError {
+            source: error,
+            path: path.clone(),
+        })?;
+
+    Ok(AsyncFileRead::new(file))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to delete file version")]
+pub struct DeleteFileVersionError {
+    pub source: IoError,
+    pub path: PathBuf,
+}
+
+/// Permanently deletes a stored file version. Versions that no longer exist are ignored.
+pub async fn delete_file_version(
+    storage: &UserStorage,
+    file_version_name: &str,
+) -> Result<(), DeleteFileVersionError> {
+    let path = file_version_path(storage, file_version_name);
+
+    match storage.storage_root.backend.delete_file(&path).await {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(DeleteFileVersionError {
+            source: error,
+            path,
+        }),
+        _ => Ok(()),
+    }
+}
@@ -1,14 +1,22 @@
-use std::{io::Error as IoError, path::PathBuf};
+use std::{
+    io::Error as IoError,
+    path::{Path, PathBuf},
+};
 
 use futures::pin_mut;
-use tokio::io::BufWriter;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
 use tokio_util::io::StreamReader;
 
 use crate::{
-    storage_item::StorageItemCreationError, storage_path::StoragePath, user_storage::UserStorage,
+    storage_item::StorageItemCreationError,
+    storage_path::StoragePath,
+    user_storage::{UserStorage, temporary_files_directory},
 };
 
-use super::error::{DirectoryCreationError, OpenFileError};
+use super::{
+    checksum::copy_with_checksum,
+    error::{DirectoryCreationError, OpenFileError},
+};
 
 #[derive(thiserror::Error, Debug)]
 #[error("could not ensure that the storage exists")]
@@ -17,11 +25,14 @@ pub struct EnsureUserStorageExistsError(#[from] DirectoryCreationError);
 pub async fn ensure_user_storage_exists(
     storage: &UserStorage,
 ) -> Result<(), EnsureUserStorageExistsError> {
-    tokio::fs::create_dir_all(&storage.data_directory())
+    storage
+        .storage_root
+        .backend
+        .create_directory_all(&storage.data_directory())
         .await
         .map_err(|error| DirectoryCreationError {
             source: error,
-            path: storage.storage_root.clone(),
+            path: storage.data_directory(),
         })?;
 
     Ok(())
@@ -37,37 +48,194 @@ pub enum AsyncFileReadError {
 #[derive(thiserror::Error, Debug)]
 #[error("could not read a file's contents")]
 pub enum WriteFileStreamError {
+    DirectoryCreation(#[from] DirectoryCreationError),
     OpenFile(#[from] OpenFileError),
-    StorageItemCreation(#[from] StorageItemCreationError),
     StreamWrite(#[source] std::io::Error),
+    FileSync(#[source] std::io::Error),
+    SizeLimitExceeded(#[from] FileSizeLimitExceededError),
+    PersistTemporaryFile(#[from] PersistTemporaryFileError),
 }
 
-pub async fn write_file_stream<S, B, E>(
-    path: &StoragePath,
+#[derive(thiserror::Error, Debug)]
+#[error("the file exceeds the size limit of {size_limit} bytes")]
+pub struct FileSizeLimitExceededError {
+    pub size_limit: u64,
+}
+
+/// A completely written file that is not visible in the user's storage yet. It is removed when
+/// dropped without being persisted.
+pub struct TemporaryFile {
+    path: PathBuf,
+    checksum: String,
+    is_persisted: bool,
+}
+
+impl TemporaryFile {
+    /// The hex encoded SHA-256 checksum of the written content.
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+}
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        if !self.is_persisted {
+            // leftovers are removed by `clean_temporary_files` on the next start
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Streams into a temporary file next to the user's storage, calculating its checksum, and syncs
+/// it to disk.
+pub async fn write_temporary_file_stream<S, B, E>(
+    storage: &UserStorage,
     stream: StreamReader<S, B>,
-) -> Result<(), WriteFileStreamError>
+    size_limit: Option<u64>,
+) -> Result<TemporaryFile, WriteFileStreamError>
 where
     S: futures::Stream<Item = Result<B, E>>,
     B: bytes::Buf,
     E: Into<std::io::Error>,
 {
-    let file = tokio::fs::File::create(path.local_directory())
+    let temporary_directory = storage.temporary_directory();
+
+    tokio::fs::create_dir_all(&temporary_directory)
+        .await
+        .map_err(|error| DirectoryCreationError {
+            source: error,
+            path: temporary_directory.clone(),
+        })?;
+
+    let temporary_path = temporary_directory.join(uuid::Uuid::new_v4().to_string());
+
+    let file = tokio::fs::File::create(&temporary_path)
         .await
         .map_err(|error| OpenFileError {
             source: error,
-            path: path.local_directory(),
+            path: temporary_path.clone(),
         })?;
 
+    let mut temporary_file = TemporaryFile {
+        path: temporary_path,
+        checksum: String::new(),
+        is_persisted: false,
+    };
+
     let mut file_writer = BufWriter::new(file);
     pin_mut!(stream);
 
-    tokio::io::copy(&mut stream, &mut file_writer)
+    temporary_file.checksum = match size_limit {
+        Some(size_limit) => {
+            // reading one byte past the limit is enough to detect an oversized upload
+            // without buffering the rest of the request body
+            let mut limited_stream = stream.take(size_limit + 1);
+
+            let (written_bytes, checksum) =
+                copy_with_checksum(&mut limited_stream, &mut file_writer)
+                    .await
+                    .map_err(WriteFileStreamError::StreamWrite)?;
+
+            if written_bytes > size_limit {
+                Err(FileSizeLimitExceededError { size_limit })?;
+            }
+
+            checksum
+        }
+        None => {
+            let (_, checksum) = copy_with_checksum(&mut stream, &mut file_writer)
+                .await
+                .map_err(WriteFileStreamError::StreamWrite)?;
+
+            checksum
+        }
+    };
+
+    file_writer
+        .flush()
         .await
         .map_err(WriteFileStreamError::StreamWrite)?;
+    file_writer
+        .get_ref()
+        .sync_all()
+        .await
+        .map_err(WriteFileStreamError::FileSync)?;
+
+    Ok(temporary_file)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to move a temporary file into place")]
+pub struct PersistTemporaryFileError {
+    pub source: IoError,
+    pub to_path: PathBuf,
+}
+
+/// Replaces `destination` with the temporary file, atomically unless the storage backend lives
+/// outside the local filesystem.
+pub async fn persist_temporary_file(
+    mut temporary_file: TemporaryFile,
+    destination: &StoragePath,
+) -> Result<(), PersistTemporaryFileError> {
+    destination
+        .storage
+        .storage_root
+        .backend
+        .import_local_file(&temporary_file.path, &destination.backend_path())
+        .await
+        .map_err(|error| PersistTemporaryFileError {
+            source: error,
+            to_path: destination.backend_path(),
+        })?;
+
+    temporary_file.is_persisted = true;
 
     Ok(())
 }
 
+/// Writes `stream` to `path` without exposing partially written content and returns the
+/// checksum of the written file. The previous file stays in place until the new content is
+/// completely on disk.
+pub async fn write_file_stream<S, B, E>(
+    path: &StoragePath,
+    stream: StreamReader<S, B>,
+    size_limit: Option<u64>,
+) -> Result<String, WriteFileStreamError>
+where
+    S: futures::Stream<Item = Result<B, E>>,
+    B: bytes::Buf,
+    E: Into<std::io::Error>,
+{
+    let temporary_file = write_temporary_file_stream(&path.storage, stream, size_limit).await?;
+    let checksum = temporary_file.checksum().to_owned();
+
+    persist_temporary_file(temporary_file, path).await?;
+
+    Ok(checksum)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to clean up temporary files")]
+pub struct CleanTemporaryFilesError {
+    pub source: IoError,
+    pub path: PathBuf,
+}
+
+/// Removes temporary files that were left behind by interrupted writes. Must only run while no
+/// writes are in progress.
+pub async fn clean_temporary_files(storage_root: &Path) -> Result<(), CleanTemporaryFilesError> {
+    let temporary_files_directory = temporary_files_directory(storage_root);
+
+    match tokio::fs::remove_dir_all(&temporary_files_directory).await {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(CleanTemporaryFilesError {
+            source: error,
+            path: temporary_files_directory,
+        }),
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("failed to remove directory")]
 pub struct DeleteDirectoryError {
@@ -83,26 +251,54 @@ pub struct DeleteUserStorageError {
 }
 
 pub async fn delete_user_storage(storage: &UserStorage) -> Result<(), DeleteUserStorageError> {
-    let data_directory = storage.data_directory();
-
-    if data_directory.exists() {
-        tokio::fs::remove_dir_all(&data_directory)
+    for directory in [
+        storage.data_directory(),
+        storage.trash_directory(),
+        storage.versions_directory(),
+    ] {
+        match storage
+            .storage_root
+            .backend
+            .delete_directory(&directory)
             .await
-            .map_err(|error| DeleteUserStorageError {
-                source: error,
-                path: data_directory,
-            })?;
+        {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                Err(DeleteUserStorageError {
+                    source: error,
+                    path: directory,
+                })?
+            }
+            _ => {}
+        }
+    }
+
+    for directory in [
+        storage.uploads_directory(),
+        storage.thumbnails_directory(),
+        storage.temporary_directory(),
+    ] {
+        if directory.exists() {
+            tokio::fs::remove_dir_all(&directory)
+                .await
+                .map_err(|error| DeleteUserStorageError {
+                    source: error,
+                    path: directory,
+                })?;
+        }
     }
 
     Ok(())
 }
 
 pub async fn delete_directory(path: &StoragePath) -> Result<(), DeleteDirectoryError> {
-    tokio::fs::remove_dir_all(path.local_directory())
+    path.storage
+        .storage_root
+        .backend
+        .delete_directory(&path.backend_path())
         .await
         .map_err(|error| DeleteDirectoryError {
             source: error,
-            path: path.local_directory(),
+            path: path.backend_path(),
         })?;
 
     Ok(())
@@ -116,11 +312,14 @@ pub struct DeleteFileError {
 }
 
 pub async fn delete_file(path: &StoragePath) -> Result<(), DeleteFileError> {
-    tokio::fs::remove_file(path.local_directory())
+    path.storage
+        .storage_root
+        .backend
+        .delete_file(&path.backend_path())
         .await
         .map_err(|error| DeleteFileError {
             source: error,
-            path: path.local_directory(),
+            path: path.backend_path(),
         })?;
 
     Ok(())
@@ -138,12 +337,44 @@ pub async fn rename_item(
     from_path: &StoragePath,
     to_path: &StoragePath,
 ) -> Result<(), RenameItemError> {
-    tokio::fs::rename(from_path.local_directory(), to_path.local_directory())
+    from_path
+        .storage
+        .storage_root
+        .backend
+        .rename(&from_path.backend_path(), &to_path.backend_path())
         .await
         .map_err(|error| RenameItemError {
             source: error,
-            from_path: from_path.local_directory(),
-            to_path: to_path.local_directory(),
+            from_path: from_path.backend_path(),
+            to_path: to_path.backend_path(),
+        })?;
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to copy item")]
+pub struct CopyItemError {
+    pub source: IoError,
+    pub from_path: PathBuf,
+    pub to_path: PathBuf,
+}
+
+/// Copies a file, or a directory including all of its contents, to a path that does not exist yet.
+pub async fn copy_item(
+    from_path: &StoragePath,
+    to_path: &StoragePath,
+) -> Result<(), CopyItemError> {
+    from_path
+        .storage
+        .storage_root
+        .backend
+        .copy(&from_path.backend_path(), &to_path.backend_path())
+        .await
+        .map_err(|error| CopyItemError {
+            source: error,
+            from_path: from_path.backend_path(),
+            to_path: to_path.backend_path(),
         })?;
 
     Ok(())
@@ -157,11 +388,15 @@ pub struct CreateDirectoryError {
 }
 
 pub async fn create_directory(directory_path: &StoragePath) -> Result<(), CreateDirectoryError> {
-    tokio::fs::create_dir(directory_path.local_directory())
+    directory_path
+        .storage
+        .storage_root
+        .backend
+        .create_directory(&directory_path.backend_path())
         .await
         .map_err(|error| CreateDirectoryError {
             source: error,
-            direcory_path: directory_path.local_directory(),
+            direcory_path: directory_path.backend_path(),
         })?;
 
     Ok(())
@@ -1,6 +1,6 @@
-use std::{io::Error as IoError, path::PathBuf};
+use std::{path::PathBuf, time::SystemTime};
 
-use crate::storage_path::StoragePath;
+use crate::{backend::ItemMetadata, storage_path::StoragePath};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum StorageItemKind {
@@ -13,68 +13,33 @@ pub struct StorageItem {
     pub path: StoragePath,
     pub size: u64,
     pub kind: StorageItemKind,
+    /// Not available on every platform.
+    pub modified_at: Option<SystemTime>,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum StorageItemCreationError {
     #[error("storage item is of unsupported type: symlink")]
     IsSymlink { path: PathBuf },
-    #[error("could not gather metadata while creating a storage item")]
-    Metadata { source: IoError, path: PathBuf },
 }
 
-impl StorageItem {
-    pub(crate) async fn from_dir_entry(
-        path: StoragePath,
-        dir_entry: tokio::fs::DirEntry,
-    ) -> Result<Self, StorageItemCreationError> {
-        let metadata =
-            dir_entry
-                .metadata()
-                .await
-                .map_err(|error| StorageItemCreationError::Metadata {
-                    source: error,
-                    path: path.local_directory(),
-                })?;
-
-        let kind = if metadata.file_type().is_dir() {
-            StorageItemKind::Directory
-        } else if metadata.file_type().is_file() {
-            StorageItemKind::File
-        } else {
-            return Err(StorageItemCreationError::IsSymlink {
-                path: path.local_directory(),
-            });
-        };
-
-        Ok(Self {
-            path,
-            size: metadata.len(),
-            kind,
-        })
-    }
-}
-
-impl TryFrom<(StoragePath, std::fs::Metadata)> for StorageItem {
+impl TryFrom<(StoragePath, ItemMetadata)> for StorageItem {
     type Error = StorageItemCreationError;
 
     fn try_from(
-        (path, metadata): (StoragePath, std::fs::Metadata),
+        (path, metadata): (StoragePath, ItemMetadata),
     ) -> Result<Self, StorageItemCreationError> {
-        let kind = if metadata.file_type().is_dir() {
-            StorageItemKind::Directory
-        } else if metadata.file_type().is_file() {
-            StorageItemKind::File
-        } else {
+        let Some(kind) = metadata.kind else {
             return Err(StorageItemCreationError::IsSymlink {
-                path: path.local_directory(),
+                path: path.backend_path(),
             });
         };
 
         Ok(Self {
             path,
-            size: metadata.len(),
+            size: metadata.size,
             kind,
+            modified_at: metadata.modified_at,
         })
     }
 }
@@ -28,7 +28,8 @@ impl StoragePath {
         })
     }
 
-    pub fn local_directory(&self) -> PathBuf {
+    /// The path of the item in the storage backend.
+    pub fn backend_path(&self) -> PathBuf {
         let scoped_path = self.scoped_path.clone();
 
         let mut user_directory = self.storage.data_directory();
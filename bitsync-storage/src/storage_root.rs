@@ -0,0 +1,23 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::backend::{StorageBackend, local::LocalStorageBackend};
+
+/// The storage of all users.
+#[derive(Clone, Debug)]
+pub struct StorageRoot {
+    /// Holds temporary files, staged uploads and cached thumbnails, which never leave the local
+    /// filesystem.
+    pub local_directory: PathBuf,
+    /// Holds the files, trash and versions of all users.
+    pub backend: Arc<dyn StorageBackend>,
+}
+
+impl StorageRoot {
+    /// Keeps everything in `local_directory`.
+    pub fn local(local_directory: PathBuf) -> Self {
+        Self {
+            backend: Arc::new(LocalStorageBackend::new(local_directory.clone())),
+            local_directory,
+        }
+    }
+}
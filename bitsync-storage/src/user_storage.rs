@@ -1,26 +1,75 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::storage_root::StorageRoot;
+
+/// The data, trash and versions directories of a user are paths of the storage backend. All other
+/// directories are located in the local directory of the storage root.
 #[derive(Clone, Debug)]
 pub struct UserStorage {
     pub user_id: uuid::Uuid,
-    pub storage_root: PathBuf,
+    pub storage_root: StorageRoot,
 }
 
 static USER_DATA_DIR: &str = "user";
+static USER_TRASH_DIR: &str = "trash";
+static USER_VERSIONS_DIR: &str = "versions";
+static USER_UPLOADS_DIR: &str = "uploads";
+static USER_THUMBNAILS_DIR: &str = "thumbnails";
+static TEMPORARY_FILES_DIR: &str = "tmp";
+
+/// The directory holding the temporary files of all users. Its contents never outlive a request.
+pub fn temporary_files_directory(storage_root: &Path) -> PathBuf {
+    storage_root.join(TEMPORARY_FILES_DIR)
+}
 
 impl UserStorage {
     pub fn data_directory(&self) -> PathBuf {
-        let mut storage_path = self.storage_root.clone();
+        let mut storage_path = PathBuf::from(USER_DATA_DIR);
 
-        storage_path.push(USER_DATA_DIR);
         storage_path.push(self.user_id.to_string());
 
         storage_path
     }
 
-    pub fn strip_data_dir(&self, path: PathBuf) -> PathBuf {
-        path.strip_prefix(self.data_directory())
-            .map(|path| path.to_path_buf())
-            .unwrap_or(path)
+    pub fn trash_directory(&self) -> PathBuf {
+        let mut trash_path = PathBuf::from(USER_TRASH_DIR);
+
+        trash_path.push(self.user_id.to_string());
+
+        trash_path
+    }
+
+    pub fn versions_directory(&self) -> PathBuf {
+        let mut versions_path = PathBuf::from(USER_VERSIONS_DIR);
+
+        versions_path.push(self.user_id.to_string());
+
+        versions_path
+    }
+
+    pub fn uploads_directory(&self) -> PathBuf {
+        let mut uploads_path = self.storage_root.local_directory.clone();
+
+        uploads_path.push(USER_UPLOADS_DIR);
+        uploads_path.push(self.user_id.to_string());
+
+        uploads_path
+    }
+
+    pub fn thumbnails_directory(&self) -> PathBuf {
+        let mut thumbnails_path = self.storage_root.local_directory.clone();
+
+        thumbnails_path.push(USER_THUMBNAILS_DIR);
+        thumbnails_path.push(self.user_id.to_string());
+
+        thumbnails_path
+    }
+
+    pub fn temporary_directory(&self) -> PathBuf {
+        let mut temporary_path = temporary_files_directory(&self.storage_root.local_directory);
+
+        temporary_path.push(self.user_id.to_string());
+
+        temporary_path
     }
 }
@@ -2,13 +2,14 @@ use std::{convert::Infallible, sync::Arc};
 
 use crate::{
     AppState,
+    error_report::emit_error,
     handler::{Redirection, redirect_response},
 };
 use axum::{
-    extract::{FromRef, FromRequestParts, Request},
-    http::request::Parts,
+    extract::{FromRef, FromRequestParts, Request, State},
+    http::{StatusCode, header, request::Parts},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use axum_extra::extract::{
     CookieJar,
@@ -16,9 +17,18 @@ use axum_extra::extract::{
 };
 use bitsync_core::{
     jwt::{JwtClaims, LoginState},
-    use_case::auth::resolve_session::{ResolveSessionError, resolve_session},
+    use_case::{
+        app_password::authenticate_app_password::{
+            AuthenticateAppPasswordError, authenticate_app_password,
+        },
+        auth::resolve_session::{ResolveSessionError, resolve_session},
+    },
+};
+use bitsync_database::entity::{AppPassword, AppPasswordScope, Session, User};
+use headers::{
+    Authorization, HeaderMapExt,
+    authorization::{Basic, Bearer},
 };
-use bitsync_database::entity::{Session, User};
 
 #[derive(Debug, thiserror::Error)]
 #[error("the provided auth token is invalid")]
@@ -56,7 +66,10 @@ pub struct AuthData {
 pub enum AuthStatus {
     Missing,
     Invalid,
-    User(AuthData),
+    User(Box<AuthData>),
+    /// Authenticated with an app password sent as bearer token. Browser pages require a session
+    /// and treat this like a missing login.
+    AppPassword(Box<AppPasswordAuthData>),
 }
 
 impl<S> FromRequestParts<S> for AuthStatus
@@ -69,11 +82,23 @@ where
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let app_state = Arc::<AppState>::from_ref(state);
 
+        if let Some(Authorization(bearer)) = parts.headers.typed_get::<Authorization<Bearer>>() {
+            return Ok(
+                match authenticate_app_password(&app_state.database, None, bearer.token()).await {
+                    Ok(authentication) => AuthStatus::AppPassword(Box::new(AppPasswordAuthData {
+                        app_password: authentication.app_password,
+                        user: authentication.user,
+                    })),
+                    Err(..) => AuthStatus::Invalid,
+                },
+            );
+        }
+
         Ok(match CookieJar::from_request_parts(parts, state).await {
             Ok(cookie_jar) => match cookie_jar.get(AUTH_COOKIE_NAME) {
                 Some(auth_cookie) => {
                     match decode_auth_token(app_state, auth_cookie.value()).await {
-                        Ok(auth) => AuthStatus::User(auth),
+                        Ok(auth) => AuthStatus::User(Box::new(auth)),
                         Err(..) => AuthStatus::Invalid,
                     }
                 }
@@ -90,7 +115,9 @@ pub async fn require_logout_middleware<KIND: Redirection>(
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => next.run(request).await,
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
+            next.run(request).await
+        }
         AuthStatus::User(auth_data) if auth_data.user.is_suspended => {
             redirect_response::<KIND>(&bitsync_routes::GetSuspendedPage.to_string())
         }
@@ -106,7 +133,7 @@ pub async fn require_suspended_middleware<KIND: Redirection>(
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => {
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
             redirect_response::<KIND>(&bitsync_routes::GetLoginPage.to_string())
         }
         AuthStatus::User(auth_data) if auth_data.user.is_suspended => next.run(request).await,
@@ -122,12 +149,12 @@ pub async fn require_any_login_no_totp_required_ignore_suspension_middleware<KIN
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => {
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
             redirect_response::<KIND>(&bitsync_routes::GetLoginPage.to_string())
         }
         AuthStatus::User(auth_data) => {
             let extensions = request.extensions_mut();
-            extensions.insert(auth_data);
+            extensions.insert(*auth_data);
 
             next.run(request).await
         }
@@ -140,7 +167,7 @@ pub async fn require_login_and_totp_setup_middleware<KIND: Redirection>(
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => {
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
             redirect_response::<KIND>(&bitsync_routes::GetLoginPage.to_string())
         }
         AuthStatus::User(auth_data) if auth_data.user.is_suspended => {
@@ -159,7 +186,7 @@ pub async fn require_login_and_totp_setup_middleware<KIND: Redirection>(
             }
 
             let extensions = request.extensions_mut();
-            extensions.insert(auth_data);
+            extensions.insert(*auth_data);
 
             next.run(request).await
         }
@@ -172,7 +199,7 @@ pub async fn require_admin_login_and_totp_setup_middleware<KIND: Redirection>(
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => {
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
             redirect_response::<KIND>(&bitsync_routes::GetLoginPage.to_string())
         }
         AuthStatus::User(auth_data) if auth_data.user.is_suspended => {
@@ -194,7 +221,7 @@ pub async fn require_admin_login_and_totp_setup_middleware<KIND: Redirection>(
             }
 
             let extensions = request.extensions_mut();
-            extensions.insert(auth_data);
+            extensions.insert(*auth_data);
 
             next.run(request).await
         }
@@ -207,7 +234,7 @@ pub async fn require_basic_login_and_totp_setup_middleware<KIND: Redirection>(
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => {
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
             redirect_response::<KIND>(&bitsync_routes::GetLoginPage.to_string())
         }
         AuthStatus::User(auth_data) if auth_data.user.is_suspended => {
@@ -224,7 +251,7 @@ pub async fn require_basic_login_and_totp_setup_middleware<KIND: Redirection>(
             }
 
             let extensions = request.extensions_mut();
-            extensions.insert(auth_data);
+            extensions.insert(*auth_data);
 
             next.run(request).await
         }
@@ -237,7 +264,7 @@ pub async fn require_login_and_no_totp_setup_middleware<KIND: Redirection>(
     next: Next,
 ) -> Response {
     match auth_status {
-        AuthStatus::Missing | AuthStatus::Invalid => {
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::AppPassword(..) => {
             redirect_response::<KIND>(&bitsync_routes::GetLoginPage.to_string())
         }
         AuthStatus::User(auth_data) if auth_data.user.is_suspended => {
@@ -252,11 +279,85 @@ pub async fn require_login_and_no_totp_setup_middleware<KIND: Redirection>(
                 );
             }
 
+            let extensions = request.extensions_mut();
+            extensions.insert(*auth_data);
+
+            next.run(request).await
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct AppPasswordAuthData {
+    pub app_password: AppPassword,
+    pub user: User,
+}
+
+impl AppPasswordAuthData {
+    /// The admin scope is only honored while the owner is still an admin.
+    pub fn has_scope(&self, scope: AppPasswordScope) -> bool {
+        self.app_password.scope >= scope && (scope != AppPasswordScope::Admin || self.user.is_admin)
+    }
+}
+
+fn app_password_challenge_response() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(
+            header::WWW_AUTHENTICATE,
+            r#"Basic realm="BitSync", charset="UTF-8""#,
+        )],
+    )
+        .into_response()
+}
+
+/// Authenticates clients that cannot go through the browser login, using either a bearer token or
+/// HTTP basic auth with the username and one of the user's app passwords.
+pub async fn require_app_password_middleware(
+    State(app_state): State<Arc<AppState>>,
+    auth_status: AuthStatus,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let authentication = match auth_status {
+        AuthStatus::AppPassword(auth_data) => Ok(*auth_data),
+        _ => {
+            let Some(Authorization(credentials)) =
+                request.headers().typed_get::<Authorization<Basic>>()
+            else {
+                return app_password_challenge_response();
+            };
+
+            authenticate_app_password(
+                &app_state.database,
+                Some(credentials.username()),
+                credentials.password(),
+            )
+            .await
+            .map(|authentication| AppPasswordAuthData {
+                app_password: authentication.app_password,
+                user: authentication.user,
+            })
+        }
+    };
+
+    match authentication {
+        Ok(auth_data) if auth_data.user.is_suspended => StatusCode::FORBIDDEN.into_response(),
+        Ok(auth_data) => {
             let extensions = request.extensions_mut();
             extensions.insert(auth_data);
 
             next.run(request).await
         }
+        Err(AuthenticateAppPasswordError::InvalidCredentials(..)) => {
+            app_password_challenge_response()
+        }
+        Err(error) => {
+            emit_error(error);
+
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
     }
 }
 
@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+/// Locks expire after this long unless they are refreshed. Clients asking for longer or infinite
+/// timeouts get this one.
+pub(crate) const MAX_DAV_LOCK_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// How many locks a single user can hold at the same time.
+const MAX_DAV_LOCKS_PER_USER: usize = 1000;
+
+/// WebDAV write locks, keyed by their token. Locks only live in memory, a restart releases them.
+#[derive(Default)]
+pub(crate) struct DavLocks {
+    locks: Mutex<HashMap<Uuid, DavLock>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DavLock {
+    pub(crate) token: Uuid,
+    pub(crate) user_id: Uuid,
+    /// The scoped path of the locked item.
+    pub(crate) root_path: String,
+    pub(crate) depth: DavLockDepth,
+    pub(crate) scope: DavLockScope,
+    /// The owner as sent by the client, already rendered as XML.
+    pub(crate) owner: Option<String>,
+    expires_at: Instant,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DavLockDepth {
+    Zero,
+    Infinity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DavLockScope {
+    Exclusive,
+    Shared,
+}
+
+/// What a request does to an item, which decides the locks that have to be submitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DavChange {
+    /// Changes the content or properties of an existing item.
+    Content,
+    /// Adds the item to its parent directory or removes it along with everything below it.
+    Membership,
+}
+
+pub(crate) struct DavLockRequest<'a> {
+    pub(crate) user_id: Uuid,
+    pub(crate) root_path: &'a str,
+    pub(crate) depth: DavLockDepth,
+    pub(crate) scope: DavLockScope,
+    pub(crate) owner: Option<String>,
+    pub(crate) timeout: Duration,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the item is locked by a lock whose token was not submitted")]
+pub(crate) struct DavLockedError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to create a lock")]
+pub(crate) enum DavLockCreateError {
+    Locked(#[from] DavLockedError),
+    #[error("the user already holds the maximum number of locks")]
+    LimitReached,
+}
+
+fn is_path_at_or_below(path: &str, parent_path: &str) -> bool {
+    parent_path == "/"
+        || path == parent_path
+        || path
+            .strip_prefix(parent_path)
+            .is_some_and(|remainder| remainder.starts_with('/'))
+}
+
+fn parent_path(path: &str) -> Option<&str> {
+    match path.rsplit_once('/')? {
+        ("", "") => None,
+        ("", _) => Some("/"),
+        (parent_path, _) => Some(parent_path),
+    }
+}
+
+impl DavLock {
+    /// Whether the lock applies to the item at `path`, either directly or through a locked
+    /// directory above it.
+    pub(crate) fn covers(&self, path: &str) -> bool {
+        match self.depth {
+            DavLockDepth::Zero => self.root_path == path,
+            DavLockDepth::Infinity => is_path_at_or_below(path, &self.root_path),
+        }
+    }
+
+    fn protects(&self, path: &str, change: DavChange) -> bool {
+        match change {
+            DavChange::Content => self.covers(path),
+            DavChange::Membership => {
+                self.covers(path)
+                    || parent_path(path).is_some_and(|parent_path| self.covers(parent_path))
+                    || is_path_at_or_below(&self.root_path, path)
+            }
+        }
+    }
+
+    fn conflicts_with(&self, request: &DavLockRequest<'_>) -> bool {
+        let overlaps = self.covers(request.root_path)
+            || (request.depth == DavLockDepth::Infinity
+                && is_path_at_or_below(&self.root_path, request.root_path));
+
+        overlaps
+            && (self.scope == DavLockScope::Exclusive || request.scope == DavLockScope::Exclusive)
+    }
+
+    /// The seconds left until the lock expires.
+    pub(crate) fn remaining_seconds(&self) -> u64 {
+        self.expires_at
+            .saturating_duration_since(Instant::now())
+            .as_secs()
+    }
+}
+
+impl DavLocks {
+    fn active_locks(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, DavLock>> {
+        let mut locks = self.locks.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+
+        locks.retain(|_, lock| lock.expires_at > now);
+
+        locks
+    }
+
+    pub(crate) fn lock(&self, request: DavLockRequest<'_>) -> Result<DavLock, DavLockCreateError> {
+        let mut locks = self.active_locks();
+        let user_locks = locks
+            .values()
+            .filter(|lock| lock.user_id == request.user_id);
+
+        if user_locks.clone().any(|lock| lock.conflicts_with(&request)) {
+            return Err(DavLockedError.into());
+        }
+
+        if user_locks.count() >= MAX_DAV_LOCKS_PER_USER {
+            return Err(DavLockCreateError::LimitReached);
+        }
+
+        let lock = DavLock {
+            token: Uuid::new_v4(),
+            user_id: request.user_id,
+            root_path: request.root_path.to_owned(),
+            depth: request.depth,
+            scope: request.scope,
+            owner: request.owner,
+            expires_at: Instant::now() + request.timeout,
+        };
+
+        locks.insert(lock.token, lock.clone());
+
+        Ok(lock)
+    }
+
+    /// Restarts the timeout of the first submitted lock that applies to `path`.
+    pub(crate) fn refresh(
+        &self,
+        user_id: Uuid,
+        path: &str,
+        lock_tokens: &[Uuid],
+        timeout: Duration,
+    ) -> Option<DavLock> {
+        let mut locks = self.active_locks();
+        let lock = lock_tokens
+            .iter()
+            .filter_map(|lock_token| locks.get(lock_token))
+            .find(|lock| lock.user_id == user_id && lock.covers(path))?
+            .token;
+        let lock = locks.get_mut(&lock)?;
+
+        lock.expires_at = Instant::now() + timeout;
+
+        Some(lock.clone())
+    }
+
+    /// Releases the lock with `lock_token` if it applies to `path`.
+    pub(crate) fn unlock(&self, user_id: Uuid, path: &str, lock_token: &Uuid) -> bool {
+        let mut locks = self.active_locks();
+
+        if !locks
+            .get(lock_token)
+            .is_some_and(|lock| lock.user_id == user_id && lock.covers(path))
+        {
+            return false;
+        }
+
+        locks.remove(lock_token).is_some()
+    }
+
+    /// Releases all locks on `path` and the items below it, once these items are gone.
+    pub(crate) fn unlock_all_below(&self, user_id: Uuid, path: &str) {
+        self.active_locks().retain(|_, lock| {
+            lock.user_id != user_id || !is_path_at_or_below(&lock.root_path, path)
+        });
+    }
+
+    pub(crate) fn user_locks(&self, user_id: Uuid) -> Vec<DavLock> {
+        self.active_locks()
+            .values()
+            .filter(|lock| lock.user_id == user_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Whether at least one of `lock_tokens` belongs to an active lock of the user.
+    pub(crate) fn is_any_active(&self, user_id: Uuid, lock_tokens: &[Uuid]) -> bool {
+        let locks = self.active_locks();
+
+        lock_tokens.iter().any(|lock_token| {
+            locks
+                .get(lock_token)
+                .is_some_and(|lock| lock.user_id == user_id)
+        })
+    }
+
+    /// Fails if a lock protects `path` against `change` and its token is not in `lock_tokens`.
+    pub(crate) fn ensure_unlocked(
+        &self,
+        user_id: Uuid,
+        path: &str,
+        change: DavChange,
+        lock_tokens: &[Uuid],
+    ) -> Result<(), DavLockedError> {
+        let is_locked = self.active_locks().values().any(|lock| {
+            lock.user_id == user_id
+                && lock.protects(path, change)
+                && !lock_tokens.contains(&lock.token)
+        });
+
+        match is_locked {
+            true => Err(DavLockedError),
+            false => Ok(()),
+        }
+    }
+}
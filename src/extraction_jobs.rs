@@ -0,0 +1,131 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use bitsync_core::use_case::user_files::extract_user_file::{
+    ExtractionProgress, UserFileExtractError, UserFileExtractResult,
+};
+use tokio::{
+    sync::watch,
+    task::{JoinError, JoinHandle},
+};
+use uuid::Uuid;
+
+/// Results of finished extractions that were never polled are dropped after this long.
+const FINISHED_EXTRACTION_JOB_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Extractions still running after this long are aborted and dropped.
+const MAX_EXTRACTION_JOB_DURATION: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// How many extractions a single user can run at the same time.
+const MAX_RUNNING_EXTRACTION_JOBS_PER_USER: usize = 1;
+
+/// Archive extractions running in the background, polled by the user who started them.
+#[derive(Default)]
+pub(crate) struct ExtractionJobs {
+    jobs: Mutex<HashMap<Uuid, ExtractionJob>>,
+}
+
+struct ExtractionJob {
+    user_id: Uuid,
+    started_at: Instant,
+    progress: watch::Receiver<ExtractionProgress>,
+    task: JoinHandle<Result<UserFileExtractResult, UserFileExtractError>>,
+}
+
+pub(crate) enum ExtractionJobStatus {
+    Running(ExtractionProgress),
+    Finished(Result<UserFileExtractResult, ExtractionJobError>),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("the user already has the maximum number of running extractions")]
+pub(crate) struct ExtractionJobLimitError;
+
+#[derive(thiserror::Error, Debug)]
+#[error("an extraction job failed")]
+pub(crate) enum ExtractionJobError {
+    Extract(#[from] UserFileExtractError),
+    Join(#[from] JoinError),
+}
+
+impl ExtractionJobs {
+    /// Spawns the future returned by `extraction`, which reports its progress through the given
+    /// sender, and returns the id of the job.
+    pub(crate) fn start<F, Fut>(
+        &self,
+        user_id: Uuid,
+        extraction: F,
+    ) -> Result<Uuid, ExtractionJobLimitError>
+    where
+        F: FnOnce(watch::Sender<ExtractionProgress>) -> Fut,
+        Fut: Future<Output = Result<UserFileExtractResult, UserFileExtractError>> + Send + 'static,
+    {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+
+        jobs.retain(|_, job| {
+            let retention = match job.task.is_finished() {
+                true => FINISHED_EXTRACTION_JOB_RETENTION,
+                false => MAX_EXTRACTION_JOB_DURATION,
+            };
+            let is_retained = job.started_at.elapsed() < retention;
+
+            // dropping the handle would only detach the task
+            if !is_retained {
+                job.task.abort();
+            }
+
+            is_retained
+        });
+
+        let running_job_count = jobs
+            .values()
+            .filter(|job| job.user_id == user_id && !job.task.is_finished())
+            .count();
+
+        if running_job_count >= MAX_RUNNING_EXTRACTION_JOBS_PER_USER {
+            return Err(ExtractionJobLimitError);
+        }
+
+        let (progress_sender, progress_receiver) = watch::channel(ExtractionProgress::default());
+        let job_id = Uuid::new_v4();
+        let job = ExtractionJob {
+            user_id,
+            started_at: Instant::now(),
+            progress: progress_receiver,
+            task: tokio::spawn(extraction(progress_sender)),
+        };
+
+        jobs.insert(job_id, job);
+
+        Ok(job_id)
+    }
+
+    /// The status of a job started by `user_id`. A finished job is removed once its result has
+    /// been returned.
+    pub(crate) async fn status(
+        &self,
+        job_id: &Uuid,
+        user_id: &Uuid,
+    ) -> Option<ExtractionJobStatus> {
+        let finished_job = {
+            let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            let job = jobs.get(job_id).filter(|job| job.user_id == *user_id)?;
+
+            if !job.task.is_finished() {
+                return Some(ExtractionJobStatus::Running(*job.progress.borrow()));
+            }
+
+            jobs.remove(job_id)?
+        };
+
+        let result = match finished_job.task.await {
+            Ok(result) => result.map_err(ExtractionJobError::from),
+            Err(error) => Err(error.into()),
+        };
+
+        Some(ExtractionJobStatus::Finished(result))
+    }
+}
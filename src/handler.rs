@@ -4,26 +4,37 @@ use crate::AppState;
 use axum::{
     Json, Router,
     extract::DefaultBodyLimit,
-    http::StatusCode,
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use base64::Engine;
 use bitsync_frontend::{
     Render,
     toast::{TOAST_CONTAINER_SELECTOR, Toast},
 };
 use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
 use headers::Header;
-use tower_http::trace::TraceLayer;
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
+mod api;
+mod file_download;
 mod frontend;
 mod static_assets;
+mod webdav;
 
 pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
-    Router::new()
+    let cors_routes = Router::new()
         .merge(static_assets::create_routes().await)
         .merge(frontend::create_routes(state.clone()).await)
-        .layer(DefaultBodyLimit::disable())
+        .merge(api::create_routes(state.clone()).await)
         .fallback(handler_404)
+        .layer(CorsLayer::permissive());
+
+    // WebDAV clients send `OPTIONS` requests that must not be answered as CORS preflights
+    Router::new()
+        .merge(webdav::create_routes(state).await)
+        .merge(cors_routes)
+        .layer(DefaultBodyLimit::disable())
         .layer(TraceLayer::new_for_http())
 }
 
@@ -104,3 +115,26 @@ pub fn user_error_toast_response(message: impl Into<String>) -> Response {
     )
         .into_response()
 }
+
+/// Builds `ETag` and `Digest` headers from a hex encoded SHA-256 checksum.
+pub fn checksum_headers(checksum: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    let Some(checksum) = checksum else {
+        return headers;
+    };
+
+    if let Ok(etag) = HeaderValue::from_str(&format!("\"{checksum}\"")) {
+        headers.insert(header::ETAG, etag);
+    }
+
+    if let Ok(digest) = hex::decode(checksum) {
+        let digest = base64::engine::general_purpose::STANDARD.encode(digest);
+
+        if let Ok(digest) = HeaderValue::from_str(&format!("sha-256={digest}")) {
+            headers.insert(HeaderName::from_static("digest"), digest);
+        }
+    }
+
+    headers
+}
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::Router;
+
+use crate::AppState;
+
+mod v1;
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new().merge(v1::create_routes(state).await)
+}
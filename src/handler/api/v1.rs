@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::Request,
+    http::StatusCode,
+    middleware::{Next, from_fn_with_state},
+    response::{IntoResponse, Response},
+};
+use axum_extra::routing::RouterExt;
+use bitsync_database::entity::AppPasswordScope;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+};
+
+use crate::{AppState, auth::AuthStatus};
+
+use self::error::ApiError;
+
+mod error;
+mod files;
+mod shares;
+mod users;
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .merge(
+            Router::new()
+                .typed_get(files::list_directory_handler)
+                .typed_get(files::stat_handler)
+                .typed_get(files::download_handler)
+                .typed_get(files::search_handler)
+                .typed_get(shares::list_shares_handler)
+                .typed_get(shares::list_shared_paths_handler)
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_api_token_middleware::<ReadScope>,
+                )),
+        )
+        .merge(
+            Router::new()
+                .typed_put(files::upload_handler)
+                .typed_post(files::move_handler)
+                .typed_post(files::copy_handler)
+                .typed_delete(files::delete_handler)
+                .typed_post(files::create_directory_handler)
+                .typed_post(shares::create_share_handler)
+                .typed_delete(shares::delete_share_handler)
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_api_token_middleware::<WriteScope>,
+                )),
+        )
+        .merge(
+            Router::new()
+                .typed_get(users::list_users_handler)
+                .typed_post(users::suspend_user_handler)
+                .typed_post(users::unsuspend_user_handler)
+                .typed_put(users::set_storage_quota_handler)
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_api_token_middleware::<AdminScope>,
+                )),
+        )
+        .typed_get(openapi_document_handler)
+        .with_state(state)
+}
+
+pub trait RequiredScope {
+    fn required_scope() -> AppPasswordScope;
+}
+
+struct ReadScope;
+
+impl RequiredScope for ReadScope {
+    fn required_scope() -> AppPasswordScope {
+        AppPasswordScope::Read
+    }
+}
+
+struct WriteScope;
+
+impl RequiredScope for WriteScope {
+    fn required_scope() -> AppPasswordScope {
+        AppPasswordScope::Write
+    }
+}
+
+struct AdminScope;
+
+impl RequiredScope for AdminScope {
+    fn required_scope() -> AppPasswordScope {
+        AppPasswordScope::Admin
+    }
+}
+
+async fn require_api_token_middleware<SCOPE: RequiredScope>(
+    auth_status: AuthStatus,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match auth_status {
+        AuthStatus::AppPassword(auth_data) if auth_data.user.is_suspended => ApiError::new(
+            StatusCode::FORBIDDEN,
+            "user_suspended",
+            "the account is suspended",
+        )
+        .into_response(),
+        AuthStatus::AppPassword(auth_data) if !auth_data.has_scope(SCOPE::required_scope()) => {
+            ApiError::new(
+                StatusCode::FORBIDDEN,
+                "insufficient_scope",
+                "the app password does not grant access to this operation",
+            )
+            .into_response()
+        }
+        AuthStatus::AppPassword(auth_data) => {
+            let extensions = request.extensions_mut();
+            extensions.insert(*auth_data);
+
+            next.run(request).await
+        }
+        AuthStatus::Missing | AuthStatus::Invalid | AuthStatus::User(..) => {
+            ApiError::unauthorized().into_response()
+        }
+    }
+}
+
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+        }
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "BitSync API",
+        version = "1",
+        description = "Authenticate with an app password sent as bearer token. Read only app passwords cannot use operations that change files or shares. User management requires an app password with the admin scope whose owner is still an admin.",
+    ),
+    paths(
+        files::list_directory_handler,
+        files::stat_handler,
+        files::download_handler,
+        files::upload_handler,
+        files::move_handler,
+        files::copy_handler,
+        files::delete_handler,
+        files::create_directory_handler,
+        files::search_handler,
+        shares::list_shares_handler,
+        shares::create_share_handler,
+        shares::delete_share_handler,
+        shares::list_shared_paths_handler,
+        users::list_users_handler,
+        users::suspend_user_handler,
+        users::unsuspend_user_handler,
+        users::set_storage_quota_handler,
+    ),
+    modifiers(&BearerSecurity),
+    tags(
+        (name = "files", description = "Files and directories in the user's storage"),
+        (name = "shares", description = "Shares of files and directories"),
+        (name = "users", description = "Management of the other users, for admins"),
+    ),
+)]
+struct ApiDocument;
+
+async fn openapi_document_handler(_: bitsync_routes::GetApiV1OpenApiDocument) -> impl IntoResponse {
+    Json(ApiDocument::openapi())
+}
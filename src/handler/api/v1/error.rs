@@ -0,0 +1,301 @@
+use axum::{
+    Json,
+    extract::rejection::{JsonRejection, QueryRejection},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use bitsync_core::use_case::{
+    file_version::shared::WriteVersionedFileStreamError,
+    user::{
+        list_users::ListUsersError, set_user_storage_quota::SetUserStorageQuotaError,
+        suspend_user::SuspendUserError, unsuspend_user::UnsuspendUserError,
+    },
+    user_files::{
+        copy_user_file::UserFileCopyError, create_directory::UserFileDirecoryCreationError,
+        delete_user_file::UserFileDeletionError, download_user_file::UserFileDownloadError,
+        list_user_directory::ListUserDirectoryError, move_user_file::UserFileMoveError,
+        search_user_files::SearchUserFilesError, shared::ResolveDestinationConflictError,
+        stat_user_file::StatUserFileError, upload_user_file::UserFileUploadError,
+    },
+    user_share::{
+        create_user_share::CreateUserShareError, delete_user_share::DeleteUserShareError,
+        list_shared_paths::ListSharedPathsError, list_user_shares::ListUserSharesError,
+    },
+};
+use bitsync_storage::operation::write::WriteFileStreamError;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::error_report::emit_error;
+
+/// The body of every unsuccessful API response.
+#[derive(Serialize, ToSchema)]
+pub struct ApiErrorBody {
+    /// A stable, machine-readable identifier of the error.
+    #[schema(example = "destination_exists")]
+    pub code: &'static str,
+    /// A human-readable description of the error.
+    pub message: String,
+}
+
+pub struct ApiError {
+    status: StatusCode,
+    body: ApiErrorBody,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl ToString) -> Self {
+        Self {
+            status,
+            body: ApiErrorBody {
+                code,
+                message: message.to_string(),
+            },
+        }
+    }
+
+    /// Reports `error` and hides its details from the client.
+    pub fn internal(error: impl std::error::Error + 'static) -> Self {
+        emit_error(error);
+
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_server_error",
+            "an internal server error occurred",
+        )
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            "unauthorized",
+            "a valid app password is required as bearer token",
+        )
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            "no item exists at the given path",
+        )
+    }
+
+    fn invalid_path(error: impl std::error::Error) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_path", error)
+    }
+
+    fn storage_quota_exceeded(error: impl std::error::Error) -> Self {
+        Self::new(
+            StatusCode::INSUFFICIENT_STORAGE,
+            "storage_quota_exceeded",
+            error,
+        )
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status == StatusCode::UNAUTHORIZED {
+            return (
+                self.status,
+                [(header::WWW_AUTHENTICATE, r#"Bearer realm="BitSync""#)],
+                Json(self.body),
+            )
+                .into_response();
+        }
+
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(value: JsonRejection) -> Self {
+        Self::new(value.status(), "invalid_body", value.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(value: QueryRejection) -> Self {
+        Self::new(value.status(), "invalid_query", value.body_text())
+    }
+}
+
+impl From<StatUserFileError> for ApiError {
+    fn from(value: StatUserFileError) -> Self {
+        match value {
+            StatUserFileError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<ListUserDirectoryError> for ApiError {
+    fn from(value: ListUserDirectoryError) -> Self {
+        match value {
+            ListUserDirectoryError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<UserFileDownloadError> for ApiError {
+    fn from(value: UserFileDownloadError) -> Self {
+        match value {
+            UserFileDownloadError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<UserFileUploadError> for ApiError {
+    fn from(value: UserFileUploadError) -> Self {
+        match value {
+            UserFileUploadError::StoragePath(error) => Self::invalid_path(error),
+            UserFileUploadError::WriteVersionedFileStream(
+                WriteVersionedFileStreamError::WriteFileStream(
+                    WriteFileStreamError::SizeLimitExceeded(error),
+                ),
+            ) => Self::storage_quota_exceeded(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<UserFileMoveError> for ApiError {
+    fn from(value: UserFileMoveError) -> Self {
+        match value {
+            UserFileMoveError::StoragePath(error) => Self::invalid_path(error),
+            UserFileMoveError::DestinationSameAsSource(error) => {
+                Self::new(StatusCode::CONFLICT, "destination_same_as_source", error)
+            }
+            UserFileMoveError::DestinationInsideSource(error) => {
+                Self::new(StatusCode::CONFLICT, "destination_inside_source", error)
+            }
+            UserFileMoveError::SourceInsideDestination(error) => {
+                Self::new(StatusCode::CONFLICT, "source_inside_destination", error)
+            }
+            UserFileMoveError::ResolveDestinationConflict(
+                ResolveDestinationConflictError::DestinationExists(error),
+            ) => Self::new(StatusCode::CONFLICT, "destination_exists", error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<UserFileCopyError> for ApiError {
+    fn from(value: UserFileCopyError) -> Self {
+        match value {
+            UserFileCopyError::StoragePath(error) => Self::invalid_path(error),
+            UserFileCopyError::DestinationSameAsSource(error) => {
+                Self::new(StatusCode::CONFLICT, "destination_same_as_source", error)
+            }
+            UserFileCopyError::DestinationInsideSource(error) => {
+                Self::new(StatusCode::CONFLICT, "destination_inside_source", error)
+            }
+            UserFileCopyError::SourceInsideDestination(error) => {
+                Self::new(StatusCode::CONFLICT, "source_inside_destination", error)
+            }
+            UserFileCopyError::DestinationExists(error) => {
+                Self::new(StatusCode::CONFLICT, "destination_exists", error)
+            }
+            UserFileCopyError::StorageQuotaExceeded(error) => Self::storage_quota_exceeded(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<UserFileDeletionError> for ApiError {
+    fn from(value: UserFileDeletionError) -> Self {
+        match value {
+            UserFileDeletionError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<UserFileDirecoryCreationError> for ApiError {
+    fn from(value: UserFileDirecoryCreationError) -> Self {
+        match value {
+            UserFileDirecoryCreationError::StoragePath(error) => Self::invalid_path(error),
+            UserFileDirecoryCreationError::DirectoryNameContainsSeparator(error) => {
+                Self::new(StatusCode::BAD_REQUEST, "invalid_directory_name", error)
+            }
+            UserFileDirecoryCreationError::EmptyPath(error) => {
+                Self::new(StatusCode::BAD_REQUEST, "empty_path", error)
+            }
+            UserFileDirecoryCreationError::ResolveDestinationConflict(
+                ResolveDestinationConflictError::DestinationExists(error),
+            ) => Self::new(StatusCode::CONFLICT, "destination_exists", error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<SearchUserFilesError> for ApiError {
+    fn from(value: SearchUserFilesError) -> Self {
+        match value {
+            SearchUserFilesError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<ListUserSharesError> for ApiError {
+    fn from(value: ListUserSharesError) -> Self {
+        Self::internal(value)
+    }
+}
+
+impl From<CreateUserShareError> for ApiError {
+    fn from(value: CreateUserShareError) -> Self {
+        match value {
+            CreateUserShareError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<DeleteUserShareError> for ApiError {
+    fn from(value: DeleteUserShareError) -> Self {
+        Self::internal(value)
+    }
+}
+
+impl From<ListSharedPathsError> for ApiError {
+    fn from(value: ListSharedPathsError) -> Self {
+        match value {
+            ListSharedPathsError::StoragePath(error) => Self::invalid_path(error),
+            error => Self::internal(error),
+        }
+    }
+}
+
+impl From<ListUsersError> for ApiError {
+    fn from(value: ListUsersError) -> Self {
+        Self::internal(value)
+    }
+}
+
+impl From<SuspendUserError> for ApiError {
+    fn from(value: SuspendUserError) -> Self {
+        Self::internal(value)
+    }
+}
+
+impl From<UnsuspendUserError> for ApiError {
+    fn from(value: UnsuspendUserError) -> Self {
+        Self::internal(value)
+    }
+}
+
+impl From<SetUserStorageQuotaError> for ApiError {
+    fn from(value: SetUserStorageQuotaError) -> Self {
+        match value {
+            SetUserStorageQuotaError::StorageQuotaTooLarge(error) => {
+                Self::new(StatusCode::BAD_REQUEST, "storage_quota_too_large", error)
+            }
+            error => Self::internal(error),
+        }
+    }
+}
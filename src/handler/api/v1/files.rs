@@ -0,0 +1,630 @@
+use std::{path::Path, sync::Arc};
+
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::{
+        Query, State,
+        rejection::{JsonRejection, QueryRejection},
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use bitsync_core::use_case::user_files::{
+    copy_user_file::{CopyConflictPolicy, copy_user_file},
+    create_directory::create_direcory,
+    delete_user_file::delete_user_file,
+    download_user_file::{DirectoryArchiveFormat, download_user_file},
+    list_user_directory::list_user_directory,
+    move_user_file::move_user_file,
+    search_user_files::{SearchResult, SearchUserFilesResult, search_user_files},
+    shared::{ConflictPolicy, UploadPolicy},
+    stat_user_file::find_user_file,
+    upload_user_file::{UserFileUpload, upload_user_file},
+};
+use bitsync_database::entity::User;
+use bitsync_routes::TypedPath;
+use bitsync_storage::storage_item::{StorageItem, StorageItemKind};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use crate::{
+    AppState,
+    auth::AppPasswordAuthData,
+    handler::file_download::{FileDisposition, file_download_response},
+};
+
+use super::error::{ApiError, ApiErrorBody};
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    File,
+    Directory,
+}
+
+impl From<StorageItemKind> for FileKind {
+    fn from(value: StorageItemKind) -> Self {
+        match value {
+            StorageItemKind::File => Self::File,
+            StorageItemKind::Directory => Self::Directory,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FileEntry {
+    /// The absolute path of the item within the user's storage.
+    #[schema(example = "/documents/report.pdf")]
+    pub path: String,
+    pub name: String,
+    pub kind: FileKind,
+    /// The size in bytes. Only set for files.
+    pub size: Option<u64>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub modified_at: Option<OffsetDateTime>,
+}
+
+impl From<StorageItem> for FileEntry {
+    fn from(value: StorageItem) -> Self {
+        let size = match value.kind {
+            StorageItemKind::File => Some(value.size),
+            StorageItemKind::Directory => None,
+        };
+
+        Self {
+            // items read from a directory carry paths relative to the storage root
+            path: Path::new("/")
+                .join(&value.path.scoped_path)
+                .to_string_lossy()
+                .to_string(),
+            name: value.path.file_name(),
+            kind: FileKind::from(value.kind),
+            size,
+            modified_at: value.modified_at.map(OffsetDateTime::from),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DirectoryListing {
+    pub directory: FileEntry,
+    pub items: Vec<FileEntry>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct FileTransferRequest {
+    #[schema(example = "/documents/report.pdf")]
+    pub source_path: String,
+    #[schema(example = "/archive/report.pdf")]
+    pub destination_path: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateDirectoryRequest {
+    #[schema(example = "/documents/invoices")]
+    pub path: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchHit {
+    pub item: FileEntry,
+    pub parent_path: String,
+    pub score: u32,
+}
+
+impl From<SearchResult> for SearchHit {
+    fn from(value: SearchResult) -> Self {
+        Self {
+            item: FileEntry::from(value.storage_item),
+            parent_path: value.parent_path,
+            score: value.score,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResults {
+    /// Matches inside the directory given as `path`.
+    pub current_directory: Vec<SearchHit>,
+    /// Matches anywhere in the user's storage.
+    pub global: Vec<SearchHit>,
+}
+
+/// Splits a path into its parent directory and item name. The root has neither.
+fn split_path(path: &str) -> Option<(String, String)> {
+    let path = Path::new(path);
+
+    let parent = path.parent()?.to_string_lossy().to_string();
+    let name = path.file_name()?.to_string_lossy().to_string();
+
+    Some((parent, name))
+}
+
+fn root_path_error() -> ApiError {
+    ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "root_path",
+        "the operation is not possible on the root directory",
+    )
+}
+
+fn parent_not_found_error() -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        "parent_not_found",
+        "the parent directory does not exist",
+    )
+}
+
+async fn find_existing_item(
+    app_state: &AppState,
+    user: &User,
+    path: &str,
+) -> Result<StorageItem, ApiError> {
+    find_user_file(&app_state.storage_root, path, user)
+        .await?
+        .ok_or_else(ApiError::not_found)
+}
+
+async fn ensure_parent_directory_exists(
+    app_state: &AppState,
+    user: &User,
+    parent_path: &str,
+) -> Result<(), ApiError> {
+    match find_user_file(&app_state.storage_root, parent_path, user).await? {
+        Some(storage_item) if storage_item.kind == StorageItemKind::Directory => Ok(()),
+        _ => Err(parent_not_found_error()),
+    }
+}
+
+async fn ensure_destination_is_free(
+    app_state: &AppState,
+    user: &User,
+    destination_path: &str,
+) -> Result<(), ApiError> {
+    let Some((parent_path, _)) = split_path(destination_path) else {
+        return Err(root_path_error());
+    };
+
+    if find_user_file(&app_state.storage_root, destination_path, user)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "destination_exists",
+            "an item already exists at the destination path",
+        ));
+    }
+
+    ensure_parent_directory_exists(app_state, user, &parent_path).await
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1Files::PATH,
+    tag = "files",
+    params(("path" = Option<String>, Query, description = "The directory to list, defaults to the root")),
+    responses(
+        (status = OK, body = DirectoryListing),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["read"])),
+)]
+pub(super) async fn list_directory_handler(
+    _: bitsync_routes::GetApiV1Files,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<Query<bitsync_routes::GetApiV1FilesQueryParameters>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    let directory = find_existing_item(&app_state, &auth_data.user, &query_parameters.path).await?;
+
+    if directory.kind != StorageItemKind::Directory {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "not_a_directory",
+            "the item at the given path is not a directory",
+        ));
+    }
+
+    let items = list_user_directory(
+        &app_state.storage_root,
+        &query_parameters.path,
+        &auth_data.user,
+    )
+    .await?;
+
+    Ok(Json(DirectoryListing {
+        directory: FileEntry::from(directory),
+        items: items.into_iter().map(FileEntry::from).collect(),
+    })
+    .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1FileStat::PATH,
+    tag = "files",
+    params(("path" = String, Query, description = "The path of the file or directory")),
+    responses(
+        (status = OK, body = FileEntry),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["read"])),
+)]
+pub(super) async fn stat_handler(
+    _: bitsync_routes::GetApiV1FileStat,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<
+        Query<bitsync_routes::GetApiV1FileStatQueryParameters>,
+        QueryRejection,
+    >,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    let storage_item =
+        find_existing_item(&app_state, &auth_data.user, &query_parameters.path).await?;
+
+    Ok(Json(FileEntry::from(storage_item)).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1FileContent::PATH,
+    tag = "files",
+    description = "Downloads a file. Directories are downloaded as zip archive. Files support `Range` and conditional requests.",
+    params(("path" = String, Query, description = "The path of the file or directory")),
+    responses(
+        (status = OK, content_type = "application/octet-stream", description = "The file contents"),
+        (status = PARTIAL_CONTENT, description = "The requested ranges of the file"),
+        (status = NOT_MODIFIED, description = "The file matches the conditional request headers"),
+        (status = RANGE_NOT_SATISFIABLE, description = "None of the requested ranges is within the file"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["read"])),
+)]
+pub(super) async fn download_handler(
+    _: bitsync_routes::GetApiV1FileContent,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<
+        Query<bitsync_routes::GetApiV1FileContentQueryParameters>,
+        QueryRejection,
+    >,
+    request_headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    find_existing_item(&app_state, &auth_data.user, &query_parameters.path).await?;
+
+    let result = download_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &query_parameters.path,
+        DirectoryArchiveFormat::default(),
+        &auth_data.user,
+    )
+    .await?;
+
+    file_download_response(&request_headers, result, FileDisposition::Attachment)
+        .await
+        .map_err(ApiError::internal)
+}
+
+#[utoipa::path(
+    put,
+    path = bitsync_routes::PutApiV1FileContent::PATH,
+    tag = "files",
+    description = "Uploads a file. An existing file is replaced and its previous contents are kept as version.",
+    params(("path" = String, Query, description = "The path of the file to write")),
+    request_body(content_type = "application/octet-stream", description = "The file contents"),
+    responses(
+        (status = CREATED, body = FileEntry, description = "The file was created"),
+        (status = OK, body = FileEntry, description = "An existing file was replaced"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = CONFLICT, body = ApiErrorBody),
+        (status = INSUFFICIENT_STORAGE, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn upload_handler(
+    _: bitsync_routes::PutApiV1FileContent,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<
+        Query<bitsync_routes::PutApiV1FileContentQueryParameters>,
+        QueryRejection,
+    >,
+    body: Body,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+    let user = &auth_data.user;
+
+    let Some((parent_path, file_name)) = split_path(&query_parameters.path) else {
+        return Err(root_path_error());
+    };
+
+    let existing_storage_item =
+        find_user_file(&app_state.storage_root, &query_parameters.path, user).await?;
+
+    if existing_storage_item
+        .as_ref()
+        .is_some_and(|storage_item| storage_item.kind == StorageItemKind::Directory)
+    {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "is_a_directory",
+            "a directory exists at the given path",
+        ));
+    }
+
+    ensure_parent_directory_exists(&app_state, user, &parent_path).await?;
+
+    upload_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &UploadPolicy::from(&app_state.config),
+        &parent_path,
+        user,
+        UserFileUpload {
+            relative_path: &file_name,
+            stream: body.into_data_stream(),
+            conflict_policy: ConflictPolicy::Overwrite,
+        },
+    )
+    .await?;
+
+    let storage_item = find_existing_item(&app_state, user, &query_parameters.path).await?;
+    let status_code = match existing_storage_item {
+        Some(..) => StatusCode::OK,
+        None => StatusCode::CREATED,
+    };
+
+    Ok((status_code, Json(FileEntry::from(storage_item))).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = bitsync_routes::PostApiV1FileMove::PATH,
+    tag = "files",
+    request_body = FileTransferRequest,
+    responses(
+        (status = OK, body = FileEntry, description = "The moved item at its new path"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+        (status = CONFLICT, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn move_handler(
+    _: bitsync_routes::PostApiV1FileMove,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    request: Result<Json<FileTransferRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(request) = request?;
+    let user = &auth_data.user;
+
+    if split_path(&request.source_path).is_none() {
+        return Err(root_path_error());
+    }
+
+    find_existing_item(&app_state, user, &request.source_path).await?;
+
+    if Path::new(&request.destination_path).starts_with(&request.source_path)
+        && request.destination_path != request.source_path
+    {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "destination_inside_source",
+            "a directory cannot be moved into itself",
+        ));
+    }
+
+    if request.destination_path != request.source_path {
+        ensure_destination_is_free(&app_state, user, &request.destination_path).await?;
+    }
+
+    move_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &request.source_path,
+        &request.destination_path,
+        ConflictPolicy::Fail,
+        user,
+    )
+    .await?;
+
+    let storage_item = find_existing_item(&app_state, user, &request.destination_path).await?;
+
+    Ok(Json(FileEntry::from(storage_item)).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = bitsync_routes::PostApiV1FileCopy::PATH,
+    tag = "files",
+    request_body = FileTransferRequest,
+    responses(
+        (status = CREATED, body = FileEntry, description = "The created copy"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+        (status = CONFLICT, body = ApiErrorBody),
+        (status = INSUFFICIENT_STORAGE, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn copy_handler(
+    _: bitsync_routes::PostApiV1FileCopy,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    request: Result<Json<FileTransferRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(request) = request?;
+    let user = &auth_data.user;
+
+    if split_path(&request.source_path).is_none() {
+        return Err(root_path_error());
+    }
+
+    find_existing_item(&app_state, user, &request.source_path).await?;
+
+    if let Some((parent_path, _)) = split_path(&request.destination_path) {
+        ensure_parent_directory_exists(&app_state, user, &parent_path).await?;
+    }
+
+    copy_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        app_state.config.default_user_storage_quota,
+        &request.source_path,
+        &request.destination_path,
+        CopyConflictPolicy::Fail,
+        user,
+    )
+    .await?;
+
+    let storage_item = find_existing_item(&app_state, user, &request.destination_path).await?;
+
+    Ok((StatusCode::CREATED, Json(FileEntry::from(storage_item))).into_response())
+}
+
+#[utoipa::path(
+    delete,
+    path = bitsync_routes::DeleteApiV1Files::PATH,
+    tag = "files",
+    description = "Moves a file or directory to the trash.",
+    params(("path" = String, Query, description = "The path of the file or directory")),
+    responses(
+        (status = NO_CONTENT, description = "The item was moved to the trash"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn delete_handler(
+    _: bitsync_routes::DeleteApiV1Files,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<
+        Query<bitsync_routes::DeleteApiV1FilesQueryParameters>,
+        QueryRejection,
+    >,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    if split_path(&query_parameters.path).is_none() {
+        return Err(root_path_error());
+    }
+
+    find_existing_item(&app_state, &auth_data.user, &query_parameters.path).await?;
+
+    delete_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &query_parameters.path,
+        &auth_data.user,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = bitsync_routes::PostApiV1Directories::PATH,
+    tag = "files",
+    request_body = CreateDirectoryRequest,
+    responses(
+        (status = CREATED, body = FileEntry, description = "The created directory"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = CONFLICT, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn create_directory_handler(
+    _: bitsync_routes::PostApiV1Directories,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    request: Result<Json<CreateDirectoryRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(request) = request?;
+    let user = &auth_data.user;
+
+    let Some((parent_path, directory_name)) = split_path(&request.path) else {
+        return Err(root_path_error());
+    };
+
+    ensure_destination_is_free(&app_state, user, &request.path).await?;
+
+    create_direcory(
+        &app_state.database,
+        &app_state.storage_root,
+        &parent_path,
+        &directory_name,
+        ConflictPolicy::Fail,
+        user,
+    )
+    .await?;
+
+    let storage_item = find_existing_item(&app_state, user, &request.path).await?;
+
+    Ok((StatusCode::CREATED, Json(FileEntry::from(storage_item))).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1Search::PATH,
+    tag = "files",
+    params(
+        ("query" = String, Query, description = "The fuzzy search query"),
+        ("path" = Option<String>, Query, description = "The directory whose matches are listed separately"),
+    ),
+    responses(
+        (status = OK, body = SearchResults),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["read"])),
+)]
+pub(super) async fn search_handler(
+    _: bitsync_routes::GetApiV1Search,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<Query<bitsync_routes::GetApiV1SearchQueryParameters>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    let search_results = match search_user_files(
+        &app_state.storage_root,
+        &query_parameters.query,
+        &auth_data.user,
+        query_parameters.path.as_deref(),
+    )
+    .await?
+    {
+        SearchUserFilesResult::NoSearch => SearchResults {
+            current_directory: Vec::new(),
+            global: Vec::new(),
+        },
+        SearchUserFilesResult::Results {
+            current_dir_results,
+            global_results,
+        } => SearchResults {
+            current_directory: current_dir_results
+                .into_iter()
+                .map(SearchHit::from)
+                .collect(),
+            global: global_results.into_iter().map(SearchHit::from).collect(),
+        },
+    };
+
+    Ok(Json(search_results).into_response())
+}
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{
+        Query, State,
+        rejection::{JsonRejection, QueryRejection},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use bitsync_core::use_case::{
+    user_files::stat_user_file::find_user_file,
+    user_share::{
+        create_user_share::create_user_share, delete_user_share::delete_user_share,
+        list_shared_paths::list_shared_paths, list_user_shares::list_user_shares,
+        shared::UserShareListing,
+    },
+};
+use bitsync_database::entity::UserSharePermission;
+use bitsync_routes::TypedPath;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{AppState, auth::AppPasswordAuthData};
+
+use super::{
+    error::{ApiError, ApiErrorBody},
+    files::FileKind,
+};
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SharePermission {
+    Read,
+    ReadWrite,
+}
+
+impl From<UserSharePermission> for SharePermission {
+    fn from(value: UserSharePermission) -> Self {
+        match value {
+            UserSharePermission::Read => Self::Read,
+            UserSharePermission::ReadWrite => Self::ReadWrite,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Share {
+    pub id: Uuid,
+    pub path: String,
+    /// The public link of the share. Only set for link shares.
+    pub url: Option<String>,
+    /// The user the item is shared with. Only set for shares with another user.
+    pub recipient_username: Option<String>,
+    pub permission: SharePermission,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+    pub password_protected: bool,
+    pub max_downloads: Option<i32>,
+    pub download_count: i32,
+}
+
+impl From<UserShareListing> for Share {
+    fn from(value: UserShareListing) -> Self {
+        let user_share = value.user_share;
+        let url = match user_share.shared_with_user_id {
+            Some(..) => None,
+            None => Some(
+                bitsync_routes::GetPublicSharePage {
+                    user_share_id: user_share.id,
+                }
+                .to_string(),
+            ),
+        };
+
+        Self {
+            id: user_share.id,
+            path: user_share.item_path,
+            url,
+            recipient_username: value.recipient_username,
+            permission: SharePermission::from(user_share.permission),
+            expires_at: user_share.expires_at,
+            password_protected: user_share.password.is_some(),
+            max_downloads: user_share.max_downloads,
+            download_count: user_share.download_count,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ShareList {
+    pub shares: Vec<Share>,
+}
+
+impl From<Vec<UserShareListing>> for ShareList {
+    fn from(value: Vec<UserShareListing>) -> Self {
+        Self {
+            shares: value.into_iter().map(Share::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateShareRequest {
+    #[schema(example = "/documents/report.pdf")]
+    pub path: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SharedPathEntry {
+    pub path: String,
+    pub kind: FileKind,
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1Shares::PATH,
+    tag = "shares",
+    params(("path" = String, Query, description = "The path of the shared item")),
+    responses(
+        (status = OK, body = ShareList),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["read"])),
+)]
+pub(super) async fn list_shares_handler(
+    _: bitsync_routes::GetApiV1Shares,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<Query<bitsync_routes::GetApiV1SharesQueryParameters>, QueryRejection>,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    let user_shares = list_user_shares(
+        &app_state.database,
+        &auth_data.user.id,
+        &query_parameters.path,
+    )
+    .await?;
+
+    Ok(Json(ShareList::from(user_shares)).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = bitsync_routes::PostApiV1Shares::PATH,
+    tag = "shares",
+    description = "Creates a public link share for a file or directory.",
+    request_body = CreateShareRequest,
+    responses(
+        (status = CREATED, body = ShareList, description = "All shares of the item"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = NOT_FOUND, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn create_share_handler(
+    _: bitsync_routes::PostApiV1Shares,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    request: Result<Json<CreateShareRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(request) = request?;
+
+    let storage_item = find_user_file(&app_state.storage_root, &request.path, &auth_data.user)
+        .await?
+        .ok_or_else(ApiError::not_found)?;
+
+    let user_shares = create_user_share(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user.id,
+        &storage_item.path.path(),
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(ShareList::from(user_shares))).into_response())
+}
+
+#[utoipa::path(
+    delete,
+    path = bitsync_routes::DeleteApiV1Share::PATH,
+    tag = "shares",
+    params(
+        ("user_share_id" = Uuid, Path, description = "The id of the share"),
+        ("path" = String, Query, description = "The path of the shared item"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "The share was deleted"),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["write"])),
+)]
+pub(super) async fn delete_share_handler(
+    path: bitsync_routes::DeleteApiV1Share,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    query_parameters: Result<
+        Query<bitsync_routes::DeleteApiV1ShareQueryParameters>,
+        QueryRejection,
+    >,
+) -> Result<Response, ApiError> {
+    let Query(query_parameters) = query_parameters?;
+
+    delete_user_share(
+        &app_state.database,
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1SharedPaths::PATH,
+    tag = "shares",
+    description = "Lists all items of the user that have at least one share.",
+    responses(
+        (status = OK, body = Vec<SharedPathEntry>),
+    ),
+    security(("bearer" = ["read"])),
+)]
+pub(super) async fn list_shared_paths_handler(
+    _: bitsync_routes::GetApiV1SharedPaths,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+) -> Result<Response, ApiError> {
+    let shared_paths = list_shared_paths(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+    )
+    .await?;
+
+    Ok(Json(
+        shared_paths
+            .into_iter()
+            .map(|shared_path| SharedPathEntry {
+                path: shared_path.path,
+                kind: FileKind::from(shared_path.kind),
+            })
+            .collect::<Vec<_>>(),
+    )
+    .into_response())
+}
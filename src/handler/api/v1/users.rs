@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{State, rejection::JsonRejection},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use bitsync_core::use_case::user::{
+    list_users::list_users, set_user_storage_quota::set_user_storage_quota,
+    suspend_user::suspend_user, unsuspend_user::unsuspend_user,
+};
+use bitsync_database::entity::User;
+use bitsync_routes::TypedPath;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{AppState, auth::AppPasswordAuthData};
+
+use super::error::{ApiError, ApiErrorBody};
+
+#[derive(Serialize, ToSchema)]
+pub struct UserEntry {
+    pub id: Uuid,
+    pub username: String,
+    pub is_admin: bool,
+    pub is_suspended: bool,
+    /// The storage quota in bytes. Not set if the default quota applies.
+    pub storage_quota: Option<i64>,
+}
+
+impl From<User> for UserEntry {
+    fn from(value: User) -> Self {
+        Self {
+            id: value.id,
+            username: value.username,
+            is_admin: value.is_admin,
+            is_suspended: value.is_suspended,
+            storage_quota: value.storage_quota,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UserList {
+    /// All users except the owner of the app password.
+    pub users: Vec<UserEntry>,
+}
+
+impl From<Vec<User>> for UserList {
+    fn from(value: Vec<User>) -> Self {
+        Self {
+            users: value.into_iter().map(UserEntry::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetStorageQuotaRequest {
+    /// The storage quota in bytes. Not setting it applies the default quota.
+    #[schema(example = 10737418240u64)]
+    pub storage_quota: Option<u64>,
+}
+
+/// The API cannot be used to lock the owner of the app password out of their own account.
+fn ensure_other_user(auth_data: &AppPasswordAuthData, user_id: &Uuid) -> Result<(), ApiError> {
+    match auth_data.user.id == *user_id {
+        true => Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "own_account",
+            "the operation cannot be applied to the own account",
+        )),
+        false => Ok(()),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = bitsync_routes::GetApiV1Users::PATH,
+    tag = "users",
+    responses(
+        (status = OK, body = UserList),
+    ),
+    security(("bearer" = ["admin"])),
+)]
+pub(super) async fn list_users_handler(
+    _: bitsync_routes::GetApiV1Users,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+) -> Result<Response, ApiError> {
+    let users = list_users(&app_state.database, &auth_data.user.id).await?;
+
+    Ok(Json(UserList::from(users)).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = bitsync_routes::PostApiV1UserSuspend::PATH,
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "The id of the user")),
+    responses(
+        (status = OK, body = UserList),
+        (status = CONFLICT, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["admin"])),
+)]
+pub(super) async fn suspend_user_handler(
+    path: bitsync_routes::PostApiV1UserSuspend,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+) -> Result<Response, ApiError> {
+    ensure_other_user(&auth_data, &path.user_id)?;
+
+    let users = suspend_user(&app_state.database, &path.user_id, &auth_data.user.id).await?;
+
+    Ok(Json(UserList::from(users)).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = bitsync_routes::PostApiV1UserUnsuspend::PATH,
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "The id of the user")),
+    responses(
+        (status = OK, body = UserList),
+        (status = CONFLICT, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["admin"])),
+)]
+pub(super) async fn unsuspend_user_handler(
+    path: bitsync_routes::PostApiV1UserUnsuspend,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+) -> Result<Response, ApiError> {
+    ensure_other_user(&auth_data, &path.user_id)?;
+
+    let users = unsuspend_user(&app_state.database, &path.user_id, &auth_data.user.id).await?;
+
+    Ok(Json(UserList::from(users)).into_response())
+}
+
+#[utoipa::path(
+    put,
+    path = bitsync_routes::PutApiV1UserStorageQuota::PATH,
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "The id of the user")),
+    request_body = SetStorageQuotaRequest,
+    responses(
+        (status = OK, body = UserList),
+        (status = BAD_REQUEST, body = ApiErrorBody),
+        (status = CONFLICT, body = ApiErrorBody),
+    ),
+    security(("bearer" = ["admin"])),
+)]
+pub(super) async fn set_storage_quota_handler(
+    path: bitsync_routes::PutApiV1UserStorageQuota,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    request: Result<Json<SetStorageQuotaRequest>, JsonRejection>,
+) -> Result<Response, ApiError> {
+    let Json(request) = request?;
+
+    ensure_other_user(&auth_data, &path.user_id)?;
+
+    let users = set_user_storage_quota(
+        &app_state.database,
+        &path.user_id,
+        request.storage_quota,
+        &auth_data.user.id,
+    )
+    .await?;
+
+    Ok(Json(UserList::from(users)).into_response())
+}
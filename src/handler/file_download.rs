@@ -0,0 +1,611 @@
+use std::{
+    ops::Range,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use axum_extra::{body::AsyncReadBody, response::Attachment};
+use bitsync_core::use_case::user_files::{
+    download_user_file::UserFileDownloadResult, shared::AsyncStorageItemRead,
+};
+use bitsync_storage::async_file_read::AsyncFileRead;
+use headers::{
+    AcceptRanges, ContentLength, ContentRange, ETag, HeaderMapExt, IfModifiedSince, IfNoneMatch,
+    IfRange, LastModified,
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::Level;
+use uuid::Uuid;
+
+use super::checksum_headers;
+
+/// Requests for more ranges than this are answered with the whole file.
+const MAX_BYTE_RANGE_COUNT: usize = 32;
+
+/// Which part of a file a request is answered with.
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRangeSelection {
+    Full,
+    /// None of the requested ranges overlap the file.
+    Unsatisfiable,
+    /// Sorted ranges that neither overlap nor touch each other.
+    Partial(Vec<Range<u64>>),
+}
+
+/// Whether a file is saved by the browser or shown in it.
+#[derive(Clone, Copy)]
+pub(crate) enum FileDisposition {
+    Attachment,
+    Inline,
+}
+
+/// Responds with a downloaded file. Files of a known size can be requested in byte ranges and are
+/// answered with `304 Not Modified` if the client already has them.
+pub(crate) async fn file_download_response(
+    request_headers: &HeaderMap,
+    result: UserFileDownloadResult,
+    disposition: FileDisposition,
+) -> Result<Response, std::io::Error> {
+    let file_name = result.path.file_name();
+    let mut response_headers = checksum_headers(result.checksum.as_deref());
+
+    if let Ok(content_type) = HeaderValue::from_str(result.mime.as_ref()) {
+        response_headers.insert(header::CONTENT_TYPE, content_type);
+    }
+
+    let (mut file, size) = match (result.file, result.size) {
+        (AsyncStorageItemRead::File(file), Some(size)) => (file, size),
+        // archives are written while they are sent, so neither their size nor their content is
+        // known upfront
+        (file, _) => {
+            return Ok(file_response(
+                StatusCode::OK,
+                response_headers,
+                file_name,
+                disposition,
+                AsyncReadBody::new(file),
+            ));
+        }
+    };
+
+    let etag = entity_tag(result.checksum.as_deref(), size, result.modified_at);
+    let last_modified = result.modified_at.map(LastModified::from);
+
+    if let Some(etag) = &etag {
+        response_headers.typed_insert(etag.clone());
+    }
+    if let Some(last_modified) = last_modified {
+        response_headers.typed_insert(last_modified);
+    }
+    response_headers.typed_insert(AcceptRanges::bytes());
+
+    if is_not_modified(request_headers, etag.as_ref(), result.modified_at) {
+        return Ok((StatusCode::NOT_MODIFIED, response_headers).into_response());
+    }
+
+    let ranges = match select_byte_ranges(request_headers, size, etag.as_ref(), last_modified) {
+        ByteRangeSelection::Full => {
+            response_headers.typed_insert(ContentLength(size));
+
+            return Ok(file_response(
+                StatusCode::OK,
+                response_headers,
+                file_name,
+                disposition,
+                AsyncReadBody::new(file),
+            ));
+        }
+        ByteRangeSelection::Unsatisfiable => {
+            response_headers.typed_insert(ContentRange::unsatisfied_bytes(size));
+
+            return Ok((StatusCode::RANGE_NOT_SATISFIABLE, response_headers).into_response());
+        }
+        ByteRangeSelection::Partial(ranges) => ranges,
+    };
+
+    match ranges.as_slice() {
+        [range] => {
+            if let Ok(content_range) = ContentRange::bytes(range.clone(), size) {
+                response_headers.typed_insert(content_range);
+            }
+            response_headers.typed_insert(ContentLength(range.end - range.start));
+
+            file.seek_to_range(range.clone()).await?;
+
+            Ok(file_response(
+                StatusCode::PARTIAL_CONTENT,
+                response_headers,
+                file_name,
+                disposition,
+                AsyncReadBody::new(file),
+            ))
+        }
+        _ => {
+            let boundary = Uuid::new_v4().simple().to_string();
+            let parts = ranges
+                .iter()
+                .enumerate()
+                .map(|(index, range)| {
+                    let delimiter = if index == 0 { "" } else { "\r\n" };
+                    let part_header = format!(
+                        "{delimiter}--{boundary}\r\nContent-Type: {mime}\r\nContent-Range: bytes {start}-{end}/{size}\r\n\r\n",
+                        mime = result.mime,
+                        start = range.start,
+                        end = range.end - 1,
+                    );
+
+                    (part_header, range.clone())
+                })
+                .collect::<Vec<_>>();
+            let closing_delimiter = format!("\r\n--{boundary}--\r\n");
+
+            let content_length = parts
+                .iter()
+                .map(|(part_header, range)| part_header.len() as u64 + range.end - range.start)
+                .sum::<u64>()
+                + closing_delimiter.len() as u64;
+
+            if let Ok(content_type) =
+                HeaderValue::from_str(&format!("multipart/byteranges; boundary={boundary}"))
+            {
+                response_headers.insert(header::CONTENT_TYPE, content_type);
+            }
+            response_headers.typed_insert(ContentLength(content_length));
+
+            let (mut write_stream, read_stream) = tokio::io::duplex(4096);
+
+            tokio::spawn(async move {
+                if let Err(error) =
+                    write_byte_ranges(&mut write_stream, &mut file, parts, &closing_delimiter).await
+                {
+                    tracing::event!(
+                        Level::ERROR,
+                        message = "Writing byte ranges failed",
+                        error_trace = error.to_string(),
+                    )
+                }
+            });
+
+            Ok(file_response(
+                StatusCode::PARTIAL_CONTENT,
+                response_headers,
+                file_name,
+                disposition,
+                AsyncReadBody::new(read_stream),
+            ))
+        }
+    }
+}
+
+fn file_response(
+    status: StatusCode,
+    mut headers: HeaderMap,
+    file_name: String,
+    disposition: FileDisposition,
+    body: AsyncReadBody,
+) -> Response {
+    match disposition {
+        FileDisposition::Attachment => {
+            (status, headers, Attachment::new(body).filename(file_name)).into_response()
+        }
+        FileDisposition::Inline => {
+            let escaped_file_name = file_name.replace('\\', "\\\\").replace('"', "\\\"");
+
+            if let Ok(content_disposition) =
+                HeaderValue::from_str(&format!("inline; filename=\"{escaped_file_name}\""))
+            {
+                headers.insert(header::CONTENT_DISPOSITION, content_disposition);
+            }
+            // the content type decides whether a file is shown, so browsers must not guess it
+            headers.insert(
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            );
+
+            (status, headers, body).into_response()
+        }
+    }
+}
+
+/// A strong tag from the checksum of a file, or a weak one from its size and modification time.
+fn entity_tag(checksum: Option<&str>, size: u64, modified_at: Option<SystemTime>) -> Option<ETag> {
+    if let Some(checksum) = checksum {
+        return format!("\"{checksum}\"").parse().ok();
+    }
+
+    let modified_at = modified_at?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+
+    format!("W/\"{size:x}-{modified_at:x}\"").parse().ok()
+}
+
+fn is_not_modified(
+    request_headers: &HeaderMap,
+    etag: Option<&ETag>,
+    modified_at: Option<SystemTime>,
+) -> bool {
+    // `If-Modified-Since` is ignored when `If-None-Match` is sent
+    if let Some(if_none_match) = request_headers.typed_get::<IfNoneMatch>() {
+        return etag.is_some_and(|etag| !if_none_match.precondition_passes(etag));
+    }
+
+    match (request_headers.typed_get::<IfModifiedSince>(), modified_at) {
+        (Some(if_modified_since), Some(modified_at)) => !if_modified_since.is_modified(modified_at),
+        _ => false,
+    }
+}
+
+/// Applies the `Range` and `If-Range` headers of a request for a file of `size` bytes. Invalid
+/// range headers are ignored, as are requests for more than [`MAX_BYTE_RANGE_COUNT`] ranges.
+fn select_byte_ranges(
+    request_headers: &HeaderMap,
+    size: u64,
+    etag: Option<&ETag>,
+    last_modified: Option<LastModified>,
+) -> ByteRangeSelection {
+    let Some(ranges) = request_headers
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| parse_byte_range_set(range, size))
+    else {
+        return ByteRangeSelection::Full;
+    };
+
+    // a range of a different version of the file is useless to the client
+    if let Some(if_range) = request_headers.typed_get::<IfRange>()
+        && if_range.is_modified(etag, last_modified.as_ref())
+    {
+        return ByteRangeSelection::Full;
+    }
+
+    if ranges.len() > MAX_BYTE_RANGE_COUNT {
+        return ByteRangeSelection::Full;
+    }
+
+    match ranges.is_empty() {
+        true => ByteRangeSelection::Unsatisfiable,
+        false => ByteRangeSelection::Partial(coalesce_byte_ranges(ranges)),
+    }
+}
+
+/// Parses a `bytes=` range set into the ranges that overlap a file of `size` bytes. `None` means
+/// that the range set is invalid.
+fn parse_byte_range_set(range_set: &str, size: u64) -> Option<Vec<Range<u64>>> {
+    let range_set = range_set.strip_prefix("bytes=")?;
+    let mut ranges = Vec::new();
+
+    for range_spec in range_set.split(',').map(str::trim) {
+        if range_spec.is_empty() {
+            continue;
+        }
+
+        let range = match range_spec.split_once('-')? {
+            ("", suffix_length) => size.saturating_sub(parse_byte_position(suffix_length)?)..size,
+            (start, "") => parse_byte_position(start)?..size,
+            (start, end) => {
+                let start = parse_byte_position(start)?;
+                let end = parse_byte_position(end)?;
+
+                if end < start {
+                    return None;
+                }
+
+                start..end.saturating_add(1).min(size)
+            }
+        };
+
+        if range.start < range.end {
+            ranges.push(range);
+        }
+    }
+
+    Some(ranges)
+}
+
+fn parse_byte_position(position: &str) -> Option<u64> {
+    if position.is_empty() || !position.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    position.parse().ok()
+}
+
+/// Merges overlapping and adjacent ranges, so no byte is sent twice.
+fn coalesce_byte_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.sort_by_key(|range| range.start);
+
+    let mut coalesced_ranges: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match coalesced_ranges.last_mut() {
+            Some(previous_range) if range.start <= previous_range.end => {
+                previous_range.end = previous_range.end.max(range.end);
+            }
+            _ => coalesced_ranges.push(range),
+        }
+    }
+
+    coalesced_ranges
+}
+
+async fn write_byte_ranges<W>(
+    writer: &mut W,
+    file: &mut AsyncFileRead,
+    parts: Vec<(String, Range<u64>)>,
+    closing_delimiter: &str,
+) -> Result<(), std::io::Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    for (part_header, range) in parts {
+        writer.write_all(part_header.as_bytes()).await?;
+
+        file.seek_to_range(range).await?;
+        tokio::io::copy(file, writer).await?;
+    }
+
+    writer.write_all(closing_delimiter.as_bytes()).await?;
+    writer.shutdown().await
+}
+
+#[cfg(test)]
+// the expected selections are lists of byte ranges, not ranges to collect
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const SIZE: u64 = 100;
+
+    fn request_headers(headers: &[(header::HeaderName, &str)]) -> HeaderMap {
+        headers
+            .iter()
+            .map(|(name, value)| (name.clone(), HeaderValue::from_str(value).unwrap()))
+            .collect()
+    }
+
+    fn select(headers: &[(header::HeaderName, &str)], etag: Option<&ETag>) -> ByteRangeSelection {
+        select_byte_ranges(&request_headers(headers), SIZE, etag, None)
+    }
+
+    fn strong_etag() -> ETag {
+        "\"abc\"".parse().unwrap()
+    }
+
+    fn weak_etag() -> ETag {
+        "W/\"abc\"".parse().unwrap()
+    }
+
+    #[test]
+    fn without_range_header_the_whole_file_is_sent() {
+        assert_eq!(select(&[], None), ByteRangeSelection::Full);
+    }
+
+    #[test]
+    fn bounded_range_is_inclusive_and_clamped_to_the_file() {
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=0-0")], None),
+            ByteRangeSelection::Partial(vec![0..1])
+        );
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=90-500")], None),
+            ByteRangeSelection::Partial(vec![90..100])
+        );
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=40-")], None),
+            ByteRangeSelection::Partial(vec![40..100])
+        );
+    }
+
+    #[test]
+    fn suffix_range_selects_the_end_of_the_file() {
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=-10")], None),
+            ByteRangeSelection::Partial(vec![90..100])
+        );
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=-500")], None),
+            ByteRangeSelection::Partial(vec![0..100])
+        );
+    }
+
+    #[test]
+    fn ranges_outside_of_the_file_are_unsatisfiable() {
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=100-200")], None),
+            ByteRangeSelection::Unsatisfiable
+        );
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=-0")], None),
+            ByteRangeSelection::Unsatisfiable
+        );
+        assert_eq!(
+            select_byte_ranges(
+                &request_headers(&[(header::RANGE, "bytes=0-")]),
+                0,
+                None,
+                None
+            ),
+            ByteRangeSelection::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn unsatisfiable_ranges_are_dropped_next_to_satisfiable_ones() {
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=200-300, 0-9")], None),
+            ByteRangeSelection::Partial(vec![0..10])
+        );
+    }
+
+    #[test]
+    fn overlapping_and_adjacent_ranges_are_coalesced() {
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=40-99,0-49")], None),
+            ByteRangeSelection::Partial(vec![0..100])
+        );
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=0-9,10-19,30-39")], None),
+            ByteRangeSelection::Partial(vec![0..20, 30..40])
+        );
+        assert_eq!(
+            select(&[(header::RANGE, "bytes=0-0,0-0,0-0")], None),
+            ByteRangeSelection::Partial(vec![0..1])
+        );
+    }
+
+    #[test]
+    fn invalid_range_headers_are_ignored() {
+        for range in [
+            "items=0-9",
+            "bytes=9-0",
+            "bytes=a-9",
+            "bytes=+1-9",
+            "bytes=-",
+            "bytes=0-9,x",
+        ] {
+            assert_eq!(
+                select(&[(header::RANGE, range)], None),
+                ByteRangeSelection::Full,
+                "{range}"
+            );
+        }
+    }
+
+    #[test]
+    fn too_many_ranges_are_answered_with_the_whole_file() {
+        let range_set = |count: u64| {
+            let ranges = (0..count)
+                .map(|index| format!("{start}-{start}", start = index * 2))
+                .collect::<Vec<_>>();
+
+            format!("bytes={}", ranges.join(","))
+        };
+
+        assert!(matches!(
+            select(&[(header::RANGE, &range_set(MAX_BYTE_RANGE_COUNT as u64))], None),
+            ByteRangeSelection::Partial(ranges) if ranges.len() == MAX_BYTE_RANGE_COUNT
+        ));
+        assert_eq!(
+            select(
+                &[(header::RANGE, &range_set(MAX_BYTE_RANGE_COUNT as u64 + 1))],
+                None
+            ),
+            ByteRangeSelection::Full
+        );
+    }
+
+    #[test]
+    fn if_range_with_a_matching_strong_etag_keeps_the_range() {
+        assert_eq!(
+            select(
+                &[(header::RANGE, "bytes=0-9"), (header::IF_RANGE, "\"abc\"")],
+                Some(&strong_etag())
+            ),
+            ByteRangeSelection::Partial(vec![0..10])
+        );
+    }
+
+    #[test]
+    fn if_range_with_a_different_etag_sends_the_whole_file() {
+        assert_eq!(
+            select(
+                &[(header::RANGE, "bytes=0-9"), (header::IF_RANGE, "\"def\"")],
+                Some(&strong_etag())
+            ),
+            ByteRangeSelection::Full
+        );
+    }
+
+    #[test]
+    fn if_range_never_matches_weak_etags() {
+        assert_eq!(
+            select(
+                &[
+                    (header::RANGE, "bytes=0-9"),
+                    (header::IF_RANGE, "W/\"abc\"")
+                ],
+                Some(&weak_etag())
+            ),
+            ByteRangeSelection::Full
+        );
+        assert_eq!(
+            select(
+                &[(header::RANGE, "bytes=0-9"), (header::IF_RANGE, "\"abc\"")],
+                Some(&weak_etag())
+            ),
+            ByteRangeSelection::Full
+        );
+    }
+
+    #[test]
+    fn if_range_with_a_date_compares_the_modification_time() {
+        let modified_at = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let range_headers = |if_range: SystemTime| {
+            request_headers(&[
+                (header::RANGE, "bytes=0-9"),
+                (header::IF_RANGE, &httpdate::fmt_http_date(if_range)),
+            ])
+        };
+
+        assert_eq!(
+            select_byte_ranges(
+                &range_headers(modified_at),
+                SIZE,
+                None,
+                Some(LastModified::from(modified_at))
+            ),
+            ByteRangeSelection::Partial(vec![0..10])
+        );
+        assert_eq!(
+            select_byte_ranges(
+                &range_headers(modified_at - Duration::from_secs(60)),
+                SIZE,
+                None,
+                Some(LastModified::from(modified_at))
+            ),
+            ByteRangeSelection::Full
+        );
+    }
+
+    #[test]
+    fn if_none_match_compares_etags_weakly() {
+        for if_none_match in ["\"abc\"", "W/\"abc\"", "\"def\", W/\"abc\"", "*"] {
+            let headers = request_headers(&[(header::IF_NONE_MATCH, if_none_match)]);
+
+            assert!(
+                is_not_modified(&headers, Some(&strong_etag()), None),
+                "{if_none_match}"
+            );
+            assert!(
+                is_not_modified(&headers, Some(&weak_etag()), None),
+                "{if_none_match}"
+            );
+        }
+
+        let headers = request_headers(&[(header::IF_NONE_MATCH, "\"def\"")]);
+
+        assert!(!is_not_modified(&headers, Some(&strong_etag()), None));
+    }
+
+    #[test]
+    fn if_none_match_takes_precedence_over_if_modified_since() {
+        let modified_at = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let headers = request_headers(&[
+            (header::IF_NONE_MATCH, "\"def\""),
+            (
+                header::IF_MODIFIED_SINCE,
+                &httpdate::fmt_http_date(modified_at),
+            ),
+        ]);
+
+        assert!(!is_not_modified(
+            &headers,
+            Some(&strong_etag()),
+            Some(modified_at)
+        ));
+    }
+}
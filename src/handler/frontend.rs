@@ -4,23 +4,37 @@ use axum::Router;
 
 use crate::AppState;
 
+mod file_version;
 mod files_home;
 mod login;
 mod logout;
+mod public_share;
 mod register;
 mod search;
+mod shared_with_me;
 mod suspended;
+mod trash;
+mod upload_session;
 mod user_file;
+mod user_file_batch;
+mod user_file_extract;
 mod user_settings;
 
 pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .merge(user_settings::create_routes(state.clone()).await)
         .merge(files_home::create_routes(state.clone()).await)
+        .merge(file_version::create_routes(state.clone()).await)
         .merge(search::create_routes(state.clone()).await)
         .merge(login::create_routes(state.clone()).await)
         .merge(register::create_routes(state.clone()).await)
         .merge(logout::create_routes(state.clone()).await)
         .merge(suspended::create_routes(state.clone()).await)
-        .merge(user_file::create_routes(state).await)
+        .merge(public_share::create_routes(state.clone()).await)
+        .merge(shared_with_me::create_routes(state.clone()).await)
+        .merge(trash::create_routes(state.clone()).await)
+        .merge(upload_session::create_routes(state.clone()).await)
+        .merge(user_file::create_routes(state.clone()).await)
+        .merge(user_file_batch::create_routes(state.clone()).await)
+        .merge(user_file_extract::create_routes(state).await)
 }
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router, extract::State, http::HeaderMap, middleware::from_fn_with_state,
+    response::IntoResponse,
+};
+use axum_extra::routing::RouterExt;
+use bitsync_core::use_case::file_version::{
+    download_user_file_version::{DownloadUserFileVersionError, download_user_file_version},
+    restore_user_file_version::{RestoreUserFileVersionError, restore_user_file_version},
+};
+use bitsync_routes::TypedPath;
+
+use crate::{
+    AppState,
+    auth::{AuthData, require_login_and_totp_setup_middleware},
+    error_report::emit_error,
+    handler::{
+        RedirectHttp, RedirectHyperStim,
+        file_download::{FileDisposition, file_download_response},
+        hyperstim_redirect_response, internal_server_error_toast_response,
+        user_error_toast_response,
+    },
+};
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .merge(
+            Router::new()
+                .typed_get(user_file_version_download_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHttp>,
+                ))
+                .with_state(state.clone()),
+        )
+        .merge(
+            Router::new()
+                .typed_post(user_file_version_restore_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state),
+        )
+}
+
+async fn user_file_version_download_handler(
+    path: bitsync_routes::GetUserFileVersionDownload,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    request_headers: HeaderMap,
+) -> impl IntoResponse {
+    match download_user_file_version(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+        &path.file_version_id,
+    )
+    .await
+    {
+        Ok(result) => {
+            match file_download_response(&request_headers, result, FileDisposition::Attachment)
+                .await
+            {
+                Ok(response) => response,
+                Err(error) => {
+                    emit_error(error);
+
+                    internal_server_error_toast_response()
+                }
+            }
+        }
+        Err(DownloadUserFileVersionError::FileVersionNotFound(..)) => {
+            user_error_toast_response("The file version does not exist")
+        }
+        Err(error) => {
+            emit_error(error);
+
+            internal_server_error_toast_response()
+        }
+    }
+}
+
+async fn user_file_version_restore_handler(
+    path: bitsync_routes::PostUserFileVersionRestore,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    match restore_user_file_version(
+        &app_state.database,
+        &app_state.storage_root,
+        app_state.config.max_file_versions,
+        &auth_data.user,
+        &path.file_version_id,
+    )
+    .await
+    {
+        Ok(result) => hyperstim_redirect_response(
+            &bitsync_routes::GetFilesHomePage
+                .with_query_params(bitsync_routes::GetFilesHomePageQueryParameters {
+                    path: result.path.path(),
+                })
+                .to_string(),
+        ),
+        Err(RestoreUserFileVersionError::FileVersionNotFound(..)) => {
+            user_error_toast_response("The file version does not exist")
+        }
+        Err(error) => {
+            emit_error(error);
+
+            internal_server_error_toast_response()
+        }
+    }
+}
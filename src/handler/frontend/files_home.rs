@@ -39,7 +39,9 @@ async fn files_home_page_handler(
     query_parameters: Query<bitsync_routes::GetFilesHomePageQueryParameters>,
 ) -> impl IntoResponse {
     match read_user_file_item(
-        &app_state.config.fs_storage_root_dir,
+        &app_state.database,
+        &app_state.storage_root,
+        app_state.config.default_user_storage_quota,
         &query_parameters.path,
         &auth_data.user,
     )
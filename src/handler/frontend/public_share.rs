@@ -0,0 +1,335 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Html, IntoResponse},
+};
+use axum_extra::{
+    body::AsyncReadBody,
+    extract::{
+        CookieJar, Form,
+        cookie::{Cookie, SameSite},
+    },
+    response::Attachment,
+    routing::RouterExt,
+};
+use bitsync_core::use_case::{
+    user_files::download_user_file::UserFileDownloadError,
+    user_share::{
+        download_user_share_item::{DownloadUserShareItemError, download_user_share_item},
+        read_user_share_item::{
+            ReadResolvedUserShareItemError, ReadUserShareItemError, read_user_share_item,
+        },
+        shared::ResolveUserSharePathError,
+        unlock_user_share::{UnlockUserShareError, unlock_user_share},
+    },
+};
+use bitsync_frontend::{
+    Component, Render,
+    pages::public_share::{
+        PublicShareDisplayError, PublicShareErrorPage, PublicSharePage, PublicSharePasswordForm,
+        PublicSharePasswordPage, PublicShareUnlockDisplayError,
+    },
+};
+use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
+use bitsync_routes::TypedPath;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    auth::AuthStatus,
+    error_report::emit_error,
+    handler::{http_redirect_response, hyperstim_redirect_response},
+};
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .typed_get(public_share_page_handler)
+        .typed_get(public_share_download_handler)
+        .typed_post(public_share_unlock_handler)
+        .route_layer(axum::middleware::from_fn(
+            crate::body_limit::request_body_size_limit,
+        ))
+        .with_state(state)
+}
+
+fn share_access_cookie_name(user_share_id: &Uuid) -> String {
+    format!("share-access-{user_share_id}")
+}
+
+fn share_access_cookie<'a>(user_share_id: &Uuid, token: &str) -> Cookie<'a> {
+    let mut share_access_cookie =
+        Cookie::new(share_access_cookie_name(user_share_id), token.to_owned());
+    share_access_cookie.set_same_site(SameSite::Lax);
+    share_access_cookie.set_http_only(true);
+    share_access_cookie.set_path(
+        bitsync_routes::GetPublicSharePage {
+            user_share_id: *user_share_id,
+        }
+        .to_string(),
+    );
+
+    #[cfg(not(debug_assertions))]
+    share_access_cookie.set_secure(true);
+
+    share_access_cookie
+}
+
+fn public_share_page_url(user_share_id: Uuid, path: String) -> String {
+    bitsync_routes::GetPublicSharePage { user_share_id }
+        .with_query_params(bitsync_routes::GetPublicSharePageQueryParameters { path })
+        .to_string()
+}
+
+fn is_user_logged_in(auth_status: &AuthStatus) -> bool {
+    matches!(auth_status, AuthStatus::User(auth_data) if !auth_data.user.is_suspended)
+}
+
+fn public_share_error_response(
+    is_user_logged_in: bool,
+    error: PublicShareDisplayError,
+) -> axum::response::Response {
+    let status_code = match error {
+        PublicShareDisplayError::NotFound => StatusCode::NOT_FOUND,
+        PublicShareDisplayError::Expired | PublicShareDisplayError::DownloadLimitReached => {
+            StatusCode::GONE
+        }
+        PublicShareDisplayError::InvalidPath => StatusCode::BAD_REQUEST,
+        PublicShareDisplayError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (
+        status_code,
+        Html(
+            PublicShareErrorPage {
+                is_user_logged_in,
+                error,
+            }
+            .render(),
+        ),
+    )
+        .into_response()
+}
+
+fn resolve_error_to_display_error(error: ResolveUserSharePathError) -> PublicShareDisplayError {
+    match error {
+        ResolveUserSharePathError::UserShareNotFound(..) => PublicShareDisplayError::NotFound,
+        ResolveUserSharePathError::UserShareExpired(..) => PublicShareDisplayError::Expired,
+        ResolveUserSharePathError::UserShareDownloadLimitReached(..) => {
+            PublicShareDisplayError::DownloadLimitReached
+        }
+        ResolveUserSharePathError::StoragePath(..) => PublicShareDisplayError::InvalidPath,
+        error => {
+            emit_error(error);
+            PublicShareDisplayError::InternalServerError
+        }
+    }
+}
+
+async fn public_share_page_handler(
+    path: bitsync_routes::GetPublicSharePage,
+    State(app_state): State<Arc<AppState>>,
+    auth_status: AuthStatus,
+    cookie_jar: CookieJar,
+    query_parameters: Query<bitsync_routes::GetPublicSharePageQueryParameters>,
+) -> impl IntoResponse {
+    let is_user_logged_in = is_user_logged_in(&auth_status);
+    let share_access_token = cookie_jar
+        .get(&share_access_cookie_name(&path.user_share_id))
+        .map(|cookie| cookie.value().to_owned());
+
+    match read_user_share_item(
+        &app_state.database,
+        &app_state.storage_root,
+        &app_state.config.auth.jwt_secret,
+        &path.user_share_id,
+        share_access_token.as_deref(),
+        &query_parameters.path,
+    )
+    .await
+    {
+        Ok(result) => Html(
+            PublicSharePage {
+                is_user_logged_in,
+                item: result.into(),
+            }
+            .render(),
+        )
+        .into_response(),
+        Err(ReadUserShareItemError::ResolveUserSharePath(
+            ResolveUserSharePathError::UserSharePasswordRequired(..),
+        )) => {
+            let unlock_url = bitsync_routes::PostPublicShareUnlock {
+                user_share_id: path.user_share_id,
+            }
+            .with_query_params(bitsync_routes::PostPublicShareUnlockQueryParameters {
+                path: query_parameters.path.clone(),
+            })
+            .to_string();
+
+            (
+                StatusCode::UNAUTHORIZED,
+                Html(
+                    PublicSharePasswordPage {
+                        is_user_logged_in,
+                        form: PublicSharePasswordForm {
+                            unlock_url,
+                            error: None,
+                        },
+                    }
+                    .render(),
+                ),
+            )
+                .into_response()
+        }
+        Err(error) => {
+            let display_error = match error {
+                ReadUserShareItemError::ResolveUserSharePath(error) => {
+                    resolve_error_to_display_error(error)
+                }
+                ReadUserShareItemError::ReadResolvedUserShareItem(
+                    ReadResolvedUserShareItemError::ReadStorageItem(..),
+                ) => PublicShareDisplayError::NotFound,
+                error => {
+                    emit_error(error);
+                    PublicShareDisplayError::InternalServerError
+                }
+            };
+
+            public_share_error_response(is_user_logged_in, display_error)
+        }
+    }
+}
+
+async fn public_share_download_handler(
+    path: bitsync_routes::GetPublicShareDownload,
+    State(app_state): State<Arc<AppState>>,
+    auth_status: AuthStatus,
+    cookie_jar: CookieJar,
+    query_parameters: Query<bitsync_routes::GetPublicShareDownloadQueryParameters>,
+) -> impl IntoResponse {
+    let share_access_token = cookie_jar
+        .get(&share_access_cookie_name(&path.user_share_id))
+        .map(|cookie| cookie.value().to_owned());
+
+    match download_user_share_item(
+        &app_state.database,
+        &app_state.storage_root,
+        &app_state.config.auth.jwt_secret,
+        &path.user_share_id,
+        share_access_token.as_deref(),
+        &query_parameters.path,
+    )
+    .await
+    {
+        Ok(result) => {
+            let content_type = headers::ContentType::from(result.mime);
+            let stream_body = AsyncReadBody::new(result.file);
+            let attachment = Attachment::new(stream_body).filename(result.path.file_name());
+
+            (axum_extra::TypedHeader(content_type), attachment).into_response()
+        }
+        Err(DownloadUserShareItemError::ResolveUserSharePath(
+            ResolveUserSharePathError::UserSharePasswordRequired(..),
+        )) => http_redirect_response(&public_share_page_url(
+            path.user_share_id,
+            query_parameters.path.clone(),
+        )),
+        Err(error) => {
+            let display_error = match error {
+                DownloadUserShareItemError::UserShareDownloadLimitReached(..) => {
+                    PublicShareDisplayError::DownloadLimitReached
+                }
+                DownloadUserShareItemError::ResolveUserSharePath(error) => {
+                    resolve_error_to_display_error(error)
+                }
+                DownloadUserShareItemError::UserFileDownload(
+                    UserFileDownloadError::ReadStorageItem(..),
+                ) => PublicShareDisplayError::NotFound,
+                error => {
+                    emit_error(error);
+                    PublicShareDisplayError::InternalServerError
+                }
+            };
+
+            public_share_error_response(is_user_logged_in(&auth_status), display_error)
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct PublicShareUnlockFormData {
+    password: String,
+}
+
+async fn public_share_unlock_handler(
+    path: bitsync_routes::PostPublicShareUnlock,
+    State(app_state): State<Arc<AppState>>,
+    cookie_jar: CookieJar,
+    query_parameters: Query<bitsync_routes::PostPublicShareUnlockQueryParameters>,
+    Form(form_data): Form<PublicShareUnlockFormData>,
+) -> impl IntoResponse {
+    let unlock_url = bitsync_routes::PostPublicShareUnlock {
+        user_share_id: path.user_share_id,
+    }
+    .with_query_params(bitsync_routes::PostPublicShareUnlockQueryParameters {
+        path: query_parameters.path.clone(),
+    })
+    .to_string();
+
+    match unlock_user_share(
+        &app_state.database,
+        &app_state.config.auth.jwt_secret,
+        &path.user_share_id,
+        &form_data.password,
+    )
+    .await
+    {
+        Ok(token) => {
+            let cookie_jar = cookie_jar.add(share_access_cookie(&path.user_share_id, &token));
+
+            (
+                cookie_jar,
+                hyperstim_redirect_response(&public_share_page_url(
+                    path.user_share_id,
+                    query_parameters.path.clone(),
+                )),
+            )
+                .into_response()
+        }
+        Err(error) => {
+            let (status_code, display_error) = match error {
+                UnlockUserShareError::PasswordHashVerification(..)
+                | UnlockUserShareError::UserShareNotFound(..) => (
+                    StatusCode::UNAUTHORIZED,
+                    PublicShareUnlockDisplayError::InvalidPassword,
+                ),
+                error => {
+                    emit_error(error);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        PublicShareUnlockDisplayError::InternalServerError,
+                    )
+                }
+            };
+
+            let form = PublicSharePasswordForm {
+                unlock_url,
+                error: Some(display_error),
+            };
+
+            (
+                status_code,
+                Json(HyperStimCommand::HsPatchHtml {
+                    html: form.render(),
+                    patch_target: form.id_target(),
+                    patch_mode: HyperStimPatchMode::Outer,
+                }),
+            )
+                .into_response()
+        }
+    }
+}
@@ -205,7 +205,7 @@ async fn register_action_handler(
 
     match perform_registration(
         &state.database,
-        &state.config.fs_storage_root_dir,
+        &state.storage_root,
         &registration_data.username,
         &registration_data.password,
         &token_uuid,
@@ -45,7 +45,7 @@ async fn search_handler(
     query_parameters: Query<bitsync_routes::GetSearchQueryParameters>,
 ) -> impl IntoResponse {
     match search_user_files(
-        &app_state.config.fs_storage_root_dir,
+        &app_state.storage_root,
         &query_parameters.query,
         &auth_data.user,
         query_parameters.path.as_deref(),
@@ -0,0 +1,451 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    middleware::from_fn_with_state,
+    response::{Html, IntoResponse},
+};
+use axum_extra::{extract::Form, routing::RouterExt};
+use bitsync_core::use_case::{
+    file_version::shared::WriteVersionedFileStreamError,
+    shared_with_me::{
+        create_shared_directory::{SharedDirectoryCreationError, create_shared_directory},
+        delete_shared_item::{SharedItemDeletionError, delete_shared_item},
+        download_shared_item::{DownloadSharedItemError, download_shared_item},
+        list_shared_with_me::list_shared_with_me,
+        read_shared_item::{ReadSharedItemError, read_shared_item},
+        shared::ResolveSharedItemPathError,
+        upload_shared_file::{SharedFileUpload, SharedFileUploadError, upload_shared_file},
+    },
+    user_files::{download_user_file::UserFileDownloadError, shared::UploadPolicy},
+    user_share::read_user_share_item::ReadResolvedUserShareItemError,
+};
+use bitsync_frontend::{
+    Component, DIALOG_WRAPPER_SELECTOR, Render,
+    pages::{
+        error::ErrorPage,
+        files::directory_creation::{
+            DirectoryCreationDialog, DirectoryCreationDisplayError, DirectoryCreationForm,
+        },
+        shared_with_me::{
+            SharedDirectoryTable, SharedItemDisplayError, SharedItemErrorPage,
+            SharedItemOperationDisplayError, SharedItemPage, SharedWithMePage,
+        },
+    },
+};
+use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
+use bitsync_routes::TypedPath;
+use bitsync_storage::operation::write::WriteFileStreamError;
+use serde::Deserialize;
+
+use super::user_file::UserFileMultipartField;
+use crate::{
+    AppState,
+    auth::{AuthData, require_login_and_totp_setup_middleware},
+    error_report::emit_error,
+    handler::{
+        RedirectHttp, RedirectHyperStim,
+        file_download::{FileDisposition, file_download_response},
+        user_error_toast_response,
+    },
+};
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .merge(
+            Router::new()
+                .typed_post(shared_item_upload_handler)
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state.clone()),
+        )
+        .merge(
+            Router::new()
+                .typed_get(shared_with_me_page_handler)
+                .typed_get(shared_item_page_handler)
+                .typed_get(shared_item_download_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHttp>,
+                ))
+                .with_state(state.clone()),
+        )
+        .merge(
+            Router::new()
+                .typed_post(shared_item_directory_creation_handler)
+                .typed_get(shared_item_directory_creation_dialog_handler)
+                .typed_get(shared_item_delete_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state),
+        )
+}
+
+fn shared_item_error_response(error: SharedItemDisplayError) -> axum::response::Response {
+    let status_code = match error {
+        SharedItemDisplayError::NotFound => StatusCode::NOT_FOUND,
+        SharedItemDisplayError::InvalidPath => StatusCode::BAD_REQUEST,
+        SharedItemDisplayError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (status_code, Html(SharedItemErrorPage { error }.render())).into_response()
+}
+
+fn resolve_error_to_display_error(error: ResolveSharedItemPathError) -> SharedItemDisplayError {
+    match error {
+        ResolveSharedItemPathError::UserShareNotFound(..) => SharedItemDisplayError::NotFound,
+        ResolveSharedItemPathError::StoragePath(..) => SharedItemDisplayError::InvalidPath,
+        error => {
+            emit_error(error);
+            SharedItemDisplayError::InternalServerError
+        }
+    }
+}
+
+fn resolve_error_to_operation_display_error(
+    error: ResolveSharedItemPathError,
+) -> SharedItemOperationDisplayError {
+    match error {
+        ResolveSharedItemPathError::UserShareNotFound(..) => {
+            SharedItemOperationDisplayError::NotFound
+        }
+        ResolveSharedItemPathError::StoragePath(..) => SharedItemOperationDisplayError::InvalidPath,
+        error => {
+            emit_error(error);
+            SharedItemOperationDisplayError::InternalServerError
+        }
+    }
+}
+
+fn shared_directory_table_response(
+    directory_table: SharedDirectoryTable,
+) -> axum::response::Response {
+    Json(HyperStimCommand::HsPatchHtml {
+        html: directory_table.render(),
+        patch_target: directory_table.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
+async fn shared_with_me_page_handler(
+    _: bitsync_routes::GetSharedWithMePage,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    match list_shared_with_me(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(entries) => Html(SharedWithMePage { entries }.render()),
+        Err(error) => Html(ErrorPage::from(error).render()),
+    }
+}
+
+async fn shared_item_page_handler(
+    path: bitsync_routes::GetSharedItemPage,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::GetSharedItemPageQueryParameters>,
+) -> impl IntoResponse {
+    match read_shared_item(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+    )
+    .await
+    {
+        Ok(result) => Html(SharedItemPage::from(result).render()).into_response(),
+        Err(error) => {
+            let display_error = match error {
+                ReadSharedItemError::ResolveSharedItemPath(error) => {
+                    resolve_error_to_display_error(error)
+                }
+                ReadSharedItemError::ReadResolvedUserShareItem(
+                    ReadResolvedUserShareItemError::ReadStorageItem(..),
+                ) => SharedItemDisplayError::NotFound,
+                error => {
+                    emit_error(error);
+                    SharedItemDisplayError::InternalServerError
+                }
+            };
+
+            shared_item_error_response(display_error)
+        }
+    }
+}
+
+async fn shared_item_download_handler(
+    path: bitsync_routes::GetSharedItemDownload,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::GetSharedItemDownloadQueryParameters>,
+    request_headers: HeaderMap,
+) -> impl IntoResponse {
+    match download_shared_item(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+    )
+    .await
+    {
+        Ok(result) => {
+            match file_download_response(&request_headers, result, FileDisposition::Attachment)
+                .await
+            {
+                Ok(response) => response,
+                Err(error) => {
+                    emit_error(error);
+
+                    shared_item_error_response(SharedItemDisplayError::InternalServerError)
+                }
+            }
+        }
+        Err(error) => {
+            let display_error = match error {
+                DownloadSharedItemError::ResolveSharedItemPath(error) => {
+                    resolve_error_to_display_error(error)
+                }
+                DownloadSharedItemError::UserFileDownload(
+                    UserFileDownloadError::ReadStorageItem(..),
+                ) => SharedItemDisplayError::NotFound,
+                error => {
+                    emit_error(error);
+                    SharedItemDisplayError::InternalServerError
+                }
+            };
+
+            shared_item_error_response(display_error)
+        }
+    }
+}
+
+async fn shared_item_upload_handler(
+    path: bitsync_routes::PostSharedItemUpload,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostSharedItemUploadQueryParameters>,
+    multipart_data: UserFileMultipartField,
+) -> impl IntoResponse {
+    match upload_shared_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &UploadPolicy::from(&app_state.config),
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+        SharedFileUpload {
+            file_name: &multipart_data.file_name,
+            stream: multipart_data.field,
+        },
+    )
+    .await
+    {
+        Ok(result) => shared_directory_table_response(SharedDirectoryTable::from(result)),
+        Err(error) => {
+            let display_error = match error {
+                SharedFileUploadError::ResolveSharedItemPath(error) => {
+                    resolve_error_to_operation_display_error(error)
+                }
+                SharedFileUploadError::SharedItemReadOnly(..) => {
+                    SharedItemOperationDisplayError::ReadOnly
+                }
+                SharedFileUploadError::InvalidFileName(..) => {
+                    SharedItemOperationDisplayError::InvalidName
+                }
+                SharedFileUploadError::StoragePath(..) => {
+                    SharedItemOperationDisplayError::InvalidPath
+                }
+                SharedFileUploadError::WriteVersionedFileStream(
+                    WriteVersionedFileStreamError::WriteFileStream(
+                        WriteFileStreamError::SizeLimitExceeded(..),
+                    ),
+                ) => SharedItemOperationDisplayError::QuotaExceeded,
+                error => {
+                    emit_error(error);
+                    SharedItemOperationDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AddSharedDirectoryFormData {
+    pub directory_name: String,
+}
+
+async fn shared_item_directory_creation_handler(
+    path: bitsync_routes::PostSharedItemDirectoryCreation,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostSharedItemDirectoryCreationQueryParameters>,
+    Form(AddSharedDirectoryFormData { directory_name }): Form<AddSharedDirectoryFormData>,
+) -> impl IntoResponse {
+    match create_shared_directory(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+        &directory_name,
+    )
+    .await
+    {
+        Ok(result) => {
+            let directory_table = SharedDirectoryTable::from(result);
+            let dialog_id =
+                bitsync_frontend::pages::files::FilesHomePageElementId::DirectoryCreationDialog
+                    .to_str();
+
+            Json(vec![
+                HyperStimCommand::HsPatchHtml {
+                    html: directory_table.render(),
+                    patch_target: directory_table.id_target(),
+                    patch_mode: HyperStimPatchMode::Outer,
+                },
+                HyperStimCommand::HsExecute {
+                    code: format!(
+                        "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}'))"
+                    ),
+                },
+            ])
+            .into_response()
+        }
+        Err(SharedDirectoryCreationError::ResolveSharedItemPath(error)) => {
+            user_error_toast_response(resolve_error_to_operation_display_error(error).message())
+        }
+        Err(SharedDirectoryCreationError::SharedItemReadOnly(..)) => {
+            user_error_toast_response(SharedItemOperationDisplayError::ReadOnly.message())
+        }
+        Err(error) => {
+            let display_error = match error {
+                SharedDirectoryCreationError::EmptyPath(..) => {
+                    DirectoryCreationDisplayError::EmptyName
+                }
+                SharedDirectoryCreationError::DirectoryNameContainsSeparator(..) => {
+                    DirectoryCreationDisplayError::InvalidName
+                }
+                SharedDirectoryCreationError::StoragePath(..) => {
+                    DirectoryCreationDisplayError::InvalidPath
+                }
+                error => {
+                    emit_error(error);
+                    DirectoryCreationDisplayError::InternalServerError
+                }
+            };
+
+            let directory_creation_url = bitsync_routes::PostSharedItemDirectoryCreation {
+                user_share_id: path.user_share_id,
+            }
+            .with_query_params(
+                bitsync_routes::PostSharedItemDirectoryCreationQueryParameters {
+                    path: query_parameters.path.clone(),
+                },
+            )
+            .to_string();
+
+            let form = DirectoryCreationForm {
+                action_url: directory_creation_url,
+                directory_name: Some(directory_name),
+                error: Some(display_error),
+            };
+
+            Json(HyperStimCommand::HsPatchHtml {
+                html: form.render(),
+                patch_target: form.id_target(),
+                patch_mode: HyperStimPatchMode::Outer,
+            })
+            .into_response()
+        }
+    }
+}
+
+async fn shared_item_directory_creation_dialog_handler(
+    path: bitsync_routes::GetSharedItemDirectoryCreationDialog,
+    query_parameters: Query<bitsync_routes::GetSharedItemDirectoryCreationDialogQueryParameters>,
+) -> impl IntoResponse {
+    let action_url = bitsync_routes::PostSharedItemDirectoryCreation {
+        user_share_id: path.user_share_id,
+    }
+    .with_query_params(
+        bitsync_routes::PostSharedItemDirectoryCreationQueryParameters {
+            path: query_parameters.path.clone(),
+        },
+    )
+    .to_string();
+
+    let dialog = DirectoryCreationDialog { action_url };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
+async fn shared_item_delete_handler(
+    path: bitsync_routes::GetSharedItemDelete,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::GetSharedItemDeleteQueryParameters>,
+) -> impl IntoResponse {
+    match delete_shared_item(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+    )
+    .await
+    {
+        Ok(result) => shared_directory_table_response(SharedDirectoryTable::from(result)),
+        Err(error) => {
+            let display_error = match error {
+                SharedItemDeletionError::ResolveSharedItemPath(error) => {
+                    resolve_error_to_operation_display_error(error)
+                }
+                SharedItemDeletionError::SharedItemReadOnly(..) => {
+                    SharedItemOperationDisplayError::ReadOnly
+                }
+                SharedItemDeletionError::SharedRootDeletion(..) => {
+                    SharedItemOperationDisplayError::CannotDeleteSharedRoot
+                }
+                SharedItemDeletionError::StoragePath(..) => {
+                    SharedItemOperationDisplayError::InvalidPath
+                }
+                SharedItemDeletionError::ReadStorageItem(..) => {
+                    SharedItemOperationDisplayError::NotFound
+                }
+                error => {
+                    emit_error(error);
+                    SharedItemOperationDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
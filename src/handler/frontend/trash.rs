@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::{Html, IntoResponse},
+};
+use axum_extra::routing::RouterExt;
+use bitsync_core::use_case::trash::{
+    empty_trash::empty_trash,
+    list_trash_entries::list_trash_entries,
+    restore_trash_entry::{RestoreTrashEntryError, restore_trash_entry},
+};
+use bitsync_frontend::{
+    Component, DIALOG_WRAPPER_SELECTOR, Render,
+    confirmation_dialog::ConfirmationDialog,
+    pages::{
+        error::ErrorPage,
+        trash::{TrashEntryList, TrashPage},
+    },
+};
+use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
+
+use crate::{
+    AppState,
+    auth::{AuthData, require_login_and_totp_setup_middleware},
+    error_report::emit_error,
+    handler::{
+        RedirectHttp, RedirectHyperStim, internal_server_error_toast_response,
+        user_error_toast_response,
+    },
+};
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .merge(
+            Router::new()
+                .typed_get(trash_page_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHttp>,
+                ))
+                .with_state(state.clone()),
+        )
+        .merge(
+            Router::new()
+                .typed_post(trash_entry_restore_handler)
+                .typed_get(trash_empty_dialog_handler)
+                .typed_post(trash_empty_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state),
+        )
+}
+
+fn trash_entry_list_response(entry_list: TrashEntryList) -> axum::response::Response {
+    Json(HyperStimCommand::HsPatchHtml {
+        html: entry_list.render(),
+        patch_target: entry_list.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
+async fn trash_page_handler(
+    _: bitsync_routes::GetTrashPage,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    match list_trash_entries(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(entries) => Html(
+            TrashPage {
+                entry_list: entries.into(),
+            }
+            .render(),
+        ),
+        Err(error) => Html(ErrorPage::from(error).render()),
+    }
+}
+
+async fn trash_entry_restore_handler(
+    path: bitsync_routes::PostTrashEntryRestore,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    match restore_trash_entry(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+        &path.trash_entry_id,
+    )
+    .await
+    {
+        Ok(result) => trash_entry_list_response(result.trash_entries.into()),
+        Err(RestoreTrashEntryError::TrashEntryNotFound(..)) => {
+            user_error_toast_response("The item is no longer in the trash")
+        }
+        Err(error) => {
+            emit_error(error);
+
+            internal_server_error_toast_response()
+        }
+    }
+}
+
+async fn trash_empty_dialog_handler(_: bitsync_routes::GetTrashEmptyDialog) -> impl IntoResponse {
+    let dialog = ConfirmationDialog {
+        title: "Empty trash".to_owned(),
+        message:
+            "This will permanently delete all items in the trash. This action cannot be undone."
+                .to_owned(),
+        confirm_label: "Empty trash".to_owned(),
+        action_url: bitsync_routes::PostTrashEmpty.to_string(),
+        is_danger: true,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+}
+
+async fn trash_empty_handler(
+    _: bitsync_routes::PostTrashEmpty,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    match empty_trash(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(()) => trash_entry_list_response(TrashEntryList {
+            entries: Vec::new(),
+        }),
+        Err(error) => {
+            emit_error(error);
+
+            internal_server_error_toast_response()
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Response},
+};
+use axum_extra::routing::RouterExt;
+use base64::Engine;
+use bitsync_core::use_case::{
+    upload_session::{
+        append_upload_session_chunk::{AppendUploadSessionChunkError, append_upload_session_chunk},
+        create_upload_session::{
+            CreateUploadSessionError, UploadSessionCreation, create_upload_session,
+        },
+        delete_upload_session::{DeleteUploadSessionError, delete_upload_session},
+        read_upload_session::{ReadUploadSessionError, read_upload_session},
+        shared::FinalizeUploadSessionError,
+    },
+    user_files::shared::UploadPolicy,
+};
+use bitsync_storage::operation::{read::ReadStorageItemError, upload::WriteStagedUploadChunkError};
+
+use crate::{
+    AppState,
+    auth::{AuthData, require_login_and_totp_setup_middleware},
+    error_report::emit_error,
+    handler::RedirectHyperStim,
+};
+
+const TUS_VERSION: &str = "1.0.0";
+const OFFSET_OCTET_STREAM: &str = "application/offset+octet-stream";
+
+static TUS_RESUMABLE: HeaderName = HeaderName::from_static("tus-resumable");
+static TUS_VERSION_HEADER: HeaderName = HeaderName::from_static("tus-version");
+static UPLOAD_LENGTH: HeaderName = HeaderName::from_static("upload-length");
+static UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+static UPLOAD_METADATA: HeaderName = HeaderName::from_static("upload-metadata");
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .merge(
+            Router::new()
+                .typed_post(upload_session_create_handler)
+                .typed_head(upload_session_head_handler)
+                .typed_delete(upload_session_delete_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state.clone()),
+        )
+        .merge(
+            Router::new()
+                .typed_patch(upload_session_patch_handler)
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state),
+        )
+}
+
+fn tus_response(status: StatusCode) -> Response {
+    (
+        status,
+        [(TUS_RESUMABLE.clone(), HeaderValue::from_static(TUS_VERSION))],
+    )
+        .into_response()
+}
+
+fn tus_error_response(status: StatusCode, message: &'static str) -> Response {
+    (
+        status,
+        [(TUS_RESUMABLE.clone(), HeaderValue::from_static(TUS_VERSION))],
+        message,
+    )
+        .into_response()
+}
+
+fn tus_internal_server_error_response(
+    error: impl std::error::Error + Send + Sync + 'static,
+) -> Response {
+    emit_error(error);
+
+    tus_error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+}
+
+fn is_supported_tus_version(headers: &HeaderMap) -> bool {
+    headers
+        .get(&TUS_RESUMABLE)
+        .is_some_and(|value| value == TUS_VERSION)
+}
+
+fn tus_version_mismatch_response() -> Response {
+    (
+        StatusCode::PRECONDITION_FAILED,
+        [(
+            TUS_VERSION_HEADER.clone(),
+            HeaderValue::from_static(TUS_VERSION),
+        )],
+    )
+        .into_response()
+}
+
+fn parse_u64_header(headers: &HeaderMap, name: &HeaderName) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+/// Reads the `filename` entry of an `Upload-Metadata` header, whose values are base64 encoded.
+fn parse_upload_metadata_file_name(headers: &HeaderMap) -> Option<String> {
+    let metadata = headers.get(&UPLOAD_METADATA)?.to_str().ok()?;
+
+    metadata.split(',').find_map(|pair| {
+        let (key, value) = pair.trim().split_once(' ')?;
+
+        if key != "filename" {
+            return None;
+        }
+
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(value.trim())
+            .ok()?;
+
+        String::from_utf8(decoded).ok()
+    })
+}
+
+async fn upload_session_create_handler(
+    _: bitsync_routes::PostUserFileUploadSession,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileUploadSessionQueryParameters>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_supported_tus_version(&headers) {
+        return tus_version_mismatch_response();
+    }
+
+    let Some(upload_length) = parse_u64_header(&headers, &UPLOAD_LENGTH) else {
+        return tus_error_response(StatusCode::BAD_REQUEST, "Missing or invalid Upload-Length");
+    };
+
+    let Some(file_name) = parse_upload_metadata_file_name(&headers) else {
+        return tus_error_response(
+            StatusCode::BAD_REQUEST,
+            "Missing or invalid filename in Upload-Metadata",
+        );
+    };
+
+    match create_upload_session(
+        &app_state.database,
+        &app_state.storage_root,
+        &UploadPolicy::from(&app_state.config),
+        &auth_data.user,
+        UploadSessionCreation {
+            directory_path: &query_parameters.path,
+            file_name: &file_name,
+            upload_length,
+        },
+    )
+    .await
+    {
+        Ok(upload_session) => {
+            let location = bitsync_routes::PatchUserFileUploadSession {
+                upload_session_id: upload_session.id,
+            }
+            .to_string();
+
+            (
+                StatusCode::CREATED,
+                [
+                    (TUS_RESUMABLE.clone(), HeaderValue::from_static(TUS_VERSION)),
+                    (
+                        header::LOCATION,
+                        HeaderValue::from_str(&location)
+                            .expect("upload session location is a valid header value"),
+                    ),
+                ],
+            )
+                .into_response()
+        }
+        Err(CreateUploadSessionError::InvalidFileName(..)) => {
+            tus_error_response(StatusCode::BAD_REQUEST, "Invalid file name")
+        }
+        Err(CreateUploadSessionError::DestinationNotADirectory(..)) => {
+            tus_error_response(StatusCode::NOT_FOUND, "The destination is not a directory")
+        }
+        Err(CreateUploadSessionError::ReadStorageItem(ReadStorageItemError::Metadata(error)))
+            if error.source.kind() == std::io::ErrorKind::NotFound =>
+        {
+            tus_error_response(StatusCode::NOT_FOUND, "The destination does not exist")
+        }
+        Err(
+            CreateUploadSessionError::StorageQuotaExceeded(..)
+            | CreateUploadSessionError::FinalizeUploadSession(
+                FinalizeUploadSessionError::StorageQuotaExceeded(..),
+            ),
+        ) => tus_error_response(StatusCode::PAYLOAD_TOO_LARGE, "Storage quota exceeded"),
+        Err(CreateUploadSessionError::UploadSessionLimit(..)) => tus_error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many unfinished uploads, finish or cancel some of them first",
+        ),
+        Err(error) => tus_internal_server_error_response(error),
+    }
+}
+
+async fn upload_session_head_handler(
+    path: bitsync_routes::HeadUserFileUploadSession,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_supported_tus_version(&headers) {
+        return tus_version_mismatch_response();
+    }
+
+    match read_upload_session(
+        &app_state.database,
+        &auth_data.user,
+        &path.upload_session_id,
+    )
+    .await
+    {
+        Ok(upload_session) => (
+            StatusCode::OK,
+            [
+                (TUS_RESUMABLE.clone(), HeaderValue::from_static(TUS_VERSION)),
+                (
+                    UPLOAD_OFFSET.clone(),
+                    HeaderValue::from(upload_session.upload_offset),
+                ),
+                (
+                    UPLOAD_LENGTH.clone(),
+                    HeaderValue::from(upload_session.upload_length),
+                ),
+                (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+            ],
+        )
+            .into_response(),
+        Err(ReadUploadSessionError::UploadSessionNotFound(..)) => {
+            tus_response(StatusCode::NOT_FOUND)
+        }
+        Err(error) => tus_internal_server_error_response(error),
+    }
+}
+
+async fn upload_session_patch_handler(
+    path: bitsync_routes::PatchUserFileUploadSession,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    if !is_supported_tus_version(&headers) {
+        return tus_version_mismatch_response();
+    }
+
+    if headers
+        .get(header::CONTENT_TYPE)
+        .is_none_or(|content_type| content_type != OFFSET_OCTET_STREAM)
+    {
+        return tus_error_response(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Expected Content-Type application/offset+octet-stream",
+        );
+    }
+
+    let Some(upload_offset) = parse_u64_header(&headers, &UPLOAD_OFFSET) else {
+        return tus_error_response(StatusCode::BAD_REQUEST, "Missing or invalid Upload-Offset");
+    };
+
+    match append_upload_session_chunk(
+        &app_state.database,
+        &app_state.storage_root,
+        &UploadPolicy::from(&app_state.config),
+        &auth_data.user,
+        &path.upload_session_id,
+        upload_offset,
+        body.into_data_stream(),
+    )
+    .await
+    {
+        Ok(result) => (
+            StatusCode::NO_CONTENT,
+            [
+                (TUS_RESUMABLE.clone(), HeaderValue::from_static(TUS_VERSION)),
+                (
+                    UPLOAD_OFFSET.clone(),
+                    HeaderValue::from(result.upload_offset),
+                ),
+            ],
+        )
+            .into_response(),
+        Err(AppendUploadSessionChunkError::UploadSessionNotFound(..)) => {
+            tus_response(StatusCode::NOT_FOUND)
+        }
+        Err(AppendUploadSessionChunkError::UploadOffsetMismatch(..)) => {
+            tus_error_response(StatusCode::CONFLICT, "Upload-Offset does not match")
+        }
+        Err(AppendUploadSessionChunkError::UploadSessionBusy(..)) => tus_error_response(
+            StatusCode::CONFLICT,
+            "Another chunk is being appended to this upload",
+        ),
+        Err(AppendUploadSessionChunkError::WriteStagedUploadChunk(
+            WriteStagedUploadChunkError::SizeLimitExceeded { .. },
+        )) => tus_error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "The chunk exceeds the declared Upload-Length",
+        ),
+        Err(AppendUploadSessionChunkError::FinalizeUploadSession(
+            FinalizeUploadSessionError::StorageQuotaExceeded(..),
+        )) => tus_error_response(StatusCode::PAYLOAD_TOO_LARGE, "Storage quota exceeded"),
+        Err(error) => tus_internal_server_error_response(error),
+    }
+}
+
+async fn upload_session_delete_handler(
+    path: bitsync_routes::DeleteUserFileUploadSession,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_supported_tus_version(&headers) {
+        return tus_version_mismatch_response();
+    }
+
+    match delete_upload_session(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user,
+        &path.upload_session_id,
+    )
+    .await
+    {
+        Ok(()) => tus_response(StatusCode::NO_CONTENT),
+        Err(DeleteUploadSessionError::UploadSessionNotFound(..)) => {
+            tus_response(StatusCode::NOT_FOUND)
+        }
+        Err(DeleteUploadSessionError::UploadSessionBusy(..)) => tus_error_response(
+            StatusCode::CONFLICT,
+            "A chunk is being appended to this upload",
+        ),
+        Err(error) => tus_internal_server_error_response(error),
+    }
+}
@@ -1,44 +1,68 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use axum::{
     Extension, Json, Router,
     extract::{FromRequest, Query, Request, State},
+    http::{HeaderMap, HeaderValue, header},
     middleware::from_fn_with_state,
     response::{IntoResponse, Response},
 };
 use axum_extra::{
-    body::AsyncReadBody,
     extract::{Form, Multipart, multipart::Field},
-    response::Attachment,
     routing::RouterExt,
 };
 use bitsync_core::use_case::{
     self,
+    file_version::shared::WriteVersionedFileStreamError,
     user_files::{
+        copy_user_file::{
+            CopyConflictPolicy, UserFileCopyError, UserFileCopyOutcome, copy_user_file,
+        },
         create_directory::UserFileDirecoryCreationError,
         delete_user_file::UserFileDeletionError,
-        download_user_file::UserFileDownloadError,
+        download_user_file::{DirectoryArchiveFormat, UserFileDownloadError},
         move_user_file::UserFileMoveError,
-        upload_user_file::{UserFileUploadError, upload_user_file},
+        rename_user_file::{UserFileRenameError, rename_user_file},
+        shared::{
+            ConflictPolicy, CreateDirectoriesError, ResolveDestinationConflictError, UploadPolicy,
+        },
+        thumbnail_user_file::{UserFileThumbnailError, thumbnail_user_file},
+        upload_user_file::{UserFileUpload, UserFileUploadError, upload_user_file},
+        view_user_file::{UserFileViewError, view_user_file},
+    },
+    user_share::{
+        share_with_user::{ShareWithUserError, share_with_user},
+        update_user_share::{
+            UpdateUserShareError, UserSharePasswordChange, UserShareRestrictions, update_user_share,
+        },
     },
 };
+use bitsync_database::entity::UserSharePermission;
 use bitsync_frontend::{
     Component, DIALOG_WRAPPER_SELECTOR, Render,
-    components::user_settings::shares::file_share::{FileShareDialog, ShareDialogBody},
+    components::user_settings::shares::file_share::{
+        FileShareDialog, ShareDialogBody, ShareDisplayError,
+    },
     pages::files::{
-        FilesHomePageChangeResult,
+        FilesHomePageChangeResult, FilesHomePageElementId,
+        conflict_resolution::{ConflictResolutionDialog, ConflictRetry},
         directory_creation::{
             DirectoryCreationDialog, DirectoryCreationDisplayError, DirectoryCreationForm,
         },
+        file_copy::FileCopyDialog,
+        file_download::FileDownloadDialog,
         file_move::FileMoveDialog,
         file_operations::{
-            UserFileDeletionDisplayError, UserFileDownloadDisplayError, UserFileMoveDisplayError,
-            UserFileUploadDisplayError,
+            UserFileCopyDisplayError, UserFileDeletionDisplayError, UserFileDownloadDisplayError,
+            UserFileMoveDisplayError, UserFileRenameDisplayError, UserFileThumbnailDisplayError,
+            UserFileUploadDisplayError, UserFileViewDisplayError,
         },
     },
+    toast::{TOAST_CONTAINER_SELECTOR, Toast},
 };
 use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
 use bitsync_routes::TypedPath;
+use bitsync_storage::operation::{read::ReadStorageItemError, write::WriteFileStreamError};
 use serde::Deserialize;
 
 use crate::{
@@ -46,8 +70,9 @@ use crate::{
     auth::{AuthData, require_login_and_totp_setup_middleware},
     error_report::emit_error,
     handler::{
-        RedirectHttp, RedirectHyperStim, internal_server_error_toast_response,
-        user_error_toast_response,
+        RedirectHttp, RedirectHyperStim,
+        file_download::{FileDisposition, file_download_response},
+        internal_server_error_toast_response, user_error_toast_response,
     },
 };
 
@@ -65,6 +90,8 @@ pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
         .merge(
             Router::new()
                 .typed_get(user_file_download_handler)
+                .typed_get(user_file_view_handler)
+                .typed_get(user_file_thumbnail_handler)
                 .route_layer(axum::middleware::from_fn(
                     crate::body_limit::request_body_size_limit,
                 ))
@@ -79,12 +106,18 @@ pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
                 .typed_get(user_file_delete_handler)
                 .typed_post(user_file_move_handler)
                 .typed_get(user_file_move_dialog_handler)
+                .typed_get(user_file_download_dialog_handler)
+                .typed_post(user_file_rename_handler)
+                .typed_post(user_file_copy_handler)
+                .typed_get(user_file_copy_dialog_handler)
                 .typed_post(user_file_directory_creation_handler)
                 .typed_get(user_file_directory_creation_dialog_handler)
                 .typed_get(user_file_share_dialog_handler)
                 .typed_post(user_file_share_create_handler)
                 .typed_post(user_file_share_delete_handler)
                 .typed_post(user_file_share_delete_all_handler)
+                .typed_post(user_file_share_update_handler)
+                .typed_post(user_file_share_with_user_handler)
                 .route_layer(axum::middleware::from_fn(
                     crate::body_limit::request_body_size_limit,
                 ))
@@ -96,7 +129,7 @@ pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
         )
 }
 
-struct UserFileMultipartField {
+pub(super) struct UserFileMultipartField {
     pub field: Field,
     pub file_name: String,
 }
@@ -139,38 +172,175 @@ where
     }
 }
 
+/// Lets an operation be repeated with an explicit conflict policy. Without one, name
+/// collisions fail.
+#[derive(Deserialize)]
+struct ConflictPolicyQueryParameters {
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+}
+
+fn conflict_resolution_response(dialog: ConflictResolutionDialog) -> Response {
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
 async fn user_file_upload_handler(
     _: bitsync_routes::PostUserFileUpload,
     State(app_state): State<Arc<AppState>>,
     Extension(auth_data): Extension<AuthData>,
     query_parameters: Query<bitsync_routes::PostUserFileUploadQueryParameters>,
-    multipart_data: UserFileMultipartField,
+    Query(ConflictPolicyQueryParameters { conflict_policy }): Query<ConflictPolicyQueryParameters>,
+    mut multipart_data: Multipart,
 ) -> impl IntoResponse {
-    match upload_user_file(
-        &app_state.config.fs_storage_root_dir,
+    let upload_policy = UploadPolicy::from(&app_state.config);
+    let mut upload_result = None;
+    let mut conflicting_paths = Vec::new();
+    let mut upload_error = None;
+
+    // every field is a file, folder uploads name their files by the path below the folder
+    loop {
+        let multipart_field = match multipart_data.next_field().await {
+            Ok(Some(multipart_field)) => multipart_field,
+            Ok(None) => break,
+            Err(error) => return error.body_text().into_response(),
+        };
+
+        let relative_path = match multipart_field.file_name() {
+            Some("") => continue,
+            Some(relative_path) => relative_path.to_owned(),
+            None => {
+                upload_error = Some(UserFileUploadDisplayError::NoFileNameProvided);
+                break;
+            }
+        };
+
+        match upload_user_file(
+            &app_state.database,
+            &app_state.storage_root,
+            &upload_policy,
+            &query_parameters.path,
+            &auth_data.user,
+            UserFileUpload {
+                relative_path: &relative_path,
+                stream: multipart_field,
+                conflict_policy,
+            },
+        )
+        .await
+        {
+            Ok(result) => upload_result = Some(result),
+            Err(UserFileUploadError::ResolveDestinationConflict(
+                ResolveDestinationConflictError::DestinationExists(..),
+            )) => conflicting_paths.push(relative_path),
+            Err(error) => {
+                upload_error = Some(match error {
+                    UserFileUploadError::StoragePath(..)
+                    | UserFileUploadError::InvalidRelativePath(..) => {
+                        UserFileUploadDisplayError::InvalidPath
+                    }
+                    UserFileUploadError::CreateDirectories(
+                        CreateDirectoriesError::ParentNotADirectory(..),
+                    ) => UserFileUploadDisplayError::ParentNotADirectory,
+                    UserFileUploadError::WriteVersionedFileStream(
+                        WriteVersionedFileStreamError::WriteFileStream(
+                            WriteFileStreamError::SizeLimitExceeded(..),
+                        ),
+                    ) => UserFileUploadDisplayError::QuotaExceeded,
+                    error => {
+                        emit_error(error);
+                        UserFileUploadDisplayError::InternalServerError
+                    }
+                });
+                break;
+            }
+        }
+    }
+
+    if upload_result.is_none() && conflicting_paths.is_empty() && upload_error.is_none() {
+        upload_error = Some(UserFileUploadDisplayError::NoFileProvided);
+    }
+
+    let mut commands = Vec::new();
+
+    if let Some(result) = upload_result {
+        let files_component = FilesHomePageChangeResult::from(result);
+
+        commands.push(HyperStimCommand::HsPatchHtml {
+            html: files_component.render(),
+            patch_target: files_component.id_target(),
+            patch_mode: HyperStimPatchMode::Outer,
+        });
+    }
+
+    if let Some(upload_error) = upload_error {
+        commands.push(HyperStimCommand::HsPatchHtml {
+            html: Toast::error(upload_error.message().to_owned()).render(),
+            patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+            patch_mode: HyperStimPatchMode::Append,
+        });
+    } else if !conflicting_paths.is_empty() {
+        let message = match conflicting_paths.len() {
+            1 => UserFileUploadDisplayError::DestinationExists,
+            _ => UserFileUploadDisplayError::DestinationsExist,
+        }
+        .message();
+        let dialog = ConflictResolutionDialog {
+            message,
+            item_names: conflicting_paths,
+            retry: ConflictRetry::ExistingForm {
+                form_id: FilesHomePageElementId::FileUploadForm.to_str(),
+            },
+        };
+
+        commands.push(HyperStimCommand::HsPatchHtml {
+            html: dialog.render(),
+            patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+            patch_mode: HyperStimPatchMode::Append,
+        });
+    }
+
+    Json(commands).into_response()
+}
+
+async fn user_file_download_handler(
+    _: bitsync_routes::GetUserFileDownload,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::GetUserFileDownloadQueryParameters>,
+    request_headers: HeaderMap,
+) -> impl IntoResponse {
+    match use_case::user_files::download_user_file::download_user_file(
+        &app_state.database,
+        &app_state.storage_root,
         &query_parameters.path,
+        directory_archive_format(query_parameters.archive_format.unwrap_or_default()),
         &auth_data.user,
-        &multipart_data.file_name,
-        multipart_data.field,
     )
     .await
     {
         Ok(result) => {
-            let files_component = FilesHomePageChangeResult::from(result);
+            match file_download_response(&request_headers, result, FileDisposition::Attachment)
+                .await
+            {
+                Ok(response) => response,
+                Err(error) => {
+                    emit_error(error);
 
-            Json(HyperStimCommand::HsPatchHtml {
-                html: files_component.render(),
-                patch_target: files_component.id_target(),
-                patch_mode: HyperStimPatchMode::Outer,
-            })
-            .into_response()
+                    internal_server_error_toast_response()
+                }
+            }
         }
         Err(error) => {
             let display_error = match error {
-                UserFileUploadError::StoragePath(..) => UserFileUploadDisplayError::InvalidPath,
+                UserFileDownloadError::StoragePath(..) => UserFileDownloadDisplayError::InvalidPath,
                 error => {
                     emit_error(error);
-                    UserFileUploadDisplayError::InternalServerError
+                    UserFileDownloadDisplayError::InternalServerError
                 }
             };
 
@@ -179,32 +349,93 @@ async fn user_file_upload_handler(
     }
 }
 
-async fn user_file_download_handler(
-    _: bitsync_routes::GetUserFileDownload,
+async fn user_file_view_handler(
+    _: bitsync_routes::GetUserFileView,
     State(app_state): State<Arc<AppState>>,
     Extension(auth_data): Extension<AuthData>,
-    query_parameters: Query<bitsync_routes::GetUserFileDownloadQueryParameters>,
+    query_parameters: Query<bitsync_routes::GetUserFileViewQueryParameters>,
+    request_headers: HeaderMap,
 ) -> impl IntoResponse {
-    match use_case::user_files::download_user_file::download_user_file(
-        &app_state.config.fs_storage_root_dir,
+    match view_user_file(
+        &app_state.database,
+        &app_state.storage_root,
         &query_parameters.path,
         &auth_data.user,
     )
     .await
     {
         Ok(result) => {
-            let content_type = headers::ContentType::from(result.mime);
-            let stream_body = AsyncReadBody::new(result.file);
-            let attachment = Attachment::new(stream_body).filename(result.path.file_name());
+            match file_download_response(&request_headers, result, FileDisposition::Inline).await {
+                Ok(response) => response,
+                Err(error) => {
+                    emit_error(error);
 
-            (axum_extra::TypedHeader(content_type), attachment).into_response()
+                    internal_server_error_toast_response()
+                }
+            }
         }
         Err(error) => {
             let display_error = match error {
-                UserFileDownloadError::StoragePath(..) => UserFileDownloadDisplayError::InvalidPath,
+                UserFileViewError::StoragePath(..) => UserFileViewDisplayError::InvalidPath,
+                UserFileViewError::NotAFile(..) => UserFileViewDisplayError::NotAFile,
+                UserFileViewError::ReadStorageItem(..) => UserFileViewDisplayError::NotFound,
                 error => {
                     emit_error(error);
-                    UserFileDownloadDisplayError::InternalServerError
+                    UserFileViewDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
+
+async fn user_file_thumbnail_handler(
+    _: bitsync_routes::GetUserFileThumbnail,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::GetUserFileThumbnailQueryParameters>,
+    request_headers: HeaderMap,
+) -> impl IntoResponse {
+    match thumbnail_user_file(
+        &app_state.storage_root,
+        &app_state.thumbnail_creation_permits,
+        &query_parameters.path,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(result) => {
+            match file_download_response(&request_headers, result, FileDisposition::Inline).await {
+                Ok(mut response) => {
+                    // the thumbnail of a modified image is served under the same url
+                    response.headers_mut().insert(
+                        header::CACHE_CONTROL,
+                        HeaderValue::from_static("private, no-cache"),
+                    );
+
+                    response
+                }
+                Err(error) => {
+                    emit_error(error);
+
+                    internal_server_error_toast_response()
+                }
+            }
+        }
+        Err(error) => {
+            let display_error = match error {
+                UserFileThumbnailError::StoragePath(..) => {
+                    UserFileThumbnailDisplayError::InvalidPath
+                }
+                UserFileThumbnailError::UnsupportedSource(..)
+                | UserFileThumbnailError::Image(..) => UserFileThumbnailDisplayError::Unsupported,
+                UserFileThumbnailError::ReadStorageItem(..) => {
+                    UserFileThumbnailDisplayError::NotFound
+                }
+                error => {
+                    emit_error(error);
+                    UserFileThumbnailDisplayError::InternalServerError
                 }
             };
 
@@ -213,6 +444,35 @@ async fn user_file_download_handler(
     }
 }
 
+fn directory_archive_format(
+    archive_format: bitsync_routes::ArchiveFormat,
+) -> DirectoryArchiveFormat {
+    match archive_format {
+        bitsync_routes::ArchiveFormat::Zip => DirectoryArchiveFormat::Zip,
+        bitsync_routes::ArchiveFormat::ZipDeflate => DirectoryArchiveFormat::ZipDeflate,
+        bitsync_routes::ArchiveFormat::ZipZstd => DirectoryArchiveFormat::ZipZstd,
+        bitsync_routes::ArchiveFormat::TarGz => DirectoryArchiveFormat::TarGz,
+        bitsync_routes::ArchiveFormat::TarZst => DirectoryArchiveFormat::TarZst,
+    }
+}
+
+async fn user_file_download_dialog_handler(
+    _: bitsync_routes::GetUserFileDownloadDialog,
+    query_parameters: Query<bitsync_routes::GetUserFileDownloadDialogQueryParameters>,
+) -> impl IntoResponse {
+    let dialog = FileDownloadDialog {
+        action_url: bitsync_routes::GetUserFileDownload.to_string(),
+        path: query_parameters.0.path,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
 async fn user_file_delete_handler(
     _: bitsync_routes::GetUserFileDelete,
     State(app_state): State<Arc<AppState>>,
@@ -220,7 +480,8 @@ async fn user_file_delete_handler(
     query_parameters: Query<bitsync_routes::GetUserFileDeleteQueryParameters>,
 ) -> impl IntoResponse {
     match use_case::user_files::delete_user_file::delete_user_file(
-        &app_state.config.fs_storage_root_dir,
+        &app_state.database,
+        &app_state.storage_root,
         &query_parameters.path,
         &auth_data.user,
     )
@@ -253,6 +514,8 @@ async fn user_file_delete_handler(
 #[derive(Deserialize)]
 struct MoveItemFormData {
     pub destination_path: String,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
 }
 
 async fn user_file_move_handler(
@@ -260,20 +523,25 @@ async fn user_file_move_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_data): Extension<AuthData>,
     query_parameters: Query<bitsync_routes::PostUserFileMoveQueryParameters>,
-    Form(MoveItemFormData { destination_path }): Form<MoveItemFormData>,
+    Form(MoveItemFormData {
+        destination_path,
+        conflict_policy,
+    }): Form<MoveItemFormData>,
 ) -> impl IntoResponse {
     match use_case::user_files::move_user_file::move_user_file(
-        &app_state.config.fs_storage_root_dir,
+        &app_state.database,
+        &app_state.storage_root,
         &query_parameters.path,
         &destination_path,
+        conflict_policy,
         &auth_data.user,
     )
     .await
     {
         Ok(result) => {
             let files_component = FilesHomePageChangeResult::from(result);
-            let dialog_id =
-                bitsync_frontend::pages::files::FilesHomePageElementId::FileMoveDialog.to_str();
+            let dialog_id = FilesHomePageElementId::FileMoveDialog.to_str();
+            let conflict_dialog_id = FilesHomePageElementId::ConflictResolutionDialog.to_str();
 
             Json(vec![
                 HyperStimCommand::HsPatchHtml {
@@ -283,7 +551,7 @@ async fn user_file_move_handler(
                 },
                 HyperStimCommand::HsExecute {
                     code: format!(
-                        "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}'))"
+                        "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}')), document.querySelectorAll('#{conflict_dialog_id}').forEach(closeClosestDialogAndRemoveElement)"
                     ),
                 },
             ])
@@ -295,6 +563,36 @@ async fn user_file_move_handler(
                 UserFileMoveError::DestinationSameAsSource(..) => {
                     UserFileMoveDisplayError::DestinationSameAsSource
                 }
+                UserFileMoveError::DestinationInsideSource(..) => {
+                    UserFileMoveDisplayError::DestinationInsideSource
+                }
+                UserFileMoveError::SourceInsideDestination(..) => {
+                    UserFileMoveDisplayError::SourceInsideDestination
+                }
+                UserFileMoveError::ResolveDestinationConflict(
+                    ResolveDestinationConflictError::DestinationExists(..),
+                ) => {
+                    let action_url = bitsync_routes::PostUserFileMove
+                        .with_query_params(bitsync_routes::PostUserFileMoveQueryParameters {
+                            path: query_parameters.path.clone(),
+                        })
+                        .to_string();
+
+                    return conflict_resolution_response(ConflictResolutionDialog {
+                        message: UserFileMoveDisplayError::DestinationExists.message(),
+                        item_names: vec![
+                            Path::new(&destination_path)
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy()
+                                .to_string(),
+                        ],
+                        retry: ConflictRetry::Form {
+                            action_url,
+                            fields: vec![("destination_path", destination_path)],
+                        },
+                    });
+                }
                 error => {
                     emit_error(error);
                     UserFileMoveDisplayError::InternalServerError
@@ -306,9 +604,160 @@ async fn user_file_move_handler(
     }
 }
 
+#[derive(Deserialize)]
+struct RenameItemFormData {
+    pub new_file_name: String,
+}
+
+async fn user_file_rename_handler(
+    _: bitsync_routes::PostUserFileRename,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileRenameQueryParameters>,
+    Form(RenameItemFormData { new_file_name }): Form<RenameItemFormData>,
+) -> impl IntoResponse {
+    match rename_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &query_parameters.path,
+        new_file_name.trim(),
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(result) => {
+            let files_component = FilesHomePageChangeResult::from(result);
+
+            Json(HyperStimCommand::HsPatchHtml {
+                html: files_component.render(),
+                patch_target: files_component.id_target(),
+                patch_mode: HyperStimPatchMode::Outer,
+            })
+            .into_response()
+        }
+        Err(error) => {
+            let display_error = match error {
+                UserFileRenameError::EmptyFileName(..) => UserFileRenameDisplayError::EmptyFileName,
+                UserFileRenameError::InvalidFileName(..) | UserFileRenameError::StoragePath(..) => {
+                    UserFileRenameDisplayError::InvalidFileName
+                }
+                UserFileRenameError::RootNotRenamable(..) => {
+                    UserFileRenameDisplayError::RootNotRenamable
+                }
+                UserFileRenameError::ReadStorageItem(ReadStorageItemError::Metadata(error))
+                    if error.source.kind() == std::io::ErrorKind::NotFound =>
+                {
+                    UserFileRenameDisplayError::ItemNotFound
+                }
+                UserFileRenameError::DestinationExists(..) => {
+                    UserFileRenameDisplayError::DestinationExists
+                }
+                error => {
+                    emit_error(error);
+                    UserFileRenameDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CopyItemFormData {
+    pub destination_path: String,
+    pub conflict_policy: CopyConflictPolicy,
+}
+
+async fn user_file_copy_handler(
+    _: bitsync_routes::PostUserFileCopy,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileCopyQueryParameters>,
+    Form(CopyItemFormData {
+        destination_path,
+        conflict_policy,
+    }): Form<CopyItemFormData>,
+) -> impl IntoResponse {
+    match copy_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        app_state.config.default_user_storage_quota,
+        &query_parameters.path,
+        &destination_path,
+        conflict_policy,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(result) => {
+            let skipped_toast = match result.outcome {
+                UserFileCopyOutcome::Copied { .. } => None,
+                UserFileCopyOutcome::Skipped => Some(Toast::success(
+                    "An item already exists at the copy path, nothing was copied",
+                )),
+            };
+            let files_component = FilesHomePageChangeResult::from(result);
+            let dialog_id =
+                bitsync_frontend::pages::files::FilesHomePageElementId::FileCopyDialog.to_str();
+
+            let mut commands = vec![
+                HyperStimCommand::HsPatchHtml {
+                    html: files_component.render(),
+                    patch_target: files_component.id_target(),
+                    patch_mode: HyperStimPatchMode::Outer,
+                },
+                HyperStimCommand::HsExecute {
+                    code: format!(
+                        "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}'))"
+                    ),
+                },
+            ];
+
+            if let Some(toast) = skipped_toast {
+                commands.push(HyperStimCommand::HsPatchHtml {
+                    html: toast.render(),
+                    patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+                    patch_mode: HyperStimPatchMode::Append,
+                });
+            }
+
+            Json(commands).into_response()
+        }
+        Err(error) => {
+            let display_error = match error {
+                UserFileCopyError::StoragePath(..) => UserFileCopyDisplayError::InvalidPath,
+                UserFileCopyError::DestinationSameAsSource(..) => {
+                    UserFileCopyDisplayError::DestinationSameAsSource
+                }
+                UserFileCopyError::DestinationInsideSource(..) => {
+                    UserFileCopyDisplayError::DestinationInsideSource
+                }
+                UserFileCopyError::SourceInsideDestination(..) => {
+                    UserFileCopyDisplayError::SourceInsideDestination
+                }
+                UserFileCopyError::DestinationExists(..) => {
+                    UserFileCopyDisplayError::DestinationExists
+                }
+                UserFileCopyError::StorageQuotaExceeded(..) => {
+                    UserFileCopyDisplayError::QuotaExceeded
+                }
+                error => {
+                    emit_error(error);
+                    UserFileCopyDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct AddDirectoryFormData {
     pub directory_name: String,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
 }
 
 async fn user_file_directory_creation_handler(
@@ -316,21 +765,25 @@ async fn user_file_directory_creation_handler(
     State(app_state): State<Arc<AppState>>,
     Extension(auth_data): Extension<AuthData>,
     query_parameters: Query<bitsync_routes::PostUserFileDirectoryCreationQueryParameters>,
-    Form(AddDirectoryFormData { directory_name }): Form<AddDirectoryFormData>,
+    Form(AddDirectoryFormData {
+        directory_name,
+        conflict_policy,
+    }): Form<AddDirectoryFormData>,
 ) -> impl IntoResponse {
     match use_case::user_files::create_directory::create_direcory(
-        &app_state.config.fs_storage_root_dir,
+        &app_state.database,
+        &app_state.storage_root,
         &query_parameters.path,
         &directory_name,
+        conflict_policy,
         &auth_data.user,
     )
     .await
     {
         Ok(result) => {
             let files_component = FilesHomePageChangeResult::from(result);
-            let dialog_id =
-                bitsync_frontend::pages::files::FilesHomePageElementId::DirectoryCreationDialog
-                    .to_str();
+            let dialog_id = FilesHomePageElementId::DirectoryCreationDialog.to_str();
+            let conflict_dialog_id = FilesHomePageElementId::ConflictResolutionDialog.to_str();
 
             Json(vec![
                 HyperStimCommand::HsPatchHtml {
@@ -340,7 +793,7 @@ async fn user_file_directory_creation_handler(
                 },
                 HyperStimCommand::HsExecute {
                     code: format!(
-                        "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}'))"
+                        "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}')), document.querySelectorAll('#{conflict_dialog_id}').forEach(closeClosestDialogAndRemoveElement)"
                     ),
                 },
             ])
@@ -357,6 +810,26 @@ async fn user_file_directory_creation_handler(
                 UserFileDirecoryCreationError::StoragePath(..) => {
                     DirectoryCreationDisplayError::InvalidPath
                 }
+                UserFileDirecoryCreationError::ResolveDestinationConflict(
+                    ResolveDestinationConflictError::DestinationExists(..),
+                ) => {
+                    let action_url = bitsync_routes::PostUserFileDirectoryCreation
+                        .with_query_params(
+                            bitsync_routes::PostUserFileDirectoryCreationQueryParameters {
+                                path: query_parameters.path.clone(),
+                            },
+                        )
+                        .to_string();
+
+                    return conflict_resolution_response(ConflictResolutionDialog {
+                        message: DirectoryCreationDisplayError::DestinationExists.message(),
+                        item_names: vec![directory_name.clone()],
+                        retry: ConflictRetry::Form {
+                            action_url,
+                            fields: vec![("directory_name", directory_name)],
+                        },
+                    });
+                }
                 error => {
                     emit_error(error);
                     DirectoryCreationDisplayError::InternalServerError
@@ -432,6 +905,29 @@ async fn user_file_move_dialog_handler(
     .into_response()
 }
 
+async fn user_file_copy_dialog_handler(
+    _: bitsync_routes::GetUserFileCopyDialog,
+    query_parameters: Query<bitsync_routes::GetUserFileCopyDialogQueryParameters>,
+) -> impl IntoResponse {
+    let action_url = bitsync_routes::PostUserFileCopy
+        .with_query_params(bitsync_routes::PostUserFileCopyQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let dialog = FileCopyDialog {
+        action_url,
+        source_path: query_parameters.path.clone(),
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
 async fn user_file_share_dialog_handler(
     _: bitsync_routes::GetUserFileShareDialog,
     State(app_state): State<Arc<AppState>>,
@@ -465,11 +961,18 @@ async fn user_file_share_dialog_handler(
         })
         .to_string();
 
+    let share_with_user_url = bitsync_routes::PostUserFileShareWithUser
+        .with_query_params(bitsync_routes::PostUserFileShareWithUserQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
     let dialog = FileShareDialog {
         user_shares,
         item_path: query_parameters.path.clone(),
         create_user_share_url,
         delete_all_user_shares_url,
+        share_with_user_url,
     };
 
     Json(HyperStimCommand::HsPatchHtml {
@@ -488,6 +991,7 @@ async fn user_file_share_create_handler(
 ) -> impl IntoResponse {
     let user_shares = match use_case::user_share::create_user_share::create_user_share(
         &app_state.database,
+        &app_state.storage_root,
         &auth_data.user.id,
         &query_parameters.path,
     )
@@ -513,11 +1017,18 @@ async fn user_file_share_create_handler(
         })
         .to_string();
 
+    let share_with_user_url = bitsync_routes::PostUserFileShareWithUser
+        .with_query_params(bitsync_routes::PostUserFileShareWithUserQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
     let share_dialog_body = ShareDialogBody {
         user_shares,
         item_path: query_parameters.path.clone(),
         create_user_share_url,
         delete_all_user_shares_url,
+        share_with_user_url,
         error: None,
     };
 
@@ -563,11 +1074,18 @@ async fn user_file_share_delete_handler(
         })
         .to_string();
 
+    let share_with_user_url = bitsync_routes::PostUserFileShareWithUser
+        .with_query_params(bitsync_routes::PostUserFileShareWithUserQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
     let share_dialog_body = ShareDialogBody {
         user_shares,
         item_path: query_parameters.path.clone(),
         create_user_share_url,
         delete_all_user_shares_url,
+        share_with_user_url,
         error: None,
     };
 
@@ -612,11 +1130,196 @@ async fn user_file_share_delete_all_handler(
         })
         .to_string();
 
+    let share_with_user_url = bitsync_routes::PostUserFileShareWithUser
+        .with_query_params(bitsync_routes::PostUserFileShareWithUserQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let share_dialog_body = ShareDialogBody {
+        user_shares,
+        item_path: query_parameters.path.clone(),
+        create_user_share_url,
+        delete_all_user_shares_url,
+        share_with_user_url,
+        error: None,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: share_dialog_body.render(),
+        patch_target: share_dialog_body.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
+static SHARE_EXPIRY_INPUT_FORMAT: &[time::format_description::BorrowedFormatItem<'static>] =
+    time::macros::format_description!("[year]-[month]-[day]T[hour]:[minute]");
+
+#[derive(Deserialize, Clone, Debug)]
+struct UserFileShareUpdateFormData {
+    expires_at: String,
+    max_downloads: String,
+    password: String,
+    remove_password: Option<String>,
+}
+
+async fn user_file_share_update_handler(
+    path: bitsync_routes::PostUserFileShareUpdate,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileShareUpdateQueryParameters>,
+    Form(form_data): Form<UserFileShareUpdateFormData>,
+) -> impl IntoResponse {
+    let expires_at = match form_data.expires_at.trim() {
+        "" => None,
+        expires_at => match time::PrimitiveDateTime::parse(expires_at, SHARE_EXPIRY_INPUT_FORMAT) {
+            Ok(expires_at) => Some(expires_at.assume_utc()),
+            Err(_) => {
+                return user_error_toast_response(ShareDisplayError::InvalidExpiry.message());
+            }
+        },
+    };
+
+    let max_downloads = match form_data.max_downloads.trim() {
+        "" => None,
+        max_downloads => match max_downloads.parse::<i32>() {
+            Ok(max_downloads) => Some(max_downloads),
+            Err(_) => {
+                return user_error_toast_response(ShareDisplayError::InvalidMaxDownloads.message());
+            }
+        },
+    };
+
+    let password = match (form_data.remove_password, form_data.password) {
+        (Some(_), _) => UserSharePasswordChange::Remove,
+        (None, password) if !password.is_empty() => UserSharePasswordChange::Set(password),
+        (None, _) => UserSharePasswordChange::Keep,
+    };
+
+    let user_shares = match update_user_share(
+        &app_state.database,
+        &auth_data.user.id,
+        &path.user_share_id,
+        &query_parameters.path,
+        UserShareRestrictions {
+            expires_at,
+            max_downloads,
+            password,
+        },
+    )
+    .await
+    {
+        Ok(user_shares) => user_shares,
+        Err(UpdateUserShareError::InvalidMaxDownloads(..)) => {
+            return user_error_toast_response(ShareDisplayError::InvalidMaxDownloads.message());
+        }
+        Err(UpdateUserShareError::UserShareNotFound(..)) => {
+            return user_error_toast_response(ShareDisplayError::NotFound.message());
+        }
+        Err(error) => {
+            emit_error(error);
+
+            return internal_server_error_toast_response();
+        }
+    };
+
+    let create_user_share_url = bitsync_routes::PostUserFileShareCreate
+        .with_query_params(bitsync_routes::PostUserFileShareCreateQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let delete_all_user_shares_url = bitsync_routes::PostUserFileShareDeleteAll
+        .with_query_params(bitsync_routes::PostUserFileShareDeleteAllQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let share_with_user_url = bitsync_routes::PostUserFileShareWithUser
+        .with_query_params(bitsync_routes::PostUserFileShareWithUserQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let share_dialog_body = ShareDialogBody {
+        user_shares,
+        item_path: query_parameters.path.clone(),
+        create_user_share_url,
+        delete_all_user_shares_url,
+        share_with_user_url,
+        error: None,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: share_dialog_body.render(),
+        patch_target: share_dialog_body.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct UserFileShareWithUserFormData {
+    username: String,
+    permission: UserSharePermission,
+}
+
+async fn user_file_share_with_user_handler(
+    _: bitsync_routes::PostUserFileShareWithUser,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileShareWithUserQueryParameters>,
+    Form(form_data): Form<UserFileShareWithUserFormData>,
+) -> impl IntoResponse {
+    let user_shares = match share_with_user(
+        &app_state.database,
+        &app_state.storage_root,
+        &auth_data.user.id,
+        &query_parameters.path,
+        &form_data.username,
+        form_data.permission,
+    )
+    .await
+    {
+        Ok(user_shares) => user_shares,
+        Err(ShareWithUserError::RecipientNotFound(..)) => {
+            return user_error_toast_response(ShareDisplayError::RecipientNotFound.message());
+        }
+        Err(ShareWithUserError::RecipientIsOwner(..)) => {
+            return user_error_toast_response(ShareDisplayError::RecipientIsOwner.message());
+        }
+        Err(error) => {
+            emit_error(error);
+
+            return internal_server_error_toast_response();
+        }
+    };
+
+    let create_user_share_url = bitsync_routes::PostUserFileShareCreate
+        .with_query_params(bitsync_routes::PostUserFileShareCreateQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let delete_all_user_shares_url = bitsync_routes::PostUserFileShareDeleteAll
+        .with_query_params(bitsync_routes::PostUserFileShareDeleteAllQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    let share_with_user_url = bitsync_routes::PostUserFileShareWithUser
+        .with_query_params(bitsync_routes::PostUserFileShareWithUserQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
     let share_dialog_body = ShareDialogBody {
         user_shares,
         item_path: query_parameters.path.clone(),
         create_user_share_url,
         delete_all_user_shares_url,
+        share_with_user_url,
         error: None,
     };
 
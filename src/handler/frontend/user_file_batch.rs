@@ -0,0 +1,622 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Response},
+};
+use axum_extra::{body::AsyncReadBody, extract::Form, response::Attachment, routing::RouterExt};
+use bitsync_core::use_case::{
+    user_files::{
+        batch_copy_user_files::{BatchUserFileCopy, BatchUserFileCopyError, batch_copy_user_files},
+        batch_delete_user_files::batch_delete_user_files,
+        batch_download_user_files::{BatchUserFileDownloadError, batch_download_user_files},
+        batch_move_user_files::{BatchUserFileMoveError, batch_move_user_files},
+        copy_user_file::{CopyConflictPolicy, UserFileCopyError, UserFileCopyOutcome},
+        delete_user_file::UserFileDeletionError,
+        move_user_file::UserFileMoveError,
+        shared::{BatchItemResult, ConflictPolicy, ResolveDestinationConflictError},
+        stat_user_file::StatUserFileError,
+    },
+    user_share::batch_create_user_shares::{
+        BatchUserShareCreationItemError, batch_create_user_shares,
+    },
+};
+use bitsync_frontend::{
+    Component, DIALOG_WRAPPER_SELECTOR, Render,
+    pages::files::{
+        FilesHomePageChangeResult, FilesHomePageElementId,
+        batch_operations::{
+            BatchResultDialog, BatchResultItem, BatchResultStatus, BatchTransferDialog,
+            BatchTransferKind,
+        },
+        file_operations::{
+            BatchOperationDisplayError, UserFileCopyDisplayError, UserFileDeletionDisplayError,
+            UserFileMoveDisplayError,
+        },
+    },
+    toast::{TOAST_CONTAINER_SELECTOR, Toast},
+};
+use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
+use bitsync_routes::TypedPath;
+use bitsync_storage::operation::read::ReadStorageItemError;
+use serde::Deserialize;
+
+use crate::{
+    AppState,
+    auth::{AuthData, require_login_and_totp_setup_middleware},
+    error_report::emit_error,
+    handler::{
+        RedirectHttp, RedirectHyperStim, internal_server_error_toast_response,
+        user_error_toast_response,
+    },
+};
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .merge(
+            Router::new()
+                .typed_post(user_file_batch_download_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHttp>,
+                ))
+                .with_state(state.clone()),
+        )
+        .merge(
+            Router::new()
+                .typed_post(user_file_batch_delete_handler)
+                .typed_post(user_file_batch_move_dialog_handler)
+                .typed_post(user_file_batch_move_handler)
+                .typed_post(user_file_batch_copy_dialog_handler)
+                .typed_post(user_file_batch_copy_handler)
+                .typed_post(user_file_batch_share_handler)
+                .route_layer(axum::middleware::from_fn(
+                    crate::body_limit::request_body_size_limit,
+                ))
+                .route_layer(from_fn_with_state(
+                    state.clone(),
+                    require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+                ))
+                .with_state(state),
+        )
+}
+
+#[derive(Deserialize)]
+struct BatchSelectionFormData {
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchMoveFormData {
+    #[serde(default)]
+    pub paths: Vec<String>,
+    pub destination_directory_path: String,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+}
+
+#[derive(Deserialize)]
+struct BatchCopyFormData {
+    #[serde(default)]
+    pub paths: Vec<String>,
+    pub destination_directory_path: String,
+    pub conflict_policy: CopyConflictPolicy,
+}
+
+fn nothing_selected_response() -> Response {
+    user_error_toast_response(BatchOperationDisplayError::NothingSelected.message())
+}
+
+fn item_count(count: usize) -> String {
+    match count {
+        1 => "1 item".to_owned(),
+        count => format!("{count} items"),
+    }
+}
+
+/// Replaces the file listing and reports the item results, as a toast if every item succeeded
+/// and as a result dialog otherwise.
+fn batch_outcome_response(
+    files_component: FilesHomePageChangeResult,
+    closed_dialog: Option<FilesHomePageElementId>,
+    action: &str,
+    items: Vec<BatchResultItem>,
+) -> Response {
+    let failed_count = items
+        .iter()
+        .filter(|item| matches!(item.status, BatchResultStatus::Failed { .. }))
+        .count();
+
+    let mut commands = vec![HyperStimCommand::HsPatchHtml {
+        html: files_component.render(),
+        patch_target: files_component.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    }];
+
+    if let Some(closed_dialog) = closed_dialog {
+        commands.push(HyperStimCommand::HsExecute {
+            code: format!(
+                "closeClosestDialogAndRemoveElement(document.getElementById('{dialog_id}'))",
+                dialog_id = closed_dialog.to_str(),
+            ),
+        });
+    }
+
+    if failed_count == 0 {
+        commands.push(HyperStimCommand::HsPatchHtml {
+            html: Toast::success(format!("{action} {}", item_count(items.len()))).render(),
+            patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+            patch_mode: HyperStimPatchMode::Append,
+        });
+    } else {
+        let dialog = BatchResultDialog {
+            title: format!(
+                "{action} {} of {}",
+                items.len() - failed_count,
+                item_count(items.len()),
+            ),
+            items,
+        };
+
+        commands.push(HyperStimCommand::HsPatchHtml {
+            html: dialog.render(),
+            patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+            patch_mode: HyperStimPatchMode::Append,
+        });
+    }
+
+    Json(commands).into_response()
+}
+
+fn result_item<T, E>(
+    item_result: BatchItemResult<T, E>,
+    status: impl FnOnce(T) -> BatchResultStatus,
+    failure_message: impl FnOnce(E) -> &'static str,
+) -> BatchResultItem {
+    let status = match item_result.result {
+        Ok(value) => status(value),
+        Err(error) => BatchResultStatus::Failed {
+            message: failure_message(error),
+        },
+    };
+
+    BatchResultItem {
+        path: item_result.path,
+        status,
+    }
+}
+
+fn deletion_failure_message(error: UserFileDeletionError) -> &'static str {
+    match error {
+        UserFileDeletionError::StoragePath(..) => UserFileDeletionDisplayError::InvalidPath,
+        error => {
+            emit_error(error);
+            UserFileDeletionDisplayError::InternalServerError
+        }
+    }
+    .message()
+}
+
+fn move_failure_message(error: UserFileMoveError) -> &'static str {
+    match error {
+        UserFileMoveError::StoragePath(..) => UserFileMoveDisplayError::InvalidPath,
+        UserFileMoveError::DestinationSameAsSource(..) => {
+            UserFileMoveDisplayError::DestinationSameAsSource
+        }
+        UserFileMoveError::DestinationInsideSource(..) => {
+            UserFileMoveDisplayError::DestinationInsideSource
+        }
+        UserFileMoveError::SourceInsideDestination(..) => {
+            UserFileMoveDisplayError::SourceInsideDestination
+        }
+        UserFileMoveError::ResolveDestinationConflict(
+            ResolveDestinationConflictError::DestinationExists(..),
+        ) => UserFileMoveDisplayError::DestinationExists,
+        error => {
+            emit_error(error);
+            UserFileMoveDisplayError::InternalServerError
+        }
+    }
+    .message()
+}
+
+fn copy_failure_message(error: UserFileCopyError) -> &'static str {
+    match error {
+        UserFileCopyError::StoragePath(..) => UserFileCopyDisplayError::InvalidPath,
+        UserFileCopyError::DestinationSameAsSource(..) => {
+            UserFileCopyDisplayError::DestinationSameAsSource
+        }
+        UserFileCopyError::DestinationInsideSource(..) => {
+            UserFileCopyDisplayError::DestinationInsideSource
+        }
+        UserFileCopyError::SourceInsideDestination(..) => {
+            UserFileCopyDisplayError::SourceInsideDestination
+        }
+        UserFileCopyError::DestinationExists(..) => UserFileCopyDisplayError::DestinationExists,
+        UserFileCopyError::StorageQuotaExceeded(..) => UserFileCopyDisplayError::QuotaExceeded,
+        error => {
+            emit_error(error);
+            UserFileCopyDisplayError::InternalServerError
+        }
+    }
+    .message()
+}
+
+fn share_failure_message(error: BatchUserShareCreationItemError) -> &'static str {
+    match error {
+        BatchUserShareCreationItemError::StatUserFile(StatUserFileError::StoragePath(..)) => {
+            BatchOperationDisplayError::InvalidPath
+        }
+        BatchUserShareCreationItemError::StatUserFile(StatUserFileError::ReadStorageItem(
+            error,
+        )) if is_missing_item(&error) => BatchOperationDisplayError::ItemNotFound,
+        error => {
+            emit_error(error);
+            BatchOperationDisplayError::InternalServerError
+        }
+    }
+    .message()
+}
+
+fn is_missing_item(error: &ReadStorageItemError) -> bool {
+    match error {
+        ReadStorageItemError::Metadata(error) => matches!(
+            error.source.kind(),
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::NotADirectory
+        ),
+        ReadStorageItemError::StorageItemCreation(..) => false,
+    }
+}
+
+async fn user_file_batch_delete_handler(
+    _: bitsync_routes::PostUserFileBatchDelete,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileBatchDeleteQueryParameters>,
+    Form(BatchSelectionFormData { paths }): Form<BatchSelectionFormData>,
+) -> impl IntoResponse {
+    if paths.is_empty() {
+        return nothing_selected_response();
+    }
+
+    match batch_delete_user_files(
+        &app_state.database,
+        &app_state.storage_root,
+        &query_parameters.path,
+        &paths,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(mut result) => {
+            let items = std::mem::take(&mut result.item_results)
+                .into_iter()
+                .map(|item_result| {
+                    result_item(
+                        item_result,
+                        |()| BatchResultStatus::Succeeded,
+                        deletion_failure_message,
+                    )
+                })
+                .collect();
+            let files_component = FilesHomePageChangeResult::from(result);
+
+            batch_outcome_response(files_component, None, "Deleted", items)
+        }
+        Err(error) => {
+            emit_error(error);
+            internal_server_error_toast_response()
+        }
+    }
+}
+
+fn batch_transfer_dialog_response(
+    kind: BatchTransferKind,
+    action_url: String,
+    paths: Vec<String>,
+    directory_path: &str,
+) -> Response {
+    if paths.is_empty() {
+        return nothing_selected_response();
+    }
+
+    let dialog = BatchTransferDialog {
+        kind,
+        action_url,
+        paths,
+        destination_directory_path: directory_path.to_owned(),
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
+async fn user_file_batch_move_dialog_handler(
+    _: bitsync_routes::PostUserFileBatchMoveDialog,
+    query_parameters: Query<bitsync_routes::PostUserFileBatchMoveDialogQueryParameters>,
+    Form(BatchSelectionFormData { paths }): Form<BatchSelectionFormData>,
+) -> impl IntoResponse {
+    let action_url = bitsync_routes::PostUserFileBatchMove
+        .with_query_params(bitsync_routes::PostUserFileBatchMoveQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    batch_transfer_dialog_response(
+        BatchTransferKind::Move,
+        action_url,
+        paths,
+        &query_parameters.path,
+    )
+}
+
+async fn user_file_batch_move_handler(
+    _: bitsync_routes::PostUserFileBatchMove,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileBatchMoveQueryParameters>,
+    Form(BatchMoveFormData {
+        paths,
+        destination_directory_path,
+        conflict_policy,
+    }): Form<BatchMoveFormData>,
+) -> impl IntoResponse {
+    if paths.is_empty() {
+        return nothing_selected_response();
+    }
+
+    match batch_move_user_files(
+        &app_state.database,
+        &app_state.storage_root,
+        &query_parameters.path,
+        &paths,
+        &destination_directory_path,
+        conflict_policy,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(mut result) => {
+            let items = std::mem::take(&mut result.item_results)
+                .into_iter()
+                .map(|item_result| {
+                    result_item(
+                        item_result,
+                        |()| BatchResultStatus::Succeeded,
+                        move_failure_message,
+                    )
+                })
+                .collect();
+            let files_component = FilesHomePageChangeResult::from(result);
+
+            batch_outcome_response(
+                files_component,
+                Some(FilesHomePageElementId::BatchMoveDialog),
+                "Moved",
+                items,
+            )
+        }
+        Err(error) => {
+            let display_error = match error {
+                BatchUserFileMoveError::StoragePath(..) => BatchOperationDisplayError::InvalidPath,
+                BatchUserFileMoveError::DestinationNotADirectory(..) => {
+                    BatchOperationDisplayError::DestinationNotADirectory
+                }
+                BatchUserFileMoveError::ReadStorageItem(error) if is_missing_item(&error) => {
+                    BatchOperationDisplayError::DestinationNotADirectory
+                }
+                error => {
+                    emit_error(error);
+                    BatchOperationDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
+
+async fn user_file_batch_copy_dialog_handler(
+    _: bitsync_routes::PostUserFileBatchCopyDialog,
+    query_parameters: Query<bitsync_routes::PostUserFileBatchCopyDialogQueryParameters>,
+    Form(BatchSelectionFormData { paths }): Form<BatchSelectionFormData>,
+) -> impl IntoResponse {
+    let action_url = bitsync_routes::PostUserFileBatchCopy
+        .with_query_params(bitsync_routes::PostUserFileBatchCopyQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    batch_transfer_dialog_response(
+        BatchTransferKind::Copy,
+        action_url,
+        paths,
+        &query_parameters.path,
+    )
+}
+
+async fn user_file_batch_copy_handler(
+    _: bitsync_routes::PostUserFileBatchCopy,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileBatchCopyQueryParameters>,
+    Form(BatchCopyFormData {
+        paths,
+        destination_directory_path,
+        conflict_policy,
+    }): Form<BatchCopyFormData>,
+) -> impl IntoResponse {
+    if paths.is_empty() {
+        return nothing_selected_response();
+    }
+
+    match batch_copy_user_files(
+        &app_state.database,
+        &app_state.storage_root,
+        app_state.config.default_user_storage_quota,
+        &query_parameters.path,
+        &auth_data.user,
+        BatchUserFileCopy {
+            paths: &paths,
+            destination_directory_path: &destination_directory_path,
+            conflict_policy,
+        },
+    )
+    .await
+    {
+        Ok(mut result) => {
+            let items = std::mem::take(&mut result.item_results)
+                .into_iter()
+                .map(|item_result| {
+                    result_item(
+                        item_result,
+                        |outcome| match outcome {
+                            UserFileCopyOutcome::Copied { .. } => BatchResultStatus::Succeeded,
+                            UserFileCopyOutcome::Skipped => BatchResultStatus::Skipped,
+                        },
+                        copy_failure_message,
+                    )
+                })
+                .collect();
+            let files_component = FilesHomePageChangeResult::from(result);
+
+            batch_outcome_response(
+                files_component,
+                Some(FilesHomePageElementId::BatchCopyDialog),
+                "Copied",
+                items,
+            )
+        }
+        Err(error) => {
+            let display_error = match error {
+                BatchUserFileCopyError::StoragePath(..) => BatchOperationDisplayError::InvalidPath,
+                BatchUserFileCopyError::DestinationNotADirectory(..) => {
+                    BatchOperationDisplayError::DestinationNotADirectory
+                }
+                BatchUserFileCopyError::ReadStorageItem(error) if is_missing_item(&error) => {
+                    BatchOperationDisplayError::DestinationNotADirectory
+                }
+                error => {
+                    emit_error(error);
+                    BatchOperationDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
+
+async fn user_file_batch_share_handler(
+    _: bitsync_routes::PostUserFileBatchShare,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    Form(BatchSelectionFormData { paths }): Form<BatchSelectionFormData>,
+) -> impl IntoResponse {
+    if paths.is_empty() {
+        return nothing_selected_response();
+    }
+
+    match batch_create_user_shares(
+        &app_state.database,
+        &app_state.storage_root,
+        &paths,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(item_results) => {
+            let item_count = item_results.len();
+            let items: Vec<_> = item_results
+                .into_iter()
+                .map(|item_result| {
+                    result_item(
+                        item_result,
+                        |user_share| BatchResultStatus::Shared {
+                            share_path: bitsync_routes::GetPublicSharePage {
+                                user_share_id: user_share.id,
+                            }
+                            .to_string(),
+                        },
+                        share_failure_message,
+                    )
+                })
+                .collect();
+            let shared_count = items
+                .iter()
+                .filter(|item| matches!(item.status, BatchResultStatus::Shared { .. }))
+                .count();
+
+            let dialog = BatchResultDialog {
+                title: match shared_count == item_count {
+                    true => format!("Shared {}", self::item_count(item_count)),
+                    false => format!("Shared {shared_count} of {}", self::item_count(item_count)),
+                },
+                items,
+            };
+
+            Json(HyperStimCommand::HsPatchHtml {
+                html: dialog.render(),
+                patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+                patch_mode: HyperStimPatchMode::Append,
+            })
+            .into_response()
+        }
+        Err(error) => {
+            emit_error(error);
+            internal_server_error_toast_response()
+        }
+    }
+}
+
+async fn user_file_batch_download_handler(
+    _: bitsync_routes::PostUserFileBatchDownload,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileBatchDownloadQueryParameters>,
+    Form(BatchSelectionFormData { paths }): Form<BatchSelectionFormData>,
+) -> impl IntoResponse {
+    if paths.is_empty() {
+        return nothing_selected_response();
+    }
+
+    match batch_download_user_files(
+        &app_state.storage_root,
+        &query_parameters.path,
+        &paths,
+        &auth_data.user,
+    )
+    .await
+    {
+        Ok(result) => {
+            let content_type = headers::ContentType::from(result.mime);
+            let stream_body = AsyncReadBody::new(result.file);
+            let attachment = Attachment::new(stream_body).filename(result.path.file_name());
+
+            (axum_extra::TypedHeader(content_type), attachment).into_response()
+        }
+        Err(error) => {
+            let display_error = match error {
+                BatchUserFileDownloadError::StoragePath(..) => {
+                    BatchOperationDisplayError::InvalidPath
+                }
+                error => {
+                    emit_error(error);
+                    BatchOperationDisplayError::InternalServerError
+                }
+            };
+
+            user_error_toast_response(display_error.message())
+        }
+    }
+}
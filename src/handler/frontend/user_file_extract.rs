@@ -0,0 +1,286 @@
+use std::{path::Path, sync::Arc};
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::IntoResponse,
+};
+use axum_extra::{extract::Form, routing::RouterExt};
+use bitsync_core::use_case::user_files::{
+    extract_user_file::{
+        ExtractionInterruptedError, ExtractionProgress, UserFileExtractError, UserFileExtraction,
+        extract_user_file,
+    },
+    shared::{ConflictPolicy, CreateDirectoriesError, UploadPolicy},
+};
+use bitsync_frontend::{
+    Component, DIALOG_WRAPPER_SELECTOR, Render,
+    pages::files::{
+        FilesHomePageChangeResult, FilesHomePageElementId,
+        file_extract::{ExtractionProgressDialog, ExtractionProgressIndicator, FileExtractDialog},
+        file_operations::UserFileExtractDisplayError,
+    },
+    toast::{TOAST_CONTAINER_SELECTOR, Toast},
+};
+use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
+use bitsync_routes::TypedPath;
+use serde::Deserialize;
+
+use crate::{
+    AppState,
+    auth::{AuthData, require_login_and_totp_setup_middleware},
+    error_report::emit_error,
+    extraction_jobs::{ExtractionJobError, ExtractionJobLimitError, ExtractionJobStatus},
+    handler::RedirectHyperStim,
+};
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .typed_get(user_file_extract_dialog_handler)
+        .typed_post(user_file_extract_handler)
+        .typed_get(user_file_extract_progress_handler)
+        .route_layer(axum::middleware::from_fn(
+            crate::body_limit::request_body_size_limit,
+        ))
+        .route_layer(from_fn_with_state(
+            state.clone(),
+            require_login_and_totp_setup_middleware::<RedirectHyperStim>,
+        ))
+        .with_state(state)
+}
+
+async fn user_file_extract_dialog_handler(
+    _: bitsync_routes::GetUserFileExtractDialog,
+    query_parameters: Query<bitsync_routes::GetUserFileExtractDialogQueryParameters>,
+) -> impl IntoResponse {
+    let action_url = bitsync_routes::PostUserFileExtract
+        .with_query_params(bitsync_routes::PostUserFileExtractQueryParameters {
+            path: query_parameters.path.clone(),
+        })
+        .to_string();
+
+    // `archive.zip` is extracted into a sibling folder named `archive` by default
+    let destination_path = Path::new(&query_parameters.path)
+        .with_extension("")
+        .to_string_lossy()
+        .to_string();
+
+    let dialog = FileExtractDialog {
+        action_url,
+        destination_path,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+struct ExtractArchiveFormData {
+    pub destination_path: String,
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+}
+
+async fn user_file_extract_handler(
+    _: bitsync_routes::PostUserFileExtract,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::PostUserFileExtractQueryParameters>,
+    Form(ExtractArchiveFormData {
+        destination_path,
+        conflict_policy,
+    }): Form<ExtractArchiveFormData>,
+) -> impl IntoResponse {
+    let archive_path = query_parameters.0.path;
+    let user = auth_data.user;
+
+    let job_id = match app_state.extraction_jobs.start(user.id, |progress_sender| {
+        let app_state = app_state.clone();
+
+        async move {
+            extract_user_file(
+                &app_state.database,
+                &app_state.storage_root,
+                &UploadPolicy::from(&app_state.config),
+                &user,
+                UserFileExtraction {
+                    archive_path: &archive_path,
+                    destination_path: &destination_path,
+                    conflict_policy,
+                },
+                |progress| {
+                    progress_sender.send_replace(progress);
+                },
+            )
+            .await
+        }
+    }) {
+        Ok(job_id) => job_id,
+        Err(ExtractionJobLimitError) => {
+            return Json(HyperStimCommand::HsPatchHtml {
+                html: Toast::error(UserFileExtractDisplayError::ExtractionAlreadyRunning.message())
+                    .render(),
+                patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+                patch_mode: HyperStimPatchMode::Append,
+            })
+            .into_response();
+        }
+    };
+
+    let progress_url = bitsync_routes::GetUserFileExtractProgress
+        .with_query_params(bitsync_routes::GetUserFileExtractProgressQueryParameters { job_id })
+        .to_string();
+    let dialog = ExtractionProgressDialog {
+        progress_url,
+        progress: ExtractionProgressIndicator {
+            processed_entries: 0,
+            total_entries: 0,
+        },
+    };
+    let extract_dialog_id = FilesHomePageElementId::FileExtractDialog.to_str();
+
+    Json(vec![
+        HyperStimCommand::HsExecute {
+            code: format!(
+                "document.querySelectorAll('#{extract_dialog_id}').forEach(closeClosestDialogAndRemoveElement)"
+            ),
+        },
+        HyperStimCommand::HsPatchHtml {
+            html: dialog.render(),
+            patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+            patch_mode: HyperStimPatchMode::Append,
+        },
+    ])
+    .into_response()
+}
+
+async fn user_file_extract_progress_handler(
+    _: bitsync_routes::GetUserFileExtractProgress,
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    query_parameters: Query<bitsync_routes::GetUserFileExtractProgressQueryParameters>,
+) -> impl IntoResponse {
+    let progress_dialog_id = FilesHomePageElementId::ExtractionProgressDialog.to_str();
+    let close_progress_dialog = HyperStimCommand::HsExecute {
+        code: format!(
+            "document.querySelectorAll('#{progress_dialog_id}').forEach(closeClosestDialogAndRemoveElement)"
+        ),
+    };
+
+    let status = app_state
+        .extraction_jobs
+        .status(&query_parameters.job_id, &auth_data.user.id)
+        .await;
+
+    let commands = match status {
+        Some(ExtractionJobStatus::Running(ExtractionProgress {
+            processed_entries,
+            total_entries,
+        })) => {
+            let progress = ExtractionProgressIndicator {
+                processed_entries,
+                total_entries,
+            };
+
+            vec![HyperStimCommand::HsPatchHtml {
+                html: progress.render(),
+                patch_target: progress.id_target(),
+                patch_mode: HyperStimPatchMode::Outer,
+            }]
+        }
+        Some(ExtractionJobStatus::Finished(Ok(result))) => {
+            let message = match result.skipped_file_count {
+                0 => format!("Extracted {} files", result.extracted_file_count),
+                skipped_file_count => format!(
+                    "Extracted {} files, skipped {skipped_file_count} existing files",
+                    result.extracted_file_count
+                ),
+            };
+            let files_component = FilesHomePageChangeResult::from(result);
+
+            vec![
+                HyperStimCommand::HsPatchHtml {
+                    html: files_component.render(),
+                    patch_target: files_component.id_target(),
+                    patch_mode: HyperStimPatchMode::Outer,
+                },
+                close_progress_dialog,
+                HyperStimCommand::HsPatchHtml {
+                    html: Toast::success(message).render(),
+                    patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+                    patch_mode: HyperStimPatchMode::Append,
+                },
+            ]
+        }
+        Some(ExtractionJobStatus::Finished(Err(error))) => {
+            let (error, extracted_file_count) = match error {
+                ExtractionJobError::Extract(UserFileExtractError::Interrupted(
+                    ExtractionInterruptedError {
+                        extracted_file_count,
+                        source,
+                    },
+                )) => (ExtractionJobError::Extract(*source), extracted_file_count),
+                error => (error, 0),
+            };
+            let display_error = match error {
+                ExtractionJobError::Extract(UserFileExtractError::StoragePath(..)) => {
+                    UserFileExtractDisplayError::InvalidPath
+                }
+                ExtractionJobError::Extract(UserFileExtractError::ArchiveNotAFile(..)) => {
+                    UserFileExtractDisplayError::ArchiveNotAFile
+                }
+                ExtractionJobError::Extract(UserFileExtractError::Zip(..)) => {
+                    UserFileExtractDisplayError::InvalidArchive
+                }
+                ExtractionJobError::Extract(UserFileExtractError::UnsafeEntryPath(..)) => {
+                    UserFileExtractDisplayError::UnsafeEntryPath
+                }
+                ExtractionJobError::Extract(UserFileExtractError::EntrySizeExceeded(..)) => {
+                    UserFileExtractDisplayError::EntrySizeExceeded
+                }
+                ExtractionJobError::Extract(UserFileExtractError::CreateDirectories(
+                    CreateDirectoriesError::ParentNotADirectory(..),
+                )) => UserFileExtractDisplayError::ParentNotADirectory,
+                ExtractionJobError::Extract(UserFileExtractError::StorageQuotaExceeded(..)) => {
+                    UserFileExtractDisplayError::QuotaExceeded
+                }
+                error => {
+                    emit_error(error);
+                    UserFileExtractDisplayError::InternalServerError
+                }
+            };
+            let message = match extracted_file_count {
+                0 => display_error.message().to_owned(),
+                extracted_file_count => format!(
+                    "{} ({extracted_file_count} files were extracted before)",
+                    display_error.message()
+                ),
+            };
+
+            vec![
+                close_progress_dialog,
+                HyperStimCommand::HsPatchHtml {
+                    html: Toast::error(message).render(),
+                    patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+                    patch_mode: HyperStimPatchMode::Append,
+                },
+            ]
+        }
+        None => vec![
+            close_progress_dialog,
+            HyperStimCommand::HsPatchHtml {
+                html: Toast::error(UserFileExtractDisplayError::ExtractionNotFound.message())
+                    .render(),
+                patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+                patch_mode: HyperStimPatchMode::Append,
+            },
+        ],
+    };
+
+    Json(commands).into_response()
+}
@@ -1,23 +1,38 @@
 use std::sync::Arc;
 
 use axum::{
-    Extension, Json, Router, extract::State, http::StatusCode, middleware::from_fn_with_state,
+    Extension, Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    middleware::from_fn_with_state,
     response::IntoResponse,
 };
 use axum_extra::{extract::Form, routing::RouterExt};
 use bitsync_core::use_case::{
+    app_password::{
+        create_app_password::{CreateAppPasswordError, create_app_password},
+        list_app_passwords::list_app_passwords,
+        revoke_app_password::revoke_app_password,
+    },
     auth::{
         initiate_totp_setup::initiate_totp_setup,
         reset_totp::{ResetTotpError, reset_totp},
     },
+    file_checksum::scrub_file_checksums::{FileChecksumScrubProgress, scrub_file_checksums},
     invite_token::{
         create_invite_token::create_invite_token, delete_invite_token::delete_invite_token,
         list_invite_tokens::list_invite_tokens,
     },
     user::{
-        delete_user::delete_user, get_user::get_user, list_users::list_users,
-        make_admin::make_admin, reset_user_totp::reset_user_totp, revoke_admin::revoke_admin,
-        suspend_user::suspend_user, unsuspend_user::unsuspend_user,
+        delete_user::delete_user,
+        get_user::get_user,
+        list_users::list_users,
+        make_admin::make_admin,
+        reset_user_totp::reset_user_totp,
+        revoke_admin::revoke_admin,
+        set_user_storage_quota::{SetUserStorageQuotaError, set_user_storage_quota},
+        suspend_user::suspend_user,
+        unsuspend_user::unsuspend_user,
     },
     user_settings::{
         list_sessions::list_sessions,
@@ -27,19 +42,24 @@ use bitsync_core::use_case::{
     },
     user_share::list_shared_paths::list_shared_paths,
 };
+use bitsync_database::entity::AppPasswordScope;
 use bitsync_frontend::{
     Component, DIALOG_WRAPPER_SELECTOR, Render,
     components::user_settings::{
         SettingsDialog, SettingsTab, SettingsTabArea,
+        app_passwords::{AppPasswordsDisplayError, AppPasswordsTabContent, CreatedAppPassword},
+        integrity::{ScrubProgress, ScrubProgressIndicator, ScrubReport},
         invites::InviteList,
         password::{PasswordDisplayError, PasswordTabContent},
         sessions::{SessionList, SessionsDisplayError},
         totp::{TotpDisplayError, TotpTabContent},
-        users::UserList,
+        users::{UserList, UserStorageQuotaDialog, UserStorageQuotaDisplayError},
     },
     confirmation_dialog::ConfirmationDialog,
+    toast::{TOAST_CONTAINER_SELECTOR, Toast},
 };
 use bitsync_hyperstim::{HyperStimCommand, HyperStimPatchMode};
+use bitsync_routes::TypedPath;
 use serde::Deserialize;
 
 use crate::{
@@ -49,7 +69,8 @@ use crate::{
         require_login_and_totp_setup_middleware,
     },
     error_report::emit_error,
-    handler::{RedirectHyperStim, internal_server_error_toast_response},
+    handler::{RedirectHyperStim, internal_server_error_toast_response, user_error_toast_response},
+    scrub_jobs::ScrubJobStatus,
 };
 
 pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
@@ -62,6 +83,9 @@ pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
                 .typed_get(user_settings_sessions_tab_handler)
                 .typed_post(user_settings_terminate_session_handler)
                 .typed_post(user_settings_terminate_all_other_sessions_handler)
+                .typed_get(user_settings_app_passwords_tab_handler)
+                .typed_post(user_settings_app_password_create_handler)
+                .typed_post(user_settings_app_password_revoke_handler)
                 .typed_get(user_settings_shares_tab_handler)
                 .typed_get(user_settings_totp_tab_handler)
                 .typed_post(user_settings_totp_initiate_handler)
@@ -88,11 +112,16 @@ pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
                 .typed_post(user_settings_suspend_user_handler)
                 .typed_get(confirm_unsuspend_user_handler)
                 .typed_post(user_settings_unsuspend_user_handler)
+                .typed_get(user_storage_quota_dialog_handler)
+                .typed_post(user_settings_user_storage_quota_handler)
                 .typed_get(confirm_delete_user_handler)
                 .typed_post(user_settings_delete_user_handler)
                 .typed_get(user_settings_invites_tab_handler)
                 .typed_post(user_settings_invite_token_create_handler)
                 .typed_post(user_settings_invite_token_delete_handler)
+                .typed_get(user_settings_integrity_tab_handler)
+                .typed_post(user_settings_integrity_scrub_handler)
+                .typed_get(user_settings_integrity_scrub_progress_handler)
                 .route_layer(axum::middleware::from_fn(
                     crate::body_limit::request_body_size_limit,
                 ))
@@ -183,13 +212,7 @@ async fn user_settings_shares_tab_handler(
     State(state): State<Arc<AppState>>,
     Extension(auth_data): Extension<AuthData>,
 ) -> impl IntoResponse {
-    match list_shared_paths(
-        &state.database,
-        &state.config.fs_storage_root_dir,
-        &auth_data.user,
-    )
-    .await
-    {
+    match list_shared_paths(&state.database, &state.storage_root, &auth_data.user).await {
         Ok(shared_paths) => {
             let tab_area = SettingsTabArea {
                 active_tab: SettingsTab::Shares { shared_paths },
@@ -350,6 +373,142 @@ async fn user_settings_terminate_all_other_sessions_handler(
     .into_response()
 }
 
+async fn user_settings_app_passwords_tab_handler(
+    _: bitsync_routes::GetUserSettingsAppPasswordsTab,
+    State(state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    match list_app_passwords(&state.database, &auth_data.user.id).await {
+        Ok(app_passwords) => {
+            let tab_area = SettingsTabArea {
+                active_tab: SettingsTab::AppPasswords { app_passwords },
+                is_admin: auth_data.user.is_admin,
+            };
+
+            Json(HyperStimCommand::HsPatchHtml {
+                html: tab_area.render(),
+                patch_target: tab_area.id_target(),
+                patch_mode: HyperStimPatchMode::Outer,
+            })
+            .into_response()
+        }
+        Err(error) => {
+            emit_error(error);
+
+            internal_server_error_toast_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateAppPasswordFormData {
+    pub name: String,
+    pub scope: AppPasswordScope,
+}
+
+async fn user_settings_app_password_create_handler(
+    _: bitsync_routes::PostUserSettingsAppPasswordCreate,
+    State(state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    Form(create_app_password_form_data): Form<CreateAppPasswordFormData>,
+) -> impl IntoResponse {
+    let (status_code, tab_content) = match create_app_password(
+        &state.database,
+        &auth_data.user,
+        &create_app_password_form_data.name,
+        create_app_password_form_data.scope,
+    )
+    .await
+    {
+        Ok(result) => (
+            StatusCode::OK,
+            AppPasswordsTabContent {
+                app_passwords: result.app_passwords,
+                created_app_password: Some(CreatedAppPassword {
+                    name: result.app_password.name,
+                    token: result.token,
+                }),
+                is_admin: auth_data.user.is_admin,
+                error: None,
+            },
+        ),
+        Err(error) => {
+            let display_error = match error {
+                CreateAppPasswordError::EmptyName(..) => AppPasswordsDisplayError::EmptyName,
+                CreateAppPasswordError::AdminScopeNotAllowed(..) => {
+                    AppPasswordsDisplayError::AdminScopeNotAllowed
+                }
+                error => {
+                    emit_error(error);
+
+                    return internal_server_error_toast_response();
+                }
+            };
+
+            let app_passwords = match list_app_passwords(&state.database, &auth_data.user.id).await
+            {
+                Ok(app_passwords) => app_passwords,
+                Err(error) => {
+                    emit_error(error);
+
+                    return internal_server_error_toast_response();
+                }
+            };
+
+            (
+                StatusCode::BAD_REQUEST,
+                AppPasswordsTabContent {
+                    app_passwords,
+                    created_app_password: None,
+                    is_admin: auth_data.user.is_admin,
+                    error: Some(display_error),
+                },
+            )
+        }
+    };
+
+    (
+        status_code,
+        Json(HyperStimCommand::HsPatchHtml {
+            html: tab_content.render(),
+            patch_target: tab_content.id_target(),
+            patch_mode: HyperStimPatchMode::Outer,
+        }),
+    )
+        .into_response()
+}
+
+async fn user_settings_app_password_revoke_handler(
+    path: bitsync_routes::PostUserSettingsAppPasswordRevoke,
+    State(state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    let app_passwords =
+        match revoke_app_password(&state.database, &auth_data.user.id, &path.app_password_id).await
+        {
+            Ok(app_passwords) => app_passwords,
+            Err(error) => {
+                emit_error(error);
+
+                return internal_server_error_toast_response();
+            }
+        };
+
+    let tab_content = AppPasswordsTabContent {
+        app_passwords,
+        created_app_password: None,
+        is_admin: auth_data.user.is_admin,
+        error: None,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: tab_content.render(),
+        patch_target: tab_content.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
 async fn user_settings_totp_tab_handler(
     _: bitsync_routes::GetUserSettingsTotpTab,
     Extension(auth_data): Extension<AuthData>,
@@ -508,6 +667,126 @@ async fn user_settings_invite_token_create_handler(
     .into_response()
 }
 
+async fn user_settings_integrity_tab_handler(
+    _: bitsync_routes::GetUserSettingsIntegrityTab,
+    Extension(auth_data): Extension<AuthData>,
+) -> impl IntoResponse {
+    let tab_area = SettingsTabArea {
+        active_tab: SettingsTab::Integrity,
+        is_admin: auth_data.user.is_admin,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: tab_area.render(),
+        patch_target: tab_area.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+}
+
+async fn user_settings_integrity_scrub_handler(
+    _: bitsync_routes::PostUserSettingsIntegrityScrub,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    // the scrub runs in the background so it is neither cut off by request timeouts nor
+    // cancelled when the client disconnects
+    let job_id = state.scrub_jobs.start(|progress_sender| {
+        let state = state.clone();
+
+        async move {
+            scrub_file_checksums(&state.database, &state.storage_root, |progress| {
+                progress_sender.send_replace(progress);
+            })
+            .await
+        }
+    });
+
+    let progress_url = bitsync_routes::GetUserSettingsIntegrityScrubProgress
+        .with_query_params(
+            bitsync_routes::GetUserSettingsIntegrityScrubProgressQueryParameters { job_id },
+        )
+        .to_string();
+    let scrub_progress = ScrubProgress {
+        progress_url,
+        progress: ScrubProgressIndicator {
+            progress: FileChecksumScrubProgress::default(),
+        },
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: scrub_progress.render(),
+        patch_target: scrub_progress.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
+async fn user_settings_integrity_scrub_progress_handler(
+    _: bitsync_routes::GetUserSettingsIntegrityScrubProgress,
+    State(state): State<Arc<AppState>>,
+    query_parameters: Query<bitsync_routes::GetUserSettingsIntegrityScrubProgressQueryParameters>,
+) -> impl IntoResponse {
+    let status = state.scrub_jobs.status(&query_parameters.job_id).await;
+
+    let report = match status {
+        Some(ScrubJobStatus::Running(progress)) => {
+            let progress = ScrubProgressIndicator { progress };
+
+            return Json(HyperStimCommand::HsPatchHtml {
+                html: progress.render(),
+                patch_target: progress.id_target(),
+                patch_mode: HyperStimPatchMode::Outer,
+            })
+            .into_response();
+        }
+        Some(ScrubJobStatus::Finished(Ok(report))) => Some(report),
+        Some(ScrubJobStatus::Finished(Err(error))) => {
+            emit_error(error);
+
+            None
+        }
+        None => None,
+    };
+
+    let Some(report) = report else {
+        // replacing the progress stops the polling
+        let scrub_report = ScrubReport { report: None };
+
+        return Json(vec![
+            HyperStimCommand::HsPatchHtml {
+                html: scrub_report.render(),
+                patch_target: scrub_report.id_target(),
+                patch_mode: HyperStimPatchMode::Outer,
+            },
+            HyperStimCommand::HsPatchHtml {
+                html: Toast::error("The scrub failed or its report is no longer available")
+                    .render(),
+                patch_target: TOAST_CONTAINER_SELECTOR.to_owned(),
+                patch_mode: HyperStimPatchMode::Append,
+            },
+        ])
+        .into_response();
+    };
+
+    for mismatch in &report.mismatches {
+        tracing::warn!(
+            "checksum mismatch for {} of user {}",
+            mismatch.item_path,
+            mismatch.username,
+        );
+    }
+
+    let scrub_report = ScrubReport {
+        report: Some(report),
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: scrub_report.render(),
+        patch_target: scrub_report.id_target(),
+        patch_mode: HyperStimPatchMode::Outer,
+    })
+    .into_response()
+}
+
 async fn user_settings_invite_token_delete_handler(
     path: bitsync_routes::PostUserSettingsInviteTokenDelete,
     State(state): State<Arc<AppState>>,
@@ -681,6 +960,74 @@ async fn user_settings_unsuspend_user_handler(
     .into_response()
 }
 
+#[derive(Deserialize)]
+struct UserStorageQuotaFormData {
+    storage_quota: String,
+}
+
+async fn user_settings_user_storage_quota_handler(
+    path: bitsync_routes::PostUserSettingsUserStorageQuota,
+    State(state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AuthData>,
+    Form(form_data): Form<UserStorageQuotaFormData>,
+) -> impl IntoResponse {
+    let storage_quota =
+        match form_data.storage_quota.trim() {
+            "" => None,
+            storage_quota_mebibytes => match storage_quota_mebibytes.parse::<u64>().ok().and_then(
+                |storage_quota_mebibytes| storage_quota_mebibytes.checked_mul(1024 * 1024),
+            ) {
+                Some(storage_quota) => Some(storage_quota),
+                None => {
+                    return user_error_toast_response(
+                        UserStorageQuotaDisplayError::InvalidStorageQuota.message(),
+                    );
+                }
+            },
+        };
+
+    let users = match set_user_storage_quota(
+        &state.database,
+        &path.user_id,
+        storage_quota,
+        &auth_data.user.id,
+    )
+    .await
+    {
+        Ok(users) => users,
+        Err(error) => {
+            let display_error = match error {
+                SetUserStorageQuotaError::StorageQuotaTooLarge(..) => {
+                    UserStorageQuotaDisplayError::InvalidStorageQuota
+                }
+                error => {
+                    emit_error(error);
+                    UserStorageQuotaDisplayError::InternalServerError
+                }
+            };
+
+            return user_error_toast_response(display_error.message());
+        }
+    };
+
+    let user_list = UserList { users };
+
+    Json(vec![
+        HyperStimCommand::HsPatchHtml {
+            html: user_list.render(),
+            patch_target: user_list.id_target(),
+            patch_mode: HyperStimPatchMode::Outer,
+        },
+        HyperStimCommand::HsExecute {
+            code: format!(
+                "closeClosestDialogAndRemoveElement(document.getElementById('{}'))",
+                bitsync_frontend::components::user_settings::users::USER_STORAGE_QUOTA_DIALOG_ID,
+            ),
+        },
+    ])
+    .into_response()
+}
+
 async fn user_settings_delete_user_handler(
     path: bitsync_routes::PostUserSettingsDeleteUser,
     State(state): State<Arc<AppState>>,
@@ -688,7 +1035,7 @@ async fn user_settings_delete_user_handler(
 ) -> impl IntoResponse {
     let users = match delete_user(
         &state.database,
-        &state.config.fs_storage_root_dir,
+        &state.storage_root,
         &path.user_id,
         &auth_data.user.id,
     )
@@ -823,6 +1170,38 @@ async fn confirm_reset_user_totp_handler(
     .into_response()
 }
 
+async fn user_storage_quota_dialog_handler(
+    path: bitsync_routes::GetUserStorageQuotaDialog,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    let user = match get_user(&state.database, &path.user_id).await {
+        Ok(user) => user,
+        Err(error) => {
+            emit_error(error);
+
+            return internal_server_error_toast_response();
+        }
+    };
+
+    let action_url = bitsync_routes::PostUserSettingsUserStorageQuota {
+        user_id: path.user_id,
+    }
+    .to_string();
+
+    let dialog = UserStorageQuotaDialog {
+        action_url,
+        username: user.username,
+        storage_quota: user.storage_quota,
+    };
+
+    Json(HyperStimCommand::HsPatchHtml {
+        html: dialog.render(),
+        patch_target: DIALOG_WRAPPER_SELECTOR.to_owned(),
+        patch_mode: HyperStimPatchMode::Append,
+    })
+    .into_response()
+}
+
 async fn confirm_suspend_user_handler(
     path: bitsync_routes::GetSuspendUserDialog,
     State(state): State<Arc<AppState>>,
@@ -0,0 +1,928 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use axum::{
+    Extension, Router,
+    body::{Body, to_bytes},
+    extract::State,
+    http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header},
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Response},
+    routing::any,
+};
+use axum_extra::body::AsyncReadBody;
+use bitsync_core::use_case::{
+    file_version::shared::WriteVersionedFileStreamError,
+    user_files::{
+        copy_user_file::{CopyConflictPolicy, UserFileCopyError, copy_user_file},
+        create_directory::create_direcory,
+        delete_user_file::delete_user_file,
+        download_user_file::{DirectoryArchiveFormat, download_user_file},
+        list_user_directory::list_user_directory,
+        move_user_file::{UserFileMoveError, move_user_file},
+        shared::{
+            ConflictPolicy, ResolveDestinationConflictError, UploadPolicy, user_root_directory_name,
+        },
+        stat_user_file::{StatUserFileError, find_user_file},
+        upload_user_file::{UserFileUpload, UserFileUploadError, upload_user_file},
+    },
+};
+use bitsync_database::entity::{AppPasswordScope, User};
+use bitsync_routes::TypedPath;
+use bitsync_storage::{
+    operation::write::WriteFileStreamError,
+    storage_item::{StorageItem, StorageItemKind},
+};
+use percent_encoding::percent_decode_str;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    auth::{AppPasswordAuthData, require_app_password_middleware},
+    dav_locks::{
+        DavChange, DavLock, DavLockCreateError, DavLockDepth, DavLockRequest, MAX_DAV_LOCK_TIMEOUT,
+    },
+    error_report::emit_error,
+};
+
+mod xml;
+
+const ALLOWED_METHODS: &str =
+    "OPTIONS, PROPFIND, PROPPATCH, GET, HEAD, PUT, MKCOL, DELETE, MOVE, COPY, LOCK, UNLOCK";
+
+/// The largest `LOCK` or `PROPPATCH` body that is read.
+const MAX_XML_BODY_SIZE: usize = 64 * 1024;
+
+pub(crate) async fn create_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(bitsync_routes::DavRoot::PATH, any(dav_handler))
+        .route(bitsync_routes::DavRootDirectory::PATH, any(dav_handler))
+        .route(bitsync_routes::DavResource::PATH, any(dav_handler))
+        .route_layer(from_fn_with_state(
+            state.clone(),
+            require_app_password_middleware,
+        ))
+        .with_state(state)
+}
+
+/// Maps a request path below the WebDAV prefix to a path in the user's storage.
+fn dav_scoped_path(request_path: &str) -> Option<String> {
+    let scoped_path = request_path.strip_prefix(bitsync_routes::DavRoot::PATH)?;
+    let scoped_path = percent_decode_str(scoped_path).decode_utf8().ok()?;
+    let scoped_path = scoped_path.trim_end_matches('/');
+
+    if scoped_path.is_empty() {
+        Some("/".to_owned())
+    } else if scoped_path.starts_with('/') {
+        Some(scoped_path.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Splits a scoped path into its parent directory and file name. The root has neither.
+fn split_scoped_path(scoped_path: &str) -> Option<(String, String)> {
+    let scoped_path = Path::new(scoped_path);
+
+    let parent = scoped_path.parent()?.to_string_lossy().to_string();
+    let file_name = scoped_path.file_name()?.to_string_lossy().to_string();
+
+    Some((parent, file_name))
+}
+
+fn is_root_path(scoped_path: &str) -> bool {
+    scoped_path == "/"
+}
+
+fn is_path_at_or_below(path: &str, parent_path: &str) -> bool {
+    path == parent_path
+        || path
+            .strip_prefix(parent_path)
+            .is_some_and(|remainder| remainder.starts_with('/'))
+}
+
+fn dav_internal_server_error_response(error: impl std::error::Error + 'static) -> Response {
+    emit_error(error);
+
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
+
+fn dav_xml_response(status_code: StatusCode, xml: String) -> Response {
+    (
+        status_code,
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        xml,
+    )
+        .into_response()
+}
+
+async fn read_xml_body(body: Body) -> Result<String, StatusCode> {
+    let body = to_bytes(body, MAX_XML_BODY_SIZE)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+
+    String::from_utf8(body.to_vec()).map_err(|_| StatusCode::BAD_REQUEST)
+}
+
+fn parse_lock_token_uri(uri: &str) -> Option<Uuid> {
+    uri.trim().strip_prefix("opaquelocktoken:")?.parse().ok()
+}
+
+/// The lock tokens submitted in the `If` header. Resource and entity tags are not evaluated, a
+/// token counts as submitted for every item the request changes.
+fn submitted_lock_tokens(headers: &HeaderMap) -> Vec<Uuid> {
+    let Some(if_header) = headers
+        .get(header::HeaderName::from_static("if"))
+        .and_then(|if_header| if_header.to_str().ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut lock_tokens = Vec::new();
+    let mut is_in_list = false;
+    let mut remainder = if_header;
+
+    while let Some(index) = remainder.find(['(', ')', '<']) {
+        let delimiter = remainder.as_bytes()[index];
+        remainder = &remainder[index + 1..];
+
+        match delimiter {
+            b'(' => is_in_list = true,
+            b')' => is_in_list = false,
+            _ => {
+                let Some((uri, rest)) = remainder.split_once('>') else {
+                    break;
+                };
+                remainder = rest;
+
+                // only tokens inside a list are state tokens, the others tag a resource
+                if is_in_list && let Some(lock_token) = parse_lock_token_uri(uri) {
+                    lock_tokens.push(lock_token);
+                }
+            }
+        }
+    }
+
+    lock_tokens
+}
+
+/// Fails with `423 Locked` if a lock protects `scoped_path` and was not submitted.
+fn ensure_unlocked(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    change: DavChange,
+    lock_tokens: &[Uuid],
+) -> Result<(), StatusCode> {
+    app_state
+        .dav_locks
+        .ensure_unlocked(user.id, scoped_path, change, lock_tokens)
+        .map_err(|_| StatusCode::LOCKED)
+}
+
+/// Reads the item at `scoped_path`, treating a missing item as `None`.
+async fn find_storage_item(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+) -> Result<Option<StorageItem>, StatUserFileError> {
+    find_user_file(&app_state.storage_root, scoped_path, user).await
+}
+
+fn stat_error_response(error: StatUserFileError) -> Response {
+    match error {
+        StatUserFileError::StoragePath(..) => StatusCode::BAD_REQUEST.into_response(),
+        error => dav_internal_server_error_response(error),
+    }
+}
+
+/// Whether `scoped_path` is an existing directory that can receive a new item.
+async fn is_existing_directory(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+) -> Result<bool, StatUserFileError> {
+    Ok(find_storage_item(app_state, user, scoped_path)
+        .await?
+        .is_some_and(|storage_item| storage_item.kind == StorageItemKind::Directory))
+}
+
+async fn dav_handler(
+    State(app_state): State<Arc<AppState>>,
+    Extension(auth_data): Extension<AppPasswordAuthData>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    let Some(scoped_path) = dav_scoped_path(uri.path()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let user = &auth_data.user;
+
+    let required_scope = match method.as_str() {
+        "OPTIONS" | "PROPFIND" | "GET" | "HEAD" => AppPasswordScope::Read,
+        _ => AppPasswordScope::Write,
+    };
+
+    if !auth_data.has_scope(required_scope) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let lock_tokens = submitted_lock_tokens(&headers);
+
+    // a client whose locks all expired has to lock again instead of writing unprotected
+    if !lock_tokens.is_empty() && !app_state.dav_locks.is_any_active(user.id, &lock_tokens) {
+        return StatusCode::PRECONDITION_FAILED.into_response();
+    }
+
+    match method.as_str() {
+        "OPTIONS" => dav_options_response(),
+        "PROPFIND" => dav_propfind(&app_state, user, &scoped_path, &headers).await,
+        "PROPPATCH" => dav_proppatch(&app_state, user, &scoped_path, &lock_tokens, body).await,
+        "GET" => dav_get(&app_state, user, &scoped_path, true).await,
+        "HEAD" => dav_get(&app_state, user, &scoped_path, false).await,
+        "PUT" => dav_put(&app_state, user, &scoped_path, &lock_tokens, body).await,
+        "MKCOL" => dav_mkcol(&app_state, user, &scoped_path, &lock_tokens).await,
+        "DELETE" => dav_delete(&app_state, user, &scoped_path, &lock_tokens).await,
+        "MOVE" => {
+            dav_transfer(
+                &app_state,
+                user,
+                &scoped_path,
+                &headers,
+                &lock_tokens,
+                DavTransfer::Move,
+            )
+            .await
+        }
+        "COPY" => {
+            dav_transfer(
+                &app_state,
+                user,
+                &scoped_path,
+                &headers,
+                &lock_tokens,
+                DavTransfer::Copy,
+            )
+            .await
+        }
+        "LOCK" => dav_lock(&app_state, user, &scoped_path, &headers, &lock_tokens, body).await,
+        "UNLOCK" => dav_unlock(&app_state, user, &scoped_path, &headers),
+        _ => (
+            StatusCode::METHOD_NOT_ALLOWED,
+            [(header::ALLOW, ALLOWED_METHODS)],
+        )
+            .into_response(),
+    }
+}
+
+fn dav_options_response() -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::ALLOW, ALLOWED_METHODS),
+            (header::HeaderName::from_static("dav"), "1, 2"),
+            (header::HeaderName::from_static("ms-author-via"), "DAV"),
+        ],
+    )
+        .into_response()
+}
+
+async fn dav_propfind(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    headers: &HeaderMap,
+) -> Response {
+    // a missing depth means infinity, which would walk the whole storage in a single request
+    let include_children = match headers.get(header::HeaderName::from_static("depth")) {
+        Some(depth) if depth == "0" => false,
+        Some(depth) if depth == "1" => true,
+        Some(depth) if depth != "infinity" => return StatusCode::BAD_REQUEST.into_response(),
+        _ => {
+            return dav_xml_response(
+                StatusCode::FORBIDDEN,
+                xml::render_error("propfind-finite-depth"),
+            );
+        }
+    };
+
+    let storage_item = match find_storage_item(app_state, user, scoped_path).await {
+        Ok(Some(storage_item)) => storage_item,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(error) => return stat_error_response(error),
+    };
+
+    let is_directory = storage_item.kind == StorageItemKind::Directory;
+    let mut storage_items = vec![storage_item];
+
+    if include_children && is_directory {
+        match list_user_directory(&app_state.storage_root, scoped_path, user).await {
+            Ok(dir_contents) => storage_items.extend(dir_contents),
+            Err(error) => return dav_internal_server_error_response(error),
+        }
+    }
+
+    dav_xml_response(
+        StatusCode::MULTI_STATUS,
+        xml::render_multistatus(
+            &storage_items,
+            &user_root_directory_name(&user.username),
+            &app_state.dav_locks.user_locks(user.id),
+        ),
+    )
+}
+
+/// Accepts property changes without storing them, see `xml::render_property_update`.
+async fn dav_proppatch(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    lock_tokens: &[Uuid],
+    body: Body,
+) -> Response {
+    let storage_item = match find_storage_item(app_state, user, scoped_path).await {
+        Ok(Some(storage_item)) => storage_item,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(error) => return stat_error_response(error),
+    };
+
+    if let Err(status_code) = ensure_unlocked(
+        app_state,
+        user,
+        scoped_path,
+        DavChange::Content,
+        lock_tokens,
+    ) {
+        return status_code.into_response();
+    }
+
+    let body = match read_xml_body(body).await {
+        Ok(body) => body,
+        Err(status_code) => return status_code.into_response(),
+    };
+
+    let Some(property_names) = xml::parse_property_update(&body) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    dav_xml_response(
+        StatusCode::MULTI_STATUS,
+        xml::render_property_update(
+            &xml::dav_href(
+                Path::new(scoped_path),
+                storage_item.kind == StorageItemKind::Directory,
+            ),
+            &property_names,
+        ),
+    )
+}
+
+async fn dav_get(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    include_body: bool,
+) -> Response {
+    let storage_item = match find_storage_item(app_state, user, scoped_path).await {
+        Ok(Some(storage_item)) => storage_item,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(error) => return stat_error_response(error),
+    };
+
+    if storage_item.kind == StorageItemKind::Directory {
+        return (
+            StatusCode::METHOD_NOT_ALLOWED,
+            [(header::ALLOW, ALLOWED_METHODS)],
+        )
+            .into_response();
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(storage_item.size));
+
+    if let Some(modified_at) = storage_item.modified_at
+        && let Ok(last_modified) = HeaderValue::from_str(&httpdate::fmt_http_date(modified_at))
+    {
+        headers.insert(header::LAST_MODIFIED, last_modified);
+    }
+
+    if !include_body {
+        if let Ok(content_type) = HeaderValue::from_str(
+            mime_guess::from_path(&storage_item.path.scoped_path)
+                .first_or_octet_stream()
+                .essence_str(),
+        ) {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+
+        return (StatusCode::OK, headers).into_response();
+    }
+
+    match download_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        scoped_path,
+        DirectoryArchiveFormat::default(),
+        user,
+    )
+    .await
+    {
+        Ok(result) => {
+            if let Ok(content_type) = HeaderValue::from_str(result.mime.essence_str()) {
+                headers.insert(header::CONTENT_TYPE, content_type);
+            }
+
+            if let Some(checksum) = result.checksum
+                && let Ok(etag) = HeaderValue::from_str(&format!("\"{checksum}\""))
+            {
+                headers.insert(header::ETAG, etag);
+            }
+
+            (StatusCode::OK, headers, AsyncReadBody::new(result.file)).into_response()
+        }
+        Err(error) => dav_internal_server_error_response(error),
+    }
+}
+
+async fn dav_put(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    lock_tokens: &[Uuid],
+    body: Body,
+) -> Response {
+    let Some((parent_path, file_name)) = split_scoped_path(scoped_path) else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
+
+    let existing_storage_item = match find_storage_item(app_state, user, scoped_path).await {
+        Ok(storage_item) => storage_item,
+        Err(error) => return stat_error_response(error),
+    };
+
+    if existing_storage_item
+        .as_ref()
+        .is_some_and(|storage_item| storage_item.kind == StorageItemKind::Directory)
+    {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+
+    match is_existing_directory(app_state, user, &parent_path).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::CONFLICT.into_response(),
+        Err(error) => return stat_error_response(error),
+    }
+
+    let change = match existing_storage_item {
+        Some(..) => DavChange::Content,
+        None => DavChange::Membership,
+    };
+
+    if let Err(status_code) = ensure_unlocked(app_state, user, scoped_path, change, lock_tokens) {
+        return status_code.into_response();
+    }
+
+    match upload_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &UploadPolicy::from(&app_state.config),
+        &parent_path,
+        user,
+        UserFileUpload {
+            relative_path: &file_name,
+            stream: body.into_data_stream(),
+            conflict_policy: ConflictPolicy::Overwrite,
+        },
+    )
+    .await
+    {
+        Ok(..) if existing_storage_item.is_some() => StatusCode::NO_CONTENT.into_response(),
+        Ok(..) => StatusCode::CREATED.into_response(),
+        Err(UserFileUploadError::StoragePath(..)) => StatusCode::BAD_REQUEST.into_response(),
+        Err(UserFileUploadError::WriteVersionedFileStream(
+            WriteVersionedFileStreamError::WriteFileStream(
+                WriteFileStreamError::SizeLimitExceeded(..),
+            ),
+        )) => StatusCode::INSUFFICIENT_STORAGE.into_response(),
+        Err(error) => dav_internal_server_error_response(error),
+    }
+}
+
+async fn dav_mkcol(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    lock_tokens: &[Uuid],
+) -> Response {
+    let Some((parent_path, directory_name)) = split_scoped_path(scoped_path) else {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    };
+
+    match find_storage_item(app_state, user, scoped_path).await {
+        Ok(Some(..)) => return StatusCode::METHOD_NOT_ALLOWED.into_response(),
+        Ok(None) => {}
+        Err(error) => return stat_error_response(error),
+    }
+
+    match is_existing_directory(app_state, user, &parent_path).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::CONFLICT.into_response(),
+        Err(error) => return stat_error_response(error),
+    }
+
+    if let Err(status_code) = ensure_unlocked(
+        app_state,
+        user,
+        scoped_path,
+        DavChange::Membership,
+        lock_tokens,
+    ) {
+        return status_code.into_response();
+    }
+
+    match create_direcory(
+        &app_state.database,
+        &app_state.storage_root,
+        &parent_path,
+        &directory_name,
+        ConflictPolicy::Fail,
+        user,
+    )
+    .await
+    {
+        Ok(..) => StatusCode::CREATED.into_response(),
+        Err(error) => dav_internal_server_error_response(error),
+    }
+}
+
+async fn dav_delete(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    lock_tokens: &[Uuid],
+) -> Response {
+    if is_root_path(scoped_path) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    match find_storage_item(app_state, user, scoped_path).await {
+        Ok(Some(..)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(error) => return stat_error_response(error),
+    }
+
+    if let Err(status_code) = ensure_unlocked(
+        app_state,
+        user,
+        scoped_path,
+        DavChange::Membership,
+        lock_tokens,
+    ) {
+        return status_code.into_response();
+    }
+
+    // deleted items go to the trash, just like deleting them in the browser
+    match delete_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        scoped_path,
+        user,
+    )
+    .await
+    {
+        Ok(..) => {
+            app_state.dav_locks.unlock_all_below(user.id, scoped_path);
+
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => dav_internal_server_error_response(error),
+    }
+}
+
+enum DavTransfer {
+    Move,
+    Copy,
+}
+
+async fn dav_transfer(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    headers: &HeaderMap,
+    lock_tokens: &[Uuid],
+    transfer: DavTransfer,
+) -> Response {
+    if is_root_path(scoped_path) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let Some(destination) = headers
+        .get(header::HeaderName::from_static("destination"))
+        .and_then(|destination| destination.to_str().ok())
+        .and_then(|destination| destination.parse::<Uri>().ok())
+    else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    // the destination may be an absolute URL, only its path is relevant
+    let Some(destination_path) = dav_scoped_path(destination.path()) else {
+        return StatusCode::BAD_GATEWAY.into_response();
+    };
+
+    let Some((destination_parent_path, _)) = split_scoped_path(&destination_path) else {
+        return StatusCode::FORBIDDEN.into_response();
+    };
+
+    if is_path_at_or_below(&destination_path, scoped_path) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let is_overwrite_allowed = headers
+        .get(header::HeaderName::from_static("overwrite"))
+        .is_none_or(|overwrite| overwrite != "F");
+
+    match find_storage_item(app_state, user, scoped_path).await {
+        Ok(Some(..)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(error) => return stat_error_response(error),
+    }
+
+    match is_existing_directory(app_state, user, &destination_parent_path).await {
+        Ok(true) => {}
+        Ok(false) => return StatusCode::CONFLICT.into_response(),
+        Err(error) => return stat_error_response(error),
+    }
+
+    // a move removes the source from its directory, a copy leaves it as it is
+    let changed_paths = match transfer {
+        DavTransfer::Move => vec![scoped_path, destination_path.as_str()],
+        DavTransfer::Copy => vec![destination_path.as_str()],
+    };
+
+    for changed_path in changed_paths {
+        if let Err(status_code) = ensure_unlocked(
+            app_state,
+            user,
+            changed_path,
+            DavChange::Membership,
+            lock_tokens,
+        ) {
+            return status_code.into_response();
+        }
+    }
+
+    // only decides between 201 and 204, the conflict itself is resolved by the move or copy
+    let is_destination_replaced = match find_storage_item(app_state, user, &destination_path).await
+    {
+        Ok(destination_storage_item) => destination_storage_item.is_some(),
+        Err(error) => return stat_error_response(error),
+    };
+
+    let result = match transfer {
+        DavTransfer::Move => match move_user_file(
+            &app_state.database,
+            &app_state.storage_root,
+            scoped_path,
+            &destination_path,
+            match is_overwrite_allowed {
+                true => ConflictPolicy::Overwrite,
+                false => ConflictPolicy::Fail,
+            },
+            user,
+        )
+        .await
+        {
+            // locks belong to a path and do not move along with the item
+            Ok(..) => {
+                app_state.dav_locks.unlock_all_below(user.id, scoped_path);
+
+                Ok(())
+            }
+            Err(UserFileMoveError::ResolveDestinationConflict(
+                ResolveDestinationConflictError::DestinationExists(..),
+            )) => Err(StatusCode::PRECONDITION_FAILED.into_response()),
+            Err(
+                UserFileMoveError::DestinationSameAsSource(..)
+                | UserFileMoveError::SourceInsideDestination(..),
+            ) => Err(StatusCode::FORBIDDEN.into_response()),
+            Err(error) => Err(dav_internal_server_error_response(error)),
+        },
+        DavTransfer::Copy => match copy_user_file(
+            &app_state.database,
+            &app_state.storage_root,
+            app_state.config.default_user_storage_quota,
+            scoped_path,
+            &destination_path,
+            match is_overwrite_allowed {
+                true => CopyConflictPolicy::Overwrite,
+                false => CopyConflictPolicy::Fail,
+            },
+            user,
+        )
+        .await
+        {
+            Ok(..) => Ok(()),
+            Err(UserFileCopyError::DestinationExists(..)) => {
+                Err(StatusCode::PRECONDITION_FAILED.into_response())
+            }
+            Err(
+                UserFileCopyError::DestinationSameAsSource(..)
+                | UserFileCopyError::SourceInsideDestination(..),
+            ) => Err(StatusCode::FORBIDDEN.into_response()),
+            Err(UserFileCopyError::StorageQuotaExceeded(..)) => {
+                Err(StatusCode::INSUFFICIENT_STORAGE.into_response())
+            }
+            Err(error) => Err(dav_internal_server_error_response(error)),
+        },
+    };
+
+    match result {
+        Ok(()) if is_destination_replaced => StatusCode::NO_CONTENT.into_response(),
+        Ok(()) => StatusCode::CREATED.into_response(),
+        Err(response) => response,
+    }
+}
+
+/// The timeout the client asked for in the `Timeout` header, shortened to the maximum.
+fn requested_lock_timeout(headers: &HeaderMap) -> Duration {
+    headers
+        .get(header::HeaderName::from_static("timeout"))
+        .and_then(|timeout| timeout.to_str().ok())
+        .and_then(|timeout| {
+            timeout
+                .split(',')
+                .find_map(|timeout| timeout.trim().strip_prefix("Second-")?.parse::<u64>().ok())
+        })
+        .map_or(MAX_DAV_LOCK_TIMEOUT, |timeout| {
+            Duration::from_secs(timeout).min(MAX_DAV_LOCK_TIMEOUT)
+        })
+}
+
+fn dav_lock_response(status_code: StatusCode, lock: &DavLock) -> Response {
+    let Ok(lock_token_header) = HeaderValue::from_str(&format!("<{}>", xml::lock_token_uri(lock)))
+    else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    (
+        status_code,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/xml; charset=utf-8"),
+            ),
+            (
+                header::HeaderName::from_static("lock-token"),
+                lock_token_header,
+            ),
+        ],
+        xml::render_lock_discovery(lock),
+    )
+        .into_response()
+}
+
+async fn dav_lock(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    headers: &HeaderMap,
+    lock_tokens: &[Uuid],
+    body: Body,
+) -> Response {
+    let timeout = requested_lock_timeout(headers);
+
+    let body = match read_xml_body(body).await {
+        Ok(body) => body,
+        Err(status_code) => return status_code.into_response(),
+    };
+
+    // a request without a body refreshes a lock submitted in the `If` header
+    if body.trim().is_empty() {
+        return match app_state
+            .dav_locks
+            .refresh(user.id, scoped_path, lock_tokens, timeout)
+        {
+            Some(lock) => dav_lock_response(StatusCode::OK, &lock),
+            None => StatusCode::PRECONDITION_FAILED.into_response(),
+        };
+    }
+
+    let Some(lock_info) = xml::parse_lock_info(&body) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let depth = match headers.get(header::HeaderName::from_static("depth")) {
+        None => DavLockDepth::Infinity,
+        Some(depth) if depth == "infinity" => DavLockDepth::Infinity,
+        Some(depth) if depth == "0" => DavLockDepth::Zero,
+        Some(..) => return StatusCode::BAD_REQUEST.into_response(),
+    };
+
+    let existing_storage_item = match find_storage_item(app_state, user, scoped_path).await {
+        Ok(storage_item) => storage_item,
+        Err(error) => return stat_error_response(error),
+    };
+
+    // locking a missing item creates an empty file, see RFC 4918 section 7.3
+    let new_file = match existing_storage_item {
+        Some(..) => None,
+        None => {
+            let Some((parent_path, file_name)) = split_scoped_path(scoped_path) else {
+                return StatusCode::METHOD_NOT_ALLOWED.into_response();
+            };
+
+            match is_existing_directory(app_state, user, &parent_path).await {
+                Ok(true) => {}
+                Ok(false) => return StatusCode::CONFLICT.into_response(),
+                Err(error) => return stat_error_response(error),
+            }
+
+            if let Err(status_code) = ensure_unlocked(
+                app_state,
+                user,
+                scoped_path,
+                DavChange::Membership,
+                lock_tokens,
+            ) {
+                return status_code.into_response();
+            }
+
+            Some((parent_path, file_name))
+        }
+    };
+
+    let lock = match app_state.dav_locks.lock(DavLockRequest {
+        user_id: user.id,
+        root_path: scoped_path,
+        depth,
+        scope: lock_info.scope,
+        owner: lock_info.owner,
+        timeout,
+    }) {
+        Ok(lock) => lock,
+        Err(DavLockCreateError::Locked(..)) => return StatusCode::LOCKED.into_response(),
+        Err(DavLockCreateError::LimitReached) => {
+            return StatusCode::SERVICE_UNAVAILABLE.into_response();
+        }
+    };
+
+    let Some((parent_path, file_name)) = new_file else {
+        return dav_lock_response(StatusCode::OK, &lock);
+    };
+
+    match upload_user_file(
+        &app_state.database,
+        &app_state.storage_root,
+        &UploadPolicy::from(&app_state.config),
+        &parent_path,
+        user,
+        UserFileUpload {
+            relative_path: &file_name,
+            stream: Body::empty().into_data_stream(),
+            conflict_policy: ConflictPolicy::Fail,
+        },
+    )
+    .await
+    {
+        Ok(..) => dav_lock_response(StatusCode::CREATED, &lock),
+        Err(error) => {
+            app_state
+                .dav_locks
+                .unlock(user.id, scoped_path, &lock.token);
+
+            match error {
+                UserFileUploadError::StoragePath(..) => StatusCode::BAD_REQUEST.into_response(),
+                error => dav_internal_server_error_response(error),
+            }
+        }
+    }
+}
+
+fn dav_unlock(
+    app_state: &AppState,
+    user: &User,
+    scoped_path: &str,
+    headers: &HeaderMap,
+) -> Response {
+    let Some(lock_token) = headers
+        .get(header::HeaderName::from_static("lock-token"))
+        .and_then(|lock_token| lock_token.to_str().ok())
+        .and_then(|lock_token| lock_token.trim().strip_prefix('<')?.strip_suffix('>'))
+        .and_then(parse_lock_token_uri)
+    else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    match app_state
+        .dav_locks
+        .unlock(user.id, scoped_path, &lock_token)
+    {
+        true => StatusCode::NO_CONTENT.into_response(),
+        false => dav_xml_response(
+            StatusCode::CONFLICT,
+            xml::render_error("lock-token-matches-request-uri"),
+        ),
+    }
+}
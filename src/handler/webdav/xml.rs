@@ -0,0 +1,324 @@
+use std::{
+    fmt::Write,
+    path::{Component, Path},
+};
+
+use bitsync_routes::TypedPath;
+use bitsync_storage::storage_item::{StorageItem, StorageItemKind};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use roxmltree::{Document, Node};
+
+use crate::dav_locks::{DavLock, DavLockDepth, DavLockScope};
+
+const DAV_NAMESPACE: &str = "DAV:";
+
+/// The namespace of the file times and attributes Windows sets after writing a file.
+const WIN32_NAMESPACE: &str = "urn:schemas-microsoft-com:";
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="utf-8"?>"#;
+
+/// Characters that are kept as they are in a path segment, see RFC 3986 `unreserved`.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// The URL path of a storage item below the WebDAV prefix. Collections end with a slash.
+pub(super) fn dav_href(scoped_path: &Path, is_collection: bool) -> String {
+    let mut href = bitsync_routes::DavRoot::PATH.to_owned();
+
+    for component in scoped_path.components() {
+        if let Component::Normal(segment) = component {
+            href.push('/');
+            href.extend(utf8_percent_encode(
+                &segment.to_string_lossy(),
+                PATH_SEGMENT,
+            ));
+        }
+    }
+
+    if is_collection {
+        href.push('/');
+    }
+
+    href
+}
+
+/// Renders a `207 Multi-Status` body describing the live properties of `storage_items`.
+/// `root_directory_name` is used as the display name of the storage root, `locks` are listed on
+/// the items they apply to.
+pub(super) fn render_multistatus(
+    storage_items: &[StorageItem],
+    root_directory_name: &str,
+    locks: &[DavLock],
+) -> String {
+    let mut xml = format!(r#"{XML_DECLARATION}<D:multistatus xmlns:D="DAV:">"#);
+
+    for storage_item in storage_items {
+        let is_collection = storage_item.kind == StorageItemKind::Directory;
+        let display_name = storage_item
+            .path
+            .scoped_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_else(|| root_directory_name.to_owned());
+
+        let _ = write!(
+            xml,
+            "<D:response><D:href>{href}</D:href><D:propstat><D:prop><D:displayname>{display_name}</D:displayname>",
+            href = dav_href(&storage_item.path.scoped_path, is_collection),
+            display_name = escape_xml(&display_name),
+        );
+
+        match storage_item.kind {
+            StorageItemKind::Directory => {
+                xml.push_str("<D:resourcetype><D:collection/></D:resourcetype>");
+            }
+            StorageItemKind::File => {
+                let content_type =
+                    mime_guess::from_path(&storage_item.path.scoped_path).first_or_octet_stream();
+
+                let _ = write!(
+                    xml,
+                    "<D:resourcetype/><D:getcontentlength>{size}</D:getcontentlength><D:getcontenttype>{content_type}</D:getcontenttype>",
+                    size = storage_item.size,
+                    content_type = escape_xml(content_type.essence_str()),
+                );
+            }
+        }
+
+        if let Some(modified_at) = storage_item.modified_at {
+            let _ = write!(
+                xml,
+                "<D:getlastmodified>{}</D:getlastmodified>",
+                httpdate::fmt_http_date(modified_at),
+            );
+        }
+
+        xml.push_str(
+            "<D:supportedlock><D:lockentry><D:lockscope><D:exclusive/></D:lockscope><D:locktype><D:write/></D:locktype></D:lockentry><D:lockentry><D:lockscope><D:shared/></D:lockscope><D:locktype><D:write/></D:locktype></D:lockentry></D:supportedlock>",
+        );
+
+        let scoped_path = storage_item.path.scoped_path.to_string_lossy();
+
+        xml.push_str("<D:lockdiscovery>");
+
+        for lock in locks.iter().filter(|lock| lock.covers(&scoped_path)) {
+            render_active_lock(&mut xml, lock);
+        }
+
+        xml.push_str("</D:lockdiscovery>");
+        xml.push_str("</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>");
+    }
+
+    xml.push_str("</D:multistatus>");
+
+    xml
+}
+
+fn render_active_lock(xml: &mut String, lock: &DavLock) {
+    let _ = write!(
+        xml,
+        concat!(
+            "<D:activelock><D:locktype><D:write/></D:locktype><D:lockscope>{scope}</D:lockscope>",
+            "<D:depth>{depth}</D:depth>{owner}<D:timeout>Second-{timeout}</D:timeout>",
+            "<D:locktoken><D:href>{lock_token}</D:href></D:locktoken>",
+            "<D:lockroot><D:href>{lock_root}</D:href></D:lockroot></D:activelock>",
+        ),
+        scope = match lock.scope {
+            DavLockScope::Exclusive => "<D:exclusive/>",
+            DavLockScope::Shared => "<D:shared/>",
+        },
+        depth = match lock.depth {
+            DavLockDepth::Zero => "0",
+            DavLockDepth::Infinity => "infinity",
+        },
+        owner = match &lock.owner {
+            Some(owner) => format!("<D:owner>{owner}</D:owner>"),
+            None => String::new(),
+        },
+        timeout = lock.remaining_seconds(),
+        lock_token = lock_token_uri(lock),
+        lock_root = dav_href(Path::new(&lock.root_path), lock.root_path == "/"),
+    );
+}
+
+/// The lock token as it appears in headers and bodies, see RFC 4918 section 6.5.
+pub(super) fn lock_token_uri(lock: &DavLock) -> String {
+    format!("opaquelocktoken:{}", lock.token)
+}
+
+/// Renders the body of a `LOCK` response for a granted or refreshed lock.
+pub(super) fn render_lock_discovery(lock: &DavLock) -> String {
+    let mut xml = format!(r#"{XML_DECLARATION}<D:prop xmlns:D="DAV:"><D:lockdiscovery>"#);
+
+    render_active_lock(&mut xml, lock);
+    xml.push_str("</D:lockdiscovery></D:prop>");
+
+    xml
+}
+
+/// Renders an error body naming the precondition a request failed, see RFC 4918 section 16.
+pub(super) fn render_error(precondition: &str) -> String {
+    format!(r#"{XML_DECLARATION}<D:error xmlns:D="DAV:"><D:{precondition}/></D:error>"#)
+}
+
+fn is_dav_element(node: &Node<'_, '_>, name: &str) -> bool {
+    node.is_element()
+        && node.tag_name().name() == name
+        && node.tag_name().namespace() == Some(DAV_NAMESPACE)
+}
+
+fn dav_child<'a, 'input>(node: &Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is_dav_element(child, name))
+}
+
+pub(super) struct LockInfo {
+    pub(super) scope: DavLockScope,
+    pub(super) owner: Option<String>,
+}
+
+/// Reads the `lockinfo` body of a `LOCK` request. Only write locks exist.
+pub(super) fn parse_lock_info(body: &str) -> Option<LockInfo> {
+    let document = Document::parse(body).ok()?;
+    let lock_info = document.root_element();
+
+    if !is_dav_element(&lock_info, "lockinfo") {
+        return None;
+    }
+
+    let lock_scope = dav_child(&lock_info, "lockscope")?;
+    let scope = if dav_child(&lock_scope, "exclusive").is_some() {
+        DavLockScope::Exclusive
+    } else if dav_child(&lock_scope, "shared").is_some() {
+        DavLockScope::Shared
+    } else {
+        return None;
+    };
+
+    dav_child(&dav_child(&lock_info, "locktype")?, "write")?;
+
+    // the owner is kept as a link or plain text, which is what clients send in practice
+    let owner = dav_child(&lock_info, "owner").map(|owner| match dav_child(&owner, "href") {
+        Some(href) => format!(
+            "<D:href>{}</D:href>",
+            escape_xml(href.text().unwrap_or_default().trim())
+        ),
+        None => escape_xml(
+            owner
+                .descendants()
+                .filter(Node::is_text)
+                .filter_map(|node| node.text())
+                .collect::<String>()
+                .trim(),
+        ),
+    });
+
+    Some(LockInfo { scope, owner })
+}
+
+pub(super) struct PropertyName {
+    namespace: Option<String>,
+    name: String,
+}
+
+/// Reads the names of the properties a `PROPPATCH` request sets or removes.
+pub(super) fn parse_property_update(body: &str) -> Option<Vec<PropertyName>> {
+    let document = Document::parse(body).ok()?;
+    let property_update = document.root_element();
+
+    if !is_dav_element(&property_update, "propertyupdate") {
+        return None;
+    }
+
+    let property_names = property_update
+        .children()
+        .filter(|child| is_dav_element(child, "set") || is_dav_element(child, "remove"))
+        .filter_map(|instruction| dav_child(&instruction, "prop"))
+        .flat_map(|prop| prop.children().filter(Node::is_element))
+        .map(|property| PropertyName {
+            namespace: property
+                .tag_name()
+                .namespace()
+                .filter(|namespace| !namespace.is_empty())
+                .map(str::to_owned),
+            name: property.tag_name().name().to_owned(),
+        })
+        .collect();
+
+    Some(property_names)
+}
+
+/// Renders the `207 Multi-Status` body of a `PROPPATCH` request. No properties are stored, but
+/// the Windows file times and attributes are accepted, since Windows treats failing to set them
+/// as a failed write. As the update is atomic, any other property fails all of them.
+pub(super) fn render_property_update(href: &str, property_names: &[PropertyName]) -> String {
+    let is_accepted = property_names
+        .iter()
+        .all(|property_name| property_name.namespace.as_deref() == Some(WIN32_NAMESPACE));
+
+    let mut xml = format!(
+        r#"{XML_DECLARATION}<D:multistatus xmlns:D="DAV:"><D:response><D:href>{href}</D:href>"#
+    );
+
+    let mut render_propstat = |property_names: &mut dyn Iterator<Item = &PropertyName>,
+                               status: &str| {
+        xml.push_str("<D:propstat><D:prop>");
+
+        for (index, property_name) in property_names.enumerate() {
+            let _ = match &property_name.namespace {
+                Some(namespace) => write!(
+                    xml,
+                    r#"<P{index}:{name} xmlns:P{index}="{namespace}"/>"#,
+                    name = property_name.name,
+                    namespace = escape_xml(namespace),
+                ),
+                None => write!(xml, r#"<{name} xmlns=""/>"#, name = property_name.name),
+            };
+        }
+
+        let _ = write!(
+            xml,
+            "</D:prop><D:status>HTTP/1.1 {status}</D:status></D:propstat>"
+        );
+    };
+
+    if is_accepted {
+        render_propstat(&mut property_names.iter(), "200 OK");
+    } else {
+        let (win32_property_names, other_property_names): (Vec<_>, Vec<_>) = property_names
+            .iter()
+            .partition(|property_name| property_name.namespace.as_deref() == Some(WIN32_NAMESPACE));
+
+        render_propstat(&mut other_property_names.into_iter(), "403 Forbidden");
+
+        if !win32_property_names.is_empty() {
+            render_propstat(
+                &mut win32_property_names.into_iter(),
+                "424 Failed Dependency",
+            );
+        }
+    }
+
+    xml.push_str("</D:response></D:multistatus>");
+
+    xml
+}
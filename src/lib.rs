@@ -3,22 +3,40 @@ use std::sync::Arc;
 use axum::{Router, routing::IntoMakeService};
 use bitsync_core::{
     config::Config,
-    use_case::auth::ensure_admin_bootstrap::{
-        AdminBootstrapStatus, EnsureAdminBootstrapError, ensure_admin_bootstrap,
+    use_case::{
+        auth::ensure_admin_bootstrap::{
+            AdminBootstrapStatus, EnsureAdminBootstrapError, ensure_admin_bootstrap,
+        },
+        user_files::thumbnail_user_file::MAX_CONCURRENT_THUMBNAIL_CREATIONS,
     },
 };
 use bitsync_database::database::{ConnectAndMigrateError, Database};
-use tower_http::cors::CorsLayer;
+use bitsync_storage::{
+    backend::s3::CreateS3StorageBackendError,
+    operation::write::{CleanTemporaryFilesError, clean_temporary_files},
+    storage_root::StorageRoot,
+};
+use tokio::sync::Semaphore;
 
 mod auth;
 mod body_limit;
 pub mod config;
+mod dav_locks;
 mod error_report;
+mod extraction_jobs;
 mod handler;
+mod scrub_jobs;
+mod trash_purge;
+mod upload_session_purge;
 
 pub struct AppState {
     pub(crate) config: Config,
     pub(crate) database: Database,
+    pub(crate) storage_root: StorageRoot,
+    pub(crate) extraction_jobs: extraction_jobs::ExtractionJobs,
+    pub(crate) dav_locks: dav_locks::DavLocks,
+    pub(crate) scrub_jobs: scrub_jobs::ScrubJobs,
+    pub(crate) thumbnail_creation_permits: Arc<Semaphore>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -26,13 +44,25 @@ pub struct AppState {
 pub enum ApplicationStateInitializationError {
     ConnectAndMigrate(#[from] ConnectAndMigrateError),
     AdminBootstrap(#[from] EnsureAdminBootstrapError),
+    CleanTemporaryFiles(#[from] CleanTemporaryFilesError),
+    CreateS3StorageBackend(#[from] CreateS3StorageBackendError),
 }
 
 impl AppState {
     pub async fn from_config(config: Config) -> Result<Self, ApplicationStateInitializationError> {
+        // no writes are in progress yet, so anything left over was interrupted by a shutdown
+        clean_temporary_files(&config.fs_storage_root_dir).await?;
+
         let state = Self {
             database: Database::connect_and_migrate(&config.database_url).await?,
+            storage_root: config.storage_root()?,
             config,
+            extraction_jobs: Default::default(),
+            dav_locks: Default::default(),
+            scrub_jobs: Default::default(),
+            thumbnail_creation_permits: Arc::new(Semaphore::new(
+                MAX_CONCURRENT_THUMBNAIL_CREATIONS,
+            )),
         };
 
         Ok(state)
@@ -53,8 +83,10 @@ pub async fn make_service(
         println!("==========================================================");
     }
 
-    Ok(handler::create_routes(Arc::new(app_state))
-        .await
-        .layer(CorsLayer::permissive())
-        .into_make_service())
+    let app_state = Arc::new(app_state);
+
+    trash_purge::spawn_trash_purge_task(app_state.clone());
+    upload_session_purge::spawn_upload_session_purge_task(app_state.clone());
+
+    Ok(handler::create_routes(app_state).await.into_make_service())
 }
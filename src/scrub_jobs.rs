@@ -0,0 +1,99 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use bitsync_core::use_case::file_checksum::scrub_file_checksums::{
+    FileChecksumScrubProgress, FileChecksumScrubReport, ScrubFileChecksumsError,
+};
+use tokio::{
+    sync::watch,
+    task::{JoinError, JoinHandle},
+};
+use uuid::Uuid;
+
+/// Reports of finished scrubs that were never polled are dropped after this long.
+const FINISHED_SCRUB_JOB_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Checksum scrubs running in the background, polled by any admin. A scrub covers the storage of
+/// all users, so only one runs at a time.
+#[derive(Default)]
+pub(crate) struct ScrubJobs {
+    jobs: Mutex<HashMap<Uuid, ScrubJob>>,
+}
+
+struct ScrubJob {
+    started_at: Instant,
+    progress: watch::Receiver<FileChecksumScrubProgress>,
+    task: JoinHandle<Result<FileChecksumScrubReport, ScrubFileChecksumsError>>,
+}
+
+pub(crate) enum ScrubJobStatus {
+    Running(FileChecksumScrubProgress),
+    Finished(Result<FileChecksumScrubReport, ScrubJobError>),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("a scrub job failed")]
+pub(crate) enum ScrubJobError {
+    Scrub(#[from] ScrubFileChecksumsError),
+    Join(#[from] JoinError),
+}
+
+impl ScrubJobs {
+    /// Spawns the future returned by `scrub`, which reports its progress through the given
+    /// sender, and returns the id of the job. If a scrub is already running, its id is returned
+    /// instead and `scrub` is not called.
+    pub(crate) fn start<F, Fut>(&self, scrub: F) -> Uuid
+    where
+        F: FnOnce(watch::Sender<FileChecksumScrubProgress>) -> Fut,
+        Fut: Future<Output = Result<FileChecksumScrubReport, ScrubFileChecksumsError>>
+            + Send
+            + 'static,
+    {
+        let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+
+        jobs.retain(|_, job| {
+            !job.task.is_finished() || job.started_at.elapsed() < FINISHED_SCRUB_JOB_RETENTION
+        });
+
+        if let Some((job_id, _)) = jobs.iter().find(|(_, job)| !job.task.is_finished()) {
+            return *job_id;
+        }
+
+        let (progress_sender, progress_receiver) =
+            watch::channel(FileChecksumScrubProgress::default());
+        let job_id = Uuid::new_v4();
+        let job = ScrubJob {
+            started_at: Instant::now(),
+            progress: progress_receiver,
+            task: tokio::spawn(scrub(progress_sender)),
+        };
+
+        jobs.insert(job_id, job);
+
+        job_id
+    }
+
+    /// The status of a job. A finished job is removed once its report has been returned.
+    pub(crate) async fn status(&self, job_id: &Uuid) -> Option<ScrubJobStatus> {
+        let finished_job = {
+            let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            let job = jobs.get(job_id)?;
+
+            if !job.task.is_finished() {
+                return Some(ScrubJobStatus::Running(*job.progress.borrow()));
+            }
+
+            jobs.remove(job_id)?
+        };
+
+        let result = match finished_job.task.await {
+            Ok(result) => result.map_err(ScrubJobError::from),
+            Err(error) => Err(error.into()),
+        };
+
+        Some(ScrubJobStatus::Finished(result))
+    }
+}
@@ -0,0 +1,34 @@
+use std::{sync::Arc, time::Duration};
+
+use bitsync_core::use_case::trash::purge_expired_trash_entries::purge_expired_trash_entries;
+
+use crate::{AppState, error_report::emit_error};
+
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically deletes trashed items that are older than the configured retention.
+pub(crate) fn spawn_trash_purge_task(app_state: Arc<AppState>) {
+    let retention = time::Duration::days(app_state.config.trash_retention_days as i64);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TRASH_PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match purge_expired_trash_entries(
+                &app_state.database,
+                &app_state.storage_root,
+                retention,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(purged_count) => {
+                    tracing::info!("purged {purged_count} expired trash entries");
+                }
+                Err(error) => emit_error(error),
+            }
+        }
+    });
+}
@@ -0,0 +1,33 @@
+use std::{sync::Arc, time::Duration};
+
+use bitsync_core::use_case::upload_session::purge_stale_upload_sessions::purge_stale_upload_sessions;
+
+use crate::{AppState, error_report::emit_error};
+
+const UPLOAD_SESSION_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const UPLOAD_SESSION_MAX_IDLE_TIME: time::Duration = time::Duration::days(1);
+
+/// Periodically deletes upload sessions that were abandoned before they were completed.
+pub(crate) fn spawn_upload_session_purge_task(app_state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPLOAD_SESSION_PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match purge_stale_upload_sessions(
+                &app_state.database,
+                &app_state.storage_root,
+                UPLOAD_SESSION_MAX_IDLE_TIME,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(purged_count) => {
+                    tracing::info!("purged {purged_count} stale upload sessions");
+                }
+                Err(error) => emit_error(error),
+            }
+        }
+    });
+}
@@ -0,0 +1,24 @@
+// submits a form once more with an explicit conflict policy, without keeping the policy for
+// later submissions of the same form. When file names are given, only those files of the form
+// are submitted again.
+function resubmitWithConflictPolicy(form, conflictPolicy, fileNames) {
+  if (fileNames) {
+    const fileInput = form.querySelector('input[name="upload"]')
+    const dataTransfer = new DataTransfer()
+
+    Array.from(fileInput.files)
+      .filter((file) => fileNames.includes(file.name))
+      .forEach((file) => dataTransfer.items.add(file))
+
+    fileInput.files = dataTransfer.files
+  }
+
+  const originalAction = form.getAttribute('action')
+  const action = new URL(originalAction, window.location.href)
+
+  action.searchParams.set('conflict_policy', conflictPolicy)
+
+  form.setAttribute('action', action.pathname + action.search)
+  form.requestSubmit()
+  form.setAttribute('action', originalAction)
+}
@@ -8,15 +8,64 @@ function initDropUpload(dropZone, form, activeClass) {
     dragoverTimeout = setTimeout(() => dropZone.classList.remove(activeClass), 500)
   })
 
-  document.addEventListener('drop', (event) => {
+  document.addEventListener('drop', async (event) => {
     event.preventDefault()
     clearTimeout(dragoverTimeout)
     dropZone.classList.remove(activeClass)
 
     if (dropZone.contains(event.target)) {
-      const fileInput = form.querySelector('input[type="file"]')
-      fileInput.files = event.dataTransfer.files
-      fileInput.dispatchEvent(new Event('change'))
+      // entries have to be taken before the first await, the data transfer is emptied afterwards
+      const entries = Array.from(event.dataTransfer.items, (item) => item.webkitGetAsEntry?.())
+
+      const files = entries.every((entry) => entry)
+        ? (await Promise.all(entries.map((entry) => readEntryFiles(entry, '')))).flat()
+        : Array.from(event.dataTransfer.files)
+
+      submitUploadFiles(form, files)
     }
   })
 }
+
+// files of a folder picked with `webkitdirectory` carry their path within the picked folder
+function uploadFolder(folderInput) {
+  const files = Array.from(folderInput.files, (file) => new File([file], file.webkitRelativePath, { type: file.type }))
+
+  folderInput.value = ''
+  submitUploadFiles(folderInput.form, files)
+}
+
+// the multipart file name of each file is its path below the upload directory
+function submitUploadFiles(form, files) {
+  const dataTransfer = new DataTransfer()
+  files.forEach((file) => dataTransfer.items.add(file))
+
+  const fileInput = form.querySelector('input[name="upload"]')
+  fileInput.files = dataTransfer.files
+  fileInput.dispatchEvent(new Event('change'))
+}
+
+async function readEntryFiles(entry, parentPath) {
+  const path = parentPath + entry.name
+
+  if (entry.isFile) {
+    const file = await new Promise((resolve, reject) => entry.file(resolve, reject))
+    return [new File([file], path, { type: file.type })]
+  }
+
+  const reader = entry.createReader()
+  const childEntries = []
+
+  // directory readers hand out their entries in batches until an empty one
+  for (;;) {
+    const batch = await new Promise((resolve, reject) => reader.readEntries(resolve, reject))
+
+    if (batch.length === 0) {
+      break
+    }
+
+    childEntries.push(...batch)
+  }
+
+  const childFiles = await Promise.all(childEntries.map((childEntry) => readEntryFiles(childEntry, path + '/')))
+  return childFiles.flat()
+}
@@ -0,0 +1,27 @@
+function inlineRenameElements(row) {
+  return {
+    form: row.querySelector('form[data-inline-rename]'),
+    link: row.querySelector('[data-inline-rename-link]'),
+  }
+}
+
+function startInlineRename(row) {
+  const { form, link } = inlineRenameElements(row)
+  const input = form.elements.new_file_name
+
+  link.hidden = true
+  form.hidden = false
+  input.value = input.defaultValue
+  input.focus()
+
+  // keep the extension out of the initial selection, like file managers do
+  const extensionStart = input.value.lastIndexOf('.')
+  input.setSelectionRange(0, extensionStart > 0 ? extensionStart : input.value.length)
+}
+
+function cancelInlineRename(row) {
+  const { form, link } = inlineRenameElements(row)
+
+  form.hidden = true
+  link.hidden = false
+}
@@ -0,0 +1,52 @@
+function selectionCheckboxes(form) {
+  return Array.from(form.elements).filter(
+    (element) => element.type === 'checkbox' && element.name === 'paths',
+  )
+}
+
+function updateSelection(form) {
+  const checkboxes = selectionCheckboxes(form)
+  const selectedCount = checkboxes.filter((checkbox) => checkbox.checked).length
+  const counter = form.querySelector('[data-selection-count]')
+
+  if (counter != null) {
+    counter.textContent = selectedCount === 1 ? '1 item selected' : selectedCount + ' items selected'
+  }
+
+  document
+    .querySelectorAll('input[form="' + form.id + '"][data-select-all]')
+    .forEach((selectAll) => {
+      selectAll.checked = checkboxes.length > 0 && selectedCount === checkboxes.length
+      selectAll.indeterminate = selectedCount > 0 && selectedCount < checkboxes.length
+    })
+}
+
+function toggleSelection(form, checked) {
+  selectionCheckboxes(form).forEach((checkbox) => (checkbox.checked = checked))
+  updateSelection(form)
+}
+
+function submitSelection(form, actionUrl) {
+  form.action = actionUrl
+  form.requestSubmit()
+}
+
+// downloads are regular navigations, so the selection is sent by a form that is not hijacked
+function downloadSelection(form, actionUrl) {
+  const downloadForm = document.createElement('form')
+  downloadForm.method = 'POST'
+  downloadForm.action = actionUrl
+  downloadForm.hidden = true
+
+  for (const [name, value] of new FormData(form)) {
+    const input = document.createElement('input')
+    input.type = 'hidden'
+    input.name = name
+    input.value = value
+    downloadForm.append(input)
+  }
+
+  document.body.append(downloadForm)
+  downloadForm.submit()
+  downloadForm.remove()
+}
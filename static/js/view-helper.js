@@ -0,0 +1,12 @@
+const DIRECTORY_VIEW_STORAGE_KEY = 'directory-view'
+
+// runs while the page is parsed, so a directory never shows up in the wrong view first
+function initDirectoryView(element, gridClass) {
+  element.classList.toggle(gridClass, localStorage.getItem(DIRECTORY_VIEW_STORAGE_KEY) === 'grid')
+}
+
+function toggleDirectoryView(element, gridClass) {
+  const isGrid = element.classList.toggle(gridClass)
+
+  localStorage.setItem(DIRECTORY_VIEW_STORAGE_KEY, isGrid ? 'grid' : 'list')
+}